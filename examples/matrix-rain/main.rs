@@ -0,0 +1,18 @@
+pub fn main() -> std::io::Result<()> {
+    let mut terminal = ratatui::init();
+    let mut state = tui_shader::MatrixRainState::default();
+
+    let start_time = std::time::Instant::now();
+    while start_time.elapsed().as_secs() < 7 {
+        terminal.draw(|frame| {
+            frame.render_stateful_widget(
+                tui_shader::MatrixRain::new().background(ratatui::style::Color::Black),
+                frame.area(),
+                &mut state,
+            );
+        })?;
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    ratatui::restore();
+    Ok(())
+}
@@ -0,0 +1,29 @@
+pub fn main() -> std::io::Result<()> {
+    let mut terminal = ratatui::init();
+    let mut background_state =
+        tui_shader::ShaderCanvasState::new(wgpu::include_wgsl!("../../shaders/starlight.wgsl"))
+            .unwrap();
+    let mut particle_state =
+        tui_shader::ParticleCanvasState::new(tui_shader::ParticleConfig::snow());
+
+    let start_time = std::time::Instant::now();
+    while start_time.elapsed().as_secs() < 7 {
+        let elapsed = start_time.elapsed().as_secs_f32();
+        particle_state.set_wind(elapsed.sin() * 0.1, 0.0);
+        terminal.draw(|frame| {
+            frame.render_stateful_widget(
+                tui_shader::ShaderCanvas::new(),
+                frame.area(),
+                &mut background_state,
+            );
+            frame.render_stateful_widget(
+                tui_shader::ParticleCanvas::new(),
+                frame.area(),
+                &mut particle_state,
+            );
+        })?;
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    ratatui::restore();
+    Ok(())
+}
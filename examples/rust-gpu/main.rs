@@ -0,0 +1,32 @@
+use tui_shader::{ShaderCanvas, ShaderCanvasState, SpirvShader};
+
+/// Shared with the shader crate, which reads it with
+/// `#[spirv(uniform, descriptor_set = 0, binding = 2)] uniforms: &Uniforms`.
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Uniforms {
+    hue: f32,
+    _padding: [f32; 3],
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let (Some(path), Some(entry_point)) = (args.next(), args.next()) else {
+        return Err("usage: rust-gpu <shader.spv> <entry point>".into());
+    };
+    let shader = SpirvShader::Path(&path);
+    let mut state = ShaderCanvasState::new_with_entry_point(shader, &entry_point)?;
+    state.set_uniforms(&Uniforms {
+        hue: 0.5,
+        _padding: [0.0; 3],
+    })?;
+
+    let mut terminal = ratatui::init();
+    while state.get_instant().elapsed().as_secs() < 5 {
+        terminal.draw(|frame| {
+            frame.render_stateful_widget(ShaderCanvas::new(), frame.area(), &mut state);
+        })?;
+    }
+    ratatui::restore();
+    Ok(())
+}
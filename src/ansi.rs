@@ -0,0 +1,128 @@
+use std::fmt::Write;
+
+use ratatui_core::buffer::{Buffer, Cell};
+use ratatui_core::style::{Color, Modifier};
+use unicode_width::UnicodeWidthStr;
+
+const MODIFIER_CODES: [(Modifier, u8); 9] = [
+    (Modifier::BOLD, 1),
+    (Modifier::DIM, 2),
+    (Modifier::ITALIC, 3),
+    (Modifier::UNDERLINED, 4),
+    (Modifier::SLOW_BLINK, 5),
+    (Modifier::RAPID_BLINK, 6),
+    (Modifier::REVERSED, 7),
+    (Modifier::HIDDEN, 8),
+    (Modifier::CROSSED_OUT, 9),
+];
+
+/// The set of colors used when exporting cells as text with ANSI escape sequences.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnsiColors {
+    /// 24-bit RGB colors, supported by most modern terminals.
+    #[default]
+    TrueColor,
+    /// The 256-color palette. RGB colors are mapped to the closest entry of the color cube or the
+    /// grayscale ramp.
+    Indexed,
+}
+
+/// Serializes the cells of a [`Buffer`] into text with ANSI escape sequences. Every row is terminated by
+/// a style reset followed by `line_separator`, except for the last one.
+pub(crate) fn buffer_to_ansi(buffer: &Buffer, line_separator: &str, colors: AnsiColors) -> String {
+    let mut output = String::new();
+    let width = buffer.area.width as usize;
+    for (y, row) in buffer.content().chunks(width.max(1)).enumerate() {
+        if y > 0 {
+            output.push_str(line_separator);
+        }
+        let mut previous: Option<&Cell> = None;
+        let mut skip = 0;
+        for cell in row {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let style_changed = previous.is_none_or(|previous| {
+                previous.fg != cell.fg
+                    || previous.bg != cell.bg
+                    || previous.modifier != cell.modifier
+            });
+            if style_changed {
+                write_style(&mut output, cell, colors);
+            }
+            previous = Some(cell);
+            let symbol = cell.symbol();
+            output.push_str(if symbol.is_empty() { " " } else { symbol });
+            skip = symbol.width().saturating_sub(1);
+        }
+        output.push_str("\x1b[0m");
+    }
+    output
+}
+
+fn write_style(output: &mut String, cell: &Cell, colors: AnsiColors) {
+    output.push_str("\x1b[0");
+    for (modifier, code) in MODIFIER_CODES {
+        if cell.modifier.contains(modifier) {
+            let _ = write!(output, ";{code}");
+        }
+    }
+    write_color(output, cell.fg, 30, colors);
+    write_color(output, cell.bg, 40, colors);
+    output.push('m');
+}
+
+fn write_color(output: &mut String, color: Color, base: u8, colors: AnsiColors) {
+    let _ = match color {
+        Color::Reset => Ok(()),
+        Color::Black => write!(output, ";{}", base),
+        Color::Red => write!(output, ";{}", base + 1),
+        Color::Green => write!(output, ";{}", base + 2),
+        Color::Yellow => write!(output, ";{}", base + 3),
+        Color::Blue => write!(output, ";{}", base + 4),
+        Color::Magenta => write!(output, ";{}", base + 5),
+        Color::Cyan => write!(output, ";{}", base + 6),
+        Color::Gray => write!(output, ";{}", base + 7),
+        Color::DarkGray => write!(output, ";{}", base + 60),
+        Color::LightRed => write!(output, ";{}", base + 61),
+        Color::LightGreen => write!(output, ";{}", base + 62),
+        Color::LightYellow => write!(output, ";{}", base + 63),
+        Color::LightBlue => write!(output, ";{}", base + 64),
+        Color::LightMagenta => write!(output, ";{}", base + 65),
+        Color::LightCyan => write!(output, ";{}", base + 66),
+        Color::White => write!(output, ";{}", base + 67),
+        Color::Indexed(index) => write!(output, ";{};5;{index}", base + 8),
+        Color::Rgb(r, g, b) => match colors {
+            AnsiColors::TrueColor => write!(output, ";{};2;{r};{g};{b}", base + 8),
+            AnsiColors::Indexed => write!(output, ";{};5;{}", base + 8, rgb_to_indexed(r, g, b)),
+        },
+    };
+}
+
+/// Maps an RGB color to the closest entry of the 6x6x6 color cube or the grayscale ramp of the 256-color
+/// palette.
+fn rgb_to_indexed(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let level = |value: u8| {
+        (0..LEVELS.len())
+            .min_by_key(|&i| LEVELS[i].abs_diff(value))
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (LEVELS[ri], LEVELS[gi], LEVELS[bi]);
+    let average = (r as u16 + g as u16 + b as u16) / 3;
+    let gray_index = ((average.saturating_sub(3)) / 10).min(23) as u8;
+    let gray = 8 + gray_index * 10;
+    let distance = |(cr, cg, cb): (u8, u8, u8)| {
+        let dr = cr.abs_diff(r) as u32;
+        let dg = cg.abs_diff(g) as u32;
+        let db = cb.abs_diff(b) as u32;
+        dr * dr + dg * dg + db * db
+    };
+    if distance((gray, gray, gray)) < distance(cube) {
+        232 + gray_index
+    } else {
+        16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8
+    }
+}
@@ -0,0 +1,316 @@
+//! `tui-shader` plays a WGSL or GLSL fragment shader fullscreen in the terminal and reloads it whenever
+//! the file changes.
+//!
+//! ```shell
+//! cargo install tui-shader --features cli
+//! tui-shader play shaders/voronoi.wgsl
+//! tui-shader record shaders/voronoi.wgsl --output voronoi.gif
+//! tui-shader screenshot shaders/voronoi.wgsl --time 2.5 --output voronoi.png
+//! ```
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use clap::{Args, Parser, Subcommand};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Text};
+use ratatui::widgets::{Clear, Paragraph, Wrap};
+use tui_shader::{
+    GlslShader, Recorder, ShaderCanvas, ShaderCanvasState, WgslShader, snapshot_buffer_png, testing,
+};
+
+#[derive(Parser)]
+#[command(name = "tui-shader", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Plays a shader fullscreen, reloading it whenever the file changes.
+    Play(PlayArgs),
+
+    /// Renders a shader without a terminal and records it to an animated GIF.
+    Record(RecordArgs),
+
+    /// Renders a single frame of a shader without a terminal and saves it as a PNG.
+    Screenshot(ScreenshotArgs),
+}
+
+#[derive(Args)]
+struct PlayArgs {
+    /// Path to a `.wgsl` shader, or a GLSL fragment shader (`.glsl`, `.frag`).
+    path: PathBuf,
+
+    /// Name of the `@fragment` function, required if the shader defines more than one.
+    #[arg(short, long)]
+    entry_point: Option<String>,
+
+    /// Maximum number of frames rendered per second.
+    #[arg(long, default_value_t = 60)]
+    fps: u32,
+
+    /// Hides the frame rate display.
+    #[arg(long)]
+    hide_fps: bool,
+}
+
+#[derive(Args)]
+struct RecordArgs {
+    /// Path to a `.wgsl` shader, or a GLSL fragment shader (`.glsl`, `.frag`).
+    path: PathBuf,
+
+    /// Path of the GIF to write.
+    #[arg(short, long, default_value = "recording.gif")]
+    output: PathBuf,
+
+    /// Name of the `@fragment` function, required if the shader defines more than one.
+    #[arg(short, long)]
+    entry_point: Option<String>,
+
+    /// Number of frames recorded per second.
+    #[arg(long, default_value_t = 30)]
+    fps: u32,
+
+    /// Length of the recording in seconds.
+    #[arg(long, default_value_t = 5.0)]
+    duration: f32,
+
+    /// Width of the recording in cells.
+    #[arg(long, default_value_t = 80)]
+    width: u16,
+
+    /// Height of the recording in cells.
+    #[arg(long, default_value_t = 24)]
+    height: u16,
+}
+
+#[derive(Args)]
+struct ScreenshotArgs {
+    /// Path to a `.wgsl` shader, or a GLSL fragment shader (`.glsl`, `.frag`).
+    path: PathBuf,
+
+    /// Path of the PNG to write.
+    #[arg(short, long, default_value = "screenshot.png")]
+    output: PathBuf,
+
+    /// Name of the `@fragment` function, required if the shader defines more than one.
+    #[arg(short, long)]
+    entry_point: Option<String>,
+
+    /// The `time` input of the frame in seconds.
+    #[arg(long, default_value_t = 0.0)]
+    time: f32,
+
+    /// Width of the screenshot in cells.
+    #[arg(long, default_value_t = 80)]
+    width: u16,
+
+    /// Height of the screenshot in cells.
+    #[arg(long, default_value_t = 24)]
+    height: u16,
+
+    /// Saves the pixels the shader rendered, one per cell, instead of the cells with their glyphs.
+    #[arg(long)]
+    pixels: bool,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    match Cli::parse().command {
+        Command::Play(args) => play(args),
+        Command::Record(args) => record(args),
+        Command::Screenshot(args) => screenshot(args),
+    }
+}
+
+fn screenshot(args: ScreenshotArgs) -> Result<(), Box<dyn Error>> {
+    let mut state = load(&args.path, args.entry_point.as_deref())?;
+    let time = Duration::from_secs_f32(args.time.max(0.0));
+    let buffer = testing::render(
+        &ShaderCanvas::new(),
+        &mut state,
+        args.width,
+        args.height,
+        time,
+    );
+    if args.pixels {
+        state.snapshot_png(&args.output)?;
+    } else {
+        snapshot_buffer_png(&buffer, &args.output)?;
+    }
+    println!("saved a screenshot to {}", args.output.display());
+    Ok(())
+}
+
+fn record(args: RecordArgs) -> Result<(), Box<dyn Error>> {
+    let mut state = load(&args.path, args.entry_point.as_deref())?;
+    let area = Rect::new(0, 0, args.width, args.height);
+    let mut recorder = Recorder::gif(&args.output, args.fps);
+    let frame_time = Duration::from_secs(1) / args.fps.max(1);
+    let frames = (args.duration * args.fps as f32).ceil() as u32;
+    for buffer in state.capture_frames(area, frames as usize, frame_time) {
+        recorder.capture_buffer(&buffer);
+    }
+    recorder.finish()?;
+    println!("recorded {frames} frames to {}", args.output.display());
+    Ok(())
+}
+
+fn play(args: PlayArgs) -> Result<(), Box<dyn Error>> {
+    let mut state = load(&args.path, args.entry_point.as_deref())?;
+    let mut modified = modified_time(&args.path);
+    let mut error_message: Option<String> = None;
+    let mut frame_rate = FrameRate::default();
+    let canvas = ShaderCanvas::new();
+    let frame_time = Duration::from_secs(1) / args.fps.max(1);
+
+    let mut terminal = ratatui::init();
+    state.detect_cell_size();
+    let result = loop {
+        let frame_start = Instant::now();
+        let current_modified = modified_time(&args.path);
+        if current_modified != modified {
+            modified = current_modified;
+            reload(&args, &mut state, &mut error_message);
+        }
+
+        frame_rate.tick();
+        let draw = terminal.draw(|frame| {
+            frame.render_stateful_widget(&canvas, frame.area(), &mut state);
+            if !args.hide_fps {
+                let text = format!(" {:.0} fps ", frame_rate.fps());
+                let [_, fps_area] =
+                    Layout::horizontal([Constraint::Min(0), Constraint::Length(text.len() as u16)])
+                        .areas(frame.area().rows().next().unwrap_or_default());
+                render_overlay(frame, fps_area, Line::from(text));
+            }
+            if let Some(error_message) = &error_message {
+                let [_, error_area] =
+                    Layout::vertical([Constraint::Min(0), Constraint::Percentage(40)])
+                        .areas(frame.area());
+                render_overlay(frame, error_area, Text::from(error_message.as_str()));
+            }
+        });
+        if let Err(error) = draw {
+            break Err(error.into());
+        }
+
+        let timeout = frame_time.saturating_sub(frame_start.elapsed());
+        match handle_events(timeout, &args, &mut state, &mut error_message) {
+            Ok(true) => break Ok(()),
+            Ok(false) => {}
+            Err(error) => break Err(error.into()),
+        }
+    };
+    ratatui::restore();
+    result
+}
+
+fn handle_events(
+    timeout: Duration,
+    args: &PlayArgs,
+    state: &mut ShaderCanvasState,
+    error_message: &mut Option<String>,
+) -> std::io::Result<bool> {
+    if !event::poll(timeout)? {
+        return Ok(false);
+    }
+    let event = event::read()?;
+    if let Event::Resize(..) = event {
+        state.detect_cell_size();
+    }
+    let Event::Key(key) = event else {
+        return Ok(false);
+    };
+    if key.kind != KeyEventKind::Press {
+        return Ok(false);
+    }
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => return Ok(true),
+        KeyCode::Char('r') => reload(args, state, error_message),
+        KeyCode::Char(' ') => {
+            let clock = state.clock_mut();
+            if clock.is_paused() {
+                clock.resume();
+            } else {
+                clock.pause();
+            }
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+fn reload(args: &PlayArgs, state: &mut ShaderCanvasState, error_message: &mut Option<String>) {
+    match load(&args.path, args.entry_point.as_deref()) {
+        Ok(mut new_state) => {
+            *new_state.clock_mut() = state.clock().clone();
+            let (width, height) = state.cell_size();
+            new_state.set_cell_size(width, height);
+            *state = new_state;
+            *error_message = None;
+        }
+        Err(error) => *error_message = Some(error.to_string()),
+    }
+}
+
+fn load(path: &Path, entry_point: Option<&str>) -> Result<ShaderCanvasState, Box<dyn Error>> {
+    let source = std::fs::read_to_string(path)?;
+    let is_wgsl = path
+        .extension()
+        .is_some_and(|extension| extension == "wgsl");
+    match (is_wgsl, entry_point) {
+        (true, None) => ShaderCanvasState::new(WgslShader::Source(&source)),
+        (true, Some(entry_point)) => {
+            ShaderCanvasState::new_with_entry_point(WgslShader::Source(&source), entry_point)
+        }
+        (false, None) => ShaderCanvasState::new(GlslShader::Source(&source)),
+        (false, Some(entry_point)) => {
+            ShaderCanvasState::new_with_entry_point(GlslShader::Source(&source), entry_point)
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn render_overlay<'a>(frame: &mut ratatui::Frame, area: Rect, text: impl Into<Text<'a>>) {
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .style(Style::new().fg(Color::White).bg(Color::Black)),
+        area,
+    );
+}
+
+#[derive(Default)]
+struct FrameRate {
+    last_frame: Option<Instant>,
+    fps: f32,
+}
+
+impl FrameRate {
+    fn tick(&mut self) {
+        let now = Instant::now();
+        if let Some(last_frame) = self.last_frame.replace(now) {
+            let fps = 1.0 / (now - last_frame).as_secs_f32().max(f32::EPSILON);
+            self.fps = if self.fps == 0.0 {
+                fps
+            } else {
+                self.fps * 0.9 + fps * 0.1
+            };
+        }
+    }
+
+    fn fps(&self) -> f32 {
+        self.fps
+    }
+}
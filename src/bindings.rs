@@ -0,0 +1,784 @@
+use std::error::Error;
+
+use wgpu::util::DeviceExt;
+
+use crate::gpu::storage_layout_entry;
+use crate::memory::{MemoryUsage, texture_bytes};
+
+/// The highest number of bind groups a pipeline may use with the default limits of wgpu.
+const MAX_BIND_GROUPS: u32 = 4;
+
+/// Number of bindings of `@group(0)` reserved for the built-in inputs of every shader.
+const RESERVED_BINDINGS: u32 = 9;
+
+/// The format of textures holding RGBA bytes.
+const TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// The format of volumes, 16 bit floats can be filtered on every device unlike 32 bit ones.
+const VOLUME_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R16Float;
+
+/// Storage buffers are copied when a state is cloned, see [`UserBindings::duplicate`].
+const STORAGE_BUFFER_USAGES: wgpu::BufferUsages = wgpu::BufferUsages::STORAGE
+    .union(wgpu::BufferUsages::COPY_DST)
+    .union(wgpu::BufferUsages::COPY_SRC);
+
+/// Identifies a resource in a shader by its `@group` and `@binding` attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BindingSlot {
+    pub group: u32,
+    pub binding: u32,
+}
+
+impl BindingSlot {
+    /// Creates a new [`BindingSlot`] for `@group(group) @binding(binding)`.
+    pub const fn new(group: u32, binding: u32) -> Self {
+        Self { group, binding }
+    }
+}
+
+impl From<(u32, u32)> for BindingSlot {
+    fn from((group, binding): (u32, u32)) -> Self {
+        Self::new(group, binding)
+    }
+}
+
+/// [`BindingsBuilder`] declares additional textures, samplers and storage buffers bound to a shader, on
+/// top of the built-in inputs. `@group(0)` `@binding(0)` to `@binding(8)` are reserved for the built-in
+/// inputs, all other slots of the groups `0` to `3` can be used.
+///
+/// ```rust,no_run
+/// # use tui_shader::{BindingsBuilder, ShaderCanvasState, WgslShader};
+/// let bindings = BindingsBuilder::new()
+///     .texture((1, 0), 32, 32)
+///     .sampler((1, 1), wgpu::FilterMode::Linear)
+///     .storage_buffer((1, 2), 256);
+/// let mut state = ShaderCanvasState::new_with_bindings(
+///     WgslShader::Path("shader.wgsl"),
+///     None,
+///     bindings,
+/// )
+/// .unwrap();
+/// state.write_buffer((1, 2), &[0; 256]).unwrap();
+/// state.update_texture((1, 0), &[255; 64 * 64 * 4], 64, 64).unwrap();
+/// ```
+///
+/// The resources are declared in the shader like this:
+///
+/// ```wgsl
+/// @group(1) @binding(0) var image: texture_2d<f32>;
+/// @group(1) @binding(1) var image_sampler: sampler;
+/// @group(1) @binding(2) var<storage, read> values: array<f32>;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BindingsBuilder {
+    entries: Vec<(BindingSlot, BindingDescriptor)>,
+}
+
+#[derive(Debug, Clone)]
+enum BindingDescriptor {
+    Texture {
+        size: wgpu::Extent3d,
+        dimension: wgpu::TextureDimension,
+        format: wgpu::TextureFormat,
+        data: Option<Vec<u8>>,
+    },
+    Sampler {
+        filter: wgpu::FilterMode,
+    },
+    StorageBuffer {
+        size: u64,
+        data: Option<Vec<u8>>,
+    },
+}
+
+impl BindingsBuilder {
+    /// Creates a new, empty [`BindingsBuilder`]. Equivalent to [`BindingsBuilder::default()`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares a `texture_2d<f32>` of `width` x `height` RGBA pixels, initially transparent black.
+    #[must_use]
+    pub fn texture(self, slot: impl Into<BindingSlot>, width: u32, height: u32) -> Self {
+        self.texture_entry(slot, TextureKind::Rgba2d, extent(width, height, 1), None)
+    }
+
+    /// Declares a `texture_2d<f32>` of `width` x `height` pixels, initialized with tightly packed RGBA
+    /// bytes.
+    #[must_use]
+    pub fn texture_with_data(
+        self,
+        slot: impl Into<BindingSlot>,
+        width: u32,
+        height: u32,
+        data: &[u8],
+    ) -> Self {
+        self.texture_entry(
+            slot,
+            TextureKind::Rgba2d,
+            extent(width, height, 1),
+            Some(data.to_vec()),
+        )
+    }
+
+    /// Declares a `texture_3d<f32>` of `width` x `height` x `depth` RGBA pixels, initially transparent
+    /// black, e.g. for the density and color of smoke.
+    #[must_use]
+    pub fn texture_3d(
+        self,
+        slot: impl Into<BindingSlot>,
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> Self {
+        self.texture_entry(
+            slot,
+            TextureKind::Rgba3d,
+            extent(width, height, depth),
+            None,
+        )
+    }
+
+    /// Declares a `texture_3d<f32>` of `width` x `height` x `depth` pixels, initialized with tightly
+    /// packed RGBA bytes, slice after slice.
+    #[must_use]
+    pub fn texture_3d_with_data(
+        self,
+        slot: impl Into<BindingSlot>,
+        width: u32,
+        height: u32,
+        depth: u32,
+        data: &[u8],
+    ) -> Self {
+        self.texture_entry(
+            slot,
+            TextureKind::Rgba3d,
+            extent(width, height, depth),
+            Some(data.to_vec()),
+        )
+    }
+
+    /// Declares a `texture_3d<f32>` of `width` x `height` x `depth` scalar values in the red channel,
+    /// initially zero. Volumes hold data like densities or measurements, which don't fit into 8 bits:
+    /// values are stored as 16 bit floats and can be sampled with a linear sampler.
+    #[must_use]
+    pub fn volume(self, slot: impl Into<BindingSlot>, width: u32, height: u32, depth: u32) -> Self {
+        self.texture_entry(
+            slot,
+            TextureKind::Volume,
+            extent(width, height, depth),
+            None,
+        )
+    }
+
+    /// Declares a volume of `width` x `height` x `depth` values initialized with `values`, with `x`
+    /// changing fastest and `z` slowest. See [`BindingsBuilder::volume`].
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{BindingsBuilder, ShaderCanvasState, WgslShader};
+    /// let size = 32;
+    /// let density: Vec<f32> = (0..size * size * size)
+    ///     .map(|i| ((i % size) as f32 / size as f32).powi(2))
+    ///     .collect();
+    /// let bindings = BindingsBuilder::new()
+    ///     .volume_with_data((1, 0), size, size, size, &density)
+    ///     .sampler((1, 1), wgpu::FilterMode::Linear);
+    /// let state = ShaderCanvasState::new_with_bindings(WgslShader::Path("clouds.wgsl"), None, bindings)
+    ///     .unwrap();
+    /// ```
+    ///
+    /// ```wgsl
+    /// @group(1) @binding(0) var density: texture_3d<f32>;
+    /// @group(1) @binding(1) var density_sampler: sampler;
+    ///
+    /// fn sample_density(position: vec3<f32>) -> f32 {
+    ///     return textureSampleLevel(density, density_sampler, position, 0.0).r;
+    /// }
+    /// ```
+    #[must_use]
+    pub fn volume_with_data(
+        self,
+        slot: impl Into<BindingSlot>,
+        width: u32,
+        height: u32,
+        depth: u32,
+        values: &[f32],
+    ) -> Self {
+        self.texture_entry(
+            slot,
+            TextureKind::Volume,
+            extent(width, height, depth),
+            Some(volume_bytes(values)),
+        )
+    }
+
+    /// Declares a filtering `sampler`.
+    #[must_use]
+    pub fn sampler(self, slot: impl Into<BindingSlot>, filter: wgpu::FilterMode) -> Self {
+        self.entry(slot, BindingDescriptor::Sampler { filter })
+    }
+
+    /// Declares a read-only `var<storage, read>` buffer of `size` bytes, initially zeroed.
+    #[must_use]
+    pub fn storage_buffer(self, slot: impl Into<BindingSlot>, size: u64) -> Self {
+        self.entry(slot, BindingDescriptor::StorageBuffer { size, data: None })
+    }
+
+    /// Declares a read-only `var<storage, read>` buffer initialized with `data`.
+    #[must_use]
+    pub fn storage_buffer_with_data(self, slot: impl Into<BindingSlot>, data: &[u8]) -> Self {
+        self.entry(
+            slot,
+            BindingDescriptor::StorageBuffer {
+                size: data.len() as u64,
+                data: Some(data.to_vec()),
+            },
+        )
+    }
+
+    fn texture_entry(
+        self,
+        slot: impl Into<BindingSlot>,
+        kind: TextureKind,
+        size: wgpu::Extent3d,
+        data: Option<Vec<u8>>,
+    ) -> Self {
+        let (dimension, format) = kind.layout();
+        self.entry(
+            slot,
+            BindingDescriptor::Texture {
+                size,
+                dimension,
+                format,
+                data,
+            },
+        )
+    }
+
+    fn entry(mut self, slot: impl Into<BindingSlot>, descriptor: BindingDescriptor) -> Self {
+        self.entries.push((slot.into(), descriptor));
+        self
+    }
+
+    /// Returns `true` if no resources were declared.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Checks that all slots are available and all sizes are valid.
+    pub(crate) fn validate(&self) -> Result<(), Box<dyn Error>> {
+        for (index, (slot, descriptor)) in self.entries.iter().enumerate() {
+            if slot.group >= MAX_BIND_GROUPS {
+                return Err(
+                    format!("{slot:?} exceeds the maximum of {MAX_BIND_GROUPS} groups").into(),
+                );
+            }
+            if slot.group == 0 && slot.binding < RESERVED_BINDINGS {
+                return Err(format!("{slot:?} is reserved for the built-in inputs").into());
+            }
+            if self.entries[..index].iter().any(|(other, _)| other == slot) {
+                return Err(format!("{slot:?} is declared more than once").into());
+            }
+            match descriptor {
+                BindingDescriptor::Texture {
+                    size,
+                    dimension,
+                    format,
+                    data,
+                } => {
+                    let kind = TextureKind::of(*dimension, *format);
+                    if size.width == 0 || size.height == 0 || size.depth_or_array_layers == 0 {
+                        return Err(format!("the {} at {slot:?} is empty", kind.name()).into());
+                    }
+                    if let Some(data) = data {
+                        check_texture_data(*slot, data, *size, kind)?;
+                    }
+                }
+                BindingDescriptor::Sampler { .. } => {}
+                BindingDescriptor::StorageBuffer { size, .. } => {
+                    if *size == 0 || !size.is_multiple_of(4) {
+                        return Err(format!(
+                            "the size of the storage buffer at {slot:?} must be a non-zero multiple of 4, got {size}"
+                        )
+                        .into());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The GPU resources created from a [`BindingsBuilder`].
+#[derive(Debug, Default)]
+pub(crate) struct UserBindings {
+    entries: Vec<(BindingSlot, UserResource)>,
+}
+
+#[derive(Debug)]
+enum UserResource {
+    Texture(wgpu::Texture),
+    Sampler(wgpu::Sampler),
+    StorageBuffer { buffer: wgpu::Buffer, size: u64 },
+}
+
+impl UserBindings {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        builder: &BindingsBuilder,
+    ) -> Self {
+        let entries = builder
+            .entries
+            .iter()
+            .map(|(slot, descriptor)| {
+                let resource = match descriptor {
+                    BindingDescriptor::Texture {
+                        size,
+                        dimension,
+                        format,
+                        data,
+                    } => {
+                        let texture = create_input_texture(device, *size, *dimension, *format);
+                        if let Some(data) = data {
+                            write_texture_data(queue, &texture, data);
+                        }
+                        UserResource::Texture(texture)
+                    }
+                    BindingDescriptor::Sampler { filter } => {
+                        UserResource::Sampler(device.create_sampler(&wgpu::SamplerDescriptor {
+                            mag_filter: *filter,
+                            min_filter: *filter,
+                            ..wgpu::SamplerDescriptor::default()
+                        }))
+                    }
+                    BindingDescriptor::StorageBuffer { size, data } => {
+                        let usage = STORAGE_BUFFER_USAGES;
+                        let buffer = match data {
+                            Some(data) => {
+                                device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                                    label: None,
+                                    contents: data,
+                                    usage,
+                                })
+                            }
+                            None => device.create_buffer(&wgpu::BufferDescriptor {
+                                label: None,
+                                size: *size,
+                                usage,
+                                mapped_at_creation: false,
+                            }),
+                        };
+                        UserResource::StorageBuffer {
+                            buffer,
+                            size: *size,
+                        }
+                    }
+                };
+                (*slot, resource)
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Creates new resources with the same contents, for a clone of a state. Samplers are immutable and
+    /// shared.
+    pub(crate) fn duplicate(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let mut command_encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let entries = self
+            .entries
+            .iter()
+            .map(|(slot, resource)| {
+                let resource = match resource {
+                    UserResource::Texture(texture) => {
+                        let copy = create_input_texture(
+                            device,
+                            texture.size(),
+                            texture.dimension(),
+                            texture.format(),
+                        );
+                        command_encoder.copy_texture_to_texture(
+                            texture.as_image_copy(),
+                            copy.as_image_copy(),
+                            texture.size(),
+                        );
+                        UserResource::Texture(copy)
+                    }
+                    UserResource::Sampler(sampler) => UserResource::Sampler(sampler.clone()),
+                    UserResource::StorageBuffer { buffer, size } => {
+                        let copy = device.create_buffer(&wgpu::BufferDescriptor {
+                            label: None,
+                            size: *size,
+                            usage: STORAGE_BUFFER_USAGES,
+                            mapped_at_creation: false,
+                        });
+                        command_encoder.copy_buffer_to_buffer(buffer, 0, &copy, 0, *size);
+                        UserResource::StorageBuffer {
+                            buffer: copy,
+                            size: *size,
+                        }
+                    }
+                };
+                (*slot, resource)
+            })
+            .collect();
+        queue.submit(Some(command_encoder.finish()));
+        Self { entries }
+    }
+
+    /// Destroys the textures and storage buffers. Samplers are shared between clones and are freed when
+    /// the last one is dropped.
+    /// The bytes of the textures and storage buffers.
+    pub(crate) fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = MemoryUsage::default();
+        for (_, resource) in &self.entries {
+            match resource {
+                UserResource::Texture(texture) => usage.textures += texture_bytes(texture),
+                UserResource::StorageBuffer { buffer, .. } => usage.buffers += buffer.size(),
+                UserResource::Sampler(_) => {}
+            }
+        }
+        usage
+    }
+
+    pub(crate) fn destroy(&self) {
+        for (_, resource) in &self.entries {
+            match resource {
+                UserResource::Texture(texture) => texture.destroy(),
+                UserResource::StorageBuffer { buffer, .. } => buffer.destroy(),
+                UserResource::Sampler(_) => {}
+            }
+        }
+    }
+
+    /// Creates the bind group layouts of all groups used by the shader. The layout of `@group(0)` starts
+    /// with `builtin_entries`.
+    pub(crate) fn create_layouts(
+        &self,
+        device: &wgpu::Device,
+        builtin_entries: &[wgpu::BindGroupLayoutEntry],
+    ) -> Vec<wgpu::BindGroupLayout> {
+        (0..self.group_count())
+            .map(|group| {
+                let mut entries = if group == 0 {
+                    builtin_entries.to_vec()
+                } else {
+                    Vec::new()
+                };
+                for (slot, resource) in self.entries.iter().filter(|(slot, _)| slot.group == group)
+                {
+                    entries.push(resource.layout_entry(slot.binding));
+                }
+                device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &entries,
+                })
+            })
+            .collect()
+    }
+
+    /// Creates the bind groups of all groups used by the shader. `@group(0)` starts with the
+    /// `builtin_buffers`, bound to consecutive bindings starting at `0`.
+    pub(crate) fn create_bind_groups(
+        &self,
+        device: &wgpu::Device,
+        layouts: &[wgpu::BindGroupLayout],
+        builtin_buffers: &[&wgpu::Buffer],
+    ) -> Vec<wgpu::BindGroup> {
+        let views: Vec<Option<wgpu::TextureView>> = self
+            .entries
+            .iter()
+            .map(|(_, resource)| match resource {
+                UserResource::Texture(texture) => {
+                    Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+                }
+                _ => None,
+            })
+            .collect();
+        layouts
+            .iter()
+            .enumerate()
+            .map(|(group, layout)| {
+                let mut entries = Vec::new();
+                if group == 0 {
+                    for (binding, buffer) in builtin_buffers.iter().enumerate() {
+                        entries.push(wgpu::BindGroupEntry {
+                            binding: binding as u32,
+                            resource: buffer.as_entire_binding(),
+                        });
+                    }
+                }
+                for ((slot, resource), view) in self.entries.iter().zip(&views) {
+                    if slot.group != group as u32 {
+                        continue;
+                    }
+                    let resource = match (resource, view) {
+                        (UserResource::Texture(_), Some(view)) => {
+                            wgpu::BindingResource::TextureView(view)
+                        }
+                        (UserResource::Sampler(sampler), _) => {
+                            wgpu::BindingResource::Sampler(sampler)
+                        }
+                        (UserResource::StorageBuffer { buffer, .. }, _) => {
+                            buffer.as_entire_binding()
+                        }
+                        (UserResource::Texture(_), None) => unreachable!(),
+                    };
+                    entries.push(wgpu::BindGroupEntry {
+                        binding: slot.binding,
+                        resource,
+                    });
+                }
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: None,
+                    layout,
+                    entries: &entries,
+                })
+            })
+            .collect()
+    }
+
+    /// Writes `data` to the start of the storage buffer at `slot`.
+    pub(crate) fn write_buffer(
+        &self,
+        queue: &wgpu::Queue,
+        slot: BindingSlot,
+        data: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        match self.get(slot)? {
+            UserResource::StorageBuffer { buffer, size } => {
+                if data.len() as u64 > *size || !data.len().is_multiple_of(4) {
+                    return Err(format!(
+                        "expected a multiple of 4 of at most {size} bytes for the storage buffer at {slot:?}, got {}",
+                        data.len()
+                    )
+                    .into());
+                }
+                queue.write_buffer(buffer, 0, data);
+                Ok(())
+            }
+            resource => {
+                Err(format!("{slot:?} is a {}, not a storage buffer", resource.name()).into())
+            }
+        }
+    }
+
+    /// Uploads tightly packed bytes to the texture at `slot`, which must be of the same `kind`. The
+    /// texture is reused if its size matches and recreated otherwise, in which case `true` is returned
+    /// and the bind groups have to be recreated.
+    pub(crate) fn update_texture(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        slot: BindingSlot,
+        data: &[u8],
+        size: wgpu::Extent3d,
+        kind: TextureKind,
+    ) -> Result<bool, Box<dyn Error>> {
+        if size.width == 0 || size.height == 0 || size.depth_or_array_layers == 0 {
+            return Err(format!("the {} at {slot:?} can't be empty", kind.name()).into());
+        }
+        check_texture_data(slot, data, size, kind)?;
+        let resource = self
+            .entries
+            .iter_mut()
+            .find(|(other, _)| *other == slot)
+            .map(|(_, resource)| resource)
+            .ok_or_else(|| format!("no resource is bound to {slot:?}"))?;
+        let UserResource::Texture(texture) = resource else {
+            return Err(format!("{slot:?} is a {}, not a {}", resource.name(), kind.name()).into());
+        };
+        let (dimension, format) = kind.layout();
+        if texture.dimension() != dimension || texture.format() != format {
+            return Err(format!("{slot:?} is a {}, not a {}", resource.name(), kind.name()).into());
+        }
+        let recreate = texture.size() != size;
+        if recreate {
+            *texture = create_input_texture(device, size, dimension, format);
+        }
+        write_texture_data(queue, texture, data);
+        Ok(recreate)
+    }
+
+    fn get(&self, slot: BindingSlot) -> Result<&UserResource, Box<dyn Error>> {
+        self.entries
+            .iter()
+            .find(|(other, _)| *other == slot)
+            .map(|(_, resource)| resource)
+            .ok_or_else(|| format!("no resource is bound to {slot:?}").into())
+    }
+
+    fn group_count(&self) -> u32 {
+        self.entries
+            .iter()
+            .map(|(slot, _)| slot.group + 1)
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+impl UserResource {
+    fn layout_entry(&self, binding: u32) -> wgpu::BindGroupLayoutEntry {
+        let visibility = wgpu::ShaderStages::FRAGMENT;
+        match self {
+            UserResource::Texture(texture) => wgpu::BindGroupLayoutEntry {
+                binding,
+                visibility,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: match texture.dimension() {
+                        wgpu::TextureDimension::D3 => wgpu::TextureViewDimension::D3,
+                        _ => wgpu::TextureViewDimension::D2,
+                    },
+                    multisampled: false,
+                },
+                count: None,
+            },
+            UserResource::Sampler(_) => wgpu::BindGroupLayoutEntry {
+                binding,
+                visibility,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+            UserResource::StorageBuffer { .. } => storage_layout_entry(binding, visibility, true),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            UserResource::Texture(texture) => {
+                TextureKind::of(texture.dimension(), texture.format()).name()
+            }
+            UserResource::Sampler(_) => "sampler",
+            UserResource::StorageBuffer { .. } => "storage buffer",
+        }
+    }
+}
+
+/// The textures which can be declared with a [`BindingsBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TextureKind {
+    Rgba2d,
+    Rgba3d,
+    Volume,
+}
+
+impl TextureKind {
+    fn of(dimension: wgpu::TextureDimension, format: wgpu::TextureFormat) -> Self {
+        match (dimension, format) {
+            (wgpu::TextureDimension::D3, VOLUME_FORMAT) => Self::Volume,
+            (wgpu::TextureDimension::D3, _) => Self::Rgba3d,
+            _ => Self::Rgba2d,
+        }
+    }
+
+    fn layout(self) -> (wgpu::TextureDimension, wgpu::TextureFormat) {
+        match self {
+            Self::Rgba2d => (wgpu::TextureDimension::D2, TEXTURE_FORMAT),
+            Self::Rgba3d => (wgpu::TextureDimension::D3, TEXTURE_FORMAT),
+            Self::Volume => (wgpu::TextureDimension::D3, VOLUME_FORMAT),
+        }
+    }
+
+    fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::Rgba2d | Self::Rgba3d => 4,
+            Self::Volume => 2,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Rgba2d => "texture",
+            Self::Rgba3d => "3D texture",
+            Self::Volume => "volume",
+        }
+    }
+}
+
+/// The bytes of a volume texture holding `values`.
+pub(crate) fn volume_bytes(values: &[f32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|&value| half::f16::from_f32(value).to_ne_bytes())
+        .collect()
+}
+
+fn check_texture_data(
+    slot: BindingSlot,
+    data: &[u8],
+    size: wgpu::Extent3d,
+    kind: TextureKind,
+) -> Result<(), Box<dyn Error>> {
+    let wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: depth,
+    } = size;
+    let pixels = width as usize * height as usize * depth as usize;
+    if data.len() == pixels * kind.bytes_per_pixel() {
+        return Ok(());
+    }
+    let dimensions = match kind {
+        TextureKind::Rgba2d => format!("{width}x{height}"),
+        _ => format!("{width}x{height}x{depth}"),
+    };
+    let message = match kind {
+        TextureKind::Volume => format!(
+            "expected {pixels} values for the {dimensions} volume at {slot:?}, got {}",
+            data.len() / kind.bytes_per_pixel()
+        ),
+        _ => format!(
+            "expected {} bytes for the {dimensions} {} at {slot:?}, got {}",
+            pixels * kind.bytes_per_pixel(),
+            kind.name(),
+            data.len()
+        ),
+    };
+    Err(message.into())
+}
+
+pub(crate) fn extent(width: u32, height: u32, depth: u32) -> wgpu::Extent3d {
+    wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: depth,
+    }
+}
+
+fn create_input_texture(
+    device: &wgpu::Device,
+    size: wgpu::Extent3d,
+    dimension: wgpu::TextureDimension,
+    format: wgpu::TextureFormat,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
+
+/// Writes tightly packed `data` to the whole `texture`.
+fn write_texture_data(queue: &wgpu::Queue, texture: &wgpu::Texture, data: &[u8]) {
+    let size = texture.size();
+    let bytes_per_pixel = TextureKind::of(texture.dimension(), texture.format()).bytes_per_pixel();
+    queue.write_texture(
+        texture.as_image_copy(),
+        data,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(size.width * bytes_per_pixel as u32),
+            rows_per_image: Some(size.height),
+        },
+        size,
+    );
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use crate::frame::Frame;
+
+/// The lowest factor the render scale is reduced to before renders are skipped.
+const MIN_BUDGET_SCALE: f32 = 0.25;
+
+/// How much the render scale is raised again after a render with headroom.
+const BUDGET_RECOVERY: f32 = 1.25;
+
+/// Degrades the renders of a [`ShaderCanvas`](crate::ShaderCanvas) with a
+/// [`frame_budget`](crate::ShaderCanvas::frame_budget) while they take too long and restores their
+/// quality once they fit again.
+///
+/// Renders over the budget halve the render scale, down to [`MIN_BUDGET_SCALE`]. Renders which are still
+/// too slow at that scale cause the next render to reuse the last frame instead of running the shader.
+/// Renders taking less than half of the budget raise the scale step by step.
+#[derive(Debug, Clone)]
+pub(crate) struct FrameBudget {
+    /// The factor the render scale of the canvas is multiplied with.
+    scale: f32,
+    /// Whether the next render reuses the last frame.
+    skip: bool,
+    /// Whether renders alternate with reused frames, since the last render was too slow at the lowest
+    /// scale.
+    skipping: bool,
+    /// The last two rendered frames and the time they were rendered at, oldest first. Only kept by
+    /// canvases which [`interpolate`](crate::ShaderCanvas::interpolate).
+    frames: Vec<(f32, Frame)>,
+}
+
+impl Default for FrameBudget {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            skip: false,
+            skipping: false,
+            frames: Vec::new(),
+        }
+    }
+}
+
+impl FrameBudget {
+    pub(crate) fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub(crate) fn is_skipping(&self) -> bool {
+        self.skipping
+    }
+
+    /// Whether the next render should reuse the last frame. Clears the flag, so the render after it runs
+    /// the shader again.
+    pub(crate) fn take_skip(&mut self) -> bool {
+        std::mem::take(&mut self.skip)
+    }
+
+    /// Adjusts the quality of the next render after one took `elapsed` of `budget`.
+    pub(crate) fn record(&mut self, elapsed: Duration, budget: Duration) {
+        if elapsed > budget {
+            if self.scale > MIN_BUDGET_SCALE {
+                self.scale = (self.scale * 0.5).max(MIN_BUDGET_SCALE);
+            } else {
+                self.skip = true;
+                self.skipping = true;
+            }
+            return;
+        }
+        self.skipping = false;
+        if elapsed < budget / 2 {
+            self.scale = (self.scale * BUDGET_RECOVERY).min(1.0);
+        }
+    }
+
+    /// Keeps `frame`, rendered at `time`, and the one rendered before it.
+    pub(crate) fn push_frame(&mut self, time: f32, frame: Frame) {
+        if self.frames.len() == 2 {
+            self.frames.remove(0);
+        }
+        self.frames.push((time, frame));
+    }
+
+    /// Blends the last two rendered frames by how far `time` has advanced past the last one, relative to
+    /// the time between them. The result lags one render behind, but moves smoothly between renders.
+    /// `None` if there aren't two frames of the same size.
+    pub(crate) fn interpolate(&self, time: f32) -> Option<Frame> {
+        let [(previous_time, previous), (last_time, last)] = self.frames.as_slice() else {
+            return None;
+        };
+        let span = last_time - previous_time;
+        let t = if span > 0.0 {
+            ((time - last_time) / span).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        previous.lerp(last, t)
+    }
+}
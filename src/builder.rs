@@ -0,0 +1,237 @@
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "gpu")]
+use crate::bindings::BindingsBuilder;
+use crate::clock::ShaderClock;
+use crate::color::ColorConfig;
+use crate::cpu::CpuBackend;
+#[cfg(feature = "gpu")]
+use crate::gpu::{AdapterSelection, DEFAULT_FRAGMENT_SHADER_DESCRIPTOR, DeviceOptions};
+use crate::lut::CubeLut;
+#[cfg(feature = "gpu")]
+use crate::policy::ShaderPolicy;
+use crate::state::ShaderCanvasState;
+
+/// [`ShaderCanvasStateBuilder`] configures every part of a [`ShaderCanvasState`] in one place: the shader
+/// and its entry point, additional bindings, the clock, multisampling or a [`CpuBackend`] replacing the
+/// shader. Created with [`ShaderCanvasState::builder`]. Without a shader or backend, the state runs the
+/// same default shader as [`ShaderCanvasState::default`].
+///
+/// The way pixels are mapped to cells is configured on the [`ShaderCanvas`](crate::ShaderCanvas) widget
+/// instead, see [`ShaderCanvas::character_rule`](crate::ShaderCanvas::character_rule).
+///
+/// ```rust,no_run
+/// # use tui_shader::{BindingsBuilder, ShaderCanvasState, ShaderClock, WgslShader};
+/// let mut clock = ShaderClock::new();
+/// clock.pause();
+/// let state = ShaderCanvasState::builder()
+///     .shader(WgslShader::Path("shader.wgsl"))
+///     .entry_point("main")
+///     .bindings(BindingsBuilder::new().storage_buffer((1, 0), 64))
+///     .clock(clock)
+///     .msaa(true)
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// Without the `gpu` feature only the backend and the clock can be configured, and a state without a
+/// backend runs the default shader on the CPU.
+#[derive(Default)]
+pub struct ShaderCanvasStateBuilder<'a> {
+    #[cfg(feature = "gpu")]
+    shader: Option<Result<wgpu::ShaderModuleDescriptor<'a>, Box<dyn Error>>>,
+    #[cfg(feature = "gpu")]
+    entry_point: Option<&'a str>,
+    #[cfg(feature = "gpu")]
+    bindings: BindingsBuilder,
+    backend: Option<Arc<Mutex<dyn CpuBackend>>>,
+    clock: Option<ShaderClock>,
+    color_config: ColorConfig,
+    color_grade: Option<CubeLut>,
+    #[cfg(feature = "gpu")]
+    msaa: bool,
+    #[cfg(feature = "gpu")]
+    device: DeviceOptions,
+    #[cfg(feature = "gpu")]
+    policy: Option<ShaderPolicy>,
+    #[cfg(not(feature = "gpu"))]
+    _shader: std::marker::PhantomData<&'a str>,
+}
+
+impl<'a> ShaderCanvasStateBuilder<'a> {
+    /// Creates a new [`ShaderCanvasStateBuilder`]. Equivalent to [`ShaderCanvasStateBuilder::default()`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the fragment shader. Errors converting `shader`, e.g. a [`WgslShader::Path`](crate::WgslShader)
+    /// that can't be read, are returned by [`ShaderCanvasStateBuilder::build`].
+    #[cfg(feature = "gpu")]
+    #[must_use]
+    pub fn shader<S>(mut self, shader: S) -> Self
+    where
+        S: TryInto<wgpu::ShaderModuleDescriptor<'a>>,
+        S::Error: Into<Box<dyn Error>>,
+    {
+        self.shader = Some(shader.try_into().map_err(Into::into));
+        self
+    }
+
+    /// Sets the name of the `@fragment` function, which is necessary if the shader defines more than one.
+    #[cfg(feature = "gpu")]
+    #[must_use]
+    pub fn entry_point(mut self, entry_point: &'a str) -> Self {
+        self.entry_point = Some(entry_point);
+        self
+    }
+
+    /// Declares additional textures, samplers and storage buffers bound to the shader.
+    #[cfg(feature = "gpu")]
+    #[must_use]
+    pub fn bindings(mut self, bindings: BindingsBuilder) -> Self {
+        self.bindings = bindings;
+        self
+    }
+
+    /// Computes the pixels with a [`CpuBackend`] instead of a shader. Can't be combined with a shader,
+    /// an entry point or bindings.
+    #[must_use]
+    pub fn backend(mut self, backend: impl CpuBackend + 'static) -> Self {
+        self.backend = Some(Arc::new(Mutex::new(backend)));
+        self
+    }
+
+    /// Sets the [`ShaderClock`] providing the time to the shader.
+    #[must_use]
+    pub fn clock(mut self, clock: ShaderClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Sets the [`ColorConfig`] converting the colors of the shader, see
+    /// [`ShaderCanvasState::set_color_config`].
+    #[must_use]
+    pub fn color_config(mut self, color_config: ColorConfig) -> Self {
+        self.color_config = color_config;
+        self
+    }
+
+    /// Grades the colors of the shader with a 3D lookup table, see
+    /// [`ShaderCanvasState::set_color_grade`].
+    #[must_use]
+    pub fn color_grade(mut self, lut: CubeLut) -> Self {
+        self.color_grade = Some(lut);
+        self
+    }
+
+    /// Enables 4x multisampling, see [`ShaderCanvasState::set_msaa`].
+    #[cfg(feature = "gpu")]
+    #[must_use]
+    pub fn msaa(mut self, enabled: bool) -> Self {
+        self.msaa = enabled;
+        self
+    }
+
+    /// Selects the GPU the shader runs on, see [`gpu::enumerate_adapters`](crate::gpu::enumerate_adapters).
+    /// Ignored with a [`CpuBackend`].
+    #[cfg(feature = "gpu")]
+    #[must_use]
+    pub fn adapter(mut self, adapter: AdapterSelection) -> Self {
+        self.device.adapter = adapter;
+        self
+    }
+
+    /// Configures how the GPU device is requested: the adapter, a timeout, falling back to other
+    /// backends and moving to another device when it is lost. Ignored with a [`CpuBackend`].
+    #[cfg(feature = "gpu")]
+    #[must_use]
+    pub fn device(mut self, device: DeviceOptions) -> Self {
+        self.device = device;
+        self
+    }
+
+    /// Checks the shader against a [`ShaderPolicy`] before compiling it, e.g.
+    /// [`ShaderPolicy::untrusted`] for shaders supplied by users. The shader has to be WGSL. Ignored with
+    /// a [`CpuBackend`].
+    #[cfg(feature = "gpu")]
+    #[must_use]
+    pub fn policy(mut self, policy: ShaderPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Creates the [`ShaderCanvasState`]. Fails if the shader or the bindings are invalid, if the shader
+    /// violates the [`ShaderPolicy`], or if a [`CpuBackend`] is combined with options that only apply to
+    /// shaders.
+    #[cfg(feature = "gpu")]
+    pub fn build(self) -> Result<ShaderCanvasState, Box<dyn Error>> {
+        let mut state = match self.backend {
+            Some(backend) => {
+                if self.shader.is_some() || self.entry_point.is_some() {
+                    return Err("a state can't have both a shader and a cpu backend".into());
+                }
+                if !self.bindings.is_empty() {
+                    return Err("states with a cpu backend have no bindings".into());
+                }
+                ShaderCanvasState::from_shared_backend(backend)
+            }
+            None => {
+                let shader = self
+                    .shader
+                    .unwrap_or(Ok(DEFAULT_FRAGMENT_SHADER_DESCRIPTOR))?;
+                if let Some(policy) = &self.policy {
+                    let wgpu::ShaderSource::Wgsl(source) = &shader.source else {
+                        return Err("only WGSL shaders can be checked against a policy".into());
+                    };
+                    ShaderCanvasState::check_with_policy(source, policy)?;
+                }
+                ShaderCanvasState::new_on_device(
+                    shader,
+                    self.entry_point,
+                    self.bindings,
+                    &self.device,
+                )?
+            }
+        };
+        if let Some(clock) = self.clock {
+            *state.clock_mut() = clock;
+        }
+        state.set_color_config(self.color_config);
+        state.set_color_grade(self.color_grade);
+        state.set_msaa(self.msaa);
+        Ok(state)
+    }
+
+    /// Creates the [`ShaderCanvasState`]. Never fails without the `gpu` feature.
+    #[cfg(not(feature = "gpu"))]
+    pub fn build(self) -> Result<ShaderCanvasState, Box<dyn Error>> {
+        let mut state = match self.backend {
+            Some(backend) => ShaderCanvasState::from_shared_backend(backend),
+            None => ShaderCanvasState::default(),
+        };
+        if let Some(clock) = self.clock {
+            *state.clock_mut() = clock;
+        }
+        state.set_color_config(self.color_config);
+        state.set_color_grade(self.color_grade);
+        Ok(state)
+    }
+}
+
+impl std::fmt::Debug for ShaderCanvasStateBuilder<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("ShaderCanvasStateBuilder");
+        #[cfg(feature = "gpu")]
+        f.field("shader", &self.shader)
+            .field("entry_point", &self.entry_point)
+            .field("bindings", &self.bindings)
+            .field("msaa", &self.msaa)
+            .field("device", &self.device)
+            .field("policy", &self.policy);
+        f.field("clock", &self.clock)
+            .field("color_config", &self.color_config)
+            .field("color_grade", &self.color_grade)
+            .finish_non_exhaustive()
+    }
+}
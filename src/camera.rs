@@ -0,0 +1,323 @@
+use crate::interaction::PointerEvent;
+use crate::state::Uniforms;
+
+/// The closest the eye gets to pointing along [`Camera::up`] when orbiting, in radians. Looking straight
+/// up or down would flip the camera.
+const MIN_POLAR_ANGLE: f32 = 0.01;
+
+#[cfg(feature = "gpu")]
+pub(crate) const IDENTITY: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// A perspective camera looking from [`eye`](Camera::eye) at [`target`](Camera::target), shared by
+/// [`SceneState`](crate::SceneState) and fragment shaders which ray march their own scenes. The aspect
+/// ratio is taken from the rendered area in screen pixels, so scenes aren't stretched by the tall cells
+/// of the terminal.
+///
+/// Set it on a [`ShaderCanvasState`](crate::ShaderCanvasState) with
+/// [`ShaderCanvasState::set_camera`](crate::ShaderCanvasState::set_camera) and it is written to the user
+/// defined uniforms of the same name before every execution. Uniforms the shader doesn't declare are
+/// ignored.
+///
+/// - `camera_eye: vec3<f32>`: the position of the camera.
+/// - `camera_target: vec3<f32>`: the point the camera looks at.
+/// - `camera_forward: vec3<f32>`, `camera_right: vec3<f32>`, `camera_up: vec3<f32>`: the normalized
+///   axes of the camera in world space.
+/// - `camera_fov: f32`: the vertical field of view in radians.
+/// - `camera_view_projection: mat4x4<f32>`: see [`Camera::view_projection`].
+///
+/// ```wgsl
+/// #include <camera>
+///
+/// struct Uniforms {
+///     camera_view_projection: mat4x4<f32>,
+///     camera_eye: vec3<f32>,
+///     camera_fov: f32,
+///     camera_forward: vec3<f32>,
+///     camera_right: vec3<f32>,
+///     camera_up: vec3<f32>,
+/// }
+///
+/// @group(0) @binding(5) var<uniform> cell: vec4<f32>;
+/// @group(0) @binding(2) var<uniform> uniforms: Uniforms;
+///
+/// @fragment
+/// fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+///     let direction = camera_ray(
+///         uv,
+///         cell.z / cell.w,
+///         uniforms.camera_fov,
+///         uniforms.camera_forward,
+///         uniforms.camera_right,
+///         uniforms.camera_up,
+///     );
+///     var distance = 0.0;
+///     for (var i = 0; i < 64; i++) {
+///         let sphere = length(uniforms.camera_eye + direction * distance) - 1.0;
+///         distance += sphere;
+///     }
+///     return vec4<f32>(vec3<f32>(step(distance, 10.0)), 1.0);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    /// The position of the camera.
+    pub eye: [f32; 3],
+
+    /// The point the camera looks at.
+    pub target: [f32; 3],
+
+    /// The direction pointing up on the screen.
+    pub up: [f32; 3],
+
+    /// The vertical field of view in radians.
+    pub fov_y: f32,
+
+    /// The distance of the near clipping plane.
+    pub near: f32,
+
+    /// The distance of the far clipping plane.
+    pub far: f32,
+}
+
+impl Default for Camera {
+    /// A camera at `(0.0, 0.0, 3.0)` looking at the origin, with a vertical field of view of 60 degrees.
+    fn default() -> Self {
+        Self {
+            eye: [0.0, 0.0, 3.0],
+            target: [0.0; 3],
+            up: [0.0, 1.0, 0.0],
+            fov_y: 60f32.to_radians(),
+            near: 0.1,
+            far: 100.0,
+        }
+    }
+}
+
+impl Camera {
+    /// Creates a [`Camera`] at `eye` looking at `target`, with the other settings of
+    /// [`Camera::default`].
+    pub fn new(eye: [f32; 3], target: [f32; 3]) -> Self {
+        Self {
+            eye,
+            target,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the vertical field of view in radians.
+    #[must_use]
+    pub fn fov_y(mut self, fov_y: f32) -> Self {
+        self.fov_y = fov_y;
+        self
+    }
+
+    /// The normalized direction the camera looks in.
+    pub fn forward(&self) -> [f32; 3] {
+        normalize(sub(self.target, self.eye))
+    }
+
+    /// The normalized direction pointing right on the screen.
+    pub fn right(&self) -> [f32; 3] {
+        normalize(cross(self.forward(), self.up))
+    }
+
+    /// The normalized direction pointing up on the screen, perpendicular to [`Camera::forward`] unlike
+    /// [`Camera::up`].
+    pub fn screen_up(&self) -> [f32; 3] {
+        cross(self.right(), self.forward())
+    }
+
+    /// The distance between the eye and the target.
+    pub fn distance(&self) -> f32 {
+        length(sub(self.eye, self.target))
+    }
+
+    /// Moves the eye around the target, keeping its distance. `yaw` turns it around [`Camera::up`] in
+    /// radians, positive values moving it to the right. `pitch` raises it towards `up` in radians, stopping
+    /// just short of looking straight down or up.
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        let up = normalize(self.up);
+        let offset = rotate(sub(self.eye, self.target), up, yaw);
+        let distance = length(offset);
+        if distance == 0.0 {
+            return;
+        }
+        let polar = (dot(offset, up) / distance).clamp(-1.0, 1.0).acos();
+        let raised = (polar - pitch).clamp(MIN_POLAR_ANGLE, std::f32::consts::PI - MIN_POLAR_ANGLE);
+        let axis = normalize(cross(offset, up));
+        let offset = if axis == [0.0; 3] {
+            offset
+        } else {
+            rotate(offset, axis, polar - raised)
+        };
+        self.eye = add(self.target, offset);
+    }
+
+    /// Multiplies the distance between the eye and the target by `factor`, moving the eye closer for
+    /// factors below `1.0`. The eye stays beyond the near clipping plane.
+    pub fn zoom(&mut self, factor: f32) {
+        let offset = sub(self.eye, self.target);
+        let distance = length(offset);
+        if distance == 0.0 || factor <= 0.0 {
+            return;
+        }
+        let scale = (distance * factor).max(self.near) / distance;
+        self.eye = add(self.target, offset.map(|component| component * scale));
+    }
+
+    /// The matrix transforming world space into view space, in columns like the `mat4x4<f32>` of WGSL.
+    pub fn view(&self) -> [[f32; 4]; 4] {
+        let forward = self.forward();
+        let side = self.right();
+        let up = cross(side, forward);
+        [
+            [side[0], up[0], -forward[0], 0.0],
+            [side[1], up[1], -forward[1], 0.0],
+            [side[2], up[2], -forward[2], 0.0],
+            [
+                -dot(side, self.eye),
+                -dot(up, self.eye),
+                dot(forward, self.eye),
+                1.0,
+            ],
+        ]
+    }
+
+    /// The matrix transforming view space into clip space for an area with the given aspect ratio, with
+    /// depths from `0.0` at the near to `1.0` at the far clipping plane.
+    pub fn projection(&self, aspect: f32) -> [[f32; 4]; 4] {
+        let focal = 1.0 / (self.fov_y / 2.0).tan();
+        let depth = self.far / (self.near - self.far);
+        [
+            [focal / aspect, 0.0, 0.0, 0.0],
+            [0.0, focal, 0.0, 0.0],
+            [0.0, 0.0, depth, -1.0],
+            [0.0, 0.0, depth * self.near, 0.0],
+        ]
+    }
+
+    /// The matrix transforming world space into clip space for an area with the given aspect ratio, in
+    /// columns like the `mat4x4<f32>` of WGSL.
+    pub fn view_projection(&self, aspect: f32) -> [[f32; 4]; 4] {
+        multiply(self.projection(aspect), self.view())
+    }
+
+    /// Writes the camera to the uniforms, for an area with the given aspect ratio.
+    pub(crate) fn apply(&self, uniforms: &mut Uniforms<'_>, aspect: f32) {
+        uniforms
+            .set("camera_eye", self.eye)
+            .set("camera_target", self.target)
+            .set("camera_forward", self.forward())
+            .set("camera_right", self.right())
+            .set("camera_up", self.screen_up())
+            .set("camera_fov", self.fov_y)
+            .set("camera_view_projection", self.view_projection(aspect));
+    }
+}
+
+/// [`OrbitControls`] turn [`PointerEvent`]s into movements of a [`Camera`]: dragging orbits the eye around
+/// the target, scrolling zooms in and out. States with a camera, like
+/// [`SceneState`](crate::SceneState), apply them in their `handle_pointer` methods.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitControls {
+    /// The radians the camera orbits per cell dragged.
+    pub rotate_speed: f32,
+
+    /// The fraction of the distance to the target the camera moves closer per line scrolled up.
+    pub zoom_speed: f32,
+
+    /// The position of the pointer while a button is held.
+    pointer: Option<(u16, u16)>,
+}
+
+impl Default for OrbitControls {
+    fn default() -> Self {
+        Self {
+            rotate_speed: 0.05,
+            zoom_speed: 0.1,
+            pointer: None,
+        }
+    }
+}
+
+impl OrbitControls {
+    /// Creates [`OrbitControls`] with the given speeds.
+    pub fn new(rotate_speed: f32, zoom_speed: f32) -> Self {
+        Self {
+            rotate_speed,
+            zoom_speed,
+            pointer: None,
+        }
+    }
+
+    /// Moves `camera` according to `event`. Dragging right turns the scene to the right and dragging up
+    /// tilts it upwards, like grabbing it.
+    pub fn handle(&mut self, camera: &mut Camera, event: PointerEvent) {
+        match event {
+            PointerEvent::Press { x, y } => self.pointer = Some((x, y)),
+            PointerEvent::Drag { x, y } => {
+                if let Some((previous_x, previous_y)) = self.pointer {
+                    let dx = f32::from(x) - f32::from(previous_x);
+                    let dy = f32::from(y) - f32::from(previous_y);
+                    camera.orbit(-dx * self.rotate_speed, dy * self.rotate_speed);
+                }
+                self.pointer = Some((x, y));
+            }
+            PointerEvent::Release => self.pointer = None,
+            PointerEvent::Scroll(lines) => camera.zoom((1.0 - self.zoom_speed).powf(lines)),
+        }
+    }
+}
+
+/// The product `a * b` of two matrices given in columns.
+pub(crate) fn multiply(a: [[f32; 4]; 4], b: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
+    std::array::from_fn(|column| {
+        std::array::from_fn(|row| (0..4).map(|k| a[k][row] * b[column][k]).sum())
+    })
+}
+
+/// Rotates `v` by `angle` radians around the normalized `axis`, counterclockwise when looking against
+/// the axis.
+fn rotate(v: [f32; 3], axis: [f32; 3], angle: f32) -> [f32; 3] {
+    let (sin, cos) = angle.sin_cos();
+    let across = cross(axis, v);
+    let along = dot(axis, v) * (1.0 - cos);
+    std::array::from_fn(|i| v[i] * cos + across[i] * sin + axis[i] * along)
+}
+
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub(crate) fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub(crate) fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn length(v: [f32; 3]) -> f32 {
+    dot(v, v).sqrt()
+}
+
+pub(crate) fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let length = length(v);
+    if length == 0.0 {
+        return v;
+    }
+    v.map(|component| component / length)
+}
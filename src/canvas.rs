@@ -1,12 +1,17 @@
+use std::time::{Duration, Instant};
+
 use ratatui_core::buffer::Buffer;
 use ratatui_core::layout::{Position, Rect};
 use ratatui_core::style::{Color, Style};
 use ratatui_core::widgets::StatefulWidget;
 
+use crate::Sample;
+use crate::ansi::{AnsiColors, buffer_to_ansi};
 use crate::context::ShaderContext;
+use crate::frame::Frame;
+use crate::power::{PowerProfile, frame_hash};
 use crate::state::ShaderCanvasState;
 use crate::style::{CharacterRule, StyleRule};
-use crate::{Sample, row_padding};
 
 /// [`ShaderCanvas`] implements the [`StatefulWidget`] trait from Ratatui.
 /// It holds the logic for applying the result of GPU computation to the [`Buffer`] struct which
@@ -23,10 +28,52 @@ use crate::{Sample, row_padding};
 /// }).unwrap();
 /// ratatui::restore();
 /// ```
+///
+/// With the `widget-ref` feature, [`ShaderCanvas`] and the other widgets of this crate also implement
+/// the by-reference widget traits of Ratatui, `StatefulWidgetRef` and `WidgetRef`, which are unstable
+/// and enabled by Ratatui's `unstable-widget-ref` feature.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct ShaderCanvas {
     pub character_rule: CharacterRule,
     pub style_rule: StyleRule,
+
+    /// The resolution of the rendered texture relative to the cell grid, `1.0` by default. Values below
+    /// `1.0` render fewer pixels than cells, which is cheaper but blurry, values above `1.0` supersample
+    /// every cell for antialiasing.
+    pub render_scale: f32,
+
+    /// The damaged part of the area, in the coordinates of the [`Buffer`]. If set, only the cells within
+    /// the region are rendered and styled again, the others are copied from the previous render.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub region: Option<Rect>,
+
+    /// The scroll offset of the content in front of the canvas in cells, see
+    /// [`ShaderCanvas::scroll_offset`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub scroll_offset: (f32, f32),
+
+    /// How fast the shader moves relative to the scrolled content, `1.0` by default.
+    pub parallax: f32,
+
+    /// The id of the rendered instance, see [`ShaderCanvas::instance`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub instance: u32,
+
+    /// The time a render may take before the quality is reduced, see [`ShaderCanvas::frame_budget`].
+    pub frame_budget: Option<Duration>,
+
+    /// Whether frames reused to stay within the frame budget blend between the last two renders, see
+    /// [`ShaderCanvas::interpolate`].
+    pub interpolate: bool,
+
+    /// Limits the frame rate and render scale of always-on canvases, see [`PowerProfile`].
+    pub power_profile: PowerProfile,
+
+    /// Whether the canvas shows why the last shader failed to load instead of the running shader, see
+    /// [`ShaderCanvas::show_compile_errors`].
+    pub show_compile_errors: bool,
 }
 
 impl ShaderCanvas {
@@ -35,6 +82,15 @@ impl ShaderCanvas {
         Self {
             character_rule: CharacterRule::default(),
             style_rule: StyleRule::default(),
+            render_scale: 1.0,
+            region: None,
+            scroll_offset: (0.0, 0.0),
+            parallax: 1.0,
+            instance: 0,
+            frame_budget: None,
+            interpolate: false,
+            power_profile: PowerProfile::Performance,
+            show_compile_errors: false,
         }
     }
 
@@ -51,6 +107,185 @@ impl ShaderCanvas {
         self.style_rule = style_rule;
         self
     }
+
+    /// Sets the resolution of the rendered texture relative to the cell grid. A scale of `0.5` renders
+    /// one pixel for every two cells in each direction and interpolates between them, a scale of `2.0`
+    /// renders four pixels per cell and averages them. Non-positive and non-finite scales are treated as
+    /// `1.0`.
+    #[must_use]
+    pub fn render_scale(mut self, render_scale: f32) -> Self {
+        self.render_scale = render_scale;
+        self
+    }
+
+    /// Only renders and styles the cells within `region`, e.g. the part of a huge canvas around an
+    /// animated cursor glow. The other cells are copied from the previous render into the same area. The
+    /// first render, and every render after the area was resized, falls back to rendering every cell.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{ShaderCanvas, ShaderCanvasState};
+    /// # use ratatui::layout::Rect;
+    /// let mut terminal = ratatui::init();
+    /// let mut state = ShaderCanvasState::default();
+    /// let cursor = Rect::new(10, 4, 6, 3);
+    /// terminal.draw(|frame| {
+    ///     frame.render_stateful_widget(ShaderCanvas::new().region(cursor), frame.area(), &mut state);
+    /// }).unwrap();
+    /// ratatui::restore();
+    /// ```
+    #[must_use]
+    pub fn region(mut self, region: Rect) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Sets the scroll offset of the list or editor in front of the canvas, in columns and rows, so that
+    /// the background moves along with the content instead of staying in place. `uv` is shifted by the
+    /// offset and the offset itself is passed to the shader as a `vec4<f32>` bound to
+    /// `@group(0) @binding(7)`: x and y are the offset in cells, z and w the resulting shift of `uv`.
+    ///
+    /// ```rust,no_run
+    /// # use ratatui::widgets::{List, ListState};
+    /// # use tui_shader::{ShaderCanvas, ShaderCanvasState};
+    /// let mut terminal = ratatui::init();
+    /// let mut state = ShaderCanvasState::default();
+    /// let mut list_state = ListState::default();
+    /// terminal.draw(|frame| {
+    ///     let canvas = ShaderCanvas::new().scroll_offset(0.0, list_state.offset() as f32);
+    ///     frame.render_stateful_widget(canvas, frame.area(), &mut state);
+    ///     frame.render_stateful_widget(List::new(["a", "b", "c"]), frame.area(), &mut list_state);
+    /// }).unwrap();
+    /// ratatui::restore();
+    /// ```
+    #[must_use]
+    pub fn scroll_offset(mut self, x: f32, y: f32) -> Self {
+        self.scroll_offset = (x, y);
+        self
+    }
+
+    /// Sets the id of the rendered instance, so that one state can style many repeated widgets like list
+    /// rows, tabs or cards and still vary per item, e.g. with phase offsets or alternating hues. It is
+    /// passed to the shader as a `vec4<u32>` bound to `@group(0) @binding(8)`, with the id in x, and to
+    /// [`CpuBackend`](crate::CpuBackend)s as [`PixelInput::instance`](crate::PixelInput::instance).
+    ///
+    /// ```rust,no_run
+    /// # use ratatui::layout::{Constraint, Layout};
+    /// # use tui_shader::{ShaderCanvas, ShaderCanvasState};
+    /// let mut terminal = ratatui::init();
+    /// let mut state = ShaderCanvasState::default();
+    /// terminal.draw(|frame| {
+    ///     let rows = Layout::vertical([Constraint::Length(1); 5]).split(frame.area());
+    ///     for (id, row) in rows.iter().enumerate() {
+    ///         frame.render_stateful_widget(ShaderCanvas::new().instance(id as u32), *row, &mut state);
+    ///     }
+    /// }).unwrap();
+    /// ratatui::restore();
+    /// ```
+    ///
+    /// ```wgsl
+    /// @group(0) @binding(0) var<uniform> time: vec4<f32>;
+    /// @group(0) @binding(8) var<uniform> instance: vec4<u32>;
+    ///
+    /// @fragment
+    /// fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    ///     let phase = f32(instance.x) * 0.7;
+    ///     let wave = 0.5 + 0.5 * sin(uv.x * 6.0 + time.x * 2.0 + phase);
+    ///     return vec4<f32>(wave, 0.3, 1.0 - wave, 1.0);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn instance(mut self, id: u32) -> Self {
+        self.instance = id;
+        self
+    }
+
+    /// Scales the scroll offset before it is passed to the shader. Factors below `1.0` make the background
+    /// lag behind the content for a parallax effect, `0.0` keeps it in place.
+    #[must_use]
+    pub fn parallax(mut self, parallax: f32) -> Self {
+        self.parallax = parallax;
+        self
+    }
+
+    /// Keeps heavy shaders from tanking the input latency on weak GPUs. While renders take longer than
+    /// `budget`, the render scale is halved down to a quarter, and if that's still too slow every other
+    /// render reuses the last frame instead of running the shader. Once renders fit the budget again with
+    /// headroom, the quality is restored step by step. The current reduction is
+    /// [`ShaderCanvasState::budget_scale`].
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tui_shader::{ShaderCanvas, ShaderCanvasState};
+    /// let mut terminal = ratatui::init();
+    /// let mut state = ShaderCanvasState::default();
+    /// let canvas = ShaderCanvas::new().frame_budget(Duration::from_millis(8));
+    /// terminal.draw(|frame| {
+    ///     frame.render_stateful_widget(&canvas, frame.area(), &mut state);
+    /// }).unwrap();
+    /// ratatui::restore();
+    /// ```
+    #[must_use]
+    pub fn frame_budget(mut self, budget: Duration) -> Self {
+        self.frame_budget = Some(budget);
+        self
+    }
+
+    /// Blends the colors of the last two rendered frames on the CPU instead of showing the last frame
+    /// again when the [`frame_budget`](ShaderCanvas::frame_budget) skips a render, so low shader frame
+    /// rates still look smooth at the refresh rate of the terminal. While renders are skipped, the shown
+    /// frames lag one render behind. Off by default.
+    #[must_use]
+    pub fn interpolate(mut self, interpolate: bool) -> Self {
+        self.interpolate = interpolate;
+        self
+    }
+
+    /// Sets the [`PowerProfile`] of the canvas, e.g. [`PowerProfile::LowPower`] for a background which
+    /// runs all day. Pointer input passed to [`ShaderCanvasState::handle_pointer`] makes the next render
+    /// run the shader right away.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{PowerProfile, ShaderCanvas, ShaderCanvasState};
+    /// let mut terminal = ratatui::init();
+    /// let mut state = ShaderCanvasState::default();
+    /// let canvas = ShaderCanvas::new().power_profile(PowerProfile::LowPower);
+    /// loop {
+    ///     terminal.draw(|frame| {
+    ///         frame.render_stateful_widget(&canvas, frame.area(), &mut state);
+    ///     }).unwrap();
+    ///     std::thread::sleep(std::time::Duration::from_millis(100));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn power_profile(mut self, power_profile: PowerProfile) -> Self {
+        self.power_profile = power_profile;
+        self
+    }
+
+    /// Renders the diagnostics of the last shader which failed to load into the area of the canvas, with
+    /// the offending line highlighted, instead of the running shader. Shader editors and apps which
+    /// reload shaders while they are edited don't need their own error view then. The diagnostics are
+    /// recorded by `ShaderCanvasState::load_candidate` and cleared once a shader loads again. Off by
+    /// default.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{ShaderCanvas, ShaderCanvasState, WgslShader};
+    /// let mut terminal = ratatui::init();
+    /// let mut state = ShaderCanvasState::default();
+    /// if state.load_candidate(WgslShader::Path("shader.wgsl"), None).is_ok() {
+    ///     state.promote_candidate();
+    /// }
+    /// terminal.draw(|frame| {
+    ///     let canvas = ShaderCanvas::new().show_compile_errors(true);
+    ///     frame.render_stateful_widget(canvas, frame.area(), &mut state);
+    /// }).unwrap();
+    /// ratatui::restore();
+    /// ```
+    #[must_use]
+    pub fn show_compile_errors(mut self, show: bool) -> Self {
+        self.show_compile_errors = show;
+        self
+    }
 }
 
 impl Default for ShaderCanvas {
@@ -69,34 +304,394 @@ impl StatefulWidget for ShaderCanvas {
 impl StatefulWidget for &ShaderCanvas {
     type State = ShaderCanvasState;
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        if self.render_compile_error(area, buf, state) {
+            return;
+        }
+        let now = Instant::now();
+        if state.idle_mut().should_skip(now)
+            && state.last_area() == Some(area)
+            && let Some(frame) = state.frame()
+        {
+            let frame = frame.clone();
+            state.record_skipped_frame();
+            self.style_frame(&frame, area, buf);
+            return;
+        }
+        state.idle_mut().record_render(now);
+        let time = state.clock().elapsed().as_secs_f32();
+        self.render_at(area, buf, state, time);
+    }
+}
+
+#[cfg(feature = "widget-ref")]
+impl ratatui::widgets::StatefulWidgetRef for ShaderCanvas {
+    type State = ShaderCanvasState;
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(self, area, buf, state);
+    }
+}
+
+impl ShaderCanvas {
+    /// Renders the [`ShaderCanvas`] into an offscreen [`Buffer`] of `width` x `height` cells and returns it
+    /// as a standalone block of text with 24-bit ANSI colors, e.g. for MOTDs or piping to other programs.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{ShaderCanvas, ShaderCanvasState};
+    /// let mut state = ShaderCanvasState::default();
+    /// println!("{}", ShaderCanvas::new().render_to_ansi(&mut state, 40, 10));
+    /// ```
+    pub fn render_to_ansi(&self, state: &mut ShaderCanvasState, width: u16, height: u16) -> String {
+        self.render_to_ansi_with(state, width, height, AnsiColors::TrueColor)
+    }
+
+    /// Same as [`ShaderCanvas::render_to_ansi`] but with the given [`AnsiColors`], e.g. for terminals
+    /// that only support the 256-color palette.
+    pub fn render_to_ansi_with(
+        &self,
+        state: &mut ShaderCanvasState,
+        width: u16,
+        height: u16,
+        colors: AnsiColors,
+    ) -> String {
+        let area = Rect::new(0, 0, width, height);
+        let mut buffer = Buffer::empty(area);
+        StatefulWidget::render(self, area, &mut buffer, state);
+        buffer_to_ansi(&buffer, "\n", colors)
+    }
+
+    /// Renders the [`ShaderCanvas`] with `time` seconds as the `time` input instead of the time of the
+    /// state's [`ShaderClock`](crate::ShaderClock).
+    pub(crate) fn render_at(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &mut ShaderCanvasState,
+        time: f32,
+    ) {
+        if area.is_empty() || self.render_compile_error(area, buf, state) {
+            return;
+        }
+        if self.power_profile != PowerProfile::Performance {
+            self.render_throttled(area, buf, state, time);
+            return;
+        }
+        if let Some(budget) = self.frame_budget {
+            self.render_within(budget, area, buf, state, time);
+            return;
+        }
+        let (scaled_width, scaled_height) = self.scaled_size(area);
+        let params = InstanceParams {
+            instance: self.instance,
+            scroll_offset: self.scroll_offset,
+        };
+        let ctx = self.context(area, time, params);
+        if let Some(region) = self.region
+            && self.render_region(area, region, buf, state, ctx)
+        {
+            return;
+        }
+        let frame = Frame::new(scaled_width, scaled_height, state.execute(ctx));
+
+        self.style_frame(&frame, area, buf);
+        if self.region.is_some() {
+            state.set_last_cells(copy_cells(buf, area));
+        }
+        state.set_last_frame(frame, Some(area));
+    }
+
+    /// Renders the [`ShaderCanvas`] into every area of `areas` at once, each with its own
+    /// [`InstanceParams`] overriding the instance id and scroll offset of the canvas. On the GPU all areas
+    /// are rendered into a single texture with one submission and readback, which is much faster than
+    /// rendering dashboards with dozens of small shaded tiles one by one. The uniforms are shared by all
+    /// areas and the [`region`](ShaderCanvas::region) is ignored.
+    ///
+    /// ```rust,no_run
+    /// # use ratatui::layout::{Constraint, Layout};
+    /// # use tui_shader::{InstanceParams, ShaderCanvas, ShaderCanvasState};
+    /// let mut terminal = ratatui::init();
+    /// let mut state = ShaderCanvasState::default();
+    /// terminal.draw(|frame| {
+    ///     let tiles = Layout::horizontal([Constraint::Fill(1); 8]).split(frame.area());
+    ///     let areas: Vec<_> = tiles
+    ///         .iter()
+    ///         .enumerate()
+    ///         .map(|(id, tile)| (*tile, InstanceParams::new(id as u32)))
+    ///         .collect();
+    ///     ShaderCanvas::new().render_many(&areas, frame.buffer_mut(), &mut state);
+    /// }).unwrap();
+    /// ratatui::restore();
+    /// ```
+    pub fn render_many(
+        &self,
+        areas: &[(Rect, InstanceParams)],
+        buf: &mut Buffer,
+        state: &mut ShaderCanvasState,
+    ) {
+        let time = state.clock().elapsed().as_secs_f32();
+        let areas: Vec<(Rect, InstanceParams)> = areas
+            .iter()
+            .copied()
+            .filter(|(area, _)| !area.is_empty())
+            .collect();
+        let ctxs: Vec<ShaderContext> = areas
+            .iter()
+            .map(|&(area, params)| self.context(area, time, params))
+            .collect();
+        let frames = state.execute_batch(&ctxs);
+        for ((area, _), (ctx, pixels)) in areas.into_iter().zip(ctxs.iter().zip(frames)) {
+            let frame = Frame::new(ctx.width(), ctx.height(), pixels);
+            self.style_frame(&frame, area, buf);
+            state.set_last_frame(frame, Some(area));
+        }
+    }
+
+    /// Renders the compile error of `state` if there is one and the canvas shows them.
+    fn render_compile_error(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &ShaderCanvasState,
+    ) -> bool {
+        #[cfg(feature = "gpu")]
+        if self.show_compile_errors
+            && let Some(error) = state.compile_error()
+        {
+            error.render(area, buf);
+            return true;
+        }
+        #[cfg(not(feature = "gpu"))]
+        let _ = (area, buf, state);
+        false
+    }
+
+    /// Renders with the render scale and frame rate of the power profile, or styles the last frame again if
+    /// the next render isn't due yet.
+    fn render_throttled(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &mut ShaderCanvasState,
+        time: f32,
+    ) {
+        let now = Instant::now();
+        if state.power_mut().should_skip(self.power_profile, now)
+            && state.last_area() == Some(area)
+            && let Some(frame) = state.frame()
+        {
+            let frame = frame.clone();
+            state.record_skipped_frame();
+            self.style_frame(&frame, area, buf);
+            return;
+        }
+        let canvas = Self {
+            render_scale: self.scale() * self.power_profile.render_scale(),
+            power_profile: PowerProfile::Performance,
+            ..self.clone()
+        };
+        canvas.render_at(area, buf, state, time);
+        let hash = state.frame().map(frame_hash);
+        state.power_mut().record(now, hash);
+    }
+
+    /// Renders with the render scale reduced as far as the frame budget of `state` requires, or styles the
+    /// last frame again if the previous render was too slow even at the lowest scale.
+    fn render_within(
+        &self,
+        budget: Duration,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &mut ShaderCanvasState,
+        time: f32,
+    ) {
+        if state.frame_budget_mut().take_skip()
+            && state.last_area() == Some(area)
+            && let Some(frame) = state.frame()
+        {
+            let frame = frame.clone();
+            state.record_skipped_frame();
+            let blended = self
+                .interpolate
+                .then(|| state.frame_budget_mut().interpolate(time))
+                .flatten();
+            self.style_frame(blended.as_ref().unwrap_or(&frame), area, buf);
+            return;
+        }
+        let canvas = Self {
+            render_scale: self.scale() * state.budget_scale(),
+            frame_budget: None,
+            ..self.clone()
+        };
+        let start = Instant::now();
+        canvas.render_at(area, buf, state, time);
+        state.frame_budget_mut().record(start.elapsed(), budget);
+        if !self.interpolate || state.last_area() != Some(area) {
+            return;
+        }
+        if let Some(frame) = state.frame().cloned() {
+            state.frame_budget_mut().push_frame(time, frame);
+        }
+        // While renders alternate with reused frames, the rendered frames are shown one render late, so
+        // the reused frames in between can blend towards them.
+        if state.frame_budget_mut().is_skipping()
+            && let Some(frame) = state.frame_budget_mut().interpolate(time)
+        {
+            self.style_frame(&frame, area, buf);
+        }
+    }
+
+    /// The inputs of rendering `area` at `time` seconds with `params`.
+    fn context(&self, area: Rect, time: f32, params: InstanceParams) -> ShaderContext {
+        let (scaled_width, scaled_height) = self.scaled_size(area);
+        ShaderContext::new(time, area)
+            .with_size(scaled_width, scaled_height)
+            .with_scroll_offset(
+                params.scroll_offset.0 * self.parallax,
+                params.scroll_offset.1 * self.parallax,
+            )
+            .with_instance(params.instance)
+    }
+
+    /// Renders the cells of `area` within `region` and copies the others from the previous render. Returns
+    /// `false` if there is no previous render of the same area to copy from.
+    fn render_region(
+        &self,
+        area: Rect,
+        region: Rect,
+        buf: &mut Buffer,
+        state: &mut ShaderCanvasState,
+        ctx: ShaderContext,
+    ) -> bool {
+        let scale = self.scale();
+        let Some((mut frame, mut cells)) = state.take_last_render() else {
+            return false;
+        };
+        if frame.width != ctx.width() || frame.height != ctx.height() || cells.area != area {
+            return false;
+        }
+        let region = region.intersection(area);
+        // Interpolated samples read the pixels next to the ones covered by the region.
+        let margin = if scale == 1.0 { 0.0 } else { 1.0 };
+        let left = (f32::from(region.x - area.x) * scale - margin).max(0.0) as u16;
+        let top = (f32::from(region.y - area.y) * scale - margin).max(0.0) as u16;
+        let right = (f32::from(region.right() - area.x) * scale + margin).ceil() as u16;
+        let bottom = (f32::from(region.bottom() - area.y) * scale + margin).ceil() as u16;
+        let bounds = Rect::new(0, 0, frame.width as u16, frame.height as u16);
+        let pixel_region = Rect::new(
+            left,
+            top,
+            right.saturating_sub(left),
+            bottom.saturating_sub(top),
+        )
+        .intersection(bounds);
+        let pixels = state.execute_region(ctx, pixel_region);
+        frame.patch(pixel_region, &pixels);
+
+        for position in area.positions() {
+            if region.contains(position) {
+                self.style_cell(&frame, area, position, buf);
+                cells[position] = buf[position].clone();
+            } else {
+                buf[position] = cells[position].clone();
+            }
+        }
+        state.set_last_frame(frame, Some(area));
+        state.set_last_cells(cells);
+        true
+    }
+
+    /// The render scale, falling back to `1.0` for invalid values.
+    fn scale(&self) -> f32 {
+        if self.render_scale.is_finite() && self.render_scale > 0.0 {
+            self.render_scale
+        } else {
+            1.0
+        }
+    }
+
+    /// The size of the texture rendered for `area`, taking the render scale into account.
+    pub(crate) fn scaled_size(&self, area: Rect) -> (u32, u32) {
+        let scale = self.scale();
+        let scaled_width = (f32::from(area.width) * scale)
+            .round()
+            .clamp(1.0, u16::MAX.into()) as u32;
+        let scaled_height = (f32::from(area.height) * scale)
+            .round()
+            .clamp(1.0, u16::MAX.into()) as u32;
+        (scaled_width, scaled_height)
+    }
+
+    /// Styles every cell of `area` with the colors sampled from `frame`.
+    pub(crate) fn style_frame(&self, frame: &Frame, area: Rect, buf: &mut Buffer) {
+        for position in area.positions() {
+            self.style_cell(frame, area, position, buf);
+        }
+    }
+
+    /// Styles the cell at `position` of `area` with the color sampled from `frame`.
+    pub(crate) fn style_cell(
+        &self,
+        frame: &Frame,
+        area: Rect,
+        position: Position,
+        buf: &mut Buffer,
+    ) {
+        let x = position.x - area.x;
+        let y = position.y - area.y;
         let width = area.width;
         let height = area.height;
-        let time = state.get_instant().elapsed().as_secs_f32();
-        let ctx = ShaderContext::new(time, area);
-        let samples = state.execute(ctx);
-
-        for y in 0..height {
-            for x in 0..width {
-                let index = (y * (width + row_padding(width.into()) as u16) + x) as usize;
-                let value = samples[index];
-                let position = (x, y);
-                let uv = (x as f32 / width as f32, y as f32 / height as f32);
-                let character = match self.character_rule {
-                    CharacterRule::Always(character) => character,
-                    CharacterRule::Map(map) => map(Sample::new(value, position, uv)),
-                };
-                let color = Color::Rgb(value[0], value[1], value[2]);
-                let style = match self.style_rule {
-                    StyleRule::ColorFg => Style::new().fg(color),
-                    StyleRule::ColorBg => Style::new().bg(color),
-                    StyleRule::Map(map) => map(Sample::new(value, position, uv)),
-                };
-                let cell = buf
-                    .cell_mut(Position::new(x + area.x, y + area.y))
-                    .expect("unable to get cell");
-                cell.set_style(style);
-                cell.set_char(character);
-            }
+        let value = frame.sample_cell(x, y, width, height);
+        let position = (x, y);
+        let uv = (x as f32 / width as f32, y as f32 / height as f32);
+        let character = match self.character_rule {
+            CharacterRule::Always(character) => character,
+            CharacterRule::Map(map) => map(Sample::new(value, position, uv)),
+        };
+        let color = Color::Rgb(value[0], value[1], value[2]);
+        let style = match self.style_rule {
+            StyleRule::ColorFg => Style::new().fg(color),
+            StyleRule::ColorBg => Style::new().bg(color),
+            StyleRule::Map(map) => map(Sample::new(value, position, uv)),
+        };
+        let cell = buf
+            .cell_mut(Position::new(x + area.x, y + area.y))
+            .expect("unable to get cell");
+        cell.set_style(style);
+        cell.set_char(character);
+    }
+}
+
+/// The parameters of one area of [`ShaderCanvas::render_many`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct InstanceParams {
+    /// The id of the instance, see [`ShaderCanvas::instance`].
+    pub instance: u32,
+
+    /// The scroll offset in cells, see [`ShaderCanvas::scroll_offset`].
+    pub scroll_offset: (f32, f32),
+}
+
+impl InstanceParams {
+    /// Creates new [`InstanceParams`] for the instance with the given id.
+    pub fn new(instance: u32) -> Self {
+        Self {
+            instance,
+            scroll_offset: (0.0, 0.0),
         }
     }
+
+    /// Sets the scroll offset of the instance in columns and rows.
+    #[must_use]
+    pub fn scroll_offset(mut self, x: f32, y: f32) -> Self {
+        self.scroll_offset = (x, y);
+        self
+    }
+}
+
+/// Copies the cells of `area` out of `buf`.
+fn copy_cells(buf: &Buffer, area: Rect) -> Buffer {
+    let mut cells = Buffer::empty(area);
+    for position in area.positions() {
+        cells[position] = buf[position].clone();
+    }
+    cells
 }
@@ -0,0 +1,286 @@
+use std::fmt;
+
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::style::{Color, Modifier, Style};
+use wgpu::naga;
+use wgpu::naga::valid::{ValidationFlags, Validator};
+
+use crate::context::CONTEXT_VERSION;
+use crate::gpu::TILE_BINDING;
+use crate::policy::ShaderPolicy;
+use crate::state::ShaderCanvasState;
+use crate::uniforms::{USER_UNIFORMS_BINDING, UniformLayout};
+
+/// Parses the WGSL `source`, with diagnostics pointing at the offending line if it doesn't.
+pub(crate) fn parse(source: &str) -> Result<naga::Module, ShaderDiagnostics> {
+    naga::front::wgsl::parse_str(source).map_err(|error| {
+        ShaderDiagnostics::new(
+            error.message().to_owned(),
+            error.location(source),
+            error.emit_to_string(source),
+            source,
+        )
+    })
+}
+
+/// The size in bytes of the buffers bound to the built-in inputs.
+const BUILTIN_BUFFER_SIZE: u32 = 16;
+
+/// What [`ShaderCanvasState::check`] found out about a shader which compiles.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShaderInfo {
+    /// The names of the `@fragment` functions, in the order they are declared.
+    pub entry_points: Vec<String>,
+    /// The names of the user defined uniforms in the struct at `@group(0) @binding(2)`, which can be
+    /// set with [`ShaderCanvasState::uniforms`].
+    pub uniforms: Vec<String>,
+}
+
+/// Why [`ShaderCanvasState::check`] rejected a shader. [`Display`](fmt::Display) prints the report of
+/// the shader compiler, which quotes the offending lines of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderDiagnostics {
+    /// A one line description of the problem.
+    pub message: String,
+    /// The line of the problem, starting at 1, `None` if it isn't tied to a place in the source.
+    pub line: Option<u32>,
+    /// The column of the problem, starting at 1, `None` if it isn't tied to a place in the source.
+    pub column: Option<u32>,
+    report: String,
+    source_line: Option<String>,
+}
+
+impl ShaderDiagnostics {
+    fn new(
+        message: String,
+        location: Option<naga::SourceLocation>,
+        report: String,
+        source: &str,
+    ) -> Self {
+        let line = location.map(|location| location.line_number);
+        Self {
+            message,
+            line,
+            column: location.map(|location| location.line_position),
+            report,
+            source_line: line
+                .and_then(|line| source.lines().nth(line.checked_sub(1)? as usize))
+                .map(str::to_owned),
+        }
+    }
+
+    /// A diagnostic raised by the checks of `tui-shader` itself, pointing at `span` of `source`.
+    pub(crate) fn at(message: String, span: naga::Span, source: &str) -> Self {
+        let location = span.is_defined().then(|| span.location(source));
+        let report = match location {
+            Some(location) => format!(
+                "{}:{}: {message}",
+                location.line_number, location.line_position
+            ),
+            None => message.clone(),
+        };
+        Self::new(message, location, report, source)
+    }
+
+    /// The diagnostics of a shader which failed to compile with `error`: `error` itself if it already
+    /// is diagnostics, the ones of [`ShaderCanvasState::check`] if `source` is WGSL which doesn't pass
+    /// it, since they point at the offending line, otherwise just the message of `error`.
+    pub(crate) fn from_error(
+        error: &(dyn std::error::Error + 'static),
+        source: Option<&str>,
+    ) -> Self {
+        if let Some(diagnostics) = error.downcast_ref::<Self>() {
+            return diagnostics.clone();
+        }
+        source
+            .and_then(|source| ShaderCanvasState::check(source).err())
+            .unwrap_or_else(|| Self::at(error.to_string(), naga::Span::default(), ""))
+    }
+
+    /// The text of the line of the problem, `None` if it isn't tied to a place in the source.
+    pub fn source_line(&self) -> Option<&str> {
+        self.source_line.as_deref()
+    }
+
+    /// Writes the message, followed by the line of the problem with a caret below the column, into
+    /// `area` of `buf`, see [`ShaderCanvas::show_compile_errors`](crate::ShaderCanvas::show_compile_errors).
+    pub(crate) fn render(&self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        for position in area.positions() {
+            buf[position].reset();
+        }
+        let width = usize::from(area.width);
+        let message = format!("error: {}", self.message);
+        let chars: Vec<char> = message.chars().collect();
+        let mut rows: Vec<(String, Style)> = chars
+            .chunks(width.max(1))
+            .map(|chunk| {
+                let style = Style::new().fg(Color::Red).add_modifier(Modifier::BOLD);
+                (chunk.iter().collect(), style)
+            })
+            .collect();
+        if let (Some(line), Some(source_line)) = (self.line, &self.source_line) {
+            let gutter = format!("{line} | ");
+            // Tabs are shown as single spaces to keep the caret below the column.
+            let source_line = source_line.replace('\t', " ");
+            let caret = " ".repeat(self.column.unwrap_or(1).saturating_sub(1) as usize);
+            rows.push((String::new(), Style::new()));
+            rows.push((
+                format!("{gutter}{source_line}"),
+                Style::new().fg(Color::White).bg(Color::Red),
+            ));
+            rows.push((
+                format!("{}{caret}^", " ".repeat(gutter.chars().count())),
+                Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ));
+        }
+        for ((text, style), y) in rows.iter().zip(area.top()..area.bottom()) {
+            buf.set_stringn(area.x, y, text, width, *style);
+        }
+    }
+}
+
+impl fmt::Display for ShaderDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.report)
+    }
+}
+
+impl std::error::Error for ShaderDiagnostics {}
+
+impl ShaderCanvasState {
+    /// Parses and validates the WGSL `source` without touching the GPU, so editors can report errors
+    /// on every keystroke. Besides the rules of WGSL, a shader has to declare a `@fragment` entry point,
+    /// and the built-in inputs of `@group(0)` have to fit the data `tui-shader` binds to them.
+    ///
+    /// Passing the check doesn't guarantee the shader compiles on every GPU, as the adapter may lack
+    /// features the shader uses.
+    ///
+    /// ```rust
+    /// # use tui_shader::ShaderCanvasState;
+    /// let error = ShaderCanvasState::check("@fragment fn main() -> vec4<f32> { return 1.0; }")
+    ///     .unwrap_err();
+    /// assert_eq!(error.line, Some(1));
+    /// ```
+    pub fn check(source: &str) -> Result<ShaderInfo, ShaderDiagnostics> {
+        Self::check_with_policy(source, &ShaderPolicy::trusted())
+    }
+
+    /// Like [`ShaderCanvasState::check`], but additionally rejects shaders which exceed the limits of
+    /// `policy`, e.g. before loading a shader from an untrusted source as a candidate.
+    ///
+    /// ```rust
+    /// # use tui_shader::{ShaderCanvasState, ShaderPolicy};
+    /// let source = "@fragment fn main() -> @location(0) vec4<f32> {
+    ///     var x = 0.0;
+    ///     loop { x += 1.0; if x > 1.0e9 { break; } }
+    ///     return vec4<f32>(x);
+    /// }";
+    /// assert!(ShaderCanvasState::check(source).is_ok());
+    /// assert!(ShaderCanvasState::check_with_policy(source, &ShaderPolicy::untrusted()).is_err());
+    /// ```
+    pub fn check_with_policy(
+        source: &str,
+        policy: &ShaderPolicy,
+    ) -> Result<ShaderInfo, ShaderDiagnostics> {
+        let module = parse(source)?;
+        Validator::new(ValidationFlags::all(), policy.capabilities)
+            .validate(&module)
+            .map_err(|error| {
+                ShaderDiagnostics::new(
+                    error.as_inner().to_string(),
+                    error.location(source),
+                    error.emit_to_string(source),
+                    source,
+                )
+            })?;
+        check_builtin_bindings(&module, source)?;
+        check_context_version(&module, source)?;
+        policy.enforce(&module, source)?;
+        let entry_points: Vec<String> = module
+            .entry_points
+            .iter()
+            .filter(|entry_point| entry_point.stage == naga::ShaderStage::Fragment)
+            .map(|entry_point| entry_point.name.clone())
+            .collect();
+        if entry_points.is_empty() {
+            return Err(ShaderDiagnostics::at(
+                "the shader has no @fragment entry point".to_owned(),
+                naga::Span::default(),
+                source,
+            ));
+        }
+        Ok(ShaderInfo {
+            entry_points,
+            uniforms: UniformLayout::from_module(&module)
+                .names()
+                .map(str::to_owned)
+                .collect(),
+        })
+    }
+}
+
+/// Checks that a `TUI_SHADER_CONTEXT_VERSION` constant declared by the shader, e.g. by pasting
+/// [`CONTEXT_WGSL`](crate::CONTEXT_WGSL), matches the layout of the context bound by this version of the
+/// crate.
+fn check_context_version(module: &naga::Module, source: &str) -> Result<(), ShaderDiagnostics> {
+    let Some((handle, constant)) = module
+        .constants
+        .iter()
+        .find(|(_, constant)| constant.name.as_deref() == Some("TUI_SHADER_CONTEXT_VERSION"))
+    else {
+        return Ok(());
+    };
+    let version = match module.global_expressions[constant.init] {
+        naga::Expression::Literal(naga::Literal::U32(version)) => i64::from(version),
+        naga::Expression::Literal(naga::Literal::I32(version)) => i64::from(version),
+        naga::Expression::Literal(naga::Literal::AbstractInt(version)) => version,
+        _ => return Ok(()),
+    };
+    if version == i64::from(CONTEXT_VERSION) {
+        return Ok(());
+    }
+    let message = format!(
+        "the shader expects version {version} of the context layout, but this version of \
+         tui-shader binds version {CONTEXT_VERSION}"
+    );
+    let span = module.constants.get_span(handle);
+    Err(ShaderDiagnostics::at(message, span, source))
+}
+
+/// Checks that the variables at the built-in bindings of `@group(0)` match the buffers bound to them.
+fn check_builtin_bindings(module: &naga::Module, source: &str) -> Result<(), ShaderDiagnostics> {
+    for (handle, variable) in module.global_variables.iter() {
+        let Some(binding) = &variable.binding else {
+            continue;
+        };
+        if binding.group != 0 {
+            continue;
+        }
+        let name = variable.name.as_deref().unwrap_or("_");
+        let ty = &module.types[variable.ty].inner;
+        let uniform = variable.space == naga::AddressSpace::Uniform;
+        let message = match binding.binding {
+            USER_UNIFORMS_BINDING if !uniform || !matches!(ty, naga::TypeInner::Struct { .. }) => {
+                format!("`{name}` at @group(0) @binding(2) must be a var<uniform> of a struct")
+            }
+            USER_UNIFORMS_BINDING => continue,
+            TILE_BINDING => "@group(0) @binding(3) is reserved for the vertex shader".to_owned(),
+            0..=8 if !uniform => format!(
+                "`{name}` at @group(0) @binding({}) must be a var<uniform>",
+                binding.binding
+            ),
+            0..=8 if ty.size(module.to_ctx()) > BUILTIN_BUFFER_SIZE => format!(
+                "`{name}` at @group(0) @binding({}) is {} bytes, but only {BUILTIN_BUFFER_SIZE} \
+                 bytes are bound, declare it as a vec4",
+                binding.binding,
+                ty.size(module.to_ctx())
+            ),
+            _ => continue,
+        };
+        let span = module.global_variables.get_span(handle);
+        return Err(ShaderDiagnostics::at(message, span, source));
+    }
+    Ok(())
+}
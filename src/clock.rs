@@ -0,0 +1,328 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// [`ClockSource`] supplies the time of a [`ShaderClock`] from outside the crate, e.g. the tick counter
+/// of a simulation or a mocked time in tests. Set it with [`ShaderClock::set_source`]; the clock then
+/// reports the time of the source instead of the wall clock.
+///
+/// Besides [`ExternalClock`], which is driven by setting its time, a [`ShaderClock`] itself is a source,
+/// and so is any closure returning a [`Duration`].
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tui_shader::{ClockSource, ShaderCanvasState};
+/// struct Simulation {
+///     ticks: u64,
+/// }
+///
+/// impl ClockSource for Simulation {
+///     fn elapsed(&self) -> Duration {
+///         Duration::from_millis(self.ticks * 20)
+///     }
+///
+///     fn tick(&mut self) {
+///         self.ticks += 1;
+///     }
+/// }
+///
+/// let mut state = ShaderCanvasState::default();
+/// state.clock_mut().set_source(Simulation { ticks: 0 });
+/// ```
+pub trait ClockSource: Send {
+    /// The time passed to the shader as the `time` input.
+    fn elapsed(&self) -> Duration;
+
+    /// Called after the time was read for an execution of the shader, to advance sources which step
+    /// with the frames. Does nothing by default.
+    fn tick(&mut self) {}
+}
+
+impl<F: Fn() -> Duration + Send> ClockSource for F {
+    fn elapsed(&self) -> Duration {
+        self()
+    }
+}
+
+impl ClockSource for ShaderClock {
+    fn elapsed(&self) -> Duration {
+        ShaderClock::elapsed(self)
+    }
+
+    fn tick(&mut self) {
+        ShaderClock::tick(self);
+    }
+}
+
+/// [`ExternalClock`] is a [`ClockSource`] whose time is set from the outside, e.g. by a simulation
+/// driving several shaders from its own tick counter. Clones share the same time, so keep one and set
+/// the others as sources.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tui_shader::{ExternalClock, ShaderCanvasState};
+/// let clock = ExternalClock::new();
+/// let mut state = ShaderCanvasState::default();
+/// state.clock_mut().set_source(clock.clone());
+/// // Once per simulation step:
+/// clock.advance(Duration::from_millis(20));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ExternalClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl ExternalClock {
+    /// Creates a new [`ExternalClock`] at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current time of the clock.
+    pub fn get(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+
+    /// Sets the time of the clock.
+    pub fn set(&self, elapsed: Duration) {
+        self.nanos.store(nanos(elapsed), Ordering::Relaxed);
+    }
+
+    /// Advances the time of the clock by `dt`.
+    pub fn advance(&self, dt: Duration) {
+        self.nanos.fetch_add(nanos(dt), Ordering::Relaxed);
+    }
+}
+
+impl ClockSource for ExternalClock {
+    fn elapsed(&self) -> Duration {
+        self.get()
+    }
+}
+
+/// `duration` in nanoseconds, saturating after about 584 years.
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// The [`ClockSource`] of a [`ShaderClock`], compared by identity.
+#[derive(Clone)]
+struct Source(Arc<Mutex<dyn ClockSource>>);
+
+impl Source {
+    fn with<T>(&self, f: impl FnOnce(&mut dyn ClockSource) -> T) -> T {
+        f(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl PartialEq for Source {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Source {}
+
+impl std::fmt::Debug for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Source").finish_non_exhaustive()
+    }
+}
+
+/// How a [`ShaderClock`] advances.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ClockMode {
+    /// Follows the wall clock.
+    #[default]
+    RealTime,
+
+    /// Advances by exactly `dt` with every execution of the shader, regardless of how much time passed
+    /// in between, so recordings, golden tests and replays render the same frames on every machine and
+    /// run. Executions which reuse the cached pixels advance the clock as well.
+    Fixed {
+        /// The time between two executions.
+        dt: Duration,
+    },
+}
+
+/// [`ShaderClock`] measures the time that is passed to shaders as the `time` input. Unlike a plain
+/// [`Instant`] it can be paused and resumed, freezing the animation of a shader in place.
+///
+/// In [`ClockMode::Fixed`] the clock ignores the wall clock and only advances by its step with every
+/// execution of the shader. The local date of the wall clock input still changes, keep it disabled for
+/// deterministic renders.
+///
+/// A [`ClockSource`] set with [`ShaderClock::set_source`] replaces both modes. While it is set, it alone
+/// determines the time: pausing, offsets and the mode only take effect again once it is removed.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tui_shader::{ClockMode, ShaderCanvasState};
+/// # let mut state = ShaderCanvasState::default();
+/// // Every frame is 1/30 s after the previous one, however long rendering it takes.
+/// state.set_clock_mode(ClockMode::Fixed {
+///     dt: Duration::from_secs(1) / 30,
+/// });
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderClock {
+    start: Instant,
+    paused_at: Option<Instant>,
+    offset: Duration,
+    mode: ClockMode,
+    source: Option<Source>,
+}
+
+impl ShaderClock {
+    /// Creates a new, running [`ShaderClock`] starting now. Equivalent to [`ShaderClock::default()`]
+    pub fn new() -> Self {
+        Self::from_instant(Instant::now())
+    }
+
+    /// Creates a new, running [`ShaderClock`] that started at the given [`Instant`].
+    pub fn from_instant(start: Instant) -> Self {
+        Self {
+            start,
+            paused_at: None,
+            offset: Duration::ZERO,
+            mode: ClockMode::RealTime,
+            source: None,
+        }
+    }
+
+    /// Creates a new [`ShaderClock`] reporting the time of `source`.
+    pub fn from_source(source: impl ClockSource + 'static) -> Self {
+        let mut clock = Self::new();
+        clock.set_source(source);
+        clock
+    }
+
+    /// Creates a new [`ShaderClock`] at zero in [`ClockMode::Fixed`], advancing by `dt` with every
+    /// execution.
+    pub fn fixed(dt: Duration) -> Self {
+        let mut clock = Self::new();
+        clock.set_mode(ClockMode::Fixed { dt });
+        clock.set_elapsed(Duration::ZERO);
+        clock
+    }
+
+    /// Creates a paused [`ShaderClock`] whose [`ShaderClock::elapsed`] is exactly `elapsed`.
+    pub(crate) fn frozen(elapsed: Duration) -> Self {
+        let now = Instant::now();
+        Self {
+            start: now,
+            paused_at: Some(now),
+            offset: elapsed,
+            mode: ClockMode::RealTime,
+            source: None,
+        }
+    }
+
+    /// The time passed since the clock started, excluding the time it was paused. In
+    /// [`ClockMode::Fixed`], the sum of the steps of all executions so far. With a [`ClockSource`], the
+    /// time of the source.
+    pub fn elapsed(&self) -> Duration {
+        match &self.source {
+            Some(source) => source.with(|source| source.elapsed()),
+            None => self.own_elapsed(),
+        }
+    }
+
+    /// The time of the clock itself, ignoring its source.
+    fn own_elapsed(&self) -> Duration {
+        if let ClockMode::Fixed { .. } = self.mode {
+            return self.offset;
+        }
+        let now = self.paused_at.unwrap_or_else(Instant::now);
+        now.saturating_duration_since(self.start) + self.offset
+    }
+
+    /// How the clock advances.
+    pub fn mode(&self) -> ClockMode {
+        self.mode
+    }
+
+    /// Switches how the clock advances, continuing from the current [`ShaderClock::elapsed`].
+    pub fn set_mode(&mut self, mode: ClockMode) {
+        let elapsed = self.own_elapsed();
+        self.mode = mode;
+        self.set_elapsed(elapsed);
+    }
+
+    /// Advances a clock in [`ClockMode::Fixed`] by its step, unless it is paused. Does nothing in
+    /// [`ClockMode::RealTime`]. Ticks the [`ClockSource`] instead if one is set. Widgets call it with
+    /// every execution of the shader.
+    pub fn tick(&mut self) {
+        if let Some(source) = &self.source {
+            source.with(|source| source.tick());
+        } else if let ClockMode::Fixed { dt } = self.mode
+            && !self.is_paused()
+        {
+            self.offset += dt;
+        }
+    }
+
+    /// Reports the time of `source` from now on, see [`ClockSource`].
+    pub fn set_source(&mut self, source: impl ClockSource + 'static) {
+        self.source = Some(Source(Arc::new(Mutex::new(source))));
+    }
+
+    /// Removes the [`ClockSource`], returning to the wall clock or the fixed step of the clock.
+    pub fn clear_source(&mut self) {
+        self.source = None;
+    }
+
+    /// Returns `true` if a [`ClockSource`] determines the time.
+    pub fn has_source(&self) -> bool {
+        self.source.is_some()
+    }
+
+    /// Lets the clock start at the given [`Instant`], keeping it paused or running.
+    pub fn set_instant(&mut self, instant: Instant) {
+        self.start = instant;
+        self.offset = Duration::ZERO;
+    }
+
+    /// Jumps to the point where [`ShaderClock::elapsed`] is `elapsed`, keeping the clock paused or
+    /// running.
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        self.start = self.paused_at.unwrap_or_else(Instant::now);
+        self.offset = elapsed;
+    }
+
+    /// Advances the clock by `offset`, e.g. to phase-shift several animations driven by clocks created
+    /// at the same time.
+    pub fn offset(&mut self, offset: Duration) {
+        self.offset += offset;
+    }
+
+    /// Stops the clock. [`ShaderClock::elapsed`] returns the same value until the clock is resumed.
+    pub fn pause(&mut self) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(Instant::now());
+        }
+    }
+
+    /// Resumes a paused clock from where it was paused.
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.start += paused_at.elapsed();
+        }
+    }
+
+    /// Returns `true` if the clock is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// The [`Instant`] the clock started at, shifted by the time it was paused and by its offset.
+    pub fn get_instant(&self) -> Instant {
+        self.start.checked_sub(self.offset).unwrap_or(self.start)
+    }
+}
+
+impl Default for ShaderClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
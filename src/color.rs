@@ -0,0 +1,114 @@
+use crate::Pixel;
+
+/// [`ColorConfig`] declares how the colors a shader outputs relate to the colors of the terminal. The
+/// conversions are applied to every pixel before it is mapped to cells, set them with
+/// [`ShaderCanvasState::set_color_config`](crate::ShaderCanvasState::set_color_config).
+///
+/// Colors are decoded from the [`working_space`](ColorConfig::working_space), tonemapped in linear space
+/// and encoded into the [`output_space`](ColorConfig::output_space). The default treats the output of the
+/// shader as sRGB, like terminals do, and leaves it untouched.
+///
+/// ```rust,no_run
+/// # use tui_shader::{ColorConfig, ColorSpace, ShaderCanvasState, Tonemap};
+/// let mut state = ShaderCanvasState::default();
+/// state.set_color_config(ColorConfig {
+///     working_space: ColorSpace::Linear,
+///     tonemap: Tonemap::AcesFitted,
+///     ..ColorConfig::default()
+/// });
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ColorConfig {
+    /// The space the shader computes its colors in.
+    pub working_space: ColorSpace,
+    /// The space the colors are handed to the terminal in.
+    pub output_space: ColorSpace,
+    /// The curve compressing bright colors, applied in linear space.
+    pub tonemap: Tonemap,
+}
+
+/// The encoding of color values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorSpace {
+    /// Gamma encoded sRGB, the space terminal colors are interpreted in.
+    #[default]
+    Srgb,
+
+    /// Linear light, which is what lighting and blending math in shaders usually produces.
+    Linear,
+}
+
+/// A tonemapping curve mapping bright colors into the displayable range.
+///
+/// Shaders render into a texture with 8 bits per channel, so values above `1.0` are clipped before
+/// tonemapping. The curves reshape the range up to `1.0` and keep highlights from looking harsh.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tonemap {
+    /// Leaves the colors unchanged.
+    #[default]
+    None,
+
+    /// `x / (1 + x)`, a soft curve which darkens the whole image.
+    Reinhard,
+
+    /// Krzysztof Narkowicz's fit of the ACES filmic curve, with more contrast than [`Tonemap::Reinhard`].
+    AcesFitted,
+}
+
+impl ColorConfig {
+    /// A lookup table mapping every channel value through the conversions, or `None` if they don't
+    /// change any color.
+    pub(crate) fn lut(&self) -> Option<[u8; 256]> {
+        if self.working_space == self.output_space && self.tonemap == Tonemap::None {
+            return None;
+        }
+        Some(std::array::from_fn(|value| {
+            let linear = self.working_space.decode(value as f32 / 255.0);
+            let mapped = self.tonemap.apply(linear).clamp(0.0, 1.0);
+            (self.output_space.encode(mapped) * 255.0).round() as u8
+        }))
+    }
+}
+
+impl ColorSpace {
+    fn decode(self, value: f32) -> f32 {
+        match self {
+            Self::Srgb if value <= 0.04045 => value / 12.92,
+            Self::Srgb => ((value + 0.055) / 1.055).powf(2.4),
+            Self::Linear => value,
+        }
+    }
+
+    fn encode(self, value: f32) -> f32 {
+        match self {
+            Self::Srgb if value <= 0.003_130_8 => value * 12.92,
+            Self::Srgb => 1.055 * value.powf(1.0 / 2.4) - 0.055,
+            Self::Linear => value,
+        }
+    }
+}
+
+impl Tonemap {
+    fn apply(self, value: f32) -> f32 {
+        match self {
+            Self::None => value,
+            Self::Reinhard => value / (1.0 + value),
+            Self::AcesFitted => {
+                (value * (2.51 * value + 0.03)) / (value * (2.43 * value + 0.59) + 0.14)
+            }
+        }
+    }
+}
+
+/// Maps the color channels of `pixels` through `lut`, leaving alpha untouched.
+pub(crate) fn apply_lut(pixels: &mut [Pixel], lut: &[u8; 256]) {
+    for pixel in pixels {
+        for channel in &mut pixel[..3] {
+            *channel = lut[usize::from(*channel)];
+        }
+    }
+}
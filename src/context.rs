@@ -1,37 +1,178 @@
+/// The version of the layout of [`ShaderContext`] and of the bindings declared by [`CONTEXT_WGSL`]. It
+/// only changes when a field is moved, removed or changes its meaning, new fields are appended and keep
+/// the version. Shaders declaring `const TUI_SHADER_CONTEXT_VERSION: u32` with another value are rejected
+/// by `ShaderCanvasState::check`.
+pub const CONTEXT_VERSION: u32 = 1;
+
+/// WGSL declarations of the built-in inputs of [`ShaderContext`] at their bindings, and of
+/// `TUI_SHADER_CONTEXT_VERSION`. Paste it into a shader, concatenate it with one or include it with
+/// `#include <context>`.
+///
+/// ```wgsl
+/// #include <context>
+///
+/// @fragment
+/// fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+///     let pixels = cell.zw * uv;
+///     return vec4<f32>(fract(pixels / 8.0 + time.x), 0.0, 1.0);
+/// }
+/// ```
+pub const CONTEXT_WGSL: &str = include_str!("shaders/context.wgsl");
+
+/// The built-in inputs of a shader for one execution. Every field is bound to its own
+/// `@group(0)` binding, see [`CONTEXT_WGSL`], and is 16 bytes large, so the `#[repr(C)]` struct matches
+/// an array of `vec4`s in this order. Its layout is versioned with [`CONTEXT_VERSION`].
+///
+/// | Field      | Offset | Binding | WGSL        |
+/// |------------|--------|---------|-------------|
+/// | `time`     | 0      | 0       | `vec4<f32>` |
+/// | `rect`     | 16     | 1       | `vec4<u32>` |
+/// | `date`     | 32     | 4       | `vec4<f32>` |
+/// | `cell`     | 48     | 5       | `vec4<f32>` |
+/// | `focus`    | 64     | 6       | `vec4<f32>` |
+/// | `scroll`   | 80     | 7       | `vec4<f32>` |
+/// | `instance` | 96     | 8       | `vec4<u32>` |
+///
+/// Pointer input isn't part of the context, it is written to the user defined uniforms, see
+/// [`Interaction`](crate::Interaction).
 #[repr(C)]
-#[derive(Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
-pub(crate) struct ShaderContext {
-    // time[0] = seconds
-    // time[1] = seconds * 10
-    // time[2] = sin of seconds
-    // time[3] = cos of seconds
-    pub(crate) time: [f32; 4],
-
-    // rect[0] = x,
-    // rect[1] = y,
-    // rect[2] = width,
-    // rect[3] = height,
-    pub(crate) rect: [u32; 4],
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "gpu", derive(bytemuck::Pod, bytemuck::Zeroable))]
+pub struct ShaderContext {
+    /// The time of the clock: seconds, seconds times 10, and the sine and cosine of the seconds.
+    pub time: [f32; 4],
+
+    /// The position of the rect in cells and the size of the rendered area in pixels.
+    pub rect: [u32; 4],
+
+    /// The seconds since local midnight, year, month and day of the month, zero unless the wall clock
+    /// is enabled.
+    pub date: [f32; 4],
+
+    /// The width and height of a cell and the width and height of the rect, in screen pixels.
+    pub cell: [f32; 4],
+
+    /// The normalized focus point, starting at the bottom like `uv`, then `1.0` if a focus point is set
+    /// or `0.0` otherwise, and an unused component.
+    pub focus: [f32; 4],
+
+    /// The scroll offset in cells, then the shift of `uv` it causes.
+    pub scroll: [f32; 4],
+
+    /// The id of the rendered instance, then three unused components.
+    pub instance: [u32; 4],
 }
 
+/// The size of a cell in screen pixels assumed until the real size is known.
+pub(crate) const DEFAULT_CELL_SIZE: (u16, u16) = (8, 16);
+
 impl ShaderContext {
-    pub(crate) fn new(time: f32, rect: ratatui_core::layout::Rect) -> Self {
+    /// Creates the context of rendering `rect` with one pixel per cell at `time` seconds, with the
+    /// default cell size of 8 by 16 screen pixels.
+    pub fn new(time: f32, rect: ratatui_core::layout::Rect) -> Self {
         Self {
-            time: [time, time * 10.0, time.sin(), time.cos()],
+            time: time_input(time),
             rect: [
                 rect.x.into(),
                 rect.y.into(),
                 rect.width.into(),
                 rect.height.into(),
             ],
+            date: [0.0; 4],
+            cell: cell_input(DEFAULT_CELL_SIZE, rect.width, rect.height),
+            focus: [0.0; 4],
+            scroll: [0.0; 4],
+            instance: [0; 4],
         }
     }
 
-    pub(crate) fn width(&self) -> u32 {
+    /// Replaces the size of a cell in screen pixels, keeping the number of cells of the rect.
+    pub(crate) fn with_cell_size(mut self, cell_size: (u16, u16)) -> Self {
+        let columns = self.cell[2] / self.cell[0];
+        let rows = self.cell[3] / self.cell[1];
+        self.cell = [
+            cell_size.0.into(),
+            cell_size.1.into(),
+            columns * f32::from(cell_size.0),
+            rows * f32::from(cell_size.1),
+        ];
+        self
+    }
+
+    /// Sets the focus point to the center of the cell at `x`, `y` in the coordinates of the terminal,
+    /// normalized to the rect.
+    pub(crate) fn with_focus_point(mut self, x: u16, y: u16) -> Self {
+        let [u, v] = self.normalize(x, y);
+        self.focus = [u, v, 1.0, 0.0];
+        self
+    }
+
+    /// The center of the cell at `x`, `y` in the coordinates of the terminal, normalized to the rect
+    /// like `uv`.
+    pub(crate) fn normalize(&self, x: u16, y: u16) -> [f32; 2] {
+        let columns = self.cell[2] / self.cell[0];
+        let rows = self.cell[3] / self.cell[1];
+        let u = (f32::from(x) - self.rect[0] as f32 + 0.5) / columns;
+        let v = 1.0 - (f32::from(y) - self.rect[1] as f32 + 0.5) / rows;
+        [u, v]
+    }
+
+    /// Sets the scroll offset to `x`, `y` cells, shifting `uv` so that the content of the shader moves up
+    /// and to the left as the offset grows, like the content of a scrolled list.
+    pub(crate) fn with_scroll_offset(mut self, x: f32, y: f32) -> Self {
+        let columns = self.cell[2] / self.cell[0];
+        let rows = self.cell[3] / self.cell[1];
+        self.scroll = [x, y, x / columns, -y / rows];
+        self
+    }
+
+    /// Replaces the time with `time` seconds.
+    #[cfg(feature = "gpu")]
+    pub(crate) fn with_time(mut self, time: f32) -> Self {
+        self.time = time_input(time);
+        self
+    }
+
+    /// Sets the id of the rendered instance.
+    pub(crate) fn with_instance(mut self, id: u32) -> Self {
+        self.instance[0] = id;
+        self
+    }
+
+    /// The shift of `uv` caused by the scroll offset.
+    pub(crate) fn uv_offset(&self) -> [f32; 2] {
+        [self.scroll[2], self.scroll[3]]
+    }
+
+    /// Fills in the local date and time of day.
+    #[cfg(feature = "wall-clock")]
+    pub(crate) fn with_local_date(mut self) -> Self {
+        use chrono::{Datelike, Timelike};
+        let now = chrono::Local::now();
+        let seconds = now.num_seconds_from_midnight() as f32 + now.nanosecond() as f32 / 1e9;
+        self.date = [
+            seconds,
+            now.year() as f32,
+            now.month() as f32,
+            now.day() as f32,
+        ];
+        self
+    }
+
+    /// Replaces the size of the rect, for rendering at a different resolution than the cell grid.
+    pub(crate) fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.rect[2] = width;
+        self.rect[3] = height;
+        self
+    }
+
+    /// The width of the rendered area in pixels.
+    pub fn width(&self) -> u32 {
         self.rect[2]
     }
 
-    pub(crate) fn height(&self) -> u32 {
+    /// The height of the rendered area in pixels.
+    pub fn height(&self) -> u32 {
         self.rect[3]
     }
 }
@@ -41,6 +182,26 @@ impl Default for ShaderContext {
         Self {
             time: [0.0, 0.0, 0.0, 1.0],
             rect: [0, 0, 64, 64],
+            date: [0.0; 4],
+            cell: cell_input(DEFAULT_CELL_SIZE, 64, 64),
+            focus: [0.0; 4],
+            scroll: [0.0; 4],
+            instance: [0; 4],
         }
     }
 }
+
+fn time_input(time: f32) -> [f32; 4] {
+    [time, time * 10.0, time.sin(), time.cos()]
+}
+
+fn cell_input(cell_size: (u16, u16), columns: u16, rows: u16) -> [f32; 4] {
+    let width = f32::from(cell_size.0);
+    let height = f32::from(cell_size.1);
+    [
+        width,
+        height,
+        f32::from(columns) * width,
+        f32::from(rows) * height,
+    ]
+}
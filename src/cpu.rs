@@ -0,0 +1,108 @@
+use ratatui_core::layout::Rect;
+
+use crate::context::ShaderContext;
+use crate::{Pixel, row_padding};
+
+/// The input of [`CpuBackend::pixel`], describing the pixel to compute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PixelInput {
+    /// The x coordinate of the pixel, starting at the left.
+    pub x: u32,
+
+    /// The y coordinate of the pixel, starting at the top.
+    pub y: u32,
+
+    /// The normalized x coordinate of the center of the pixel.
+    pub u: f32,
+
+    /// The normalized y coordinate of the center of the pixel. Like the `uv` input of fragment shaders,
+    /// it starts at `0.0` at the bottom.
+    pub v: f32,
+
+    /// The time in seconds.
+    pub time: f32,
+
+    /// The width of the canvas in pixels.
+    pub width: u32,
+
+    /// The height of the canvas in pixels.
+    pub height: u32,
+
+    /// The id of the rendered instance, see [`ShaderCanvas::instance`](crate::ShaderCanvas::instance).
+    pub instance: u32,
+}
+
+/// [`CpuBackend`] computes the pixels of a [`ShaderCanvasState`](crate::ShaderCanvasState) on the CPU
+/// instead of running a fragment shader on the GPU. Use it with
+/// [`ShaderCanvasState::from_backend`](crate::ShaderCanvasState::from_backend).
+///
+/// It is implemented for closures, which is the easiest way to write an effect in Rust:
+///
+/// ```rust,no_run
+/// # use tui_shader::{PixelInput, ShaderCanvasState};
+/// let state = ShaderCanvasState::from_backend(|input: PixelInput| {
+///     let red = (input.u * 255.0) as u8;
+///     let blue = ((input.time.sin() * 0.5 + 0.5) * 255.0) as u8;
+///     [red, 0, blue, 255]
+/// });
+/// ```
+pub trait CpuBackend: Send {
+    /// Called once per frame before [`CpuBackend::pixel`] is called for every pixel of the canvas.
+    fn prepare(&mut self, _time: f32, _width: u32, _height: u32) {}
+
+    /// Computes the RGBA color of a single pixel.
+    fn pixel(&mut self, input: PixelInput) -> [u8; 4];
+
+    /// Sets a named parameter of the effect. Returns `false` if the backend doesn't know the parameter,
+    /// which is the default.
+    fn set_uniform(&mut self, _name: &str, _value: f32) -> bool {
+        false
+    }
+}
+
+impl<F: FnMut(PixelInput) -> [u8; 4] + Send> CpuBackend for F {
+    fn pixel(&mut self, input: PixelInput) -> [u8; 4] {
+        self(input)
+    }
+}
+
+/// Runs a [`CpuBackend`] for every pixel of the context's rect. The result uses the same row padding as
+/// the readback of a GPU texture.
+pub(crate) fn execute(backend: &mut dyn CpuBackend, ctx: ShaderContext) -> Vec<Pixel> {
+    let region = Rect::new(0, 0, ctx.width() as u16, ctx.height() as u16);
+    execute_region(backend, ctx, region)
+}
+
+/// Runs a [`CpuBackend`] for the pixels of `region` within the context's rect.
+pub(crate) fn execute_region(
+    backend: &mut dyn CpuBackend,
+    ctx: ShaderContext,
+    region: Rect,
+) -> Vec<Pixel> {
+    let width = ctx.width();
+    let height = ctx.height();
+    let time = ctx.time[0];
+    let [u_offset, v_offset] = ctx.uv_offset();
+    backend.prepare(time, width, height);
+    let region_width = u32::from(region.width);
+    let stride = (region_width + row_padding(region_width)) as usize;
+    let mut pixels = vec![[0; 4]; stride * usize::from(region.height)];
+    for position in region.positions() {
+        let x = u32::from(position.x);
+        let y = u32::from(position.y);
+        let input = PixelInput {
+            x,
+            y,
+            u: (x as f32 + 0.5) / width as f32 + u_offset,
+            v: 1.0 - (y as f32 + 0.5) / height as f32 + v_offset,
+            time,
+            width,
+            height,
+            instance: ctx.instance[0],
+        };
+        let index =
+            usize::from(position.y - region.y) * stride + usize::from(position.x - region.x);
+        pixels[index] = backend.pixel(input);
+    }
+    pixels
+}
@@ -0,0 +1,205 @@
+//! Easing functions mapping a linear progress between `0.0` and `1.0` onto a curve, and the [`Tween`]
+//! helper that drives a value over time.
+//!
+//! All functions clamp their input to the range `0.0..=1.0`.
+
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use crate::clock::ShaderClock;
+
+/// Easing curves used to interpolate between two values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed from start to end. This is the default value.
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+impl Easing {
+    /// Maps the progress `t` between `0.0` and `1.0` onto the easing curve. Values outside of this range
+    /// are clamped.
+    pub fn apply(&self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => linear(t),
+            Easing::QuadIn => quad_in(t),
+            Easing::QuadOut => quad_out(t),
+            Easing::QuadInOut => quad_in_out(t),
+            Easing::CubicIn => cubic_in(t),
+            Easing::CubicOut => cubic_out(t),
+            Easing::CubicInOut => cubic_in_out(t),
+            Easing::ElasticIn => elastic_in(t),
+            Easing::ElasticOut => elastic_out(t),
+            Easing::ElasticInOut => elastic_in_out(t),
+            Easing::BounceIn => bounce_in(t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => bounce_in_out(t),
+        }
+    }
+
+    /// Interpolates between `from` and `to` using the easing curve.
+    pub fn interpolate(&self, from: f32, to: f32, t: f32) -> f32 {
+        from + (to - from) * self.apply(t)
+    }
+}
+
+pub fn linear(t: f32) -> f32 {
+    t.clamp(0.0, 1.0)
+}
+
+pub fn quad_in(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t
+}
+
+pub fn quad_out(t: f32) -> f32 {
+    1.0 - quad_in(1.0 - t)
+}
+
+pub fn quad_in_out(t: f32) -> f32 {
+    in_out(t, quad_in)
+}
+
+pub fn cubic_in(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * t
+}
+
+pub fn cubic_out(t: f32) -> f32 {
+    1.0 - cubic_in(1.0 - t)
+}
+
+pub fn cubic_in_out(t: f32) -> f32 {
+    in_out(t, cubic_in)
+}
+
+pub fn elastic_in(t: f32) -> f32 {
+    1.0 - elastic_out(1.0 - t)
+}
+
+pub fn elastic_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t == 0.0 || t == 1.0 {
+        return t;
+    }
+    2.0_f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+}
+
+pub fn elastic_in_out(t: f32) -> f32 {
+    in_out(t, elastic_in)
+}
+
+pub fn bounce_in(t: f32) -> f32 {
+    1.0 - bounce_out(1.0 - t)
+}
+
+pub fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    let t = t.clamp(0.0, 1.0);
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+pub fn bounce_in_out(t: f32) -> f32 {
+    in_out(t, bounce_in)
+}
+
+fn in_out(t: f32, ease_in: fn(f32) -> f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        ease_in(t * 2.0) / 2.0
+    } else {
+        1.0 - ease_in((1.0 - t) * 2.0) / 2.0
+    }
+}
+
+/// [`Tween`] maps elapsed time to a value moving from `from` to `to` over a [`Duration`]. Because it is
+/// driven by time rather than by frames, animations run at the same speed regardless of the frame rate.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tui_shader::{Easing, ShaderCanvasState, Tween, WgslShader};
+/// let mut state = ShaderCanvasState::new(WgslShader::Path("slide_in.wgsl")).unwrap();
+/// let slide_in = Tween::new(0.0, 1.0, Duration::from_millis(400)).easing(Easing::CubicOut);
+/// let offset = slide_in.value(state.clock());
+/// state.set_uniform("offset", offset);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tween {
+    from: f32,
+    to: f32,
+    duration: Duration,
+    delay: Duration,
+    easing: Easing,
+}
+
+impl Tween {
+    /// Creates a new [`Tween`] with [`Easing::Linear`] and no delay.
+    pub fn new(from: f32, to: f32, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            delay: Duration::ZERO,
+            easing: Easing::Linear,
+        }
+    }
+
+    /// Applies an [`Easing`] to a [`Tween`].
+    #[must_use]
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Holds the start value for `delay` before the [`Tween`] starts moving.
+    #[must_use]
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// The value of the [`Tween`] after `elapsed` time.
+    pub fn value_at(&self, elapsed: Duration) -> f32 {
+        let elapsed = elapsed.saturating_sub(self.delay);
+        let t = if self.duration.is_zero() {
+            1.0
+        } else {
+            elapsed.as_secs_f32() / self.duration.as_secs_f32()
+        };
+        self.easing.interpolate(self.from, self.to, t)
+    }
+
+    /// The value of the [`Tween`] at the current time of a [`ShaderClock`].
+    pub fn value(&self, clock: &ShaderClock) -> f32 {
+        self.value_at(clock.elapsed())
+    }
+
+    /// Returns `true` once the [`Tween`] has reached its end value.
+    pub fn is_finished(&self, clock: &ShaderClock) -> bool {
+        clock.elapsed() >= self.delay + self.duration
+    }
+}
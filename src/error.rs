@@ -0,0 +1,71 @@
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "gpu")]
+use crate::{Pixel, row_padding};
+
+/// A failure while executing a shader on the GPU, reported to the callbacks registered with
+/// [`ShaderCanvasState::on_error`](crate::ShaderCanvasState::on_error), or while requesting a device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TuiShaderError {
+    /// No GPU device could be created, e.g. because there is no adapter. Lists the error of every
+    /// attempted backend. Returned by the fallible constructors of the widget states, like
+    /// [`ParticleCanvasState::try_new`](crate::ParticleCanvasState::try_new).
+    NoDevice(String),
+    /// wgpu has no backend to request a device from: none of the `vulkan`, `metal`, `dx12` and `gl`
+    /// features is enabled, or the `WGPU_BACKEND` environment variable only names backends which aren't.
+    NoBackend(String),
+    /// The GPU device was lost, e.g. because the driver crashed or was updated. The state can't render
+    /// anymore and has to be created again.
+    DeviceLost(String),
+    /// wgpu rejected the work submitted for a frame, e.g. because of a validation error or because the
+    /// GPU ran out of memory.
+    Render(String),
+    /// Mapping the buffer holding the rendered pixels for reading failed.
+    Readback(String),
+    /// The GPU didn't finish the frame within the deadline set with
+    /// `ShaderCanvasState::set_frame_deadline`, e.g. because the shader loops forever. The device was
+    /// destroyed to stop the work, so the state fails with [`TuiShaderError::DeviceLost`] from now on
+    /// unless it migrates to a new device.
+    Timeout(Duration),
+}
+
+impl fmt::Display for TuiShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoDevice(attempts) => write!(f, "unable to create a GPU device, {attempts}"),
+            Self::NoBackend(reason) => write!(f, "no wgpu backend is available, {reason}"),
+            Self::DeviceLost(reason) => write!(f, "the GPU device was lost: {reason}"),
+            Self::Render(message) => write!(f, "rendering the shader failed: {message}"),
+            Self::Readback(message) => write!(f, "reading the rendered pixels failed: {message}"),
+            Self::Timeout(deadline) => {
+                write!(f, "the GPU didn't finish the frame within {deadline:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TuiShaderError {}
+
+/// Returns the pixels of a successful execution, or passes its error to the `on_error` callbacks of a
+/// state and returns transparent pixels of `width` x `height` instead. Panics if there are no callbacks.
+#[cfg(feature = "gpu")]
+pub(crate) fn recover(
+    result: Result<Vec<Pixel>, TuiShaderError>,
+    callbacks: &[fn(TuiShaderError)],
+    width: u32,
+    height: u32,
+) -> Vec<Pixel> {
+    match result {
+        Ok(pixels) => pixels,
+        Err(error) if callbacks.is_empty() => panic!("{error}"),
+        Err(error) => {
+            for callback in callbacks {
+                callback(error.clone());
+            }
+            let stride = (width + row_padding(width)) as usize;
+            vec![[0; 4]; stride * height as usize]
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::{Mutex, PoisonError};
+
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::{StatefulWidget, Widget};
+
+use crate::canvas::ShaderCanvas;
+use crate::state::ShaderCanvasState;
+
+/// [`ShaderFill`] is a [`Widget`] filling its area with a shader. Unlike [`ShaderCanvas`], it owns its
+/// [`ShaderCanvasState`] behind a [`Mutex`], so it can be rendered where no `&mut state` can be passed
+/// down, e.g. from widget trees built of `Box<dyn Widget>`, or in quick scripts.
+///
+/// ```rust,no_run
+/// # use tui_shader::{ShaderCanvasState, ShaderFill};
+/// let mut terminal = ratatui::init();
+/// let fill = ShaderFill::new(ShaderCanvasState::default());
+/// terminal.draw(|frame| {
+///     frame.render_widget(&fill, frame.area());
+/// }).unwrap();
+/// ratatui::restore();
+/// ```
+#[derive(Debug)]
+pub struct ShaderFill {
+    canvas: ShaderCanvas,
+    state: Mutex<ShaderCanvasState>,
+}
+
+impl ShaderFill {
+    /// Creates a new [`ShaderFill`] rendering `state` with a default [`ShaderCanvas`].
+    pub fn new(state: ShaderCanvasState) -> Self {
+        Self {
+            canvas: ShaderCanvas::new(),
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Sets the [`ShaderCanvas`] mapping the output of the shader to cells.
+    #[must_use]
+    pub fn canvas(mut self, canvas: ShaderCanvas) -> Self {
+        self.canvas = canvas;
+        self
+    }
+
+    /// Calls `f` with the state, e.g. to set uniforms between renders.
+    pub fn with_state<R>(&self, f: impl FnOnce(&mut ShaderCanvasState) -> R) -> R {
+        f(&mut self.state.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Returns the state, consuming the [`ShaderFill`].
+    pub fn into_state(self) -> ShaderCanvasState {
+        self.state
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for ShaderFill {
+    fn default() -> Self {
+        Self::new(ShaderCanvasState::default())
+    }
+}
+
+impl Widget for ShaderFill {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Widget::render(&self, area, buf);
+    }
+}
+
+impl Widget for &ShaderFill {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        StatefulWidget::render(&self.canvas, area, buf, &mut state);
+    }
+}
+
+#[cfg(feature = "widget-ref")]
+impl ratatui::widgets::WidgetRef for ShaderFill {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        Widget::render(self, area, buf);
+    }
+}
@@ -0,0 +1,25 @@
+use font8x8::{
+    BASIC_FONTS, BLOCK_FONTS, BOX_FONTS, GREEK_FONTS, HIRAGANA_FONTS, LATIN_FONTS, MISC_FONTS,
+    UnicodeFonts,
+};
+
+/// Width and height of a glyph of the embedded bitmap font in pixels.
+pub(crate) const GLYPH_SIZE: u32 = 8;
+
+/// Looks up the 8x8 bitmap of a character in the embedded font. Every byte is a row from top to bottom,
+/// where the least significant bit is the leftmost pixel.
+pub(crate) fn glyph(character: char) -> Option<[u8; 8]> {
+    BASIC_FONTS
+        .get(character)
+        .or_else(|| LATIN_FONTS.get(character))
+        .or_else(|| BLOCK_FONTS.get(character))
+        .or_else(|| BOX_FONTS.get(character))
+        .or_else(|| GREEK_FONTS.get(character))
+        .or_else(|| HIRAGANA_FONTS.get(character))
+        .or_else(|| MISC_FONTS.get(character))
+}
+
+/// Returns `true` if the pixel at `x`, `y` of the glyph is set.
+pub(crate) fn glyph_pixel(glyph: &[u8; 8], x: u32, y: u32) -> bool {
+    glyph[y as usize] & (1 << x) != 0
+}
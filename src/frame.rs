@@ -0,0 +1,194 @@
+use ratatui_core::layout::Rect;
+
+use crate::{Pixel, row_padding};
+
+/// The pixels produced by the most recent execution of a shader, as read back from the GPU. Rows are
+/// padded to satisfy the copy alignment of wgpu.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Frame {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) pixels: Vec<Pixel>,
+}
+
+impl Frame {
+    pub(crate) fn new(width: u32, height: u32, pixels: Vec<Pixel>) -> Self {
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn stride(&self) -> usize {
+        (self.width + row_padding(self.width)) as usize
+    }
+
+    /// Iterates over the rows of the frame without the row padding.
+    pub(crate) fn rows(&self) -> impl Iterator<Item = &[Pixel]> {
+        let width = self.width as usize;
+        self.pixels
+            .chunks(self.stride().max(1))
+            .take(self.height as usize)
+            .map(move |row| &row[..width])
+    }
+
+    /// Copies the pixels into a tightly packed RGBA byte buffer.
+    pub(crate) fn to_rgba(&self) -> Vec<u8> {
+        self.rows()
+            .flat_map(|row| row.iter().flatten().copied())
+            .collect()
+    }
+
+    /// Overwrites the pixels of `region` with the row padded `pixels` of a render of just that region.
+    pub(crate) fn patch(&mut self, region: Rect, pixels: &[Pixel]) {
+        let stride = self.stride();
+        let region_width = u32::from(region.width);
+        let region_stride = (region_width + row_padding(region_width)) as usize;
+        let width = usize::from(region.width);
+        for (row, region_row) in pixels.chunks(region_stride.max(1)).enumerate() {
+            let start = (usize::from(region.y) + row) * stride + usize::from(region.x);
+            self.pixels[start..start + width].copy_from_slice(&region_row[..width]);
+        }
+    }
+
+    /// Blends the pixels of `self` towards `other` by `t`, `None` if the frames differ in size.
+    pub(crate) fn lerp(&self, other: &Frame, t: f32) -> Option<Frame> {
+        if self.width != other.width || self.height != other.height {
+            return None;
+        }
+        let pixels = self
+            .pixels
+            .iter()
+            .zip(&other.pixels)
+            .map(|(a, b)| {
+                std::array::from_fn(|i| {
+                    (f32::from(a[i]) + (f32::from(b[i]) - f32::from(a[i])) * t).round() as u8
+                })
+            })
+            .collect();
+        Some(Frame::new(self.width, self.height, pixels))
+    }
+
+    pub(crate) fn pixel(&self, x: u32, y: u32) -> Pixel {
+        self.pixels[y as usize * self.stride() + x as usize]
+    }
+
+    /// Samples the color of the cell at `x`, `y` of a grid of `columns` x `rows` cells covering the frame.
+    /// Frames larger than the grid are averaged over the pixels covered by the cell, smaller frames are
+    /// interpolated bilinearly.
+    pub(crate) fn sample_cell(&self, x: u16, y: u16, columns: u16, rows: u16) -> Pixel {
+        if self.width == u32::from(columns) && self.height == u32::from(rows) {
+            return self.pixel(x.into(), y.into());
+        }
+        let scale_x = self.width as f32 / f32::from(columns);
+        let scale_y = self.height as f32 / f32::from(rows);
+        if scale_x >= 1.0 && scale_y >= 1.0 {
+            let (left, right) = covered_pixels(x, scale_x, self.width);
+            let (top, bottom) = covered_pixels(y, scale_y, self.height);
+            let mut sum = [0u32; 4];
+            for y in top..bottom {
+                for x in left..right {
+                    for (sum, channel) in sum.iter_mut().zip(self.pixel(x, y)) {
+                        *sum += u32::from(channel);
+                    }
+                }
+            }
+            let count = (right - left) * (bottom - top);
+            return sum.map(|sum| ((sum + count / 2) / count) as u8);
+        }
+        let (left, right, tx) = neighbours(x, scale_x, self.width);
+        let (top, bottom, ty) = neighbours(y, scale_y, self.height);
+        let lerp = |a: Pixel, b: Pixel, t: f32| -> [f32; 4] {
+            std::array::from_fn(|i| f32::from(a[i]) * (1.0 - t) + f32::from(b[i]) * t)
+        };
+        let upper = lerp(self.pixel(left, top), self.pixel(right, top), tx);
+        let lower = lerp(self.pixel(left, bottom), self.pixel(right, bottom), tx);
+        std::array::from_fn(|i| (upper[i] * (1.0 - ty) + lower[i] * ty).round() as u8)
+    }
+}
+
+/// [`FrameView`] borrows the pixels of the most recent render of a
+/// [`ShaderCanvasState`](crate::ShaderCanvasState), see
+/// [`ShaderCanvasState::last_frame`](crate::ShaderCanvasState::last_frame). Pixels are RGBA colors with
+/// the origin in the top left corner.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameView<'a> {
+    frame: &'a Frame,
+}
+
+impl<'a> FrameView<'a> {
+    pub(crate) fn new(frame: &'a Frame) -> Self {
+        Self { frame }
+    }
+
+    /// The width of the frame in pixels.
+    pub fn width(&self) -> u32 {
+        self.frame.width
+    }
+
+    /// The height of the frame in pixels.
+    pub fn height(&self) -> u32 {
+        self.frame.height
+    }
+
+    /// All pixels as read back from the GPU. Each row is followed by padding to satisfy the copy
+    /// alignment of wgpu, so rows start [`FrameView::stride`] pixels apart. Use [`FrameView::rows`] or
+    /// [`FrameView::pixel`] to skip the padding.
+    pub fn pixels(&self) -> &'a [[u8; 4]] {
+        &self.frame.pixels
+    }
+
+    /// The distance between the starts of two rows in [`FrameView::pixels`], in pixels.
+    pub fn stride(&self) -> usize {
+        self.frame.stride()
+    }
+
+    /// Iterates over the rows of the frame from top to bottom, without the row padding.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [[u8; 4]]> + use<'a> {
+        self.frame.rows()
+    }
+
+    /// The pixel at `x`, `y`, or `None` if it lies outside of the frame.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        (x < self.frame.width && y < self.frame.height).then(|| self.frame.pixel(x, y))
+    }
+
+    /// Copies the pixels into a tightly packed RGBA byte buffer without row padding.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.frame.to_rgba()
+    }
+
+    /// Copies the pixels into an [`RgbaImage`](image::RgbaImage), e.g. for further image processing or
+    /// a custom encoder.
+    #[cfg(feature = "image")]
+    pub fn to_image(&self) -> image::RgbaImage {
+        image::RgbaImage::from_raw(self.width(), self.height(), self.to_rgba())
+            .expect("the frame matches its dimensions")
+    }
+
+    /// Copies the pixels into an array of shape `(height, width, 4)`, indexed by row, column and RGBA
+    /// channel.
+    #[cfg(feature = "ndarray")]
+    pub fn to_ndarray(&self) -> ndarray::Array3<u8> {
+        let shape = (self.height() as usize, self.width() as usize, 4);
+        ndarray::Array3::from_shape_vec(shape, self.to_rgba())
+            .expect("the frame matches its dimensions")
+    }
+}
+
+/// The range of pixels covered by the cell at `index` along one axis.
+fn covered_pixels(index: u16, scale: f32, size: u32) -> (u32, u32) {
+    let start = ((f32::from(index) * scale) as u32).min(size - 1);
+    let end = ((f32::from(index + 1) * scale).ceil() as u32).clamp(start + 1, size);
+    (start, end)
+}
+
+/// The two pixels around the center of the cell at `index` along one axis and the interpolation factor
+/// between them.
+fn neighbours(index: u16, scale: f32, size: u32) -> (u32, u32, f32) {
+    let center = ((f32::from(index) + 0.5) * scale - 0.5).clamp(0.0, (size - 1) as f32);
+    let first = center.floor() as u32;
+    let second = (first + 1).min(size - 1);
+    (first, second, center.fract())
+}
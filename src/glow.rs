@@ -0,0 +1,166 @@
+use std::error::Error;
+
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::{Position, Rect};
+use ratatui_core::style::Color;
+use ratatui_core::widgets::StatefulWidget;
+
+use crate::clock::ShaderClock;
+use crate::state::ShaderCanvasState;
+use crate::util::color_to_rgb;
+
+const FOCUS_GLOW_SHADER_DESCRIPTOR: wgpu::ShaderModuleDescriptor =
+    wgpu::include_wgsl!("shaders/focus_glow.wgsl");
+
+/// [`FocusGlow`] draws an animated halo just outside of the focused pane, a focus indicator which
+/// doesn't require writing a shader. Render it after the panes, into the area the halo may extend into,
+/// e.g. the whole frame.
+///
+/// The halo only tints the background of the cells around the focused rect, blended by the alpha of
+/// the glow, so borders and text drawn there stay visible. Cells inside the focused rect are left
+/// untouched.
+///
+/// ```rust,no_run
+/// # use ratatui::layout::{Constraint, Layout};
+/// # use ratatui::widgets::Block;
+/// # use tui_shader::{FocusGlow, FocusGlowState};
+/// let mut terminal = ratatui::init();
+/// let mut glow = FocusGlowState::default();
+/// terminal.draw(|frame| {
+///     let [left, right] = Layout::horizontal([Constraint::Fill(1); 2]).margin(2).areas(frame.area());
+///     frame.render_widget(Block::bordered(), left);
+///     frame.render_widget(Block::bordered(), right);
+///     frame.render_stateful_widget(FocusGlow::new(left), frame.area(), &mut glow);
+/// }).unwrap();
+/// ratatui::restore();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FocusGlow {
+    focused: Rect,
+    color: Color,
+    radius: u16,
+    speed: f32,
+}
+
+impl FocusGlow {
+    /// Creates a new [`FocusGlow`] around `focused`, in the coordinates of the [`Buffer`].
+    pub fn new(focused: Rect) -> Self {
+        Self {
+            focused,
+            color: Color::Rgb(80, 160, 255),
+            radius: 3,
+            speed: 1.0,
+        }
+    }
+
+    /// Sets the color of the halo.
+    #[must_use]
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets how far the halo reaches beyond the focused rect, in columns. It reaches half as many rows,
+    /// since cells are about twice as high as they are wide.
+    #[must_use]
+    pub fn radius(mut self, radius: u16) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets the speed of the highlight running around the focused rect, `0.0` stops it.
+    #[must_use]
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// The part of `area` the halo covers.
+    fn halo(&self, area: Rect) -> Rect {
+        let rows = self.radius.div_ceil(2);
+        let x = self.focused.x.saturating_sub(self.radius);
+        let y = self.focused.y.saturating_sub(rows);
+        let right = self.focused.right().saturating_add(self.radius);
+        let bottom = self.focused.bottom().saturating_add(rows);
+        Rect::new(x, y, right - x, bottom - y).intersection(area)
+    }
+}
+
+/// [`FocusGlowState`] holds the shader rendering a [`FocusGlow`] and the clock animating it.
+#[derive(Debug, Clone)]
+pub struct FocusGlowState {
+    state: ShaderCanvasState,
+}
+
+impl FocusGlowState {
+    /// Creates a new [`FocusGlowState`], compiling the built-in glow shader.
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            state: ShaderCanvasState::new_with_entry_point(FOCUS_GLOW_SHADER_DESCRIPTOR, "main")?,
+        })
+    }
+
+    /// The [`ShaderClock`] animating the glow.
+    pub fn clock_mut(&mut self) -> &mut ShaderClock {
+        self.state.clock_mut()
+    }
+}
+
+impl Default for FocusGlowState {
+    fn default() -> Self {
+        Self::new().unwrap()
+    }
+}
+
+impl StatefulWidget for FocusGlow {
+    type State = FocusGlowState;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(&self, area, buf, state);
+    }
+}
+
+impl StatefulWidget for &FocusGlow {
+    type State = FocusGlowState;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let halo = self.halo(area.intersection(buf.area));
+        if halo.is_empty() {
+            return;
+        }
+        let focus = [
+            f32::from(self.focused.x) - f32::from(halo.x),
+            f32::from(self.focused.y) - f32::from(halo.y),
+            f32::from(self.focused.width),
+            f32::from(self.focused.height),
+        ];
+        state
+            .state
+            .uniforms()
+            .set("color", self.color)
+            .set("focus", focus)
+            .set("radius", f32::from(self.radius))
+            .set("speed", self.speed);
+        let glow = color_to_rgb(self.color).unwrap_or([255; 3]);
+        for sample in state.state.samples(halo) {
+            let position = Position::new(halo.x + sample.x(), halo.y + sample.y());
+            if sample.a() == 0 || self.focused.contains(position) {
+                continue;
+            }
+            let alpha = f32::from(sample.a()) / 255.0;
+            let cell = &mut buf[position];
+            let below = color_to_rgb(cell.bg).unwrap_or([0; 3]);
+            let [r, g, b] = std::array::from_fn(|channel| {
+                let below = f32::from(below[channel]);
+                (below + (f32::from(glow[channel]) - below) * alpha).round() as u8
+            });
+            cell.set_bg(Color::Rgb(r, g, b));
+        }
+    }
+}
+
+#[cfg(feature = "widget-ref")]
+impl ratatui::widgets::StatefulWidgetRef for FocusGlow {
+    type State = FocusGlowState;
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(self, area, buf, state);
+    }
+}
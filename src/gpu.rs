@@ -0,0 +1,1915 @@
+//! Selecting the GPU shaders run on. By default, wgpu picks an adapter, which is usually the most
+//! capable GPU. Multi-GPU systems can list their adapters with [`enumerate_adapters`] and create states
+//! on a specific one with [`ShaderCanvasStateBuilder::adapter`](crate::ShaderCanvasStateBuilder::adapter),
+//! e.g. to keep a status bar on the integrated GPU, or list them in order of preference with
+//! [`AdapterSelection::Ordered`]. [`DeviceOptions`] also bounds how long requesting a device may take,
+//! retries with other backends if it fails and can move states to another adapter when theirs
+//! disappears.
+
+use bytemuck::NoUninit;
+use ratatui_core::layout::Rect;
+use std::error::Error;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+use wgpu::{ExperimentalFeatures, util::DeviceExt};
+
+use crate::bindings::{BindingSlot, BindingsBuilder, TextureKind, UserBindings};
+use crate::context::ShaderContext;
+use crate::error::{self, TuiShaderError};
+use crate::handle::{CompiledShader, ShaderHandle};
+use crate::memory::{MemoryTracker, MemoryUsage, texture_bytes};
+use crate::telemetry::Telemetry;
+use crate::uniforms::{USER_UNIFORMS_BINDING, UniformLayout, UniformValue};
+use crate::{Pixel, bytes_per_row, row_padding};
+
+pub(crate) const DEFAULT_SIZE: u32 = 64;
+pub(crate) const VERTEX_SHADER_DESCRIPTOR: wgpu::ShaderModuleDescriptor =
+    wgpu::include_wgsl!("shaders/fullscreen_vertex.wgsl");
+/// The shader of [`crate::ShaderCanvasState::default`].
+pub(crate) const DEFAULT_FRAGMENT_SHADER_DESCRIPTOR: wgpu::ShaderModuleDescriptor =
+    wgpu::include_wgsl!("shaders/default_fragment.wgsl");
+const TILED_VERTEX_SHADER_DESCRIPTOR: wgpu::ShaderModuleDescriptor =
+    wgpu::include_wgsl!("shaders/tiled_vertex.wgsl");
+/// Binding of the uv transform of the current tile in the vertex shader.
+pub(crate) const TILE_BINDING: u32 = 3;
+/// Binding of the local date and time of day, see [`crate::ShaderCanvasState::set_wall_clock`].
+const DATE_BINDING: u32 = 4;
+/// Binding of the size of a cell in screen pixels, see [`crate::ShaderCanvasState::set_cell_size`].
+const CELL_BINDING: u32 = 5;
+/// Binding of the focus point, see [`crate::ShaderCanvasState::set_focus_point`].
+const FOCUS_BINDING: u32 = 6;
+/// Binding of the scroll offset, see [`crate::ShaderCanvas::scroll_offset`].
+const SCROLL_BINDING: u32 = 7;
+/// Binding of the instance id, see [`crate::ShaderCanvas::instance`].
+const INSTANCE_BINDING: u32 = 8;
+/// The uv transform covering the whole area.
+const FULL_TILE: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+/// The number of samples per pixel when multisampling is enabled.
+pub(crate) const MSAA_SAMPLE_COUNT: u32 = 4;
+
+/// Runs a fragment shader on the GPU and reads the rendered texture back.
+#[derive(Debug)]
+pub(crate) struct GpuBackend {
+    shader: Arc<CompiledShader>,
+    texture: wgpu::Texture,
+    readback_pool: ReadbackPool,
+    time_buffer: wgpu::Buffer,
+    rect_buffer: wgpu::Buffer,
+    user_uniform_buffer: wgpu::Buffer,
+    tile_buffer: wgpu::Buffer,
+    date_buffer: wgpu::Buffer,
+    cell_buffer: wgpu::Buffer,
+    focus_buffer: wgpu::Buffer,
+    scroll_buffer: wgpu::Buffer,
+    instance_buffer: wgpu::Buffer,
+    multisampled_texture: Option<wgpu::Texture>,
+    /// The inputs and pixels of the last execution, reused while the inputs don't change.
+    cache: Option<(ShaderContext, Vec<Pixel>)>,
+    /// The frame rendered ahead of time by [`GpuBackend::prerender`].
+    prerender: Option<Prerender>,
+    user_uniforms: Vec<u8>,
+    user_bindings: UserBindings,
+    bind_groups: Vec<wgpu::BindGroup>,
+    width: u32,
+    height: u32,
+    /// The counters of the work done by the backend, see [`Telemetry`].
+    telemetry: Telemetry,
+    /// How long reading back a frame may wait for the GPU, see [`GpuBackend::set_deadline`].
+    deadline: Option<Duration>,
+    /// The share of the backend in [`crate::total_memory_usage`].
+    memory: MemoryTracker,
+}
+
+/// Clones share the compiled shader, but get their own buffers and textures. The contents of the
+/// uniforms and bindings are copied, so changing them afterwards only affects one of the states.
+impl Clone for GpuBackend {
+    fn clone(&self) -> Self {
+        let user_bindings = self
+            .user_bindings
+            .duplicate(&self.shader.device, &self.shader.queue);
+        let mut backend = Self::from_shader(Arc::clone(&self.shader), user_bindings);
+        backend.set_msaa(self.multisampled_texture.is_some());
+        backend.user_uniforms = self.user_uniforms.clone();
+        backend.cache = self.cache.clone();
+        backend.telemetry = self.telemetry;
+        backend.deadline = self.deadline;
+        backend
+    }
+}
+
+/// Waits for the work submitted by the state before its resources are freed, so no buffer is dropped
+/// while it is still mapped or in use. Some drivers crash or print validation messages into the restored
+/// terminal when the process exits in that case.
+impl Drop for GpuBackend {
+    fn drop(&mut self) {
+        let _ = self.shader.device.poll(wgpu::PollType::wait_indefinitely());
+    }
+}
+
+impl GpuBackend {
+    #[allow(clippy::needless_lifetimes)]
+    pub(crate) async fn new<'a>(
+        fragment_shader_descriptor: wgpu::ShaderModuleDescriptor<'a>,
+        entry_point: Option<&str>,
+        bindings: &BindingsBuilder,
+        options: &DeviceOptions,
+    ) -> Result<Self, Box<dyn Error>> {
+        let device = request_watched_device(options)?;
+        let user_bindings = UserBindings::new(&device.device, &device.queue, bindings);
+        Ok(Self::compile(
+            device,
+            fragment_shader_descriptor,
+            entry_point,
+            bindings,
+            user_bindings,
+        ))
+    }
+
+    /// Whether the state moves to another device when its device is lost, see
+    /// [`DeviceOptions::migrate`].
+    pub(crate) fn migrates(&self) -> bool {
+        self.shader.device_options.migrate && self.shader.source.is_some()
+    }
+
+    /// Compiles the shader again on a new device, requested with the options of the lost one, so the
+    /// next adapter of an [`AdapterSelection::Ordered`] takes over once the preferred one disappeared.
+    /// The uniforms and multisampling are kept, the bindings start over with the contents they were
+    /// created with, since the textures of the lost device can't be read anymore.
+    pub(crate) fn migrate(&self) -> Result<Self, Box<dyn Error>> {
+        let source = self
+            .shader
+            .source
+            .clone()
+            .ok_or("the source of the shader can't be compiled again")?;
+        let device = request_watched_device(&self.shader.device_options)?;
+        let bindings = &self.shader.bindings;
+        let user_bindings = UserBindings::new(&device.device, &device.queue, bindings);
+        let mut backend = Self::compile(
+            device,
+            wgpu::ShaderModuleDescriptor {
+                label: None,
+                source,
+            },
+            self.shader.entry_point.as_deref(),
+            bindings,
+            user_bindings,
+        );
+        backend.set_msaa(self.multisampled_texture.is_some());
+        backend.deadline = self.deadline;
+        if backend.shader.uniform_layout == self.shader.uniform_layout {
+            backend.user_uniforms = self.user_uniforms.clone();
+        }
+        Ok(backend)
+    }
+
+    /// Compiles a shader for `device` and creates the resources of a state executing it.
+    fn compile(
+        device: WatchedDevice,
+        fragment_shader_descriptor: wgpu::ShaderModuleDescriptor<'_>,
+        entry_point: Option<&str>,
+        bindings: &BindingsBuilder,
+        user_bindings: UserBindings,
+    ) -> Self {
+        let WatchedDevice {
+            device,
+            queue,
+            lost,
+            options: device_options,
+        } = device;
+        let source = owned_source(&fragment_shader_descriptor.source);
+        let vertex_shader = device.create_shader_module(TILED_VERTEX_SHADER_DESCRIPTOR);
+        let uniform_layout = UniformLayout::reflect(&fragment_shader_descriptor.source);
+        let fragment_shader = device.create_shader_module(fragment_shader_descriptor);
+        let bind_group_layouts = user_bindings.create_layouts(&device, &builtin_layout_entries());
+        let pipeline_layout = create_pipeline_layout(&device, &bind_group_layouts);
+        let pipeline = create_render_pipeline(
+            &device,
+            &pipeline_layout,
+            &vertex_shader,
+            &fragment_shader,
+            entry_point,
+            1,
+        );
+        let shader = CompiledShader {
+            device,
+            queue,
+            pipeline_layout,
+            vertex_shader,
+            fragment_shader,
+            entry_point: entry_point.map(String::from),
+            uniform_layout,
+            bindings: bindings.clone(),
+            bind_group_layouts,
+            pipeline,
+            multisampled_pipeline: OnceLock::new(),
+            lost,
+            source,
+            device_options,
+        };
+        Self::from_shader(Arc::new(shader), user_bindings)
+    }
+
+    /// Compiles another shader on the device of this backend, with the same bindings and their contents,
+    /// multisampling and, if the layouts of their uniforms match, the same uniforms. Errors in the shader
+    /// are returned instead of being raised by the device.
+    pub(crate) async fn compile_candidate(
+        &self,
+        fragment_shader_descriptor: wgpu::ShaderModuleDescriptor<'_>,
+        entry_point: Option<&str>,
+    ) -> Result<Self, TuiShaderError> {
+        let device = &self.shader.device;
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let user_bindings = self.user_bindings.duplicate(device, &self.shader.queue);
+        let mut candidate = Self::compile(
+            WatchedDevice {
+                device: device.clone(),
+                queue: self.shader.queue.clone(),
+                lost: Arc::clone(&self.shader.lost),
+                options: self.shader.device_options.clone(),
+            },
+            fragment_shader_descriptor,
+            entry_point,
+            &self.shader.bindings,
+            user_bindings,
+        );
+        if let Some(error) = device.pop_error_scope().await {
+            return Err(TuiShaderError::Render(error.to_string()));
+        }
+        candidate.set_msaa(self.multisampled_texture.is_some());
+        candidate.deadline = self.deadline;
+        if candidate.shader.uniform_layout == self.shader.uniform_layout {
+            candidate.user_uniforms = self.user_uniforms.clone();
+        }
+        Ok(candidate)
+    }
+
+    /// Creates the buffers and textures of a single state for an already compiled shader.
+    fn from_shader(shader: Arc<CompiledShader>, user_bindings: UserBindings) -> Self {
+        let device = &shader.device;
+        let texture = create_texture(device, DEFAULT_SIZE, DEFAULT_SIZE);
+        let readback_pool = ReadbackPool::default();
+        let ctx = ShaderContext::default();
+        let time_buffer = create_input_buffer(device, ctx.time);
+        let rect_buffer = create_input_buffer(device, ctx.rect);
+        let user_uniforms = vec![0; shader.uniform_layout.buffer_size()];
+        let user_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: user_uniforms.len() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let tile_buffer = create_input_buffer(device, FULL_TILE);
+        let date_buffer = create_input_buffer(device, ctx.date);
+        let cell_buffer = create_input_buffer(device, ctx.cell);
+        let focus_buffer = create_input_buffer(device, ctx.focus);
+        let scroll_buffer = create_input_buffer(device, ctx.scroll);
+        let instance_buffer = create_input_buffer(device, ctx.instance);
+        let mut backend = Self {
+            texture,
+            readback_pool,
+            time_buffer,
+            rect_buffer,
+            user_uniform_buffer,
+            tile_buffer,
+            date_buffer,
+            cell_buffer,
+            focus_buffer,
+            scroll_buffer,
+            instance_buffer,
+            multisampled_texture: None,
+            cache: None,
+            prerender: None,
+            user_uniforms,
+            user_bindings,
+            bind_groups: Vec::new(),
+            width: DEFAULT_SIZE,
+            height: DEFAULT_SIZE,
+            telemetry: Telemetry::default(),
+            deadline: None,
+            memory: MemoryTracker::default(),
+            shader,
+        };
+        backend.create_bind_groups();
+        backend.track_memory();
+        backend
+    }
+
+    fn create_bind_groups(&mut self) {
+        self.bind_groups = self.user_bindings.create_bind_groups(
+            &self.shader.device,
+            &self.shader.bind_group_layouts,
+            &[
+                &self.time_buffer,
+                &self.rect_buffer,
+                &self.user_uniform_buffer,
+                &self.tile_buffer,
+                &self.date_buffer,
+                &self.cell_buffer,
+                &self.focus_buffer,
+                &self.scroll_buffer,
+                &self.instance_buffer,
+            ],
+        );
+    }
+
+    pub(crate) fn set_msaa(&mut self, enabled: bool) {
+        if enabled == self.multisampled_texture.is_some() {
+            return;
+        }
+        self.invalidate();
+        if enabled {
+            self.telemetry.texture_recreations += 1;
+        }
+        self.multisampled_texture = enabled.then(|| {
+            create_multisampled_texture(
+                &self.shader.device,
+                self.texture.width(),
+                self.texture.height(),
+            )
+        });
+        self.track_memory();
+    }
+
+    pub(crate) fn from_handle(handle: &ShaderHandle) -> Self {
+        let shader = Arc::clone(&handle.shader);
+        let user_bindings = UserBindings::new(&shader.device, &shader.queue, &shader.bindings);
+        Self::from_shader(shader, user_bindings)
+    }
+
+    /// Destroys the textures and buffers of the state, which also unmaps them, and waits for the GPU to
+    /// finish the work using them. The compiled shader and its device are shared with other states and
+    /// left alone.
+    pub(crate) fn destroy(&self) {
+        for buffer in [
+            &self.time_buffer,
+            &self.rect_buffer,
+            &self.user_uniform_buffer,
+            &self.tile_buffer,
+            &self.date_buffer,
+            &self.cell_buffer,
+            &self.focus_buffer,
+            &self.scroll_buffer,
+            &self.instance_buffer,
+        ] {
+            buffer.destroy();
+        }
+        self.texture.destroy();
+        if let Some(texture) = &self.multisampled_texture {
+            texture.destroy();
+        }
+        self.readback_pool.destroy();
+        if let Some(prerender) = &self.prerender {
+            prerender.buffer.destroy();
+        }
+        self.user_bindings.destroy();
+        let _ = self.shader.device.poll(wgpu::PollType::wait_indefinitely());
+    }
+
+    /// Destroys the render textures, the readback buffers and the cached frames, replacing the textures
+    /// with single pixel ones. The next frame grows them again in [`Self::begin_frame`]. The uniform
+    /// buffers and the bindings hold the inputs of the shader and are kept.
+    pub(crate) fn suspend(&mut self) {
+        self.invalidate();
+        let device = &self.shader.device;
+        self.texture.destroy();
+        self.texture = create_texture(device, 1, 1);
+        if let Some(texture) = &mut self.multisampled_texture {
+            texture.destroy();
+            *texture = create_multisampled_texture(device, 1, 1);
+        }
+        self.readback_pool.destroy();
+        self.readback_pool = ReadbackPool::default();
+        self.track_memory();
+    }
+
+    pub(crate) fn telemetry(&self) -> Telemetry {
+        self.telemetry
+    }
+
+    /// Returns the counters and starts over, when they are handed over to the state.
+    pub(crate) fn take_telemetry(&mut self) -> Telemetry {
+        std::mem::take(&mut self.telemetry)
+    }
+
+    /// Limits how long reading back a frame waits for the GPU, see
+    /// [`crate::ShaderCanvasState::set_frame_deadline`].
+    pub(crate) fn set_deadline(&mut self, deadline: Option<Duration>) {
+        self.deadline = deadline;
+    }
+
+    pub(crate) fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    /// The bytes of the textures and buffers of the backend and of the pixels it caches. The compiled
+    /// shader is shared and not counted.
+    pub(crate) fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = self.user_bindings.memory_usage();
+        usage.textures += texture_bytes(&self.texture);
+        if let Some(texture) = &self.multisampled_texture {
+            usage.textures += texture_bytes(texture);
+        }
+        usage.buffers += [
+            &self.time_buffer,
+            &self.rect_buffer,
+            &self.user_uniform_buffer,
+            &self.tile_buffer,
+            &self.date_buffer,
+            &self.cell_buffer,
+            &self.focus_buffer,
+            &self.scroll_buffer,
+            &self.instance_buffer,
+        ]
+        .into_iter()
+        .chain(&self.readback_pool.buffers)
+        .chain(self.prerender.as_ref().map(|prerender| &prerender.buffer))
+        .map(wgpu::Buffer::size)
+        .sum::<u64>();
+        usage.host += self.cache.as_ref().map_or(0, |(_, pixels)| {
+            (pixels.len() * std::mem::size_of::<Pixel>()) as u64
+        });
+        usage
+    }
+
+    /// Reports the current [`GpuBackend::memory_usage`] to [`crate::total_memory_usage`].
+    fn track_memory(&mut self) {
+        let usage = self.memory_usage();
+        self.memory.report(usage);
+    }
+
+    pub(crate) fn output_texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    pub(crate) fn handle(&self) -> ShaderHandle {
+        ShaderHandle {
+            shader: Arc::clone(&self.shader),
+        }
+    }
+
+    /// Drops the cached pixels and the frame rendered ahead of time, after an input changed.
+    fn invalidate(&mut self) {
+        self.cache = None;
+        self.cancel_prerender();
+    }
+
+    /// Drops the frame rendered ahead of time.
+    pub(crate) fn cancel_prerender(&mut self) {
+        if let Some(prerender) = self.prerender.take() {
+            prerender.buffer.destroy();
+        }
+    }
+
+    /// Writes `value` into the uniform called `name`. Returns whether it was written and whether that
+    /// changed the uniforms.
+    pub(crate) fn set_uniform(&mut self, name: &str, value: UniformValue) -> (bool, bool) {
+        let previous = self.user_uniforms.clone();
+        let written = self
+            .shader
+            .uniform_layout
+            .write(&mut self.user_uniforms, name, value);
+        let changed = self.user_uniforms != previous;
+        if changed {
+            self.invalidate();
+        }
+        (written, changed)
+    }
+
+    pub(crate) fn uniform_names(&self) -> Vec<String> {
+        self.shader
+            .uniform_layout
+            .names()
+            .map(String::from)
+            .collect()
+    }
+
+    pub(crate) fn set_user_bytes(&mut self, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+        let min_size = self.shader.uniform_layout.size();
+        let max_size = self.user_uniforms.len();
+        if bytes.len() < min_size || bytes.len() > max_size {
+            return Err(format!(
+                "expected between {min_size} and {max_size} bytes of uniforms, got {}",
+                bytes.len()
+            )
+            .into());
+        }
+        self.user_uniforms[..bytes.len()].copy_from_slice(bytes);
+        self.user_uniforms[bytes.len()..].fill(0);
+        self.invalidate();
+        Ok(())
+    }
+
+    pub(crate) fn write_buffer(
+        &mut self,
+        slot: BindingSlot,
+        data: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        self.user_bindings
+            .write_buffer(&self.shader.queue, slot, data)?;
+        self.invalidate();
+        Ok(())
+    }
+
+    pub(crate) fn update_texture(
+        &mut self,
+        slot: BindingSlot,
+        data: &[u8],
+        size: wgpu::Extent3d,
+        kind: TextureKind,
+    ) -> Result<(), Box<dyn Error>> {
+        let recreated = self.user_bindings.update_texture(
+            &self.shader.device,
+            &self.shader.queue,
+            slot,
+            data,
+            size,
+            kind,
+        )?;
+        self.invalidate();
+        if recreated {
+            self.create_bind_groups();
+        }
+        Ok(())
+    }
+
+    /// Renders the shader for the whole rect of `ctx`. Areas exceeding the maximum texture size of the
+    /// device are split into tiles which are rendered one after another and stitched together. If
+    /// neither `ctx` nor any uniform or binding changed since the last execution, the cached pixels are
+    /// returned without touching the GPU.
+    pub(crate) async fn execute(
+        &mut self,
+        ctx: ShaderContext,
+    ) -> Result<Vec<Pixel>, TuiShaderError> {
+        if let Some((cached_ctx, pixels)) = &self.cache
+            && *cached_ctx == ctx
+        {
+            self.telemetry.cache_hits += 1;
+            return Ok(pixels.clone());
+        }
+        let pixels = match self.take_prerender(&ctx).await? {
+            Some(pixels) => {
+                self.telemetry.prerender_hits += 1;
+                pixels
+            }
+            None => {
+                let region = Rect::new(0, 0, ctx.width() as u16, ctx.height() as u16);
+                self.render(ctx, region).await?
+            }
+        };
+        self.cache = Some((ctx, pixels.clone()));
+        self.track_memory();
+        Ok(pixels)
+    }
+
+    /// Renders the frame of `ctx` ahead of time, usually with a predicted time, and starts reading it
+    /// back without waiting for it. The next [`GpuBackend::execute`] returns it instead of rendering if
+    /// its context only differs in a time at most `tolerance` seconds away and no input changed in
+    /// between. Areas which need to be rendered in tiles aren't rendered ahead of time.
+    pub(crate) async fn prerender(
+        &mut self,
+        ctx: ShaderContext,
+        tolerance: f32,
+    ) -> Result<(), TuiShaderError> {
+        self.cancel_prerender();
+        let (width, height) = (ctx.width(), ctx.height());
+        let max_size = self.shader.device.limits().max_texture_dimension_2d;
+        if width > max_size || height > max_size {
+            return Ok(());
+        }
+        self.write_inputs(&ctx);
+        self.begin_frame(width, height)?;
+        let tile = tile_uv(&ctx, 0, 0, width, height);
+        self.shader
+            .queue
+            .write_buffer(&self.tile_buffer, 0, bytemuck::cast_slice(&[tile]));
+        let (texture_view, multisampled_view) = self.views();
+        let mut command_encoder = self
+            .shader
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        self.encode_render_pass(
+            &mut command_encoder,
+            &texture_view,
+            multisampled_view.as_ref(),
+            (width, height),
+        );
+        let buffer = self.submit_frame(command_encoder, width, height).await?;
+        let size = wgpu::BufferAddress::from(bytes_per_row(width) * height);
+        let mapped = map_output_range(&buffer, size);
+        self.prerender = Some(Prerender {
+            ctx,
+            tolerance,
+            buffer,
+            size,
+            mapped,
+        });
+        self.track_memory();
+        Ok(())
+    }
+
+    /// Reads back the frame rendered ahead of time if it matches `ctx`, `None` if there is none or it
+    /// doesn't match, in which case it is dropped.
+    async fn take_prerender(
+        &mut self,
+        ctx: &ShaderContext,
+    ) -> Result<Option<Vec<Pixel>>, TuiShaderError> {
+        let Some(prerender) = self.prerender.take() else {
+            return Ok(None);
+        };
+        if !prerender.matches(ctx) {
+            prerender.buffer.destroy();
+            return Ok(None);
+        }
+        self.poll_mapping(&prerender.mapped);
+        let pixels = read_mapped_range(
+            &self.shader.device,
+            &prerender.buffer,
+            prerender.size,
+            prerender.mapped,
+            Deadline::start(self.deadline),
+        )
+        .await;
+        self.readback_pool.release(prerender.buffer);
+        Ok(Some(clear_padding(pixels?, ctx.width())))
+    }
+
+    /// Renders only the pixels of `region`, reusing the cached pixels if `ctx` didn't change.
+    pub(crate) async fn execute_region(
+        &mut self,
+        ctx: ShaderContext,
+        region: Rect,
+    ) -> Result<Vec<Pixel>, TuiShaderError> {
+        if let Some((cached_ctx, pixels)) = &self.cache
+            && *cached_ctx == ctx
+        {
+            self.telemetry.cache_hits += 1;
+            return Ok(crop(pixels, ctx.width(), region));
+        }
+        self.render(ctx, region).await
+    }
+
+    /// Renders the pixels of `region`, given in pixels relative to the rect of `ctx`. The shader sees the
+    /// whole rect, so the result matches the same pixels of a full render.
+    async fn render(
+        &mut self,
+        ctx: ShaderContext,
+        region: Rect,
+    ) -> Result<Vec<Pixel>, TuiShaderError> {
+        let width = u32::from(region.width);
+        let height = u32::from(region.height);
+        self.write_inputs(&ctx);
+        let max_size = self.shader.device.limits().max_texture_dimension_2d;
+        if width <= max_size && height <= max_size {
+            let tile = tile_uv(&ctx, region.x.into(), region.y.into(), width, height);
+            return self.render_tile(tile, width, height).await;
+        }
+
+        let stride = (width + row_padding(width)) as usize;
+        let mut pixels = vec![[0; 4]; stride * height as usize];
+        for tile_y in (0..height).step_by(max_size as usize) {
+            for tile_x in (0..width).step_by(max_size as usize) {
+                let tile_width = max_size.min(width - tile_x);
+                let tile_height = max_size.min(height - tile_y);
+                let tile = tile_uv(
+                    &ctx,
+                    u32::from(region.x) + tile_x,
+                    u32::from(region.y) + tile_y,
+                    tile_width,
+                    tile_height,
+                );
+                let tile_pixels = self.render_tile(tile, tile_width, tile_height).await?;
+                let tile_stride = (tile_width + row_padding(tile_width)) as usize;
+                for (row, tile_row) in tile_pixels.chunks(tile_stride).enumerate() {
+                    let start = (tile_y as usize + row) * stride + tile_x as usize;
+                    pixels[start..start + tile_width as usize]
+                        .copy_from_slice(&tile_row[..tile_width as usize]);
+                }
+            }
+        }
+        Ok(pixels)
+    }
+
+    /// Renders into `view` of another texture, which must have been created on the device of the shader.
+    /// The output isn't read back and the cache is left untouched.
+    pub(crate) fn render_to_view(
+        &mut self,
+        ctx: ShaderContext,
+        view: &wgpu::TextureView,
+    ) -> Result<(), Box<dyn Error>> {
+        let texture = view.texture();
+        if texture.format() != wgpu::TextureFormat::Rgba8Unorm
+            || texture.sample_count() != 1
+            || !texture
+                .usage()
+                .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+        {
+            return Err(
+                "the texture must be a single sampled Rgba8Unorm texture usable as render attachment"
+                    .into(),
+            );
+        }
+        self.write_inputs(&ctx);
+        self.shader
+            .queue
+            .write_buffer(&self.tile_buffer, 0, bytemuck::cast_slice(&[FULL_TILE]));
+        // The textures of the state may have a different size, so multisampling needs its own texture.
+        let multisampled_view = self.multisampled_texture.as_ref().map(|_| {
+            create_multisampled_texture(&self.shader.device, texture.width(), texture.height())
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        });
+        let mut command_encoder = self
+            .shader
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        self.encode_render_pass(
+            &mut command_encoder,
+            view,
+            multisampled_view.as_ref(),
+            (texture.width(), texture.height()),
+        );
+        self.shader.queue.submit(Some(command_encoder.finish()));
+        Ok(())
+    }
+
+    /// Writes the built-in inputs of `ctx` and the user uniforms to their buffers.
+    fn write_inputs(&self, ctx: &ShaderContext) {
+        self.shader
+            .queue
+            .write_buffer(&self.time_buffer, 0, bytemuck::cast_slice(&[ctx.time]));
+        self.shader
+            .queue
+            .write_buffer(&self.rect_buffer, 0, bytemuck::cast_slice(&[ctx.rect]));
+        self.shader
+            .queue
+            .write_buffer(&self.date_buffer, 0, bytemuck::cast_slice(&[ctx.date]));
+        self.shader
+            .queue
+            .write_buffer(&self.cell_buffer, 0, bytemuck::cast_slice(&[ctx.cell]));
+        self.shader
+            .queue
+            .write_buffer(&self.focus_buffer, 0, bytemuck::cast_slice(&[ctx.focus]));
+        self.shader
+            .queue
+            .write_buffer(&self.scroll_buffer, 0, bytemuck::cast_slice(&[ctx.scroll]));
+        self.shader.queue.write_buffer(
+            &self.instance_buffer,
+            0,
+            bytemuck::cast_slice(&[ctx.instance]),
+        );
+        self.shader
+            .queue
+            .write_buffer(&self.user_uniform_buffer, 0, &self.user_uniforms);
+    }
+
+    /// Renders a single tile of `width` x `height` pixels, covering the part of the uv space described by
+    /// `tile`.
+    async fn render_tile(
+        &mut self,
+        tile: [f32; 4],
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<Pixel>, TuiShaderError> {
+        self.begin_frame(width, height)?;
+        self.shader
+            .queue
+            .write_buffer(&self.tile_buffer, 0, bytemuck::cast_slice(&[tile]));
+        let (texture_view, multisampled_view) = self.views();
+        let mut command_encoder = self
+            .shader
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        self.encode_render_pass(
+            &mut command_encoder,
+            &texture_view,
+            multisampled_view.as_ref(),
+            (width, height),
+        );
+        self.end_frame(command_encoder, width, height).await
+    }
+
+    /// Renders the shader once for every context into a single texture and reads it back at once, which
+    /// is much faster than executing them one after another. Falls back to executing them one after
+    /// another if they don't fit into one texture.
+    pub(crate) async fn execute_batch(
+        &mut self,
+        ctxs: &[ShaderContext],
+    ) -> Result<Vec<Vec<Pixel>>, TuiShaderError> {
+        let max_size = self.shader.device.limits().max_texture_dimension_2d;
+        let sizes: Vec<(u32, u32)> = ctxs.iter().map(|ctx| (ctx.width(), ctx.height())).collect();
+        let Some(Atlas {
+            positions,
+            width,
+            height,
+        }) = pack(&sizes, max_size)
+        else {
+            let mut frames = Vec::with_capacity(ctxs.len());
+            for ctx in ctxs {
+                frames.push(self.execute(*ctx).await?);
+            }
+            return Ok(frames);
+        };
+        self.begin_frame(width, height)?;
+        self.shader
+            .queue
+            .write_buffer(&self.user_uniform_buffer, 0, &self.user_uniforms);
+        let device = &self.shader.device;
+        let bind_groups: Vec<Vec<wgpu::BindGroup>> = ctxs
+            .iter()
+            .map(|ctx| {
+                let tile = tile_uv(ctx, 0, 0, ctx.width(), ctx.height());
+                let time = create_input_buffer(device, ctx.time);
+                let rect = create_input_buffer(device, ctx.rect);
+                let tile = create_input_buffer(device, tile);
+                let date = create_input_buffer(device, ctx.date);
+                let cell = create_input_buffer(device, ctx.cell);
+                let focus = create_input_buffer(device, ctx.focus);
+                let scroll = create_input_buffer(device, ctx.scroll);
+                let instance = create_input_buffer(device, ctx.instance);
+                self.user_bindings.create_bind_groups(
+                    device,
+                    &self.shader.bind_group_layouts,
+                    &[
+                        &time,
+                        &rect,
+                        &self.user_uniform_buffer,
+                        &tile,
+                        &date,
+                        &cell,
+                        &focus,
+                        &scroll,
+                        &instance,
+                    ],
+                )
+            })
+            .collect();
+        let (texture_view, multisampled_view) = self.views();
+        let mut command_encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut render_pass = self.begin_render_pass(
+                &mut command_encoder,
+                &texture_view,
+                multisampled_view.as_ref(),
+            );
+            for ((ctx, &(x, y)), groups) in ctxs.iter().zip(&positions).zip(&bind_groups) {
+                render_pass.set_viewport(
+                    x as f32,
+                    y as f32,
+                    ctx.width() as f32,
+                    ctx.height() as f32,
+                    0.0,
+                    1.0,
+                );
+                render_pass.set_scissor_rect(x, y, ctx.width(), ctx.height());
+                for (group, bind_group) in groups.iter().enumerate() {
+                    render_pass.set_bind_group(group as u32, bind_group, &[]);
+                }
+                render_pass.draw(0..3, 0..1);
+            }
+        }
+        let atlas = self.end_frame(command_encoder, width, height).await?;
+        Ok(ctxs
+            .iter()
+            .zip(positions)
+            .map(|(ctx, (x, y))| {
+                let region = Rect::new(x as u16, y as u16, ctx.width() as u16, ctx.height() as u16);
+                crop(&atlas, width, region)
+            })
+            .collect())
+    }
+
+    /// Prepares rendering `width` x `height` pixels: fails if the device was lost, starts capturing errors
+    /// and grows the textures if they are too small. Must be followed by [`Self::end_frame`].
+    ///
+    /// The textures are only ever grown, to the next power of two, and smaller frames are rendered into
+    /// their top left corner, so resizing the terminal doesn't reallocate them on every frame.
+    fn begin_frame(&mut self, width: u32, height: u32) -> Result<(), TuiShaderError> {
+        if let Some(reason) = self.shader.lost_reason() {
+            return Err(TuiShaderError::DeviceLost(reason));
+        }
+        let device = &self.shader.device;
+        push_error_scopes(device);
+        if width > self.texture.width() || height > self.texture.height() {
+            let max_size = device.limits().max_texture_dimension_2d;
+            let capacity_width = grow(self.texture.width(), width, max_size);
+            let capacity_height = grow(self.texture.height(), height, max_size);
+            self.texture = create_texture(device, capacity_width, capacity_height);
+            self.telemetry.texture_recreations += 1;
+            if self.multisampled_texture.is_some() {
+                self.multisampled_texture = Some(create_multisampled_texture(
+                    device,
+                    capacity_width,
+                    capacity_height,
+                ));
+            }
+        }
+        self.width = width;
+        self.height = height;
+        Ok(())
+    }
+
+    /// Copies the rendered texture into the output buffer, submits the work recorded in `command_encoder`
+    /// and reads the pixels back, reporting the errors captured since [`Self::begin_frame`].
+    async fn end_frame(
+        &mut self,
+        command_encoder: wgpu::CommandEncoder,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<Pixel>, TuiShaderError> {
+        let deadline = Deadline::start(self.deadline);
+        let output_buffer = self.submit_frame(command_encoder, width, height).await?;
+        let size = wgpu::BufferAddress::from(bytes_per_row(width) * height);
+        let mapped = map_output_range(&output_buffer, size);
+        self.poll_mapping(&mapped);
+        let pixels =
+            read_mapped_range(&self.shader.device, &output_buffer, size, mapped, deadline).await;
+        self.readback_pool.release(output_buffer);
+        self.track_memory();
+        Ok(clear_padding(pixels?, width))
+    }
+
+    /// Checks whether the GPU already finished writing the buffer of `mapped` without blocking, counting
+    /// a wait if it didn't.
+    fn poll_mapping(&mut self, mapped: &MapReceiver) {
+        let _ = self.shader.device.poll(wgpu::PollType::Poll);
+        if mapped.is_empty() {
+            self.telemetry.buffer_map_waits += 1;
+        }
+    }
+
+    /// Copies the rendered texture into a readback buffer and submits the work recorded in
+    /// `command_encoder`, reporting the errors captured since [`Self::begin_frame`]. Returns the buffer,
+    /// which belongs to the readback pool.
+    async fn submit_frame(
+        &mut self,
+        mut command_encoder: wgpu::CommandEncoder,
+        width: u32,
+        height: u32,
+    ) -> Result<wgpu::Buffer, TuiShaderError> {
+        let device = &self.shader.device;
+        let size = wgpu::BufferAddress::from(bytes_per_row(width) * height);
+        let output_buffer = self.readback_pool.acquire(device, size);
+        copy_texture_to_buffer(
+            &mut command_encoder,
+            &self.texture,
+            &output_buffer,
+            width,
+            height,
+        );
+        self.shader.queue.submit(Some(command_encoder.finish()));
+        if let Err(error) = pop_error_scopes(device, &self.shader.lost).await {
+            self.readback_pool.release(output_buffer);
+            return Err(error);
+        }
+        Ok(output_buffer)
+    }
+
+    /// Views of the output texture and the multisampled texture, if multisampling is enabled.
+    fn views(&self) -> (wgpu::TextureView, Option<wgpu::TextureView>) {
+        let texture_view = self
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let multisampled_view = self
+            .multisampled_texture
+            .as_ref()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        (texture_view, multisampled_view)
+    }
+
+    /// Draws the shader into the top left `width` x `height` pixels of `view`, rendering into
+    /// `multisampled_view` first and resolving it into `view` if given.
+    fn encode_render_pass(
+        &self,
+        command_encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        multisampled_view: Option<&wgpu::TextureView>,
+        (width, height): (u32, u32),
+    ) {
+        let mut render_pass = self.begin_render_pass(command_encoder, view, multisampled_view);
+        render_pass.set_viewport(0.0, 0.0, width as f32, height as f32, 0.0, 1.0);
+        render_pass.set_scissor_rect(0, 0, width, height);
+        for (group, bind_group) in self.bind_groups.iter().enumerate() {
+            render_pass.set_bind_group(group as u32, bind_group, &[]);
+        }
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// Starts a render pass clearing `view`, or `multisampled_view` which is resolved into `view`, with the
+    /// pipeline of the shader set.
+    fn begin_render_pass<'a>(
+        &self,
+        command_encoder: &'a mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        multisampled_view: Option<&wgpu::TextureView>,
+    ) -> wgpu::RenderPass<'a> {
+        let render_target = match multisampled_view {
+            Some(multisampled_view) => wgpu::RenderPassColorAttachment {
+                view: multisampled_view,
+                resolve_target: Some(view),
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Discard,
+                },
+                depth_slice: None,
+            },
+            None => wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            },
+        };
+        let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(render_target)],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(self.shader.pipeline(multisampled_view.is_some()));
+        render_pass
+    }
+}
+
+/// Receives the result of mapping a readback buffer.
+type MapReceiver = flume::Receiver<Result<(), wgpu::BufferAsyncError>>;
+
+/// The time the GPU may take for a frame, see [`GpuBackend::set_deadline`]. Counted from the submission,
+/// since some backends, like GL, already block while submitting.
+#[derive(Debug, Clone, Copy)]
+struct Deadline {
+    limit: Duration,
+    start: Instant,
+}
+
+impl Deadline {
+    /// Starts counting towards `limit`, if any.
+    fn start(limit: Option<Duration>) -> Option<Self> {
+        limit.map(|limit| Self {
+            limit,
+            start: Instant::now(),
+        })
+    }
+
+    fn remaining(&self) -> Duration {
+        self.limit.saturating_sub(self.start.elapsed())
+    }
+
+    fn is_missed(&self) -> bool {
+        self.start.elapsed() > self.limit
+    }
+}
+
+/// A frame rendered by [`GpuBackend::prerender`], whose readback buffer is being mapped.
+#[derive(Debug)]
+struct Prerender {
+    ctx: ShaderContext,
+    tolerance: f32,
+    buffer: wgpu::Buffer,
+    size: wgpu::BufferAddress,
+    mapped: MapReceiver,
+}
+
+impl Prerender {
+    /// Whether the frame can stand in for the frame of `ctx`.
+    fn matches(&self, ctx: &ShaderContext) -> bool {
+        let time = ctx.time[0] - self.ctx.time[0];
+        ShaderContext {
+            time: ctx.time,
+            ..self.ctx
+        } == *ctx
+            && time.abs() <= self.tolerance
+    }
+}
+
+/// The number of readback buffers a [`ReadbackPool`] keeps.
+const READBACK_POOL_CAPACITY: usize = 4;
+
+/// Buffers the rendered pixels are copied into and read back from, reused across frames of different
+/// sizes. Resizing the terminal, e.g. by dragging its corner, changes the size of every frame, and
+/// allocating a new buffer each time causes visible stutter.
+#[derive(Debug, Default)]
+struct ReadbackPool {
+    /// The pooled buffers, the most recently used last.
+    buffers: Vec<wgpu::Buffer>,
+}
+
+impl ReadbackPool {
+    /// Takes the smallest pooled buffer holding at least `size` bytes, the padded size of a frame, or
+    /// creates one with the size rounded up to the next power of two, so that growing frames reuse it.
+    fn acquire(&mut self, device: &wgpu::Device, size: wgpu::BufferAddress) -> wgpu::Buffer {
+        let smallest = self
+            .buffers
+            .iter()
+            .enumerate()
+            .filter(|(_, buffer)| buffer.size() >= size)
+            .min_by_key(|(_, buffer)| buffer.size())
+            .map(|(index, _)| index);
+        match smallest {
+            Some(index) => self.buffers.remove(index),
+            None => device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: size.next_power_of_two(),
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }),
+        }
+    }
+
+    /// Returns `buffer` to the pool, dropping the least recently used buffer if it is full.
+    fn release(&mut self, buffer: wgpu::Buffer) {
+        if self.buffers.len() == READBACK_POOL_CAPACITY {
+            self.buffers.remove(0).destroy();
+        }
+        self.buffers.push(buffer);
+    }
+
+    fn destroy(&self) {
+        for buffer in &self.buffers {
+            buffer.destroy();
+        }
+    }
+}
+
+/// The device, output texture and readback buffers of the widgets rendering with their own pipelines,
+/// like [`ParticleCanvasState`](crate::ParticleCanvasState). Frames are captured in error scopes and read
+/// back through a pool like the frames of a [`GpuBackend`], and failed frames are passed to the
+/// `on_error` callbacks of the widget.
+#[derive(Debug)]
+pub(crate) struct RenderTarget {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    lost: Arc<Mutex<Option<String>>>,
+    texture: wgpu::Texture,
+    readback_pool: ReadbackPool,
+    on_error: Vec<fn(TuiShaderError)>,
+}
+
+/// Clones share the device, but get their own texture and readback buffers.
+impl Clone for RenderTarget {
+    fn clone(&self) -> Self {
+        Self {
+            device: self.device.clone(),
+            queue: self.queue.clone(),
+            lost: Arc::clone(&self.lost),
+            texture: create_texture(&self.device, self.texture.width(), self.texture.height()),
+            readback_pool: ReadbackPool::default(),
+            on_error: self.on_error.clone(),
+        }
+    }
+}
+
+impl RenderTarget {
+    /// Requests a device with the default [`DeviceOptions`].
+    pub(crate) fn new() -> Result<Self, TuiShaderError> {
+        let WatchedDevice {
+            device,
+            queue,
+            lost,
+            ..
+        } = request_watched_device(&DeviceOptions::default())?;
+        Ok(Self {
+            texture: create_texture(&device, DEFAULT_SIZE, DEFAULT_SIZE),
+            device,
+            queue,
+            lost,
+            readback_pool: ReadbackPool::default(),
+            on_error: Vec::new(),
+        })
+    }
+
+    pub(crate) fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    pub(crate) fn on_error(&mut self, callback: fn(TuiShaderError)) {
+        self.on_error.push(callback);
+    }
+
+    /// Renders a `width` x `height` frame with the passes `encode` records into the view of the output
+    /// texture, and reads it back. Failures are passed to the `on_error` callbacks, which get
+    /// transparent pixels instead, and panic if there are none.
+    pub(crate) fn execute(
+        &mut self,
+        width: u32,
+        height: u32,
+        encode: impl FnOnce(&wgpu::Device, &wgpu::Queue, &mut wgpu::CommandEncoder, &wgpu::TextureView),
+    ) -> Vec<Pixel> {
+        let result = pollster::block_on(self.render(width, height, encode));
+        error::recover(result, &self.on_error, width, height)
+    }
+
+    async fn render(
+        &mut self,
+        width: u32,
+        height: u32,
+        encode: impl FnOnce(&wgpu::Device, &wgpu::Queue, &mut wgpu::CommandEncoder, &wgpu::TextureView),
+    ) -> Result<Vec<Pixel>, TuiShaderError> {
+        if let Some(reason) = lost_reason(&self.lost) {
+            return Err(TuiShaderError::DeviceLost(reason));
+        }
+        push_error_scopes(&self.device);
+        // The widgets draw into the whole texture, so unlike the texture of a `GpuBackend` it always has
+        // the size of the frame.
+        if self.texture.width() != width || self.texture.height() != height {
+            self.texture = create_texture(&self.device, width, height);
+        }
+        let mut command_encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let view = self
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        encode(&self.device, &self.queue, &mut command_encoder, &view);
+        let size = wgpu::BufferAddress::from(bytes_per_row(width) * height);
+        let output_buffer = self.readback_pool.acquire(&self.device, size);
+        copy_texture_to_buffer(
+            &mut command_encoder,
+            &self.texture,
+            &output_buffer,
+            width,
+            height,
+        );
+        self.queue.submit(Some(command_encoder.finish()));
+        if let Err(error) = pop_error_scopes(&self.device, &self.lost).await {
+            self.readback_pool.release(output_buffer);
+            return Err(error);
+        }
+        let pixels = read_output_range(&self.device, &output_buffer, size).await;
+        self.readback_pool.release(output_buffer);
+        Ok(clear_padding(pixels?, width))
+    }
+}
+
+/// Starts capturing the errors of the work recorded for a frame, see [`pop_error_scopes`].
+fn push_error_scopes(device: &wgpu::Device) {
+    device.push_error_scope(wgpu::ErrorFilter::Internal);
+    device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+}
+
+/// Stops capturing the errors pushed by [`push_error_scopes`] once the work of a frame is submitted,
+/// failing with the reason the device was lost or else the first captured error.
+async fn pop_error_scopes(
+    device: &wgpu::Device,
+    lost: &Mutex<Option<String>>,
+) -> Result<(), TuiShaderError> {
+    let mut error = None;
+    for _ in 0..3 {
+        error = error.or(device.pop_error_scope().await);
+    }
+    // Errors caused by a lost device are reported before its callback ran, and a buffer created on a
+    // lost device can't be mapped.
+    let _ = device.poll(wgpu::PollType::Poll);
+    if let Some(reason) = lost_reason(lost) {
+        return Err(TuiShaderError::DeviceLost(reason));
+    }
+    match error {
+        Some(error) => Err(TuiShaderError::Render(error.to_string())),
+        None => Ok(()),
+    }
+}
+
+/// The reason recorded by the device lost callback of a [`WatchedDevice`], if it was lost.
+pub(crate) fn lost_reason(lost: &Mutex<Option<String>>) -> Option<String> {
+    lost.lock().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+/// Zeroes the row padding of `pixels`, which still holds the pixels of larger frames read back through
+/// the same buffer before.
+fn clear_padding(mut pixels: Vec<Pixel>, width: u32) -> Vec<Pixel> {
+    let stride = (width + row_padding(width)) as usize;
+    for row in pixels.chunks_mut(stride) {
+        row[width as usize..].fill([0; 4]);
+    }
+    pixels
+}
+
+/// The size of a texture dimension of `current` pixels grown to hold `needed` pixels.
+fn grow(current: u32, needed: u32, max_size: u32) -> u32 {
+    needed.next_power_of_two().min(max_size).max(current)
+}
+
+/// The placement of the rects of a batch in a single texture.
+struct Atlas {
+    positions: Vec<(u32, u32)>,
+    width: u32,
+    height: u32,
+}
+
+/// Packs rects of the given sizes into rows no wider than `max_size`. Returns `None` if they don't fit
+/// into a texture of `max_size` x `max_size`.
+fn pack(sizes: &[(u32, u32)], max_size: u32) -> Option<Atlas> {
+    let (mut x, mut y, mut row_height, mut width) = (0, 0, 0, 0);
+    let mut positions = Vec::with_capacity(sizes.len());
+    for &(rect_width, rect_height) in sizes {
+        if rect_width > max_size || rect_height > max_size {
+            return None;
+        }
+        if x + rect_width > max_size {
+            y += row_height;
+            x = 0;
+            row_height = 0;
+        }
+        positions.push((x, y));
+        x += rect_width;
+        row_height = row_height.max(rect_height);
+        width = width.max(x);
+    }
+    let height = y + row_height;
+    (height <= max_size && width > 0).then_some(Atlas {
+        positions,
+        width,
+        height,
+    })
+}
+
+/// The uv transform of the tile of `width` x `height` pixels at `x`, `y` within the rect of `ctx`, shifted
+/// by the scroll offset of `ctx`.
+fn tile_uv(ctx: &ShaderContext, x: u32, y: u32, width: u32, height: u32) -> [f32; 4] {
+    let full_width = ctx.width() as f32;
+    let full_height = ctx.height() as f32;
+    let [u_offset, v_offset] = ctx.uv_offset();
+    [
+        x as f32 / full_width + u_offset,
+        (ctx.height() - y - height) as f32 / full_height + v_offset,
+        width as f32 / full_width,
+        height as f32 / full_height,
+    ]
+}
+
+/// Copies `region` out of row padded `pixels` that are `width` pixels wide, keeping the row padding.
+fn crop(pixels: &[Pixel], width: u32, region: Rect) -> Vec<Pixel> {
+    let stride = (width + row_padding(width)) as usize;
+    let region_width = u32::from(region.width);
+    let region_stride = (region_width + row_padding(region_width)) as usize;
+    let mut cropped = vec![[0; 4]; region_stride * usize::from(region.height)];
+    for (row, cropped_row) in cropped.chunks_mut(region_stride).enumerate() {
+        let start = (usize::from(region.y) + row) * stride + usize::from(region.x);
+        cropped_row[..usize::from(region.width)]
+            .copy_from_slice(&pixels[start..start + usize::from(region.width)]);
+    }
+    cropped
+}
+
+/// The backends enabled by the `vulkan`, `metal`, `dx12` and `gl` features. Backends selected with the
+/// `WGPU_BACKEND` environment variable are restricted to these.
+fn enabled_backends() -> wgpu::Backends {
+    [
+        (cfg!(feature = "vulkan"), wgpu::Backends::VULKAN),
+        (cfg!(feature = "metal"), wgpu::Backends::METAL),
+        (cfg!(feature = "dx12"), wgpu::Backends::DX12),
+        (cfg!(feature = "gl"), wgpu::Backends::GL),
+    ]
+    .into_iter()
+    .filter(|(enabled, _)| *enabled)
+    .fold(wgpu::Backends::empty(), |backends, (_, backend)| {
+        backends | backend
+    })
+}
+
+/// Which GPU a [`ShaderCanvasState`](crate::ShaderCanvasState) runs its shader on.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum AdapterSelection {
+    /// The adapter picked by wgpu.
+    #[default]
+    Default,
+
+    /// Prefers a power efficient adapter, usually an integrated GPU.
+    LowPower,
+
+    /// Prefers the fastest adapter, usually a discrete GPU.
+    HighPerformance,
+
+    /// The adapter at this index of [`enumerate_adapters`].
+    #[cfg(not(target_arch = "wasm32"))]
+    Index(usize),
+
+    /// The first adapter of [`enumerate_adapters`] whose name contains this string, ignoring case.
+    #[cfg(not(target_arch = "wasm32"))]
+    Name(String),
+
+    /// The first adapter of [`enumerate_adapters`] of this type, e.g. [`wgpu::DeviceType::IntegratedGpu`].
+    #[cfg(not(target_arch = "wasm32"))]
+    DeviceType(wgpu::DeviceType),
+
+    /// Tries the selections in order and takes the first one which finds an adapter and gets a device
+    /// from it. Together with [`DeviceOptions::migrate`] the state moves down the list when the adapter
+    /// it runs on disappears, e.g. when an external GPU is unplugged.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::ShaderCanvasState;
+    /// # use tui_shader::gpu::AdapterSelection;
+    /// let state = ShaderCanvasState::builder()
+    ///     .adapter(AdapterSelection::Ordered(vec![
+    ///         AdapterSelection::DeviceType(wgpu::DeviceType::IntegratedGpu),
+    ///         AdapterSelection::DeviceType(wgpu::DeviceType::DiscreteGpu),
+    ///         AdapterSelection::Default,
+    ///     ]))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    Ordered(Vec<AdapterSelection>),
+}
+
+impl AdapterSelection {
+    /// The selections to try in order, with nested [`AdapterSelection::Ordered`] lists flattened.
+    fn candidates(&self) -> Vec<&Self> {
+        match self {
+            Self::Ordered(selections) => selections.iter().flat_map(Self::candidates).collect(),
+            selection => vec![selection],
+        }
+    }
+}
+
+/// Lists the adapters shaders can run on, in the order used by [`AdapterSelection::Index`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn enumerate_adapters() -> Vec<wgpu::AdapterInfo> {
+    create_instance(instance_backends())
+        .enumerate_adapters(wgpu::Backends::all())
+        .iter()
+        .map(wgpu::Adapter::get_info)
+        .collect()
+}
+
+/// The backends selected with the `WGPU_BACKEND` environment variable, restricted to the enabled ones.
+fn instance_backends() -> wgpu::Backends {
+    wgpu::Backends::from_env().unwrap_or_default() & enabled_backends()
+}
+
+fn create_instance(backends: wgpu::Backends) -> wgpu::Instance {
+    let mut descriptor = wgpu::InstanceDescriptor::from_env_or_default();
+    descriptor.backends = backends;
+    wgpu::Instance::new(&descriptor)
+}
+
+/// How a [`ShaderCanvasState`](crate::ShaderCanvasState) requests its GPU device, set with
+/// [`ShaderCanvasStateBuilder::device`](crate::ShaderCanvasStateBuilder::device).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceOptions {
+    /// The GPU the shader runs on.
+    pub adapter: AdapterSelection,
+
+    /// Gives up requesting a device after the given [`Duration`] instead of hanging forever on a broken
+    /// driver stack, e.g. a half-installed Vulkan on a headless server. Waits forever if `None`.
+    pub timeout: Option<Duration>,
+
+    /// If requesting a device with all enabled backends fails or times out, retries with every backend
+    /// on its own, e.g. with GL after Vulkan failed.
+    pub fallback: bool,
+
+    /// When the device is lost, e.g. because the GPU was unplugged or its driver reset, requests a new
+    /// one with these options and compiles the shader again instead of failing every execution. The
+    /// contents of the bindings start over with the ones the state was created with. Textures created on
+    /// the [`ShaderHandle`] of the lost device have to be recreated by the app.
+    /// Shaders loaded from GLSL with defines can't be compiled again and never migrate.
+    pub migrate: bool,
+}
+
+impl Default for DeviceOptions {
+    fn default() -> Self {
+        Self {
+            adapter: AdapterSelection::Default,
+            timeout: Some(Duration::from_secs(10)),
+            fallback: true,
+            migrate: false,
+        }
+    }
+}
+
+/// Requests a device as configured by `options`. Every failed attempt is listed in the error.
+pub(crate) fn request_device(
+    options: &DeviceOptions,
+) -> Result<(wgpu::Device, wgpu::Queue), TuiShaderError> {
+    let backends = instance_backends();
+    if backends.is_empty() {
+        let enabled = enabled_backends();
+        return Err(TuiShaderError::NoBackend(if enabled.is_empty() {
+            String::from("enable at least one of the `vulkan`, `metal`, `dx12` and `gl` features")
+        } else {
+            format!("`WGPU_BACKEND` names none of the enabled backends {enabled:?}")
+        }));
+    }
+    let mut attempts = vec![backends];
+    if options.fallback {
+        attempts.extend(backends.iter().filter(|backend| *backend != backends));
+    }
+    let mut errors = Vec::new();
+    for backends in attempts {
+        match request_device_with_timeout(backends, &options.adapter, options.timeout) {
+            Ok(device) => return Ok(device),
+            Err(error) => errors.push(format!("{backends:?}: {error}")),
+        }
+    }
+    Err(TuiShaderError::NoDevice(errors.join(", ")))
+}
+
+/// A device with the reason it was lost, for [`CompiledShader::lost_reason`], and the options it was
+/// requested with.
+struct WatchedDevice {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    lost: Arc<Mutex<Option<String>>>,
+    options: DeviceOptions,
+}
+
+/// Requests a device like [`request_device`] and records the reason it is lost.
+fn request_watched_device(options: &DeviceOptions) -> Result<WatchedDevice, TuiShaderError> {
+    let (device, queue) = request_device(options)?;
+    let lost = Arc::new(Mutex::new(None));
+    let reason = Arc::clone(&lost);
+    device.set_device_lost_callback(move |_, message| {
+        *reason.lock().unwrap_or_else(PoisonError::into_inner) = Some(message);
+    });
+    Ok(WatchedDevice {
+        device,
+        queue,
+        lost,
+        options: options.clone(),
+    })
+}
+
+/// Copies `source` so the shader can be compiled again after its device was lost. `None` for GLSL
+/// with defines, which are borrowed.
+fn owned_source(source: &wgpu::ShaderSource<'_>) -> Option<wgpu::ShaderSource<'static>> {
+    match source {
+        wgpu::ShaderSource::Wgsl(source) => {
+            Some(wgpu::ShaderSource::Wgsl(source.to_string().into()))
+        }
+        #[cfg(feature = "glsl")]
+        wgpu::ShaderSource::Glsl {
+            shader,
+            stage,
+            defines,
+        } if defines.is_empty() => Some(wgpu::ShaderSource::Glsl {
+            shader: shader.to_string().into(),
+            stage: *stage,
+            defines: &[],
+        }),
+        #[cfg(feature = "spirv")]
+        wgpu::ShaderSource::SpirV(words) => Some(wgpu::ShaderSource::SpirV(words.to_vec().into())),
+        _ => None,
+    }
+}
+
+/// Runs the request on another thread, which is abandoned if it doesn't finish in time.
+#[cfg(not(target_arch = "wasm32"))]
+fn request_device_with_timeout(
+    backends: wgpu::Backends,
+    selection: &AdapterSelection,
+    timeout: Option<Duration>,
+) -> Result<(wgpu::Device, wgpu::Queue), Box<dyn Error>> {
+    let Some(timeout) = timeout else {
+        return pollster::block_on(request_device_once(backends, selection));
+    };
+    let (sender, receiver) = flume::bounded(1);
+    let selection = selection.clone();
+    std::thread::spawn(move || {
+        let result = pollster::block_on(request_device_once(backends, &selection));
+        let _ = sender.send(result.map_err(|error| error.to_string()));
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result.map_err(Into::into),
+        Err(flume::RecvTimeoutError::Timeout) => Err(format!("timed out after {timeout:?}").into()),
+        Err(flume::RecvTimeoutError::Disconnected) => Err("the request panicked".into()),
+    }
+}
+
+/// Threads aren't available on the web, where the request is never interrupted.
+#[cfg(target_arch = "wasm32")]
+fn request_device_with_timeout(
+    backends: wgpu::Backends,
+    selection: &AdapterSelection,
+    _timeout: Option<Duration>,
+) -> Result<(wgpu::Device, wgpu::Queue), Box<dyn Error>> {
+    pollster::block_on(request_device_once(backends, selection))
+}
+
+async fn request_device_once(
+    backends: wgpu::Backends,
+    selection: &AdapterSelection,
+) -> Result<(wgpu::Device, wgpu::Queue), Box<dyn Error>> {
+    let instance = create_instance(backends);
+    let candidates = selection.candidates();
+    let mut errors = Vec::new();
+    for candidate in &candidates {
+        match request_device_from(&instance, candidate).await {
+            Ok(device) => return Ok(device),
+            Err(error) if candidates.len() == 1 => return Err(error),
+            Err(error) => errors.push(format!("{candidate:?}: {error}")),
+        }
+    }
+    if errors.is_empty() {
+        return Err("the adapter preferences are empty".into());
+    }
+    Err(format!("no adapter preference succeeded, {}", errors.join(", ")).into())
+}
+
+/// Requests a device from the adapter `selection` picks, which mustn't be an
+/// [`AdapterSelection::Ordered`] list.
+async fn request_device_from(
+    instance: &wgpu::Instance,
+    selection: &AdapterSelection,
+) -> Result<(wgpu::Device, wgpu::Queue), Box<dyn Error>> {
+    let power_preference = match selection {
+        AdapterSelection::LowPower => wgpu::PowerPreference::LowPower,
+        AdapterSelection::HighPerformance => wgpu::PowerPreference::HighPerformance,
+        _ => wgpu::PowerPreference::default(),
+    };
+    let adapter = match selection {
+        #[cfg(not(target_arch = "wasm32"))]
+        AdapterSelection::Index(index) => instance
+            .enumerate_adapters(wgpu::Backends::all())
+            .into_iter()
+            .nth(*index)
+            .ok_or_else(|| format!("there is no adapter with index {index}"))?,
+        #[cfg(not(target_arch = "wasm32"))]
+        AdapterSelection::Name(name) => {
+            let name = name.to_lowercase();
+            instance
+                .enumerate_adapters(wgpu::Backends::all())
+                .into_iter()
+                .find(|adapter| adapter.get_info().name.to_lowercase().contains(&name))
+                .ok_or_else(|| format!("there is no adapter named like `{name}`"))?
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        AdapterSelection::DeviceType(device_type) => instance
+            .enumerate_adapters(wgpu::Backends::all())
+            .into_iter()
+            .find(|adapter| adapter.get_info().device_type == *device_type)
+            .ok_or_else(|| format!("there is no adapter of type {device_type:?}"))?,
+        _ => {
+            instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference,
+                    ..Default::default()
+                })
+                .await?
+        }
+    };
+
+    let device = adapter
+        .request_device(&wgpu::DeviceDescriptor {
+            label: None,
+            required_features: wgpu::Features::empty(),
+            required_limits: wgpu::Limits::downlevel_defaults(),
+            memory_hints: wgpu::MemoryHints::Performance,
+            trace: wgpu::Trace::Off,
+            experimental_features: ExperimentalFeatures::disabled(),
+        })
+        .await?;
+    Ok(device)
+}
+
+pub(crate) fn create_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+    let texture_desc = wgpu::TextureDescriptor {
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        label: None,
+        view_formats: &[wgpu::TextureFormat::Rgba8Unorm],
+    };
+    device.create_texture(&texture_desc)
+}
+
+fn create_multisampled_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: MSAA_SAMPLE_COUNT,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        label: None,
+        view_formats: &[wgpu::TextureFormat::Rgba8Unorm],
+    })
+}
+
+#[cfg(test)]
+pub(crate) fn create_output_buffer(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Buffer {
+    let row_size = width * 4;
+    let bytes_per_row = (row_size + 255) & !255;
+
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: (bytes_per_row * height) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    })
+}
+
+pub(crate) fn copy_texture_to_buffer(
+    command_encoder: &mut wgpu::CommandEncoder,
+    texture: &wgpu::Texture,
+    output_buffer: &wgpu::Buffer,
+    width: u32,
+    height: u32,
+) {
+    command_encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: output_buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row(width)),
+                rows_per_image: Some(height),
+            },
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+#[cfg(test)]
+pub(crate) async fn read_output_buffer(
+    device: &wgpu::Device,
+    output_buffer: &wgpu::Buffer,
+) -> Result<Vec<Pixel>, TuiShaderError> {
+    read_output_range(device, output_buffer, output_buffer.size()).await
+}
+
+/// Reads the first `size` bytes of `output_buffer`.
+async fn read_output_range(
+    device: &wgpu::Device,
+    output_buffer: &wgpu::Buffer,
+    size: wgpu::BufferAddress,
+) -> Result<Vec<Pixel>, TuiShaderError> {
+    let receiver = map_output_range(output_buffer, size);
+    read_mapped_range(device, output_buffer, size, receiver, None).await
+}
+
+/// Starts mapping the first `size` bytes of `output_buffer`. The returned receiver gets the result once
+/// the device was polled after the work writing the buffer finished.
+fn map_output_range(output_buffer: &wgpu::Buffer, size: wgpu::BufferAddress) -> MapReceiver {
+    let (sender, receiver) = flume::bounded(1);
+    output_buffer
+        .slice(..size)
+        .map_async(wgpu::MapMode::Read, move |r| {
+            sender
+                .send(r)
+                .expect("unable to send buffer slice data to receiver");
+        });
+    receiver
+}
+
+/// Waits until the mapping started by [`map_output_range`] finished and copies the pixels out of the
+/// buffer. If the frame misses `deadline`, the device is destroyed, since wgpu can't cancel work once it
+/// is submitted.
+async fn read_mapped_range(
+    device: &wgpu::Device,
+    output_buffer: &wgpu::Buffer,
+    size: wgpu::BufferAddress,
+    receiver: MapReceiver,
+    deadline: Option<Deadline>,
+) -> Result<Vec<Pixel>, TuiShaderError> {
+    let buffer_slice = output_buffer.slice(..size);
+    let poll = device.poll(wgpu::PollType::Wait {
+        submission_index: None,
+        timeout: deadline.map(|deadline| deadline.remaining()),
+    });
+    if let Some(deadline) = deadline
+        && (matches!(poll, Err(wgpu::PollError::Timeout)) || deadline.is_missed())
+    {
+        device.destroy();
+        return Err(TuiShaderError::Timeout(deadline.limit));
+    }
+    receiver
+        .recv_async()
+        .await
+        .expect("unable to receive message all senders have been dropped")
+        .map_err(|error| TuiShaderError::Readback(error.to_string()))?;
+    let padded_buffer: Vec<Pixel>;
+    {
+        let view = buffer_slice.get_mapped_range();
+        padded_buffer = bytemuck::cast_slice(&view).to_vec();
+    }
+    output_buffer.unmap();
+    Ok(padded_buffer)
+}
+
+pub(crate) fn create_input_buffer<A: NoUninit>(device: &wgpu::Device, content: A) -> wgpu::Buffer {
+    device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(&[content]),
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    })
+}
+
+pub(crate) fn uniform_layout_entry(
+    binding: u32,
+    visibility: wgpu::ShaderStages,
+) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+pub(crate) fn storage_layout_entry(
+    binding: u32,
+    visibility: wgpu::ShaderStages,
+    read_only: bool,
+) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+fn builtin_layout_entries() -> [wgpu::BindGroupLayoutEntry; 9] {
+    [
+        uniform_layout_entry(0, wgpu::ShaderStages::FRAGMENT),
+        uniform_layout_entry(1, wgpu::ShaderStages::FRAGMENT),
+        uniform_layout_entry(USER_UNIFORMS_BINDING, wgpu::ShaderStages::FRAGMENT),
+        uniform_layout_entry(TILE_BINDING, wgpu::ShaderStages::VERTEX),
+        uniform_layout_entry(DATE_BINDING, wgpu::ShaderStages::FRAGMENT),
+        uniform_layout_entry(CELL_BINDING, wgpu::ShaderStages::FRAGMENT),
+        uniform_layout_entry(FOCUS_BINDING, wgpu::ShaderStages::FRAGMENT),
+        uniform_layout_entry(SCROLL_BINDING, wgpu::ShaderStages::FRAGMENT),
+        uniform_layout_entry(INSTANCE_BINDING, wgpu::ShaderStages::FRAGMENT),
+    ]
+}
+
+pub(crate) fn create_pipeline_layout(
+    device: &wgpu::Device,
+    bind_group_layouts: &[wgpu::BindGroupLayout],
+) -> wgpu::PipelineLayout {
+    let bind_group_layouts: Vec<&wgpu::BindGroupLayout> = bind_group_layouts.iter().collect();
+    device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &bind_group_layouts,
+        push_constant_ranges: &[],
+    })
+}
+
+pub(crate) fn create_render_pipeline(
+    device: &wgpu::Device,
+    pipeline_layout: &wgpu::PipelineLayout,
+    vertex_shader: &wgpu::ShaderModule,
+    fragment_shader: &wgpu::ShaderModule,
+    entry_point: Option<&str>,
+    sample_count: u32,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: vertex_shader,
+            entry_point: Some("main"),
+            buffers: &[],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: fragment_shader,
+            entry_point,
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+        cache: None,
+    })
+}
@@ -0,0 +1,95 @@
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::bindings::BindingsBuilder;
+use crate::gpu::{DeviceOptions, lost_reason};
+use crate::uniforms::UniformLayout;
+
+/// [`ShaderHandle`] is a compiled shader: the GPU device it was compiled for, its render pipeline and the
+/// layout of its inputs. It never changes after creation and is reference counted, so cloning it is
+/// cheap.
+///
+/// A [`ShaderCanvasState`](crate::ShaderCanvasState) combines a handle with its per-widget parameters:
+/// uniforms, bindings, clock and the textures it renders into. Cloning a state shares the handle and
+/// copies the parameters, so the clone can be changed independently of the original without compiling
+/// the shader again. [`ShaderCanvasState::from_handle`](crate::ShaderCanvasState::from_handle) creates
+/// a state with fresh parameters instead.
+///
+/// ```rust,no_run
+/// # use tui_shader::{ShaderCanvasState, WgslShader};
+/// let mut left = ShaderCanvasState::new(WgslShader::Path("shader.wgsl")).unwrap();
+/// let mut right = ShaderCanvasState::from_handle(&left.handle().unwrap());
+/// left.set_uniform("hue", 0.2);
+/// right.set_uniform("hue", 0.7);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShaderHandle {
+    pub(crate) shader: Arc<CompiledShader>,
+}
+
+impl ShaderHandle {
+    /// Returns `true` if both handles refer to the same compiled shader.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shader, &other.shader)
+    }
+
+    /// The device the shader was compiled for. Textures and buffers used together with the shader, e.g.
+    /// by [`ShaderCanvasState::render_to_texture`](crate::ShaderCanvasState::render_to_texture), must be
+    /// created on it.
+    pub fn device(&self) -> &wgpu::Device {
+        &self.shader.device
+    }
+
+    /// The queue of [`ShaderHandle::device`].
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.shader.queue
+    }
+}
+
+/// The GPU resources shared by all states executing the same shader.
+#[derive(Debug)]
+pub(crate) struct CompiledShader {
+    pub(crate) device: wgpu::Device,
+    pub(crate) queue: wgpu::Queue,
+    pub(crate) pipeline_layout: wgpu::PipelineLayout,
+    pub(crate) vertex_shader: wgpu::ShaderModule,
+    pub(crate) fragment_shader: wgpu::ShaderModule,
+    pub(crate) entry_point: Option<String>,
+    pub(crate) uniform_layout: UniformLayout,
+    /// The bindings every new state starts with.
+    pub(crate) bindings: BindingsBuilder,
+    pub(crate) bind_group_layouts: Vec<wgpu::BindGroupLayout>,
+    pub(crate) pipeline: wgpu::RenderPipeline,
+    /// Created the first time a state enables multisampling.
+    pub(crate) multisampled_pipeline: OnceLock<wgpu::RenderPipeline>,
+    /// The reason the device was lost, set by its device lost callback.
+    pub(crate) lost: Arc<Mutex<Option<String>>>,
+    /// The source of the fragment shader, to compile it again on a new device, `None` if it can't be
+    /// copied.
+    pub(crate) source: Option<wgpu::ShaderSource<'static>>,
+    /// The options the device was requested with.
+    pub(crate) device_options: DeviceOptions,
+}
+
+impl CompiledShader {
+    /// The reason the device was lost, `None` while it is usable.
+    pub(crate) fn lost_reason(&self) -> Option<String> {
+        lost_reason(&self.lost)
+    }
+
+    /// The pipeline rendering into single or multisampled textures.
+    pub(crate) fn pipeline(&self, multisampled: bool) -> &wgpu::RenderPipeline {
+        if !multisampled {
+            return &self.pipeline;
+        }
+        self.multisampled_pipeline.get_or_init(|| {
+            crate::gpu::create_render_pipeline(
+                &self.device,
+                &self.pipeline_layout,
+                &self.vertex_shader,
+                &self.fragment_shader,
+                self.entry_point.as_deref(),
+                crate::gpu::MSAA_SAMPLE_COUNT,
+            )
+        })
+    }
+}
@@ -0,0 +1,82 @@
+use std::error::Error;
+
+use image::{DynamicImage, RgbaImage};
+use ratatui_core::layout::Rect;
+use ratatui_image::Resize;
+use ratatui_image::picker::Picker;
+use ratatui_image::protocol::Protocol;
+
+use crate::bindings::{BindingSlot, BindingsBuilder};
+use crate::context::ShaderContext;
+use crate::frame::Frame;
+use crate::state::ShaderCanvasState;
+
+impl ShaderCanvasState {
+    /// Renders the shader into an image of `width` x `height` pixels, using the time of the state's
+    /// [`ShaderClock`](crate::ShaderClock). Unlike rendering a [`ShaderCanvas`](crate::ShaderCanvas),
+    /// which produces one pixel per cell, this renders at any resolution.
+    pub fn render_image(&mut self, width: u32, height: u32) -> DynamicImage {
+        let width = width.clamp(1, u16::MAX.into());
+        let height = height.clamp(1, u16::MAX.into());
+        let time = self.clock().elapsed().as_secs_f32();
+        let rect = Rect::new(0, 0, width as u16, height as u16);
+        let pixels = self.execute(ShaderContext::new(time, rect));
+        let frame = Frame::new(width, height, pixels);
+        let image = RgbaImage::from_raw(width, height, frame.to_rgba())
+            .expect("the frame matches its dimensions");
+        self.set_last_frame(frame, None);
+        DynamicImage::ImageRgba8(image)
+    }
+
+    /// Renders the shader at the full pixel resolution of `area`, as reported by the font size of the
+    /// [`Picker`], and encodes it for the graphics protocol the [`Picker`] detected. Render the result
+    /// with a [`ratatui_image::Image`] widget.
+    ///
+    /// ```rust,no_run
+    /// # use ratatui_image::{Image, picker::Picker};
+    /// # use tui_shader::ShaderCanvasState;
+    /// let mut terminal = ratatui::init();
+    /// let picker = Picker::from_query_stdio().unwrap();
+    /// let mut state = ShaderCanvasState::default();
+    /// terminal.draw(|frame| {
+    ///     let protocol = state.render_protocol(&picker, frame.area()).unwrap();
+    ///     frame.render_widget(Image::new(&protocol), frame.area());
+    /// }).unwrap();
+    /// ratatui::restore();
+    /// ```
+    pub fn render_protocol(
+        &mut self,
+        picker: &Picker,
+        area: Rect,
+    ) -> Result<Protocol, Box<dyn Error>> {
+        let (font_width, font_height) = picker.font_size();
+        let image = self.render_image(
+            u32::from(area.width) * u32::from(font_width),
+            u32::from(area.height) * u32::from(font_height),
+        );
+        Ok(picker.new_protocol(image, area, Resize::Fit(None))?)
+    }
+
+    /// Uploads an image to the texture declared at `slot`, e.g. the
+    /// [`ImageSource::image`](ratatui_image::protocol::ImageSource::image) of an image managed by
+    /// `ratatui-image`. See [`ShaderCanvasState::update_texture`].
+    pub fn update_texture_image(
+        &mut self,
+        slot: impl Into<BindingSlot>,
+        image: &DynamicImage,
+    ) -> Result<(), Box<dyn Error>> {
+        let image = image.to_rgba8();
+        self.update_texture(slot, image.as_raw(), image.width(), image.height())
+    }
+}
+
+impl BindingsBuilder {
+    /// Declares a `texture_2d<f32>` initialized with an image, e.g. the
+    /// [`ImageSource::image`](ratatui_image::protocol::ImageSource::image) of an image managed by
+    /// `ratatui-image`.
+    #[must_use]
+    pub fn image(self, slot: impl Into<BindingSlot>, image: &DynamicImage) -> Self {
+        let image = image.to_rgba8();
+        self.texture_with_data(slot, image.width(), image.height(), image.as_raw())
+    }
+}
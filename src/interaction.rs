@@ -0,0 +1,145 @@
+use crate::context::ShaderContext;
+use crate::state::Uniforms;
+
+/// Pointer input passed to [`ShaderCanvasState::handle_pointer`](crate::ShaderCanvasState::handle_pointer).
+/// Positions are cells in the coordinates of the terminal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerEvent {
+    /// A button was pressed over the cell at `x`, `y`. Starts a drag and counts as a click.
+    Press { x: u16, y: u16 },
+
+    /// The pointer moved to the cell at `x`, `y` while a button is held.
+    Drag { x: u16, y: u16 },
+
+    /// The held button was released, ending the drag.
+    Release,
+
+    /// The wheel was scrolled by the given number of lines, positive when scrolling up.
+    Scroll(f32),
+}
+
+impl PointerEvent {
+    /// Converts a crossterm mouse event. Returns `None` for other events and mouse events which don't
+    /// affect the [`Interaction`], like moving the pointer without holding a button.
+    #[cfg(feature = "runner")]
+    pub fn from_crossterm(event: &ratatui::crossterm::event::Event) -> Option<Self> {
+        use ratatui::crossterm::event::{Event, MouseEventKind};
+
+        let Event::Mouse(mouse) = event else {
+            return None;
+        };
+        let (x, y) = (mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(_) => Some(Self::Press { x, y }),
+            MouseEventKind::Drag(_) => Some(Self::Drag { x, y }),
+            MouseEventKind::Up(_) => Some(Self::Release),
+            MouseEventKind::ScrollUp => Some(Self::Scroll(1.0)),
+            MouseEventKind::ScrollDown => Some(Self::Scroll(-1.0)),
+            _ => None,
+        }
+    }
+}
+
+/// [`Interaction`] holds the values derived from the [`PointerEvent`]s a
+/// [`ShaderCanvasState`](crate::ShaderCanvasState) received. Once the first event arrived, they are written
+/// to the user defined uniforms of the same name before every execution, so that shaders can be rotated by
+/// dragging, zoomed by scrolling or flash where they were clicked. Uniforms the shader doesn't declare are
+/// ignored.
+///
+/// - `drag: vec4<f32>`: x and y are the distance dragged in cells, accumulated over all drags, with y
+///   pointing up like `uv`. z is `1.0` while a button is held and `0.0` otherwise.
+/// - `scroll: f32`: the lines scrolled, accumulated, positive when scrolling up.
+/// - `click: vec4<f32>`: x and y are the position of the last click normalized to the canvas like `uv`, z
+///   is the value of `time.x` at the click and w is `1.0` once the canvas was clicked.
+///
+/// ```wgsl
+/// struct Uniforms {
+///     drag: vec4<f32>,
+///     click: vec4<f32>,
+///     scroll: f32,
+/// }
+///
+/// @group(0) @binding(0) var<uniform> time: vec4<f32>;
+/// @group(0) @binding(2) var<uniform> uniforms: Uniforms;
+///
+/// @fragment
+/// fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+///     let angle = uniforms.drag.x * 0.1;
+///     let flash = uniforms.click.w * exp(-(time.x - uniforms.click.z) * 4.0);
+///     let ring = flash * smoothstep(0.1, 0.0, distance(uv, uniforms.click.xy));
+///     let zoom = exp(uniforms.scroll * 0.1);
+///     let stripes = 0.5 + 0.5 * sin((uv.x * cos(angle) + uv.y * sin(angle)) * 20.0 * zoom);
+///     return vec4<f32>(stripes + ring, stripes, 1.0 - stripes, 1.0);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Interaction {
+    drag: (f32, f32),
+    /// The position of the pointer while a button is held.
+    pointer: Option<(u16, u16)>,
+    scroll: f32,
+    /// The position of the last click and the time of the clock at the click.
+    click: Option<(u16, u16, f32)>,
+}
+
+impl Interaction {
+    /// The distance dragged in cells, accumulated over all drags, with y pointing up.
+    pub fn drag(&self) -> (f32, f32) {
+        self.drag
+    }
+
+    /// Whether a button is held.
+    pub fn is_dragging(&self) -> bool {
+        self.pointer.is_some()
+    }
+
+    /// The lines scrolled, accumulated, positive when scrolling up.
+    pub fn scroll(&self) -> f32 {
+        self.scroll
+    }
+
+    /// The cell of the last click in the coordinates of the terminal, `None` before the first click.
+    pub fn click(&self) -> Option<(u16, u16)> {
+        self.click.map(|(x, y, _)| (x, y))
+    }
+
+    /// The time of the clock of the state at the last click in seconds, `None` before the first click.
+    pub fn click_time(&self) -> Option<f32> {
+        self.click.map(|(_, _, time)| time)
+    }
+
+    /// Updates the values with `event`, which happened at `time` of the clock.
+    pub(crate) fn handle(&mut self, event: PointerEvent, time: f32) {
+        match event {
+            PointerEvent::Press { x, y } => {
+                self.pointer = Some((x, y));
+                self.click = Some((x, y, time));
+            }
+            PointerEvent::Drag { x, y } => {
+                if let Some((previous_x, previous_y)) = self.pointer {
+                    self.drag.0 += f32::from(x) - f32::from(previous_x);
+                    self.drag.1 += f32::from(previous_y) - f32::from(y);
+                }
+                self.pointer = Some((x, y));
+            }
+            PointerEvent::Release => self.pointer = None,
+            PointerEvent::Scroll(lines) => self.scroll += lines,
+        }
+    }
+
+    /// Writes the values to the uniforms, normalizing the click to the rect of `ctx`.
+    pub(crate) fn apply(&self, uniforms: &mut Uniforms<'_>, ctx: &ShaderContext) {
+        let dragging = if self.is_dragging() { 1.0 } else { 0.0 };
+        let click = match self.click {
+            Some((x, y, time)) => {
+                let [u, v] = ctx.normalize(x, y);
+                [u, v, time, 1.0]
+            }
+            None => [0.0; 4],
+        };
+        uniforms
+            .set("drag", [self.drag.0, self.drag.1, dragging, 0.0])
+            .set("scroll", self.scroll)
+            .set("click", click);
+    }
+}
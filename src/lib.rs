@@ -99,25 +99,299 @@
 //! | Rect     | `vec4<u32>` | `@group(0) @binding(1)` | x: x position of rect, y: y position of rect, z: width, w: height                 |
 //! | UV       | `vec2<f32>` | `@location(0)`          | x: normalized x coordinate y: norimalized y coordinate                            |
 //! | Position | `vec4<f32>` | `@builtin(position)`    | x: absolute x position y: absolute y position z/w: useless in `tui-shader`        |
+//! | Uniforms | `struct`    | `@group(0) @binding(2)` | user defined scalar and vector members, set with [`ShaderCanvasState::uniforms`]  |
+//! | Date     | `vec4<f32>` | `@group(0) @binding(4)` | x: seconds since local midnight, y: year, z: month, w: day (`wall-clock` feature) |
+//! | Cell     | `vec4<f32>` | `@group(0) @binding(5)` | x: cell width in pixels, y: cell height in pixels, z/w: rect size in pixels       |
+//! | Focus    | `vec4<f32>` | `@group(0) @binding(6)` | xy: normalized focus point, z: `1.0` if set, `0.0` otherwise                      |
+//! | Scroll   | `vec4<f32>` | `@group(0) @binding(7)` | xy: scroll offset in cells, zw: shift of `uv` caused by the offset                |
+//! | Instance | `vec4<u32>` | `@group(0) @binding(8)` | x: id of the rendered instance, see [`ShaderCanvas::instance`]                    |
+//!
+//! The inputs besides `uv`, `position` and the uniforms form the [`ShaderContext`], whose layout is
+//! versioned with [`CONTEXT_VERSION`]. [`CONTEXT_WGSL`] declares them all, shaders preprocessed by
+//! [`WgslShader`] get it with `#include <context>`.
+//!
+//! `@group(0) @binding(3)` is reserved for the vertex shader. Areas larger than the maximum texture size of
+//! the GPU are rendered in tiles: `uv` still covers the whole area, but `@builtin(position)` is relative
+//! to the current tile, so prefer `uv` for effects spanning the whole canvas.
+//!
+//! With the `macros` feature, `include_wgsl_validated!` embeds a shader like [`include_wgsl!`] and checks
+//! it against this table while compiling, so a broken shader fails the build with the compiler's
+//! diagnostic instead of panicking on the first draw. Naming the entry point also checks that it is a
+//! `@fragment` function taking the UV coordinates and returning a color:
+//!
+//! ```rust,ignore
+//! let shader = tui_shader::include_wgsl_validated!("shader.wgsl", "main");
+//! let state = ShaderCanvasState::new_with_entry_point(shader, "main").unwrap();
+//! ```
+//!
+//! ## Shaders written in Rust
+//!
+//! With the `spirv` feature, `SpirvShader` accepts shaders compiled to SPIR-V, e.g. written in Rust with
+//! [rust-gpu](https://rust-gpu.github.io). A crate shared by the app and the shader defines the uniforms:
+//!
+//! ```rust,ignore
+//! #[repr(C)]
+//! #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+//! pub struct Uniforms {
+//!     pub hue: f32,
+//!     pub _padding: [f32; 3],
+//! }
+//! ```
+//!
+//! The shader crate reads the inputs from the same bindings as a WGSL shader. Arguments without an
+//! attribute are numbered in order, so the first one receives the UV coordinates and the output is the
+//! color. The entry point is named after the function:
+//!
+//! ```rust,ignore
+//! #[spirv(fragment)]
+//! pub fn main_fs(
+//!     #[spirv(uniform, descriptor_set = 0, binding = 0)] time: &Vec4,
+//!     #[spirv(uniform, descriptor_set = 0, binding = 2)] uniforms: &Uniforms,
+//!     uv: Vec2,
+//!     output: &mut Vec4,
+//! ) {
+//!     *output = Vec4::new(uv.x, uv.y, (uniforms.hue + time.x).fract(), 1.0);
+//! }
+//! ```
+//!
+//! The app compiles the shader crate in its build script with `spirv-builder`, embeds the result and
+//! passes the same struct to [`ShaderCanvasState::set_uniforms`]:
+//!
+//! ```rust,ignore
+//! // build.rs
+//! spirv_builder::SpirvBuilder::new("shader", "spirv-unknown-vulkan1.1").build().unwrap();
+//!
+//! // main.rs
+//! let shader = SpirvShader::Bytes(include_bytes!(env!("shader.spv")));
+//! let mut state = ShaderCanvasState::new_with_entry_point(shader, "main_fs").unwrap();
+//! state.set_uniforms(&Uniforms { hue: 0.5, _padding: [0.0; 3] }).unwrap();
+//! ```
+//!
+//! ## Without a GPU
+//!
+//! Shaders run on the GPU with [`wgpu`](https://wgpu.rs), which is enabled by the default `gpu` feature.
+//! Disabling default features removes the whole wgpu dependency tree and leaves the cell mapping
+//! machinery: [`ShaderCanvas`] with its rules and [`Sample`], driven by a [`CpuBackend`] passed to
+//! [`ShaderCanvasState::from_backend`].
+//!
+//! ```toml
+//! tui-shader = { version = "*", default-features = false }
+//! ```
+//!
+//! The GPU APIs wgpu can use are enabled by the `vulkan`, `metal`, `dx12` and `gl` features, all of which
+//! are enabled by default. Turning the `gpu` feature back on after disabling default features isn't
+//! enough, since it compiles wgpu without any of them: enable at least one, which also enables `gpu`.
+//! Apps only shipping to some platforms can pick the APIs they need to cut down compile times, e.g. only
+//! Vulkan on Linux:
+//!
+//! ```toml
+//! tui-shader = { version = "*", default-features = false, features = ["vulkan"] }
+//! ```
+//!
+//! Without any of these features, wgpu has no backend to request a device from, so creating a GPU state
+//! fails with [`TuiShaderError::NoBackend`]. The same happens when the `WGPU_BACKEND` environment variable
+//! only names backends which aren't enabled.
 
+mod ansi;
+#[cfg(feature = "gpu")]
+mod bindings;
+mod budget;
+mod builder;
+mod camera;
 mod canvas;
+#[cfg(feature = "gpu")]
+mod check;
+mod clock;
+mod color;
 mod context;
+mod cpu;
+pub mod easing;
+#[cfg(feature = "gpu")]
+mod error;
+mod fill;
+#[cfg(feature = "image")]
+mod font;
+mod frame;
+#[cfg(feature = "gpu")]
+mod glow;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "gpu")]
+mod handle;
+#[cfg(feature = "ratatui-image")]
+mod image_protocol;
+mod interaction;
+#[cfg(feature = "log-capture")]
+mod logs;
+mod lut;
+#[cfg(feature = "gpu")]
+mod matrix_rain;
+mod memory;
+#[cfg(any(feature = "gltf", feature = "obj"))]
+mod mesh_import;
+#[cfg(feature = "midir")]
+mod midi;
+#[cfg(feature = "gpu")]
+mod noise_texture;
+#[cfg(feature = "osc")]
+mod osc;
+#[cfg(feature = "gpu")]
+mod particles;
+mod player;
+#[cfg(feature = "gpu")]
+mod playlist;
+#[cfg(feature = "gpu")]
+mod policy;
+mod power;
+#[cfg(feature = "gpu")]
+mod preprocessor;
+#[cfg(all(feature = "serde", feature = "gpu"))]
+mod preset;
+#[cfg(feature = "gpu")]
+pub mod presets;
+mod provider;
+mod recorder;
+#[cfg(feature = "runner")]
+mod runner;
+#[cfg(feature = "gpu")]
+mod scene;
+#[cfg(feature = "rhai")]
+mod script;
+#[cfg(feature = "image")]
+mod snapshot;
+mod stack;
 mod state;
+pub mod stdlib;
+mod stream;
 mod style;
+mod surface;
+#[cfg(feature = "sysinfo")]
+mod system;
+mod telemetry;
+mod terminal;
+pub mod testing;
+#[cfg(feature = "image")]
+mod text_texture;
+mod timeline;
+mod uniforms;
 mod util;
+#[cfg(feature = "wasm")]
+mod wasm;
 
+pub use crate::ansi::*;
+#[cfg(feature = "gpu")]
+pub use crate::bindings::*;
+pub use crate::builder::*;
+pub use crate::camera::*;
 pub use crate::canvas::*;
+#[cfg(feature = "gpu")]
+pub use crate::check::*;
+pub use crate::clock::*;
+pub use crate::color::*;
+pub use crate::context::{CONTEXT_VERSION, CONTEXT_WGSL, ShaderContext};
+pub use crate::cpu::*;
+pub use crate::easing::{Easing, Tween};
+#[cfg(feature = "gpu")]
+pub use crate::error::TuiShaderError;
+pub use crate::fill::*;
+pub use crate::frame::FrameView;
+#[cfg(feature = "gpu")]
+pub use crate::glow::*;
+#[cfg(feature = "gpu")]
+pub use crate::handle::*;
+pub use crate::interaction::*;
+#[cfg(feature = "log-capture")]
+pub use crate::logs::*;
+pub use crate::lut::*;
+#[cfg(feature = "gpu")]
+pub use crate::matrix_rain::*;
+pub use crate::memory::{MemoryUsage, total_memory_usage};
+#[cfg(feature = "midir")]
+pub use crate::midi::*;
+#[cfg(feature = "gpu")]
+pub use crate::noise_texture::*;
+#[cfg(feature = "osc")]
+pub use crate::osc::*;
+#[cfg(feature = "gpu")]
+pub use crate::particles::*;
+pub use crate::player::*;
+#[cfg(feature = "gpu")]
+pub use crate::playlist::*;
+#[cfg(feature = "gpu")]
+pub use crate::policy::ShaderPolicy;
+pub use crate::power::{IdleFrameRate, PowerProfile};
+#[cfg(feature = "gpu")]
+pub use crate::preprocessor::ShaderFeatures;
+#[cfg(all(feature = "serde", feature = "gpu"))]
+pub use crate::preset::*;
+pub use crate::provider::*;
+pub use crate::recorder::*;
+#[cfg(feature = "runner")]
+pub use crate::runner::*;
+#[cfg(feature = "gpu")]
+pub use crate::scene::*;
+#[cfg(feature = "rhai")]
+pub use crate::script::*;
+#[cfg(feature = "image")]
+pub use crate::snapshot::*;
+pub use crate::stack::*;
 pub use crate::state::*;
+pub use crate::stream::*;
 pub use crate::style::*;
+pub use crate::surface::*;
+#[cfg(feature = "sysinfo")]
+pub use crate::system::*;
+pub use crate::telemetry::*;
+#[cfg(feature = "image")]
+pub use crate::text_texture::*;
+pub use crate::timeline::*;
+pub use crate::uniforms::*;
+#[cfg(feature = "gpu")]
 pub use crate::util::*;
+#[cfg(not(feature = "gpu"))]
+pub(crate) use crate::util::*;
+#[cfg(feature = "wasm")]
+pub use crate::wasm::*;
 
+#[cfg(feature = "macros")]
+pub use tui_shader_macros::include_wgsl_validated;
+#[cfg(feature = "gpu")]
 pub use wgpu::include_wgsl;
 
-#[cfg(test)]
+// Lets the macros refer to `::tui_shader` in the tests of this crate.
+#[cfg(all(test, feature = "macros"))]
+extern crate self as tui_shader;
+
+// The states are moved to render threads and shared behind `Arc<Mutex<..>>` by apps. wgpu handles are only
+// `Send` and `Sync` on native targets.
+#[cfg(not(target_arch = "wasm32"))]
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ShaderCanvasState>();
+    assert_send_sync::<ShaderCanvas>();
+    assert_send_sync::<ShaderFill>();
+    assert_send_sync::<SharedSurface>();
+    #[cfg(feature = "gpu")]
+    assert_send_sync::<MatrixRainState>();
+    #[cfg(feature = "gpu")]
+    assert_send_sync::<ParticleCanvasState>();
+    #[cfg(feature = "gpu")]
+    assert_send_sync::<ShaderHandle>();
+    #[cfg(feature = "gpu")]
+    assert_send_sync::<FocusGlowState>();
+};
+
+#[cfg(all(test, feature = "gpu"))]
 mod tests {
+    use std::time::Duration;
+
     use ratatui_core::{backend::TestBackend, layout::Position};
 
-    use crate::{CharacterRule, ShaderCanvas, ShaderCanvasState, context::ShaderContext};
+    use crate::{
+        AnimationTimeline, AnsiColors, BindingsBuilder, CharacterRule, Easing, MatrixRainState,
+        ParticleCanvasState, ParticleConfig, Playback, ShaderCanvas, ShaderCanvasState,
+        ShaderClock, Tween, context::ShaderContext, row_padding, testing,
+    };
 
     #[test]
     fn default_state() {
@@ -137,6 +411,43 @@ mod tests {
         assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 255, 0, 255]));
     }
 
+    #[test]
+    fn shader_defines() {
+        let source = "@fragment fn main() -> @location(0) vec4<f32> { return vec4<f32>(RED, GREEN, 1e0 - 1.0, 1.0); }";
+        let shader =
+            crate::WgslShader::Source(source).with_defines(&[("RED", "0.0"), ("GREEN", "1.0")]);
+        let mut state = ShaderCanvasState::new(shader).unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 255, 0, 255]));
+
+        assert!(ShaderCanvasState::new(crate::WgslShader::Source(source)).is_err());
+    }
+
+    #[test]
+    fn shader_features() {
+        let source = "#define BLUE 1.0
+@fragment fn main() -> @location(0) vec4<f32> {
+#ifdef GREEN
+    return vec4<f32>(0.0, 1.0, 0.0, 1.0);
+#else
+    return vec4<f32>(0.0, 0.0, BLUE, 1.0);
+#endif
+}";
+        let features = crate::ShaderFeatures::default().enable("GREEN");
+        let mut state =
+            ShaderCanvasState::new(crate::WgslShader::Source(source).with_features(features))
+                .unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 255, 0, 255]));
+
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 0, 255, 255]));
+
+        let unterminated = "#ifdef GREEN\n@fragment fn main() -> @location(0) vec4<f32> { return vec4<f32>(1.0); }";
+        assert!(ShaderCanvasState::new(crate::WgslShader::Source(unterminated)).is_err());
+    }
+
     #[test]
     fn character_rule_map() {
         let mut terminal = ratatui_core::terminal::Terminal::new(TestBackend::new(64, 64)).unwrap();
@@ -163,4 +474,2686 @@ mod tests {
             })
             .unwrap();
     }
+
+    #[test]
+    fn render_to_string() {
+        let mut state = ShaderCanvasState::default();
+        let canvas = ShaderCanvas::new().character_rule(CharacterRule::Map(|sample| {
+            if sample.x() == 0 { ' ' } else { '.' }
+        }));
+        let dump = testing::render_to_string(&canvas, &mut state, 3, 2);
+        let row = "  - #ff00ff\t. - #ff00ff\t. - #ff00ff\n";
+        assert_eq!(dump, row.repeat(2));
+    }
+
+    #[test]
+    fn cpu_backend() {
+        let mut state = ShaderCanvasState::from_backend(|input: crate::PixelInput| {
+            [(input.x * 10) as u8, 0, (input.v * 255.0) as u8, 255]
+        });
+        let rect = ratatui_core::layout::Rect::new(0, 0, 4, 2);
+        let raw_buffer = state.execute(ShaderContext::new(0.0, rect));
+        let stride = 4 + crate::row_padding(4) as usize;
+        assert_eq!(raw_buffer[0], [0, 0, 191, 255]);
+        assert_eq!(raw_buffer[stride + 3], [30, 0, 63, 255]);
+        assert!(!state.set_uniform("speed", 1.0));
+    }
+
+    #[cfg(feature = "rhai")]
+    #[test]
+    fn script_backend() {
+        use crate::CpuBackend;
+
+        let path = std::env::temp_dir().join("tui-shader-script-backend.rhai");
+        std::fs::write(&path, "[brightness, 0.0, 1.0]").unwrap();
+        let mut state = ShaderCanvasState::from_backend(crate::ScriptBackend::new(&path).unwrap());
+        assert!(state.set_uniform("brightness", 1.0));
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert_eq!(raw_buffer[0], [255, 0, 255, 255]);
+        std::thread::sleep(Duration::from_millis(10));
+        std::fs::write(&path, "[0, x * 2, 0, 128]").unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert_eq!(raw_buffer[0], [0, 0, 0, 128]);
+        assert_eq!(raw_buffer[3], [0, 6, 0, 128]);
+
+        std::fs::write(&path, "loop {}").unwrap();
+        let mut backend = crate::ScriptBackend::new(&path)
+            .unwrap()
+            .max_operations(1000);
+        backend.prepare(0.0, 2, 1);
+        let input = crate::PixelInput {
+            x: 0,
+            y: 0,
+            u: 0.25,
+            v: 0.5,
+            time: 0.0,
+            width: 2,
+            height: 1,
+            instance: 0,
+        };
+        assert_eq!(backend.pixel(input), [0, 0, 0, 255]);
+        assert!(
+            backend
+                .last_error()
+                .unwrap()
+                .contains("Too many operations")
+        );
+        let mut state = ShaderCanvasState::from_backend(crate::ScriptBackend::new(&path).unwrap());
+        assert_eq!(state.execute(ShaderContext::default())[0], [0, 0, 0, 255]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn wasm_backend() {
+        use crate::CpuBackend;
+
+        let backend = crate::WasmBackend::from_bytes(
+            br#"(module
+                (global $blue (mut i32) (i32.const 0))
+                (func (export "prepare") (param f32 i32 i32)
+                    (global.set $blue (local.get 1)))
+                (func (export "pixel") (param i32 i32 f32 i32 i32) (result i32)
+                    (i32.or
+                        (i32.or (i32.shl (local.get 0) (i32.const 24)) (i32.const 255))
+                        (i32.shl (global.get $blue) (i32.const 8)))))"#,
+        )
+        .unwrap();
+        let mut state = ShaderCanvasState::from_backend(backend);
+        let rect = ratatui_core::layout::Rect::new(0, 0, 4, 2);
+        let raw_buffer = state.execute(ShaderContext::new(0.0, rect));
+        assert_eq!(raw_buffer[0], [0, 0, 4, 255]);
+        assert_eq!(raw_buffer[3], [3, 0, 4, 255]);
+        assert!(crate::WasmBackend::from_bytes(b"(module)").is_err());
+
+        let input = crate::PixelInput {
+            x: 0,
+            y: 0,
+            u: 0.5,
+            v: 0.5,
+            time: 0.0,
+            width: 2,
+            height: 1,
+            instance: 0,
+        };
+        let mut backend = crate::WasmBackend::from_bytes(
+            br#"(module
+                (func (export "pixel") (param i32 i32 f32 i32 i32) (result i32)
+                    (loop $forever (br $forever))
+                    (i32.const -1)))"#,
+        )
+        .unwrap()
+        .fuel_per_pixel(10_000);
+        backend.prepare(0.0, 2, 1);
+        assert_eq!(backend.pixel(input), [0, 0, 0, 255]);
+        assert!(backend.last_error().is_some());
+
+        let mut backend = crate::WasmBackend::from_bytes(
+            br#"(module
+                (memory 1)
+                (func (export "pixel") (param i32 i32 f32 i32 i32) (result i32)
+                    (drop (memory.grow (i32.const 1024)))
+                    (i32.const -1)))"#,
+        )
+        .unwrap()
+        .max_memory(1 << 20);
+        backend.prepare(0.0, 2, 1);
+        assert_eq!(backend.pixel(input), [0, 0, 0, 255]);
+        assert!(backend.last_error().is_some());
+    }
+
+    #[test]
+    fn particles_spawn() {
+        let mut state = ParticleCanvasState::new(ParticleConfig {
+            spawn_rate: 1000.0,
+            ..ParticleConfig::default()
+        });
+        let rect = ratatui_core::layout::Rect::new(0, 0, 64, 64);
+        let raw_buffer = state.execute(ShaderContext::new(0.0, rect));
+        assert!(raw_buffer.iter().all(|pixel| pixel[3] == 0));
+        let raw_buffer = state.execute(ShaderContext::new(0.05, rect));
+        assert!(raw_buffer.iter().any(|pixel| pixel[3] > 0));
+
+        let mut state = ParticleCanvasState::new(ParticleConfig {
+            max_particles: 16,
+            spawn_rate: 1000.0,
+            ..ParticleConfig::sparks()
+        });
+        state.execute(ShaderContext::new(0.0, rect));
+        let mut raw_buffer = state.execute(ShaderContext::new(0.02, rect));
+        assert!(raw_buffer.iter().any(|pixel| pixel[3] > 200));
+        state.set_spawn_rate(0.0);
+        for step in 1..=10 {
+            raw_buffer = state.execute(ShaderContext::new(0.02 + step as f32 * 0.05, rect));
+        }
+        assert!(raw_buffer.iter().any(|pixel| pixel[3] > 0));
+        assert!(
+            raw_buffer.iter().all(|pixel| pixel[3] < 200),
+            "particles fade out as they age"
+        );
+
+        let state = ParticleCanvasState::try_new(ParticleConfig {
+            max_particles: u32::MAX,
+            ..ParticleConfig::default()
+        })
+        .unwrap();
+        assert!(state.get_config().max_particles < u32::MAX);
+    }
+
+    #[test]
+    fn matrix_rain_glyphs() {
+        let mut state = MatrixRainState::try_new(crate::MatrixRainConfig {
+            density: 1.0,
+            speed: 1000.0,
+            ..crate::MatrixRainConfig::default()
+        })
+        .unwrap();
+        let rect = ratatui_core::layout::Rect::new(0, 0, 64, 64);
+        state.execute(ShaderContext::new(0.0, rect));
+        let raw_buffer = state.execute(ShaderContext::new(0.1, rect));
+        assert!(raw_buffer.iter().any(|pixel| pixel[3] > 0));
+        let rect = ratatui_core::layout::Rect::new(0, 0, 60, 64);
+        let raw_buffer = state.execute(ShaderContext::new(0.2, rect));
+        assert!(raw_buffer.iter().any(|pixel| pixel[3] > 0));
+    }
+
+    #[test]
+    fn user_uniforms() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "user_uniforms",
+        )
+        .unwrap();
+        assert!(state.set_uniform("red", 1.0));
+        assert!(!state.set_uniform("green", 1.0));
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[255, 0, 0, 255]));
+    }
+
+    #[cfg(all(feature = "spirv", feature = "vulkan"))]
+    #[test]
+    fn spirv_shader() {
+        use wgpu::naga;
+        let source = include_str!("shaders/test_fragment.wgsl");
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let info = naga::valid::Validator::new(Default::default(), Default::default())
+            .validate(&module)
+            .unwrap();
+        let words = naga::back::spv::write_vec(&module, &info, &Default::default(), None).unwrap();
+        let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            crate::SpirvShader::Bytes(&bytes),
+            "user_uniforms",
+        )
+        .unwrap();
+        state
+            .set_uniforms(&[1.0f32, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0])
+            .unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[255, 0, 0, 255]));
+
+        assert!(ShaderCanvasState::new(crate::SpirvShader::Bytes(&bytes[1..])).is_err());
+    }
+
+    #[test]
+    fn shader_handle() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "user_uniforms",
+        )
+        .unwrap();
+        state.set_uniform("red", 1.0);
+        let mut clone = state.clone();
+        assert!(state.handle().unwrap().ptr_eq(&clone.handle().unwrap()));
+        clone.set_uniform("red", 0.0);
+        clone.set_uniform("blue", 1.0);
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[255, 0, 0, 255]));
+        let raw_buffer = clone.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 0, 255, 255]));
+        let mut fresh = ShaderCanvasState::from_handle(&state.handle().unwrap());
+        let raw_buffer = fresh.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 0, 0, 255]));
+        assert!(
+            ShaderCanvasState::from_backend(|_: crate::PixelInput| [0; 4])
+                .handle()
+                .is_none()
+        );
+
+        let mut state = ShaderCanvasState::new_with_bindings(
+            wgpu::include_wgsl!("shaders/test_bindings.wgsl"),
+            None,
+            BindingsBuilder::new()
+                .texture_with_data((1, 0), 1, 1, &[255, 0, 0, 255])
+                .sampler((1, 1), wgpu::FilterMode::Nearest)
+                .storage_buffer((1, 2), 16),
+        )
+        .unwrap();
+        state.write_buffer((1, 2), &1.0f32.to_ne_bytes()).unwrap();
+        let mut clone = state.clone();
+        clone
+            .update_texture((1, 0), &[0, 0, 255, 255], 1, 1)
+            .unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[255, 255, 0, 255]));
+        let raw_buffer = clone.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 255, 255, 255]));
+    }
+
+    #[test]
+    fn state_builder() {
+        let mut clock = crate::ShaderClock::new();
+        clock.pause();
+        let mut state = ShaderCanvasState::builder()
+            .shader(wgpu::include_wgsl!("shaders/test_fragment.wgsl"))
+            .entry_point("green")
+            .clock(clock)
+            .build()
+            .unwrap();
+        assert!(state.clock().is_paused());
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 255, 0, 255]));
+
+        let mut state = ShaderCanvasState::builder()
+            .backend(|_: crate::PixelInput| [0, 0, 255, 255])
+            .build()
+            .unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 0, 255, 255]));
+
+        assert!(ShaderCanvasState::builder().build().is_ok());
+        let missing = ShaderCanvasState::builder().shader(crate::WgslShader::Path("missing.wgsl"));
+        assert!(missing.build().is_err());
+        let conflicting = ShaderCanvasState::builder()
+            .entry_point("green")
+            .backend(|_: crate::PixelInput| [0; 4]);
+        assert!(conflicting.build().is_err());
+    }
+
+    #[test]
+    fn adapter_selection() {
+        use crate::gpu::{AdapterSelection, enumerate_adapters};
+        let adapters = enumerate_adapters();
+        assert!(!adapters.is_empty());
+        let state = ShaderCanvasState::builder()
+            .adapter(AdapterSelection::Name(adapters[0].name.to_uppercase()))
+            .build();
+        assert!(state.is_ok());
+        let missing = ShaderCanvasState::builder().adapter(AdapterSelection::Index(adapters.len()));
+        assert!(missing.build().is_err());
+
+        let device = crate::gpu::DeviceOptions {
+            timeout: Some(Duration::from_secs(60)),
+            fallback: false,
+            ..Default::default()
+        };
+        assert!(ShaderCanvasState::builder().device(device).build().is_ok());
+    }
+
+    #[cfg(not(any(
+        feature = "vulkan",
+        feature = "metal",
+        feature = "dx12",
+        feature = "gl"
+    )))]
+    #[test]
+    fn no_backend() {
+        let error = ShaderCanvasState::builder().build().unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(crate::TuiShaderError::NoBackend(_))
+        ));
+        assert!(matches!(
+            ParticleCanvasState::try_new(ParticleConfig::default()),
+            Err(crate::TuiShaderError::NoBackend(_))
+        ));
+    }
+
+    #[test]
+    fn render_to_texture() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "green",
+        )
+        .unwrap();
+        let rect = ratatui_core::layout::Rect::new(0, 0, 8, 4);
+        let handle = state.handle().unwrap();
+        let texture = crate::gpu::create_texture(handle.device(), 8, 4);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        state.render_to_texture(&view, rect).unwrap();
+
+        let output_buffer = crate::gpu::create_output_buffer(handle.device(), 8, 4);
+        let mut command_encoder = handle
+            .device()
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        crate::gpu::copy_texture_to_buffer(&mut command_encoder, &texture, &output_buffer, 8, 4);
+        handle.queue().submit(Some(command_encoder.finish()));
+        let pixels = pollster::block_on(crate::gpu::read_output_buffer(
+            handle.device(),
+            &output_buffer,
+        ))
+        .unwrap();
+        assert!(
+            pixels
+                .chunks(64)
+                .flat_map(|row| &row[..8])
+                .all(|pixel| pixel == &[0, 255, 0, 255])
+        );
+
+        let bgra_texture = handle.device().create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 8,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Bgra8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = bgra_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        assert!(state.render_to_texture(&view, rect).is_err());
+    }
+
+    #[cfg(feature = "macros")]
+    #[test]
+    fn validated_shader() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            crate::include_wgsl_validated!("shaders/test_fragment.wgsl", "green"),
+            "green",
+        )
+        .unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn dynamic_uniforms() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "dynamic_uniforms",
+        )
+        .unwrap();
+        let mut uniforms = state.uniforms();
+        uniforms
+            .set("tint", ratatui_core::style::Color::Rgb(255, 0, 128))
+            .set("count", 2);
+        assert!(!uniforms.try_set("tint", 1.0));
+        assert!(!uniforms.try_set("missing", 1.0));
+        assert_eq!(uniforms.names(), ["red", "blue", "tint", "count"]);
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[255, 0, 128, 255]));
+    }
+
+    #[test]
+    fn execute_hooks() {
+        use std::sync::atomic::{AtomicU8, Ordering};
+
+        static GREEN: AtomicU8 = AtomicU8::new(0);
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "dynamic_uniforms",
+        )
+        .unwrap();
+        state.on_before_execute(|uniforms| {
+            uniforms
+                .set("tint", ratatui_core::style::Color::Rgb(0, 255, 0))
+                .set("count", 2);
+        });
+        state.on_after_execute(|frame| {
+            GREEN.store(frame.pixel(0, 0).unwrap()[1], Ordering::Relaxed);
+        });
+        let area = ratatui_core::layout::Rect::new(0, 0, 2, 2);
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        ShaderCanvas::new().render_at(area, &mut buffer, &mut state, 0.0);
+        assert_eq!(GREEN.load(Ordering::Relaxed), 255);
+    }
+
+    #[test]
+    fn device_lost() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        static LOST: AtomicBool = AtomicBool::new(false);
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "green",
+        )
+        .unwrap();
+        state.on_error(|error| {
+            LOST.store(
+                matches!(error, crate::TuiShaderError::DeviceLost(_)),
+                Ordering::Relaxed,
+            );
+        });
+        state.handle().unwrap().device().destroy();
+        let rect = ratatui_core::layout::Rect::new(0, 0, 4, 2);
+        let raw_buffer = state.execute(ShaderContext::new(0.0, rect));
+        assert!(LOST.load(Ordering::Relaxed));
+        assert_eq!(raw_buffer.len(), (4 + row_padding(4) as usize) * 2);
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0; 4]));
+    }
+
+    #[cfg(feature = "log-capture")]
+    #[test]
+    fn log_capture() {
+        let path = std::env::temp_dir().join("tui-shader-log-capture.log");
+        let _ = std::fs::remove_file(&path);
+        crate::capture_logs_to_file(&path, log::LevelFilter::Warn).unwrap();
+        assert!(crate::capture_logs(log::LevelFilter::Warn).is_err());
+        log::info!("ignored");
+        log::warn!("captured");
+        let logs = crate::logs();
+        assert!(logs.contains(&"[WARN tui_shader::tests] captured".to_string()));
+        assert!(!logs.iter().any(|line| line.contains("ignored")));
+        log::logger().flush();
+        let file = std::fs::read_to_string(&path).unwrap();
+        assert!(file.contains("captured"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn destroy() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "green",
+        )
+        .unwrap();
+        state.execute(ShaderContext::default());
+        let mut clone = state.clone();
+        state.destroy();
+        let rect = ratatui_core::layout::Rect::new(0, 0, 64, 2);
+        let raw_buffer = clone.execute(ShaderContext::new(0.0, rect));
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn tiled_rendering() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "uv",
+        )
+        .unwrap();
+        let rect = ratatui_core::layout::Rect::new(0, 0, 3000, 1);
+        let raw_buffer = state.execute(ShaderContext::new(0.0, rect));
+        assert_eq!(raw_buffer.len(), 3000 + row_padding(3000) as usize);
+        assert_eq!(raw_buffer[0][0], 0);
+        assert_eq!(raw_buffer[2999][0], 255);
+        assert!(raw_buffer[2047][0].abs_diff(raw_buffer[2048][0]) <= 1);
+        assert!(
+            raw_buffer[..3000]
+                .windows(2)
+                .all(|pixels| pixels[0][0] <= pixels[1][0])
+        );
+        let region = ratatui_core::layout::Rect::new(2040, 0, 16, 1);
+        let cached = state.execute_region(ShaderContext::new(0.0, rect), region);
+        assert_eq!(cached[..16], raw_buffer[2040..2056]);
+        state.set_user_bytes(&[0; 32]).unwrap();
+        let rendered = state.execute_region(ShaderContext::new(0.0, rect), region);
+        assert_eq!(rendered[..16], raw_buffer[2040..2056]);
+    }
+
+    #[test]
+    fn uniform_provider() {
+        struct Red;
+
+        impl crate::UniformProvider for Red {
+            fn provide(&mut self, uniforms: &mut crate::Uniforms<'_>) {
+                uniforms.set("red", 1.0).set("missing", 1.0);
+            }
+        }
+
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "user_uniforms",
+        )
+        .unwrap();
+        state.add_provider(Red);
+        #[cfg(feature = "sysinfo")]
+        state.add_provider(crate::SystemMetrics::new());
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn cell_size() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "cell_size",
+        )
+        .unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[8, 16, 255, 255]));
+        state.set_cell_size(0, 20);
+        assert_eq!(state.cell_size(), (8, 16));
+        state.set_cell_size(10, 20);
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[10, 20, 255, 255]));
+        if !state.detect_cell_size() {
+            assert_eq!(state.cell_size(), (10, 20));
+        }
+
+        #[cfg(unix)]
+        {
+            use crate::terminal::parse_pixel_size;
+            assert_eq!(parse_pixel_size(b"\x1b[4;768;1024t"), Some((1024, 768)));
+            assert_eq!(parse_pixel_size(b"\x1b[8;48;128t"), None);
+        }
+    }
+
+    #[test]
+    fn focus_point() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "focus_point",
+        )
+        .unwrap();
+        let rect = ratatui_core::layout::Rect::new(10, 5, 4, 2);
+        let stride = 4 + row_padding(4) as usize;
+        let raw_buffer = state.execute(ShaderContext::new(0.0, rect));
+        assert!(raw_buffer.iter().all(|pixel| pixel[0] == 0));
+        state.set_focus_point(12, 6);
+        let raw_buffer = state.execute(ShaderContext::new(0.0, rect));
+        assert_eq!(raw_buffer[stride + 2], [255, 0, 0, 255]);
+        assert_eq!(raw_buffer[0], [0, 0, 0, 255]);
+        state.clear_focus_point();
+        let raw_buffer = state.execute(ShaderContext::new(0.0, rect));
+        assert!(raw_buffer.iter().all(|pixel| pixel[0] == 0));
+    }
+
+    #[test]
+    fn user_bytes() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "user_uniforms",
+        )
+        .unwrap();
+        assert!(state.set_user_bytes(&[0; 8]).is_err());
+        assert!(state.set_user_bytes(&[0; 48]).is_err());
+        let bytes: Vec<u8> = [0.0f32, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect();
+        state.set_user_bytes(&bytes).unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn bindings_builder() {
+        let bindings = BindingsBuilder::new()
+            .texture_with_data((1, 0), 1, 1, &[255, 0, 0, 255])
+            .sampler((1, 1), wgpu::FilterMode::Nearest)
+            .storage_buffer((1, 2), 16);
+        let mut state = ShaderCanvasState::new_with_bindings(
+            wgpu::include_wgsl!("shaders/test_bindings.wgsl"),
+            None,
+            bindings,
+        )
+        .unwrap();
+        state.write_buffer((1, 2), &1.0f32.to_ne_bytes()).unwrap();
+        assert!(state.write_buffer((1, 2), &[0; 20]).is_err());
+        assert!(state.write_buffer((1, 0), &[0; 4]).is_err());
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[255, 255, 0, 255]));
+        state
+            .update_texture((1, 0), &[0, 0, 255, 255], 1, 1)
+            .unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 255, 255, 255]));
+        state
+            .update_texture((1, 0), &[255, 0, 255, 255].repeat(4), 2, 2)
+            .unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(
+            raw_buffer
+                .iter()
+                .all(|pixel| pixel == &[255, 255, 255, 255])
+        );
+        assert!(state.update_texture((1, 0), &[0; 4], 2, 2).is_err());
+        assert!(state.update_texture((1, 1), &[0; 4], 1, 1).is_err());
+
+        let reserved = BindingsBuilder::new().storage_buffer((0, 1), 16);
+        let result = ShaderCanvasState::new_with_bindings(
+            wgpu::include_wgsl!("shaders/test_bindings.wgsl"),
+            None,
+            reserved,
+        );
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "ratatui-image")]
+    #[test]
+    fn ratatui_image_protocol() {
+        let image = image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            2,
+            2,
+            image::Rgba([255, 0, 0, 255]),
+        ));
+        let mut state = ShaderCanvasState::new_with_bindings(
+            wgpu::include_wgsl!("shaders/test_bindings.wgsl"),
+            None,
+            BindingsBuilder::new()
+                .image((1, 0), &image)
+                .sampler((1, 1), wgpu::FilterMode::Nearest)
+                .storage_buffer((1, 2), 16),
+        )
+        .unwrap();
+        let rendered = state.render_image(8, 4).to_rgba8();
+        assert_eq!(rendered.dimensions(), (8, 4));
+        assert!(rendered.pixels().all(|pixel| pixel.0 == [255, 0, 0, 255]));
+        state.update_texture_image((1, 0), &image.fliph()).unwrap();
+
+        let picker = ratatui_image::picker::Picker::halfblocks();
+        let area = ratatui_core::layout::Rect::new(0, 0, 4, 2);
+        let protocol = state.render_protocol(&picker, area).unwrap();
+        assert_eq!(protocol.area(), area);
+    }
+
+    #[test]
+    fn timeline_interpolation() {
+        let timeline = AnimationTimeline::new()
+            .keyframe("value", Duration::from_secs(2), 1.0, Easing::Linear)
+            .keyframe("value", Duration::from_secs(1), 0.0, Easing::Linear)
+            .keyframe("value", Duration::from_secs(3), 0.0, Easing::QuadIn);
+        assert_eq!(timeline.duration(), Duration::from_secs(3));
+        assert_eq!(timeline.value("value", Duration::ZERO), Some(0.0));
+        assert_eq!(
+            timeline.value("value", Duration::from_millis(1500)),
+            Some(0.5)
+        );
+        assert_eq!(
+            timeline.value("value", Duration::from_millis(2500)),
+            Some(0.75)
+        );
+        assert_eq!(timeline.value("value", Duration::from_secs(10)), Some(0.0));
+        assert_eq!(timeline.value("missing", Duration::ZERO), None);
+    }
+
+    #[test]
+    fn timeline_paused_clock() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "user_uniforms",
+        )
+        .unwrap();
+        state.clock_mut().pause();
+        state.set_elapsed(Duration::from_secs(1));
+        let timeline = AnimationTimeline::new()
+            .keyframe("red", Duration::ZERO, 0.0, Easing::Linear)
+            .keyframe("red", Duration::from_secs(2), 1.0, Easing::Linear);
+        timeline.apply(&mut state);
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel[0].abs_diff(128) <= 1));
+    }
+
+    #[test]
+    fn easing_endpoints() {
+        use Easing::*;
+        for easing in [
+            Linear,
+            QuadIn,
+            QuadOut,
+            QuadInOut,
+            CubicIn,
+            CubicOut,
+            CubicInOut,
+            ElasticIn,
+            ElasticOut,
+            ElasticInOut,
+            BounceIn,
+            BounceOut,
+            BounceInOut,
+        ] {
+            assert!(easing.apply(0.0).abs() < 1e-5, "{easing:?}");
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-5, "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn tween_paused_clock() {
+        let mut clock = ShaderClock::new();
+        clock.pause();
+        let tween = Tween::new(2.0, 4.0, Duration::ZERO);
+        assert_eq!(tween.value(&clock), 4.0);
+        assert!(tween.is_finished(&clock));
+        let tween = Tween::new(2.0, 4.0, Duration::from_secs(1)).delay(Duration::from_secs(60));
+        assert_eq!(tween.value(&clock), 2.0);
+        assert!(!tween.is_finished(&clock));
+    }
+
+    #[test]
+    fn clock_offsets() {
+        let mut state = ShaderCanvasState::from_backend(|_: crate::PixelInput| [0, 0, 0, 255]);
+        state.clock_mut().pause();
+        state.set_elapsed(Duration::from_secs(3));
+        assert_eq!(state.clock().elapsed(), Duration::from_secs(3));
+        state.offset(Duration::from_millis(500));
+        assert_eq!(state.clock().elapsed(), Duration::from_millis(3500));
+        let instant = state.get_instant();
+        state.set_instant(instant);
+        assert_eq!(state.clock().elapsed(), Duration::from_millis(3500));
+        assert!(state.clock().is_paused());
+    }
+
+    #[cfg(feature = "wall-clock")]
+    #[test]
+    fn wall_clock() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "wall_clock",
+        )
+        .unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 0, 0, 255]));
+        state.set_wall_clock(true);
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[255, 255, 0, 255]));
+    }
+
+    #[test]
+    fn playback_time() {
+        let second = Duration::from_secs(1);
+        let once = Playback::Once(second);
+        assert_eq!(once.time(second * 3), second);
+        assert!(once.is_finished(second));
+        let looping = Playback::Loop(second);
+        assert_eq!(
+            looping.time(Duration::from_millis(2500)),
+            Duration::from_millis(500)
+        );
+        assert!(!looping.is_finished(second * 3));
+        let ping_pong = Playback::PingPong(second);
+        assert_eq!(
+            ping_pong.time(Duration::from_millis(1250)),
+            Duration::from_millis(750)
+        );
+        assert_eq!(
+            ping_pong.time(Duration::from_millis(2250)),
+            Duration::from_millis(250)
+        );
+
+        let mut state = ShaderCanvasState::default();
+        let mode = crate::ClockMode::Fixed { dt: second };
+        state.set_clock_mode(mode);
+        let mut player = crate::ShaderPlayer::new(state, once);
+        player.state_mut().set_elapsed(second * 3);
+        assert!(player.is_finished());
+        player.restart();
+        assert!(!player.is_finished());
+        assert_eq!(player.state().clock().mode(), mode);
+    }
+
+    #[cfg(feature = "glsl")]
+    #[test]
+    fn glsl_shader() {
+        let source = "#version 450
+            layout(location = 0) out vec4 color;
+            void main() { color = vec4(0.0, 0.0, 1.0, 1.0); }";
+        let mut state = ShaderCanvasState::new(crate::GlslShader::Source(source)).unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 0, 255, 255]));
+    }
+
+    #[cfg(feature = "hlsl")]
+    #[test]
+    fn invalid_hlsl_shader() {
+        // Fails whether or not dxc is installed.
+        let source = "float4 main() : SV_Target { return undefined; }";
+        assert!(ShaderCanvasState::new(crate::HlslShader::Source(source)).is_err());
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn record_gif() {
+        let path = std::env::temp_dir().join("tui-shader-record-gif.gif");
+        let mut terminal = ratatui_core::terminal::Terminal::new(TestBackend::new(16, 8)).unwrap();
+        let mut state = ShaderCanvasState::default();
+        let mut recorder = crate::Recorder::gif(&path, 10);
+        for _ in 0..2 {
+            let completed = terminal
+                .draw(|frame| {
+                    frame.render_stateful_widget(ShaderCanvas::new(), frame.area(), &mut state)
+                })
+                .unwrap();
+            recorder.capture(&state);
+            recorder.capture_buffer(completed.buffer);
+        }
+        assert_eq!(recorder.len(), 4);
+        recorder.finish().unwrap();
+        let delays: Vec<u16> = crate::recorder::frame_delays(60).take(60).collect();
+        assert_eq!(delays[..3], [2, 1, 2]);
+        assert_eq!(
+            delays.iter().map(|&delay| u32::from(delay)).sum::<u32>(),
+            100
+        );
+        assert_eq!(
+            crate::recorder::frame_delays(24)
+                .take(24)
+                .map(u32::from)
+                .sum::<u32>(),
+            100
+        );
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.starts_with(b"GIF89a"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn snapshot_png() {
+        let directory = std::env::temp_dir();
+        let texture_path = directory.join("tui-shader-snapshot-texture.png");
+        let cells_path = directory.join("tui-shader-snapshot-cells.png");
+        let mut terminal = ratatui_core::terminal::Terminal::new(TestBackend::new(4, 2)).unwrap();
+        let mut state = ShaderCanvasState::default();
+        assert!(state.snapshot_png(&texture_path).is_err());
+        let completed = terminal
+            .draw(|frame| {
+                frame.render_stateful_widget(
+                    ShaderCanvas::new().character_rule(CharacterRule::Always('#')),
+                    frame.area(),
+                    &mut state,
+                )
+            })
+            .unwrap();
+        crate::snapshot_buffer_png(completed.buffer, &cells_path).unwrap();
+        state.snapshot_png(&texture_path).unwrap();
+        let texture = image::open(&texture_path).unwrap().to_rgba8();
+        assert_eq!(texture.dimensions(), (4, 2));
+        assert!(texture.pixels().all(|pixel| pixel.0 == [255, 0, 255, 255]));
+        let cells = image::open(&cells_path).unwrap().to_rgba8();
+        assert_eq!(cells.dimensions(), (32, 32));
+        assert!(cells.pixels().any(|pixel| pixel.0 == [255, 255, 255, 255]));
+        std::fs::remove_file(texture_path).unwrap();
+        std::fs::remove_file(cells_path).unwrap();
+    }
+
+    #[test]
+    fn render_to_ansi() {
+        let mut state = ShaderCanvasState::default();
+        let canvas = ShaderCanvas::new().character_rule(CharacterRule::Always('#'));
+        let text = canvas.render_to_ansi(&mut state, 4, 2);
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.starts_with("\x1b[0;48;2;255;0;255m####\x1b[0m"));
+        let text = canvas.render_to_ansi_with(&mut state, 4, 2, AnsiColors::Indexed);
+        assert!(text.starts_with("\x1b[0;48;5;201m####\x1b[0m"));
+    }
+
+    #[test]
+    fn degenerate_areas() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "green",
+        )
+        .unwrap();
+        let canvas = ShaderCanvas::new();
+        let areas = [(0, 0), (0, 5), (5, 0), (1, 1), (u16::MAX, 1), (1, u16::MAX)];
+        for (width, height) in areas {
+            let buffer = testing::render(&canvas, &mut state, width, height, Duration::ZERO);
+            let expected = ratatui_core::style::Color::Rgb(0, 255, 0);
+            assert!(buffer.content().iter().all(|cell| cell.bg == expected));
+            let raw_buffer = state.execute(ShaderContext::new(0.0, buffer.area));
+            assert_eq!(raw_buffer.is_empty(), buffer.area.is_empty());
+        }
+    }
+
+    #[test]
+    fn render_scale() {
+        let mut state = ShaderCanvasState::from_backend(|input: crate::PixelInput| {
+            [input.x as u8 * 60, 0, 0, 255]
+        });
+        let canvas = ShaderCanvas::new().render_scale(2.0);
+        let buffer = testing::render(&canvas, &mut state, 2, 1, Duration::ZERO);
+        let colors: Vec<_> = buffer.content().iter().map(|cell| cell.bg).collect();
+        assert_eq!(
+            colors,
+            [
+                ratatui_core::style::Color::Rgb(30, 0, 0),
+                ratatui_core::style::Color::Rgb(150, 0, 0)
+            ]
+        );
+
+        let canvas = ShaderCanvas::new().render_scale(0.5);
+        let buffer = testing::render(&canvas, &mut state, 4, 1, Duration::ZERO);
+        let colors: Vec<_> = buffer.content().iter().map(|cell| cell.bg).collect();
+        let expected = [0, 15, 45, 60].map(|red| ratatui_core::style::Color::Rgb(red, 0, 0));
+        assert_eq!(colors, expected);
+    }
+
+    #[test]
+    fn msaa() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "first_sample",
+        )
+        .unwrap();
+        state.set_msaa(true);
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel[0].abs_diff(64) <= 1));
+        state.set_msaa(false);
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel[0] == 255));
+    }
+
+    #[test]
+    fn memoized_execution() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "user_uniforms",
+        )
+        .unwrap();
+        let ctx = ShaderContext::default();
+        assert_eq!(state.execute(ctx), state.execute(ctx));
+        state.set_uniform("red", 1.0);
+        let raw_buffer = state.execute(ctx);
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[255, 0, 0, 255]));
+        state.set_user_bytes(&[0; 32]).unwrap();
+        let raw_buffer = state.execute(ctx);
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn damage_region() {
+        let mut state = ShaderCanvasState::from_backend(|input: crate::PixelInput| {
+            [input.time as u8 * 100, input.x as u8, 0, 255]
+        });
+        let canvas = ShaderCanvas::new().region(ratatui_core::layout::Rect::new(1, 0, 2, 1));
+        let buffer = testing::render(&canvas, &mut state, 4, 2, Duration::ZERO);
+        let reds = |buffer: &ratatui_core::buffer::Buffer| -> Vec<u8> {
+            buffer
+                .content()
+                .iter()
+                .map(|cell| match cell.bg {
+                    ratatui_core::style::Color::Rgb(red, _, _) => red,
+                    _ => panic!("expected an rgb color"),
+                })
+                .collect()
+        };
+        assert_eq!(reds(&buffer), [0; 8]);
+        let buffer = testing::render(&canvas, &mut state, 4, 2, Duration::from_secs(1));
+        assert_eq!(reds(&buffer), [0, 100, 100, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            buffer[(2, 0)].bg,
+            ratatui_core::style::Color::Rgb(100, 2, 0)
+        );
+        let buffer = testing::render(&canvas, &mut state, 5, 2, Duration::from_secs(2));
+        assert_eq!(reds(&buffer), [200; 10]);
+    }
+
+    #[test]
+    fn sample_at() {
+        let mut state = ShaderCanvasState::from_backend(|input: crate::PixelInput| {
+            [input.x as u8 * 10, input.y as u8 * 10, 0, 255]
+        });
+        assert!(state.sample_at(Position::new(0, 0)).is_none());
+        let area = ratatui_core::layout::Rect::new(2, 1, 4, 2);
+        let mut buffer =
+            ratatui_core::buffer::Buffer::empty(ratatui_core::layout::Rect::new(0, 0, 8, 4));
+        ShaderCanvas::new().render_at(area, &mut buffer, &mut state, 0.0);
+        let sample = state.sample_at(Position::new(5, 2)).unwrap();
+        assert_eq!((sample.r(), sample.g()), (30, 10));
+        assert_eq!((sample.x(), sample.y()), (3, 1));
+        assert!(state.sample_at(Position::new(1, 1)).is_none());
+        assert!(state.sample_at(Position::new(6, 1)).is_none());
+    }
+
+    #[test]
+    fn shader_fill() {
+        use ratatui_core::widgets::Widget;
+
+        let fill =
+            crate::ShaderFill::new(ShaderCanvasState::from_backend(|_: crate::PixelInput| {
+                [0, 0, 255, 255]
+            }))
+            .canvas(ShaderCanvas::new().style_rule(crate::StyleRule::ColorFg));
+        let area = ratatui_core::layout::Rect::new(0, 0, 3, 2);
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        (&fill).render(area, &mut buffer);
+        assert_eq!(
+            buffer[(2, 1)].fg,
+            ratatui_core::style::Color::Rgb(0, 0, 255)
+        );
+        assert!(fill.with_state(|state| state.last_frame().is_some()));
+    }
+
+    #[cfg(feature = "widget-ref")]
+    #[test]
+    fn widget_ref() {
+        use ratatui::widgets::{StatefulWidgetRef, WidgetRef};
+
+        let mut state = ShaderCanvasState::from_backend(|_: crate::PixelInput| [0, 0, 255, 255]);
+        let canvas: Box<dyn StatefulWidgetRef<State = ShaderCanvasState>> =
+            Box::new(ShaderCanvas::new().style_rule(crate::StyleRule::ColorFg));
+        let area = ratatui_core::layout::Rect::new(0, 0, 3, 2);
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        canvas.render_ref(area, &mut buffer, &mut state);
+        canvas.render_ref(area, &mut buffer, &mut state);
+        assert_eq!(
+            buffer[(2, 1)].fg,
+            ratatui_core::style::Color::Rgb(0, 0, 255)
+        );
+
+        let fill: Box<dyn WidgetRef> = Box::new(crate::ShaderFill::new(state));
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        fill.render_ref(area, &mut buffer);
+        assert_eq!(
+            buffer[(0, 0)].bg,
+            ratatui_core::style::Color::Rgb(0, 0, 255)
+        );
+    }
+
+    #[test]
+    fn color_config() {
+        use crate::{ColorConfig, ColorSpace, Tonemap};
+
+        let mut state = ShaderCanvasState::builder()
+            .backend(|_: crate::PixelInput| [0, 128, 255, 100])
+            .color_config(ColorConfig {
+                working_space: ColorSpace::Linear,
+                ..ColorConfig::default()
+            })
+            .build()
+            .unwrap();
+        let rect = ratatui_core::layout::Rect::new(0, 0, 1, 1);
+        assert_eq!(
+            state.execute(ShaderContext::new(0.0, rect))[0],
+            [0, 188, 255, 100]
+        );
+        state.set_color_config(ColorConfig {
+            tonemap: Tonemap::Reinhard,
+            ..state.color_config()
+        });
+        assert_eq!(
+            state.execute(ShaderContext::new(0.0, rect))[0],
+            [0, 156, 188, 100]
+        );
+        state.set_color_config(ColorConfig::default());
+        assert_eq!(
+            state.execute(ShaderContext::new(0.0, rect))[0],
+            [0, 128, 255, 100]
+        );
+    }
+
+    #[test]
+    fn samples() {
+        let mut state = ShaderCanvasState::from_backend(|input: crate::PixelInput| {
+            [input.x as u8 * 10, input.y as u8 * 10, 0, 255]
+        });
+        let area = ratatui_core::layout::Rect::new(2, 1, 4, 2);
+        let samples: Vec<_> = state.samples(area).collect();
+        assert_eq!(samples.len(), 8);
+        assert_eq!((samples[5].x(), samples[5].y()), (1, 1));
+        assert_eq!((samples[5].u(), samples[5].v()), (0.25, 0.5));
+        assert_eq!((samples[5].r(), samples[5].g()), (10, 10));
+        assert_eq!(state.sample_at(Position::new(3, 2)), Some(samples[5]));
+        assert_eq!(
+            state.samples(ratatui_core::layout::Rect::default()).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn last_frame() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "green",
+        )
+        .unwrap();
+        assert!(state.last_frame().is_none());
+        let area = ratatui_core::layout::Rect::new(0, 0, 5, 3);
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        ShaderCanvas::new().render_at(area, &mut buffer, &mut state, 0.0);
+        let frame = state.last_frame().unwrap();
+        assert_eq!((frame.width(), frame.height()), (5, 3));
+        assert_eq!(frame.pixels().len(), frame.stride() * 3);
+        assert!(
+            frame
+                .rows()
+                .flatten()
+                .all(|pixel| pixel == &[0, 255, 0, 255])
+        );
+        assert_eq!(frame.pixel(4, 2), Some([0, 255, 0, 255]));
+        assert_eq!(frame.pixel(5, 0), None);
+        assert_eq!(frame.to_rgba().len(), 5 * 3 * 4);
+        #[cfg(feature = "image")]
+        assert_eq!(
+            frame.to_image().get_pixel(4, 2),
+            &image::Rgba([0, 255, 0, 255])
+        );
+        #[cfg(feature = "ndarray")]
+        {
+            let array = frame.to_ndarray();
+            assert_eq!(array.shape(), &[3, 5, 4]);
+            assert_eq!(array[[2, 4, 1]], 255);
+        }
+        let texture = state.output_texture().unwrap();
+        assert!(texture.width() >= 5 && texture.height() >= 3);
+    }
+
+    #[test]
+    fn shader_stack() {
+        use ratatui_core::widgets::Widget;
+
+        let mut bottom = ShaderCanvasState::from_backend(|_: crate::PixelInput| [100, 0, 0, 255]);
+        let mut top = ShaderCanvasState::from_backend(|_: crate::PixelInput| [0, 200, 0, 255]);
+        let area = ratatui_core::layout::Rect::new(0, 0, 4, 2);
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        crate::ShaderStack::new()
+            .layer(&mut bottom, crate::BlendMode::Normal, 1.0)
+            .layer(&mut top, crate::BlendMode::Add, 0.5)
+            .render(area, &mut buffer);
+        let expected = ratatui_core::style::Color::Rgb(100, 100, 0);
+        assert!(buffer.content().iter().all(|cell| cell.bg == expected));
+
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        crate::ShaderStack::new()
+            .layer(&mut bottom, crate::BlendMode::Normal, 1.0)
+            .layer(&mut top, crate::BlendMode::Multiply, 1.0)
+            .render(area, &mut buffer);
+        let expected = ratatui_core::style::Color::Rgb(0, 0, 0);
+        assert!(buffer.content().iter().all(|cell| cell.bg == expected));
+    }
+
+    #[test]
+    fn shared_surface() {
+        use ratatui_core::widgets::Widget;
+
+        let mut state = ShaderCanvasState::from_backend(|input: crate::PixelInput| {
+            [input.x as u8 * 10, input.y as u8 * 10, 0, 255]
+        });
+        let area = ratatui_core::layout::Rect::new(0, 0, 8, 4);
+        let surface = crate::SharedSurface::new(&mut state, area);
+        let canvas = ShaderCanvas::new();
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        surface
+            .view(&canvas)
+            .render(ratatui_core::layout::Rect::new(1, 1, 2, 2), &mut buffer);
+        surface
+            .view(&canvas)
+            .render(ratatui_core::layout::Rect::new(5, 1, 2, 2), &mut buffer);
+        assert_eq!(
+            buffer[(1, 1)].bg,
+            ratatui_core::style::Color::Rgb(10, 10, 0)
+        );
+        assert_eq!(
+            buffer[(6, 2)].bg,
+            ratatui_core::style::Color::Rgb(60, 20, 0)
+        );
+        assert_eq!(buffer[(4, 1)].bg, ratatui_core::style::Color::Reset);
+    }
+
+    #[test]
+    fn render_on_other_thread() {
+        let state = std::sync::Arc::new(std::sync::Mutex::new(ShaderCanvasState::default()));
+        let render_thread = {
+            let state = std::sync::Arc::clone(&state);
+            std::thread::spawn(move || {
+                let mut state = state.lock().unwrap();
+                testing::render(&ShaderCanvas::new(), &mut state, 4, 2, Duration::ZERO)
+            })
+        };
+        let buffer = render_thread.join().unwrap();
+        let expected = ratatui_core::style::Color::Rgb(255, 0, 255);
+        assert!(buffer.content().iter().all(|cell| cell.bg == expected));
+    }
+
+    #[test]
+    fn capture_frames() {
+        let mut state = ShaderCanvasState::default();
+        let area = ratatui_core::layout::Rect::new(0, 0, 4, 2);
+        let frames = state.capture_frames(area, 3, Duration::from_millis(100));
+        assert_eq!(frames.len(), 3);
+        for buffer in &frames {
+            assert_eq!(buffer.area, area);
+            assert_eq!(
+                buffer[(0, 0)].bg,
+                ratatui_core::style::Color::Rgb(255, 0, 255)
+            );
+        }
+    }
+
+    #[test]
+    fn snapshot_tolerance() {
+        use testing::ColorTolerance;
+        let path = std::env::temp_dir().join("tui-shader-snapshot-tolerance.txt");
+        let _ = std::fs::remove_file(&path);
+        let mut state = ShaderCanvasState::default();
+        *state.clock_mut() = testing::frozen_clock(Duration::from_secs(3));
+        assert_eq!(state.clock().elapsed(), Duration::from_secs(3));
+        let buffer = testing::render(&ShaderCanvas::new(), &mut state, 4, 2, Duration::ZERO);
+        testing::assert_snapshot(&buffer, &path, ColorTolerance::EXACT);
+        testing::assert_snapshot(&buffer, &path, ColorTolerance::EXACT);
+        let snapshot = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, snapshot.replace("#ff00ff", "#fd02ff")).unwrap();
+        testing::assert_snapshot(&buffer, &path, ColorTolerance::new(2));
+        let result = std::panic::catch_unwind(|| {
+            testing::assert_snapshot(&buffer, &path, ColorTolerance::new(1))
+        });
+        assert!(result.is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn similar_buffers() {
+        use testing::ColorTolerance;
+        let mut state = ShaderCanvasState::default();
+        let expected = testing::render(&ShaderCanvas::new(), &mut state, 4, 2, Duration::ZERO);
+        let mut actual = expected.clone();
+        actual[(0, 0)].set_bg(ratatui_core::style::Color::Rgb(253, 2, 255));
+        actual[(1, 1)].set_bg(ratatui_core::style::Color::Rgb(255, 0, 250));
+        testing::assert_buffers_similar(&actual, &expected, ColorTolerance::new(2), 1);
+        testing::assert_buffers_similar(&actual, &expected, ColorTolerance::EXACT, 2);
+        let result = std::panic::catch_unwind(|| {
+            testing::assert_buffers_similar(&actual, &expected, ColorTolerance::new(2), 0)
+        });
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn canvas_preset() {
+        use crate::{CanvasPreset, StyleRule};
+
+        let preset: CanvasPreset = toml::from_str(
+            r#"
+            shader = "src/shaders/test_fragment.wgsl"
+            entry_point = "green"
+            character_rule = { Always = "@" }
+            style_rule = "ColorFg"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            preset.canvas.character_rule,
+            CharacterRule::Always('@')
+        ));
+        assert!(matches!(preset.canvas.style_rule, StyleRule::ColorFg));
+        let mut state = preset.to_state().unwrap();
+        let raw_buffer = state.execute(ShaderContext::default());
+        assert!(raw_buffer.iter().all(|pixel| pixel == &[0, 255, 0, 255]));
+
+        let preset: CanvasPreset = toml::from_str(&toml::to_string(&preset).unwrap()).unwrap();
+        assert_eq!(preset.entry_point.as_deref(), Some("green"));
+        let preset: CanvasPreset = toml::from_str(r#"shader = "a.wgsl""#).unwrap();
+        assert!(matches!(preset.canvas.style_rule, StyleRule::ColorBg));
+    }
+
+    #[test]
+    fn record_asciinema() {
+        let path = std::env::temp_dir().join("tui-shader-record-asciinema.cast");
+        let mut terminal = ratatui_core::terminal::Terminal::new(TestBackend::new(4, 2)).unwrap();
+        let mut state = ShaderCanvasState::default();
+        let mut recorder = crate::Recorder::asciinema(&path);
+        let completed = terminal
+            .draw(|frame| {
+                frame.render_stateful_widget(ShaderCanvas::new(), frame.area(), &mut state)
+            })
+            .unwrap();
+        recorder.capture_buffer(completed.buffer);
+        recorder.finish().unwrap();
+        let cast = std::fs::read_to_string(&path).unwrap();
+        let mut lines = cast.lines();
+        assert!(lines.next().unwrap().contains(r#""width": 4, "height": 2"#));
+        let event = lines.next().unwrap();
+        assert!(event.starts_with(r#"[0.000000, "o", "\u001b[H\u001b[0;48;2;255;0;255m    "#));
+        assert_eq!(lines.next(), None);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn focus_glow() {
+        use ratatui_core::layout::Rect;
+        use ratatui_core::style::Color;
+        use ratatui_core::widgets::StatefulWidget;
+
+        let mut state = crate::FocusGlowState::new().unwrap();
+        let area = Rect::new(0, 0, 20, 10);
+        let focused = Rect::new(5, 3, 8, 4);
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        buffer.set_style(
+            area,
+            ratatui_core::style::Style::new().bg(Color::Rgb(0, 0, 0)),
+        );
+        crate::FocusGlow::new(focused)
+            .color(Color::Rgb(255, 0, 0))
+            .radius(4)
+            .render(area, &mut buffer, &mut state);
+        let Color::Rgb(r, 0, 0) = buffer[(4, 4)].bg else {
+            panic!("expected a red tint, got {:?}", buffer[(4, 4)].bg);
+        };
+        assert!(r > 0);
+        assert!(
+            focused
+                .positions()
+                .all(|position| buffer[position].bg == Color::Rgb(0, 0, 0))
+        );
+        assert_eq!(buffer[(0, 0)].bg, Color::Rgb(0, 0, 0));
+        assert_eq!(buffer[(19, 9)].bg, Color::Rgb(0, 0, 0));
+    }
+
+    #[test]
+    fn interaction() {
+        use crate::PointerEvent;
+
+        let source = "struct Uniforms { drag: vec4<f32>, click: vec4<f32>, scroll: f32 }
+@group(0) @binding(2) var<uniform> uniforms: Uniforms;
+@fragment fn main() -> @location(0) vec4<f32> {
+    let pressed = (uniforms.click.w + uniforms.drag.z) * 0.5;
+    return vec4<f32>(uniforms.drag.x * 0.1, uniforms.scroll, pressed, 1.0);
+}";
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+        state.uniforms().set("scroll", 1.0);
+        assert_eq!(state.execute(ShaderContext::default())[0], [0, 255, 0, 255]);
+
+        state.handle_pointer(PointerEvent::Press { x: 2, y: 2 });
+        state.handle_pointer(PointerEvent::Drag { x: 12, y: 1 });
+        let interaction = state.interaction();
+        assert_eq!(interaction.drag(), (10.0, 1.0));
+        assert!(interaction.is_dragging());
+        assert_eq!(interaction.click(), Some((2, 2)));
+        assert_eq!(
+            state.execute(ShaderContext::default())[0],
+            [255, 0, 255, 255]
+        );
+
+        state.handle_pointer(PointerEvent::Release);
+        state.handle_pointer(PointerEvent::Scroll(1.0));
+        assert_eq!(
+            state.execute(ShaderContext::default())[0],
+            [255, 255, 128, 255]
+        );
+
+        state.reset_interaction();
+        assert_eq!(state.execute(ShaderContext::default())[0], [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn scroll_offset() {
+        use ratatui_core::widgets::StatefulWidget;
+
+        let source = "@group(0) @binding(7) var<uniform> scroll: vec4<f32>;
+@fragment fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(uv.x, uv.y, scroll.y * 0.1, 1.0);
+}";
+        let area = ratatui_core::layout::Rect::new(0, 0, 4, 8);
+        let canvas = ShaderCanvas::new().style_rule(crate::StyleRule::ColorBg);
+        let render = |state: &mut ShaderCanvasState, canvas: ShaderCanvas| {
+            let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+            canvas.render(area, &mut buffer, state);
+            buffer
+        };
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+        let still = render(&mut state, canvas.clone());
+        let scrolled = render(&mut state, canvas.clone().scroll_offset(1.0, 2.0));
+        let ratatui_core::style::Color::Rgb(r, g, b) = scrolled[(0, 0)].bg else {
+            panic!("expected an rgb background");
+        };
+        let ratatui_core::style::Color::Rgb(still_r, still_g, _) = still[(1, 2)].bg else {
+            panic!("expected an rgb background");
+        };
+        assert_eq!((r, g, b), (still_r, still_g, 51));
+
+        let halved = render(
+            &mut state,
+            canvas.clone().scroll_offset(0.0, 4.0).parallax(0.5),
+        );
+        assert_eq!(halved[(1, 0)].bg, scrolled[(0, 0)].bg);
+
+        let mut cpu = ShaderCanvasState::from_backend(|input: crate::PixelInput| {
+            [(input.u * 255.0) as u8, (input.v * 255.0) as u8, 0, 255]
+        });
+        let still = render(&mut cpu, canvas.clone());
+        let scrolled = render(&mut cpu, canvas.scroll_offset(0.0, 2.0));
+        assert_eq!(scrolled[(0, 0)].bg, still[(0, 2)].bg);
+    }
+
+    #[test]
+    fn instance_id() {
+        use ratatui_core::widgets::StatefulWidget;
+
+        let source = "@group(0) @binding(8) var<uniform> instance: vec4<u32>;
+@fragment fn main() -> @location(0) vec4<f32> {
+    return vec4<f32>(f32(instance.x) * 0.2, 0.0, 0.0, 1.0);
+}";
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+        let area = ratatui_core::layout::Rect::new(0, 0, 3, 3);
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        for row in 0..3 {
+            let canvas = ShaderCanvas::new()
+                .style_rule(crate::StyleRule::ColorBg)
+                .instance(u32::from(row));
+            canvas.render(
+                ratatui_core::layout::Rect::new(0, row, 3, 1),
+                &mut buffer,
+                &mut state,
+            );
+        }
+        for (row, red) in [0, 51, 102].into_iter().enumerate() {
+            assert_eq!(
+                buffer[(2, row as u16)].bg,
+                ratatui_core::style::Color::Rgb(red, 0, 0)
+            );
+        }
+        let canvas = ShaderCanvas::new().style_rule(crate::StyleRule::ColorBg);
+        let buffer = testing::render_seeded(&canvas, &mut state, 2, 1, Duration::ZERO, 3);
+        assert_eq!(
+            buffer[(0, 0)].bg,
+            ratatui_core::style::Color::Rgb(153, 0, 0)
+        );
+    }
+
+    #[test]
+    fn render_many() {
+        use ratatui_core::layout::Rect;
+        use ratatui_core::widgets::StatefulWidget;
+
+        use crate::InstanceParams;
+
+        let source = "@group(0) @binding(8) var<uniform> instance: vec4<u32>;
+@fragment fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(uv.x, uv.y, f32(instance.x) * 0.2, 1.0);
+}";
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+        let canvas = ShaderCanvas::new().style_rule(crate::StyleRule::ColorBg);
+        let area = Rect::new(0, 0, 20, 6);
+        let areas = [
+            (Rect::new(0, 0, 5, 2), InstanceParams::new(0)),
+            (Rect::new(6, 0, 14, 3), InstanceParams::new(1)),
+            (
+                Rect::new(0, 3, 9, 3),
+                InstanceParams::new(2).scroll_offset(0.0, 1.0),
+            ),
+        ];
+        let mut batched = ratatui_core::buffer::Buffer::empty(area);
+        canvas.render_many(&areas, &mut batched, &mut state);
+
+        let mut sequential = ratatui_core::buffer::Buffer::empty(area);
+        for (rect, params) in areas {
+            canvas
+                .clone()
+                .instance(params.instance)
+                .scroll_offset(params.scroll_offset.0, params.scroll_offset.1)
+                .render(rect, &mut sequential, &mut state);
+        }
+        // Interpolating uv across a viewport of the shared texture may round differently.
+        for position in area.positions() {
+            let (batched, sequential) = (batched[position].bg, sequential[position].bg);
+            let close = match (batched, sequential) {
+                (
+                    ratatui_core::style::Color::Rgb(r, g, b),
+                    ratatui_core::style::Color::Rgb(sr, sg, sb),
+                ) => r.abs_diff(sr) <= 1 && g.abs_diff(sg) <= 1 && b.abs_diff(sb) <= 1,
+                _ => batched == sequential,
+            };
+            assert!(close, "{position}: {batched:?} != {sequential:?}");
+        }
+        assert_ne!(batched[(7, 1)].bg, batched[(1, 1)].bg);
+        assert_eq!(state.last_frame().unwrap().width(), 9);
+    }
+
+    #[test]
+    fn texture_capacity() {
+        let source = "@fragment fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(uv.x, uv.y, 0.0, 1.0);
+}";
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+        let large = ratatui_core::layout::Rect::new(0, 0, 100, 40);
+        let small = ratatui_core::layout::Rect::new(0, 0, 30, 10);
+        state.execute(ShaderContext::new(0.0, large));
+        let texture = state.output_texture().unwrap();
+        assert_eq!((texture.width(), texture.height()), (128, 64));
+
+        let pixels = state.execute(ShaderContext::new(0.0, small));
+        let texture = state.output_texture().unwrap();
+        assert_eq!((texture.width(), texture.height()), (128, 64));
+        assert_eq!(pixels.len(), 64 * 10);
+
+        let mut fresh = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+        assert_eq!(pixels, fresh.execute(ShaderContext::new(0.0, small)));
+    }
+
+    #[test]
+    fn readback_pool() {
+        let source = "@fragment fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(uv.x, uv.y, 0.0, 1.0);
+}";
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+        // Dragging the corner of the terminal back and forth reuses the pooled buffers.
+        for (width, height) in [(30, 10), (31, 11), (29, 9), (80, 20), (30, 10), (31, 11)] {
+            let rect = ratatui_core::layout::Rect::new(0, 0, width, height);
+            let pixels = state.execute(ShaderContext::new(0.0, rect));
+            let mut fresh = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+            assert_eq!(pixels, fresh.execute(ShaderContext::new(0.0, rect)));
+        }
+    }
+
+    #[test]
+    fn frame_budget() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use ratatui_core::widgets::StatefulWidget;
+
+        struct Slow(Arc<AtomicUsize>);
+        impl crate::CpuBackend for Slow {
+            fn prepare(&mut self, _time: f32, _width: u32, _height: u32) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            fn pixel(&mut self, _input: crate::PixelInput) -> [u8; 4] {
+                [0, 255, 0, 255]
+            }
+        }
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut state = ShaderCanvasState::from_backend(Slow(Arc::clone(&runs)));
+        let canvas = ShaderCanvas::new().frame_budget(Duration::from_millis(1));
+        let area = ratatui_core::layout::Rect::new(0, 0, 8, 8);
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        let mut widths = Vec::new();
+        for _ in 0..4 {
+            StatefulWidget::render(&canvas, area, &mut buffer, &mut state);
+            widths.push(state.last_frame().unwrap().width());
+        }
+        // The scale is halved down to a quarter, then the last frame is reused.
+        assert_eq!(widths, [8, 4, 2, 2]);
+        assert_eq!(runs.load(Ordering::Relaxed), 3);
+        assert_eq!(state.budget_scale(), 0.25);
+        let green = ratatui_core::style::Color::Rgb(0, 255, 0);
+        assert!(buffer.content().iter().all(|cell| cell.bg == green));
+
+        let canvas = ShaderCanvas::new().frame_budget(Duration::from_secs(60));
+        StatefulWidget::render(&canvas, area, &mut buffer, &mut state);
+        assert_eq!(state.budget_scale(), 0.3125);
+    }
+
+    #[test]
+    fn frame_interpolation() {
+        struct Slow;
+        impl crate::CpuBackend for Slow {
+            fn prepare(&mut self, _time: f32, _width: u32, _height: u32) {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            fn pixel(&mut self, input: crate::PixelInput) -> [u8; 4] {
+                [(input.time * 20.0) as u8, 0, 0, 255]
+            }
+        }
+
+        let mut state = ShaderCanvasState::from_backend(Slow);
+        let canvas = ShaderCanvas::new()
+            .frame_budget(Duration::from_millis(1))
+            .interpolate(true);
+        let area = ratatui_core::layout::Rect::new(0, 0, 4, 4);
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        let mut reds = Vec::new();
+        for time in 0..6 {
+            canvas.render_at(area, &mut buffer, &mut state, time as f32);
+            let ratatui_core::style::Color::Rgb(red, _, _) = buffer[(0, 0)].bg else {
+                panic!("expected an rgb color");
+            };
+            reds.push(red);
+        }
+        // Renders alternate with reused frames from the third one on, which is the first at the lowest
+        // scale. Once two frames of that size exist, they are shown one render late and blended.
+        assert_eq!(reds, [0, 20, 40, 40, 40, 60]);
+    }
+
+    #[test]
+    fn prerender() {
+        let source = "struct Uniforms { blue: f32 }
+@group(0) @binding(0) var<uniform> time: vec4<f32>;
+@group(0) @binding(2) var<uniform> uniforms: Uniforms;
+@fragment fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(time.x * 0.1, uv.x, uniforms.blue, 1.0);
+}";
+        let rect = ratatui_core::layout::Rect::new(0, 0, 6, 3);
+        let render = |time: f32, blue: f32| {
+            let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+            state.set_uniform("blue", blue);
+            state.execute(ShaderContext::new(time, rect))
+        };
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+        state.set_prerender(true);
+        state.execute(ShaderContext::new(1.0, rect));
+        state.execute(ShaderContext::new(2.0, rect));
+        // The frame at the predicted time 3.0 stands in for the one at 2.5.
+        assert_eq!(
+            state.execute(ShaderContext::new(2.5, rect)),
+            render(3.0, 0.0)
+        );
+        // Changing a uniform drops the frame rendered ahead of time.
+        state.set_uniform("blue", 1.0);
+        assert_eq!(
+            state.execute(ShaderContext::new(3.0, rect)),
+            render(3.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn transition() {
+        let mut state = ShaderCanvasState::from_backend(|_: crate::PixelInput| [255, 0, 0, 255]);
+        state.clock_mut().pause();
+        state.set_elapsed(Duration::from_secs(1));
+        let next = ShaderCanvasState::from_backend(|_: crate::PixelInput| [0, 255, 0, 255]);
+        state.transition_to(next, Duration::from_secs(2), Easing::Linear);
+        let rect = ratatui_core::layout::Rect::new(0, 0, 2, 1);
+        assert_eq!(
+            state.execute(ShaderContext::new(1.0, rect))[0],
+            [255, 0, 0, 255]
+        );
+        assert_eq!(
+            state.execute(ShaderContext::new(2.0, rect))[0],
+            [128, 128, 0, 255]
+        );
+        assert!(state.is_transitioning());
+        assert_eq!(
+            state.execute(ShaderContext::new(3.0, rect))[0],
+            [0, 255, 0, 255]
+        );
+        assert!(!state.is_transitioning());
+    }
+
+    #[test]
+    fn candidate_slot() {
+        let mut state = ShaderCanvasState::new_with_entry_point(
+            wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+            "green",
+        )
+        .unwrap();
+        let rect = ratatui_core::layout::Rect::new(0, 0, 2, 1);
+        let broken = "@fragment fn main() -> @location(0) vec4<f32> { return vec4<f32>(1.0); ";
+        assert!(
+            state
+                .load_candidate(crate::WgslShader::Source(broken), None)
+                .is_err()
+        );
+        assert!(!state.has_candidate() && !state.promote_candidate());
+
+        let red = "@fragment fn main() -> @location(0) vec4<f32> { return vec4<f32>(1.0, 0.0, 0.0, 1.0); }";
+        state
+            .load_candidate(crate::WgslShader::Source(red), None)
+            .unwrap();
+        assert_eq!(
+            state.execute(ShaderContext::new(0.0, rect))[0],
+            [0, 255, 0, 255]
+        );
+        assert!(state.promote_candidate());
+        assert_eq!(
+            state.execute(ShaderContext::new(0.0, rect))[0],
+            [255, 0, 0, 255]
+        );
+        assert!(state.rollback());
+        assert!(!state.rollback());
+        assert_eq!(
+            state.execute(ShaderContext::new(0.0, rect))[0],
+            [0, 255, 0, 255]
+        );
+    }
+
+    #[test]
+    fn shader_check() {
+        let info = ShaderCanvasState::check(
+            "struct Uniforms { speed: f32, tint: vec4<f32> }
+             @group(0) @binding(2) var<uniform> uniforms: Uniforms;
+             @fragment fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+                 return uniforms.tint * uniforms.speed;
+             }",
+        )
+        .unwrap();
+        assert_eq!(info.entry_points, ["main"]);
+        assert_eq!(info.uniforms, ["speed", "tint"]);
+
+        let error = ShaderCanvasState::check(
+            "@fragment fn main() -> @location(0) vec4<f32> {\n    return vec4<f32>(1.0)\n}",
+        )
+        .unwrap_err();
+        assert_eq!(error.line, Some(3));
+        assert!(error.to_string().contains("expected"));
+
+        let error = ShaderCanvasState::check(
+            "@group(0) @binding(0) var<uniform> time: mat4x4<f32>;
+             @fragment fn main() -> @location(0) vec4<f32> { return time[0]; }",
+        )
+        .unwrap_err();
+        assert_eq!(error.line, Some(1));
+        assert!(error.message.contains("64 bytes"));
+    }
+
+    #[test]
+    fn shader_policy() {
+        use crate::ShaderPolicy;
+        let untrusted = ShaderPolicy::untrusted();
+        let check = |body: &str| {
+            let source = format!(
+                "@fragment fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {{
+                     var x = 0.0;
+                     {body}
+                     return vec4<f32>(x);
+                 }}"
+            );
+            assert!(ShaderCanvasState::check(&source).is_ok());
+            ShaderCanvasState::check_with_policy(&source, &untrusted)
+        };
+        assert!(check("for (var i = 0; i < 64; i++) { x += uv.x; }").is_ok());
+        let error = check(
+            "for (var i = 0; i < 128; i++) {
+                 for (var j = 0u; j < 128u; j++) { x += uv.y; }
+             }",
+        )
+        .unwrap_err();
+        assert!(error.message.contains("16512 iterations"));
+        let error = check("loop { x += uv.x; if x > 100.0 { break; } }").unwrap_err();
+        assert_eq!(error.line, Some(3));
+        assert!(check("for (var i = 64u; i > 0u; i--) { x += uv.x; }").is_ok());
+        let error = check("for (var i = 1000000u; i > 0u; i--) { x += uv.x; }").unwrap_err();
+        assert!(error.message.contains("1000000 iterations"));
+        assert!(check("for (var i = 0; i < 16; i += 0) { x += uv.x; }").is_err());
+        assert!(check("for (var i = 0; i < 16; i++) { i -= 1; x += uv.x; }").is_err());
+        assert!(check("for (var i = 0u; i >= 0u; i--) { x += uv.x; }").is_err());
+
+        let textures = (0..5)
+            .map(|i| format!("@group(1) @binding({i}) var t{i}: texture_2d<f32>;"))
+            .collect::<String>();
+        let source = format!(
+            "{textures}\n@fragment fn main() -> @location(0) vec4<f32> {{ return vec4<f32>(0.0); }}"
+        );
+        assert!(ShaderCanvasState::check(&source).is_ok());
+        let error = ShaderCanvasState::check_with_policy(&source, &untrusted).unwrap_err();
+        assert!(error.message.contains("5 textures"));
+
+        let source = "var<push_constant> tint: vec4<f32>;
+            @fragment fn main() -> @location(0) vec4<f32> { return tint; }";
+        assert!(ShaderCanvasState::check(source).is_ok());
+        assert!(ShaderCanvasState::check_with_policy(source, &untrusted).is_err());
+
+        assert!(
+            ShaderCanvasState::builder()
+                .policy(untrusted)
+                .build()
+                .is_ok()
+        );
+        let result = ShaderCanvasState::builder()
+            .shader(crate::WgslShader::Source(source))
+            .policy(untrusted)
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn shader_playlist() {
+        use crate::{PlaylistItem, ShaderPlaylist};
+        use ratatui_core::buffer::Buffer;
+        use ratatui_core::widgets::Widget;
+
+        let shader = |color: &str| {
+            format!(
+                "@fragment fn main() -> @location(0) vec4<f32> {{ return vec4<f32>({color}, 1.0); }}"
+            )
+        };
+        let directory =
+            std::env::temp_dir().join(format!("tui-shader-playlist-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("a-red.wgsl"), shader("1.0, 0.0, 0.0")).unwrap();
+        std::fs::write(directory.join("broken.wgsl"), "@fragment fn main(").unwrap();
+
+        let mut playlist = ShaderPlaylist::new()
+            .duration(Duration::from_secs(1))
+            .transition(Duration::ZERO, Easing::Linear)
+            .item(PlaylistItem::wgsl(shader("0.0, 1.0, 0.0")))
+            .directory(&directory);
+        let area = ratatui_core::layout::Rect::new(0, 0, 2, 1);
+        let mut buf = Buffer::empty(area);
+        let mut render_at = |playlist: &mut ShaderPlaylist, seconds: f32| {
+            if let Some(state) = playlist.state_mut() {
+                state.clock_mut().pause();
+                state.set_elapsed(Duration::from_secs_f32(seconds));
+            }
+            playlist.render(area, &mut buf);
+            playlist.state().unwrap().frame().unwrap().pixels[0]
+        };
+
+        assert_eq!(render_at(&mut playlist, 0.0), [0, 255, 0, 255]);
+        assert_eq!(playlist.items().len(), 3);
+        assert_eq!(render_at(&mut playlist, 1.5), [255, 0, 0, 255]);
+
+        std::fs::write(directory.join("c-blue.wgsl"), shader("0.0, 0.0, 1.0")).unwrap();
+        assert_eq!(render_at(&mut playlist, 2.5), [0, 0, 255, 255]);
+        assert!(playlist.last_error().is_some());
+        assert_eq!(playlist.current(), Some(3));
+        assert_eq!(render_at(&mut playlist, 3.5), [0, 255, 0, 255]);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[cfg(feature = "midir")]
+    #[test]
+    fn midi_mapping() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let mapping = crate::MidiMapping::new()
+            .control_change(74, "cutoff")
+            .note(36, "kick")
+            .channel(9);
+        let values: Vec<AtomicU32> = (0..2).map(|_| AtomicU32::new(0)).collect();
+        let value = |index: usize| f32::from_bits(values[index].load(Ordering::Relaxed));
+        mapping.handle(&[0xb9, 74, 127], &values);
+        mapping.handle(&[0x99, 36, 64], &values);
+        assert_eq!((value(0), value(1)), (1.0, 64.0 / 127.0));
+        mapping.handle(&[0xb0, 74, 0], &values);
+        mapping.handle(&[0x89, 36, 64], &values);
+        assert_eq!((value(0), value(1)), (1.0, 0.0));
+    }
+
+    #[cfg(feature = "osc")]
+    #[test]
+    fn osc_uniforms() {
+        fn padded(string: &str) -> Vec<u8> {
+            let mut bytes = string.as_bytes().to_vec();
+            bytes.resize((string.len() + 1).next_multiple_of(4), 0);
+            bytes
+        }
+
+        let osc = crate::OscUniforms::bind("127.0.0.1:0")
+            .unwrap()
+            .prefix("/shader")
+            .route("/color", "tint");
+        let sender = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let speed = [
+            padded("/shader/speed"),
+            padded(",f"),
+            0.5f32.to_be_bytes().to_vec(),
+        ]
+        .concat();
+        let tint = [
+            padded("/color"),
+            padded(",isf"),
+            1i32.to_be_bytes().to_vec(),
+            padded("ignored"),
+            0.0f32.to_be_bytes().to_vec(),
+        ]
+        .concat();
+        let unknown = [
+            padded("/other"),
+            padded(",f"),
+            1.0f32.to_be_bytes().to_vec(),
+        ]
+        .concat();
+        let mut bundle = [padded("#bundle"), vec![0; 8]].concat();
+        for message in [&tint, &unknown] {
+            bundle.extend((message.len() as u32).to_be_bytes());
+            bundle.extend(message);
+        }
+        let address = osc.local_addr().unwrap();
+        sender.send_to(&speed, address).unwrap();
+        sender.send_to(&bundle, address).unwrap();
+
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(
+            "struct Uniforms { speed: f32, tint: vec2<f32> }
+             @group(0) @binding(2) var<uniform> uniforms: Uniforms;
+             @fragment fn main() -> @location(0) vec4<f32> {
+                 return vec4<f32>(uniforms.speed, uniforms.tint, 1.0);
+             }",
+        ))
+        .unwrap();
+        state.add_provider(osc);
+        let pixels = state.execute(ShaderContext::default());
+        assert_eq!(pixels[0], [128, 255, 0, 255]);
+    }
+
+    #[test]
+    fn uniform_stream() {
+        let input = "0.25, 0.5\n{\"tint\": [1, 0.5], \"level\": 1.0}\n{\"speed\": }\n";
+        let stream =
+            crate::UniformStream::from_reader(std::io::Cursor::new(input), ["speed", "level"]);
+        while !stream.is_closed() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert!(stream.last_error().unwrap().contains("expected a number"));
+
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(
+            "struct Uniforms { speed: f32, level: f32, tint: vec2<f32> }
+             @group(0) @binding(2) var<uniform> uniforms: Uniforms;
+             @fragment fn main() -> @location(0) vec4<f32> {
+                 return vec4<f32>(uniforms.speed, uniforms.level, uniforms.tint.y, 1.0);
+             }",
+        ))
+        .unwrap();
+        state.add_provider(stream);
+        assert_eq!(
+            state.execute(ShaderContext::default())[0],
+            [64, 255, 128, 255]
+        );
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn text_texture() {
+        let options = crate::FontOptions {
+            scale: 2,
+            line_spacing: 1,
+            padding: 1,
+        };
+        let text = crate::TextTexture::new("Hi\n\tx", options);
+        assert_eq!((text.width(), text.height()), (5 * 16 + 2, 17 * 2 + 2));
+        let alpha = |x: u32, y: u32| text.data()[((y * text.width() + x) * 4 + 3) as usize];
+        assert_eq!((alpha(0, 0), alpha(1, 1), alpha(2, 2)), (0, 255, 255));
+        assert_eq!(crate::TextTexture::new("", options).data().len(), 2 * 2 * 4);
+
+        let mut state = ShaderCanvasState::new_with_bindings(
+            crate::WgslShader::Source(
+                "@group(1) @binding(0) var text: texture_2d<f32>;
+                 @fragment fn main() -> @location(0) vec4<f32> {
+                     let a = textureLoad(text, vec2<i32>(1, 1), 0).a;
+                     let b = textureLoad(text, vec2<i32>(0, 0), 0).a;
+                     return vec4<f32>(a, b, 0.0, 1.0);
+                 }",
+            ),
+            None,
+            BindingsBuilder::new().text_texture((1, 0), &text),
+        )
+        .unwrap();
+        assert_eq!(state.execute(ShaderContext::default())[0], [255, 0, 0, 255]);
+        state
+            .update_text_texture((1, 0), &crate::TextTexture::new(" ", options))
+            .unwrap();
+        assert_eq!(state.execute(ShaderContext::default())[0], [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn stdlib_includes() {
+        let source = "#include <fbm>
+            #include <noise>
+            #include <sdf>
+            #include <palette>
+            #include <rotation>
+            @fragment fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+                let noise = fbm(uv, 4) + perlin_noise(uv) + simplex_noise(uv) + rainbow(uv.x).x;
+                let r = -sd_box(vec2<f32>(0.0), vec2<f32>(0.25));
+                let g = (rotate2d(1.5707964) * vec2<f32>(1.0, 0.0)).y;
+                let b = hsv_to_rgb(vec3<f32>(2.0 / 3.0, 1.0, 1.0)).b;
+                return vec4<f32>(r, g, b + noise * 0.0, 1.0);
+            }";
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+        assert_eq!(
+            state.execute(ShaderContext::default())[0],
+            [64, 255, 255, 255]
+        );
+        assert!(
+            crate::stdlib::module("noise")
+                .unwrap()
+                .contains("fn simplex_noise")
+        );
+        let error =
+            ShaderCanvasState::new(crate::WgslShader::Source("#include <missing>")).unwrap_err();
+        assert_eq!(error.to_string(), "line 1: unknown module `missing`");
+    }
+
+    #[test]
+    fn builtin_textures() {
+        use crate::BuiltinTexture;
+
+        let blue_noise = BuiltinTexture::BlueNoise256.data();
+        let mut histogram = [0; 256];
+        for pixel in blue_noise.chunks_exact(4) {
+            histogram[usize::from(pixel[0])] += 1;
+        }
+        assert!(histogram.iter().all(|&count| count == 256));
+        for texture in [
+            BuiltinTexture::ValueNoise256,
+            BuiltinTexture::PerlinNoise256,
+        ] {
+            let data = texture.data();
+            assert_eq!(data.len(), 256 * 256 * 4);
+            // The texture tiles, so the first and the last column are neighbors.
+            for y in 0..256 {
+                let (first, last) = (y * 256 * 4, (y * 256 + 255) * 4);
+                assert!(data[first].abs_diff(data[last]) < 16);
+            }
+        }
+
+        let mut state = ShaderCanvasState::new_with_bindings(
+            crate::WgslShader::Source(
+                "@group(1) @binding(0) var noise: texture_2d<f32>;
+                 @fragment fn main() -> @location(0) vec4<f32> {
+                     return vec4<f32>(textureLoad(noise, vec2<i32>(0, 0), 0).rgb, 1.0);
+                 }",
+            ),
+            None,
+            BindingsBuilder::new().builtin_texture((1, 0), BuiltinTexture::BlueNoise256),
+        )
+        .unwrap();
+        let value = blue_noise[0];
+        assert_eq!(
+            state.execute(ShaderContext::default())[0],
+            [value, value, value, 255]
+        );
+    }
+
+    #[test]
+    fn cube_lut() {
+        use crate::CubeLut;
+
+        // Inverts every channel, with red changing fastest.
+        let mut source = "TITLE \"Invert\"\n# A comment\nLUT_3D_SIZE 2\n".to_owned();
+        for index in 0..8 {
+            let [r, g, b] = [index & 1, index >> 1 & 1, index >> 2].map(|bit| 1 - bit);
+            source.push_str(&format!("{r}.0 {g}.0 {b}.0\n"));
+        }
+        let lut = CubeLut::parse(&source).unwrap();
+        assert_eq!(lut.title(), Some("Invert"));
+        assert_eq!(lut.apply([0.25, 0.5, 1.0]), [0.75, 0.5, 0.0]);
+        assert!(CubeLut::parse("LUT_3D_SIZE 2\n0 0 0\n").is_err());
+        assert!(CubeLut::parse("LUT_1D_SIZE 2\n0 0 0\n1 1 1\n").is_err());
+
+        let shader = "#include <lut>
+            @group(1) @binding(0) var lut: texture_3d<f32>;
+            @group(1) @binding(1) var lut_sampler: sampler;
+            @fragment fn main() -> @location(0) vec4<f32> {
+                return vec4<f32>(apply_lut(lut, lut_sampler, vec3<f32>(1.0, 0.0, 0.2)), 1.0);
+            }";
+        let bindings = BindingsBuilder::new()
+            .lut_texture((1, 0), &lut)
+            .sampler((1, 1), wgpu::FilterMode::Linear);
+        let mut state =
+            ShaderCanvasState::new_with_bindings(crate::WgslShader::Source(shader), None, bindings)
+                .unwrap();
+        let pixel = state.execute(ShaderContext::default())[0];
+        assert_eq!(pixel[..2], [0, 255]);
+        assert!(pixel[2].abs_diff(204) <= 1);
+
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(
+            "@fragment fn main() -> @location(0) vec4<f32> { return vec4<f32>(1.0, 0.0, 0.2, 1.0); }",
+        ))
+        .unwrap();
+        state.set_color_grade(Some(lut));
+        assert_eq!(
+            state.execute(ShaderContext::default())[0],
+            [0, 255, 204, 255]
+        );
+        state.set_color_grade(None);
+        assert_eq!(
+            state.execute(ShaderContext::default())[0],
+            [255, 0, 51, 255]
+        );
+    }
+
+    #[test]
+    fn volume_textures() {
+        let shader = "@group(1) @binding(0) var density: texture_3d<f32>;
+            @group(1) @binding(1) var color: texture_3d<f32>;
+            @fragment fn main() -> @location(0) vec4<f32> {
+                let last = vec3<i32>(textureDimensions(density)) - 1;
+                let r = textureLoad(density, last, 0).r;
+                return vec4<f32>(r, textureLoad(color, vec3<i32>(0, 0, 1), 0).gb, 1.0);
+            }";
+        let values: Vec<f32> = (0..8).map(|index| index as f32 / 7.0).collect();
+        let bindings = BindingsBuilder::new()
+            .volume_with_data((1, 0), 2, 2, 2, &values)
+            .texture_3d((1, 1), 1, 1, 2);
+        let mut state =
+            ShaderCanvasState::new_with_bindings(crate::WgslShader::Source(shader), None, bindings)
+                .unwrap();
+        assert_eq!(state.execute(ShaderContext::default())[0], [255, 0, 0, 255]);
+
+        state
+            .update_volume((1, 0), &[0.0, 0.0, 0.5], 3, 1, 1)
+            .unwrap();
+        state
+            .update_texture_3d((1, 1), &[0, 0, 0, 0, 0, 64, 255, 255], 1, 1, 2)
+            .unwrap();
+        assert_eq!(
+            state.execute(ShaderContext::default())[0],
+            [128, 64, 255, 255]
+        );
+        assert!(state.update_volume((1, 0), &[0.0; 3], 2, 1, 1).is_err());
+        assert!(state.update_texture((1, 0), &[0; 4], 1, 1).is_err());
+    }
+
+    #[test]
+    fn scene() {
+        use crate::{Mesh, SceneState, SceneVertex};
+
+        // 64x32 cells of 8x16 pixels are square.
+        let ctx = ShaderContext::new(0.0, ratatui_core::layout::Rect::new(0, 0, 64, 32));
+        let pixel = |pixels: &[[u8; 4]], x: usize, y: usize| pixels[y * 64 + x];
+        let mut state = SceneState::try_new(Mesh::cube([1.0, 1.0, 1.0])).unwrap();
+        let pixels = state.execute(ctx);
+        // Lit head-on from the camera.
+        assert!(pixel(&pixels, 32, 16)[0] > 250);
+        assert_eq!(pixel(&pixels, 0, 0), [0, 0, 0, 255]);
+
+        let quad = |z: f32, color: [f32; 3]| {
+            [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]]
+                .map(|[x, y]| SceneVertex::new([x, y, z], [0.0; 3], color))
+        };
+        let vertices = [quad(0.5, [1.0, 0.0, 0.0]), quad(-0.5, [0.0, 1.0, 0.0])].concat();
+        let indices = vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7];
+        let quads = Mesh::new(vertices.clone(), indices).unwrap();
+        // The far quad is drawn last, but hidden by the depth test.
+        state.set_mesh(quads.clone());
+        assert_eq!(pixel(&state.execute(ctx), 24, 12), [255, 0, 0, 255]);
+        state.set_rotation(0.0, std::f32::consts::PI, 0.0);
+        assert_eq!(pixel(&state.execute(ctx), 24, 12), [0, 255, 0, 255]);
+
+        // Only the borders and the diagonals of the quads are left.
+        state.set_mesh(quads.wireframe());
+        state.set_rotation(0.0, 0.0, 0.0);
+        assert_eq!(state.mesh().indices().len(), 20);
+        let pixels = state.execute(ctx);
+        assert_eq!(pixel(&pixels, 24, 12), [0, 0, 0, 255]);
+        assert!(pixels.contains(&[255, 0, 0, 255]));
+        assert!(Mesh::new(vertices, vec![0, 1, 8]).is_err());
+    }
+
+    #[test]
+    fn camera() {
+        use crate::{Camera, OrbitControls, PointerEvent};
+
+        let mut camera = Camera::default();
+        camera.orbit(std::f32::consts::FRAC_PI_2, 0.0);
+        assert!((camera.eye[0] - 3.0).abs() < 1e-5 && camera.eye[2].abs() < 1e-5);
+        camera.orbit(0.0, 10.0);
+        assert!(camera.eye[1] < 3.0 && (camera.distance() - 3.0).abs() < 1e-4);
+        camera.zoom(0.5);
+        assert!((camera.distance() - 1.5).abs() < 1e-4);
+
+        let shader = "#include <camera>
+            struct Uniforms {
+                camera_view_projection: mat4x4<f32>,
+                camera_eye: vec3<f32>,
+                camera_fov: f32,
+                camera_forward: vec3<f32>,
+                camera_right: vec3<f32>,
+                camera_up: vec3<f32>,
+                camera_target: vec3<f32>,
+            }
+            @group(0) @binding(2) var<uniform> uniforms: Uniforms;
+            @fragment fn main() -> @location(0) vec4<f32> {
+                let clip = uniforms.camera_view_projection * vec4<f32>(uniforms.camera_target, 1.0);
+                let centered = all(abs(clip.xy / clip.w) < vec2<f32>(0.001));
+                let ray = camera_ray(
+                    vec2<f32>(0.5),
+                    1.0,
+                    uniforms.camera_fov,
+                    uniforms.camera_forward,
+                    uniforms.camera_right,
+                    uniforms.camera_up,
+                );
+                return vec4<f32>(uniforms.camera_eye.x / 4.0, select(0.0, 1.0, centered), dot(ray, uniforms.camera_forward), 1.0);
+            }";
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(shader)).unwrap();
+        state.set_camera(Some(Camera::new([2.0, 1.0, 3.0], [0.0, 0.5, 0.0])));
+        assert_eq!(
+            state.execute(ShaderContext::default())[0],
+            [128, 255, 255, 255]
+        );
+
+        // Dragging right moves the eye to the left of the camera, around the target.
+        state.set_orbit_controls(OrbitControls::new(0.1, 0.1));
+        state.handle_pointer(PointerEvent::Press { x: 0, y: 0 });
+        state.handle_pointer(PointerEvent::Drag { x: 5, y: 0 });
+        let camera = state.camera().unwrap();
+        assert!(camera.eye[0] < 2.0 && camera.eye[2] > 3.0);
+        assert!(
+            (camera.distance() - Camera::new([2.0, 1.0, 3.0], [0.0, 0.5, 0.0]).distance()).abs()
+                < 1e-4
+        );
+    }
+
+    #[test]
+    fn raymarch_preset() {
+        use crate::presets::{self, RaymarchQuality};
+
+        let ctx = ShaderContext::new(0.0, ratatui_core::layout::Rect::new(0, 0, 64, 32));
+        let mut state =
+            presets::raymarch("fn scene(p: vec3<f32>) -> f32 { return length(p) - 1.0; }").unwrap();
+        for quality in [RaymarchQuality::Low, RaymarchQuality::High] {
+            state.uniforms().set("quality", quality);
+            let pixels = state.execute(ctx);
+            let center = pixels[16 * 64 + 32];
+            assert!(center[0] > 40 && center[0] == center[2], "{center:?}");
+            assert_eq!(pixels[0], [0, 0, 0, 255]);
+        }
+        assert!(presets::raymarch("fn not_scene() {}").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "obj")]
+    fn obj_mesh() {
+        use crate::Mesh;
+
+        let directory = std::env::temp_dir().join("tui-shader-obj-mesh");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("quad.mtl"), "newmtl red\nKd 1.0 0.0 0.0\n").unwrap();
+        std::fs::write(
+            directory.join("quad.obj"),
+            "mtllib quad.mtl\nv 0 0 0\nv 4 0 0\nv 4 2 0\nv 0 2 0\nusemtl red\nf 1 2 3 4\n",
+        )
+        .unwrap();
+        let mesh = Mesh::from_obj(directory.join("quad.obj")).unwrap();
+        assert_eq!(mesh.indices().len(), 6);
+        for vertex in mesh.vertices() {
+            assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);
+            assert_eq!(vertex.color, [1.0, 0.0, 0.0]);
+        }
+        let mesh = mesh.centered();
+        assert_eq!(mesh.vertices()[0].position, [-1.0, -0.5, 0.0]);
+        assert!(Mesh::from_obj(directory.join("missing.obj")).is_err());
+    }
+
+    #[test]
+    fn power_profile() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use ratatui_core::widgets::StatefulWidget;
+
+        use crate::{PointerEvent, PowerProfile};
+
+        struct Counted(Arc<AtomicUsize>);
+        impl crate::CpuBackend for Counted {
+            fn prepare(&mut self, _time: f32, _width: u32, _height: u32) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+            fn pixel(&mut self, _input: crate::PixelInput) -> [u8; 4] {
+                [0, 0, 255, 255]
+            }
+        }
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut state = ShaderCanvasState::from_backend(Counted(Arc::clone(&runs)));
+        let canvas = ShaderCanvas::new().power_profile(PowerProfile::LowPower);
+        let area = ratatui_core::layout::Rect::new(0, 0, 8, 8);
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        StatefulWidget::render(&canvas, area, &mut buffer, &mut state);
+        assert_eq!(state.last_frame().unwrap().width(), 4);
+        // Renders within the frame time of the profile reuse the last frame.
+        StatefulWidget::render(&canvas, area, &mut buffer, &mut state);
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+        let blue = ratatui_core::style::Color::Rgb(0, 0, 255);
+        assert!(buffer.content().iter().all(|cell| cell.bg == blue));
+
+        // Input renders right away, and the unchanged frame doubles the time until the next render.
+        state.handle_pointer(PointerEvent::Release);
+        StatefulWidget::render(&canvas, area, &mut buffer, &mut state);
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+        std::thread::sleep(Duration::from_millis(120));
+        StatefulWidget::render(&canvas, area, &mut buffer, &mut state);
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn idle_frame_rate() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use ratatui_core::widgets::StatefulWidget;
+
+        use crate::IdleFrameRate;
+
+        struct Counted(Arc<AtomicUsize>);
+        impl crate::CpuBackend for Counted {
+            fn prepare(&mut self, _time: f32, _width: u32, _height: u32) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+            fn pixel(&mut self, _input: crate::PixelInput) -> [u8; 4] {
+                [0, 0, 255, 255]
+            }
+        }
+
+        let idle = IdleFrameRate::new(Duration::from_millis(50), 1);
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut state = ShaderCanvasState::from_backend(Counted(Arc::clone(&runs)));
+        state.set_idle_frame_rate(Some(idle));
+        let area = ratatui_core::layout::Rect::new(0, 0, 4, 4);
+        let mut buffer = ratatui_core::buffer::Buffer::empty(area);
+        StatefulWidget::render(&ShaderCanvas::new(), area, &mut buffer, &mut state);
+        StatefulWidget::render(&ShaderCanvas::new(), area, &mut buffer, &mut state);
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(state.is_idle());
+        StatefulWidget::render(&ShaderCanvas::new(), area, &mut buffer, &mut state);
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+        state.mark_active();
+        assert!(!state.is_idle());
+        StatefulWidget::render(&ShaderCanvas::new(), area, &mut buffer, &mut state);
+        assert_eq!(runs.load(Ordering::Relaxed), 3);
+
+        // Only writes which change a uniform count as activity.
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(
+            "struct Uniforms { brightness: f32 }
+            @group(0) @binding(2) var<uniform> uniforms: Uniforms;
+            @fragment fn main() -> @location(0) vec4<f32> { return vec4<f32>(uniforms.brightness); }",
+        ))
+        .unwrap();
+        state.set_idle_frame_rate(Some(idle));
+        std::thread::sleep(Duration::from_millis(60));
+        state.uniforms().set("brightness", 0.0);
+        assert!(state.is_idle());
+        state.uniforms().set("brightness", 1.0);
+        assert!(!state.is_idle());
+    }
+
+    #[test]
+    fn suspend() {
+        let mut state = ShaderCanvasState::default();
+        state.set_msaa(true);
+        let ctx = ShaderContext::new(0.0, ratatui_core::layout::Rect::new(0, 0, 64, 32));
+        let expected = state.execute(ctx);
+        state.suspend();
+        assert!(state.is_suspended());
+        // The next execution recreates the released textures and resumes the state.
+        assert_eq!(state.execute(ctx), expected);
+        assert!(!state.is_suspended());
+        state.suspend();
+        state.resume();
+        assert!(!state.is_suspended());
+        let larger = ShaderContext::new(0.0, ratatui_core::layout::Rect::new(0, 0, 128, 64));
+        assert_eq!(
+            state.execute(larger),
+            ShaderCanvasState::default().execute(larger)
+        );
+    }
+
+    #[test]
+    fn device_migration() {
+        use crate::gpu::{AdapterSelection, DeviceOptions, enumerate_adapters};
+        let adapters = enumerate_adapters();
+        let device = DeviceOptions {
+            // The first preference doesn't exist, so the state runs on the second one.
+            adapter: AdapterSelection::Ordered(vec![
+                AdapterSelection::Index(adapters.len()),
+                AdapterSelection::Default,
+            ]),
+            migrate: true,
+            ..Default::default()
+        };
+        let mut state = ShaderCanvasState::builder()
+            .shader(wgpu::include_wgsl!("shaders/test_fragment.wgsl"))
+            .entry_point("green")
+            .device(device)
+            .build()
+            .unwrap();
+        let lost = state.handle().unwrap();
+        lost.device().destroy();
+        let rect = ratatui_core::layout::Rect::new(0, 0, 4, 2);
+        let raw_buffer = state.execute(ShaderContext::new(0.0, rect));
+        assert_eq!(raw_buffer[0], [0, 255, 0, 255]);
+        assert!(!state.handle().unwrap().ptr_eq(&lost));
+
+        let empty = ShaderCanvasState::builder().adapter(AdapterSelection::Ordered(Vec::new()));
+        assert!(empty.build().is_err());
+    }
+
+    #[test]
+    fn telemetry() {
+        let mut state = ShaderCanvasState::default();
+        let ctx = ShaderContext::new(0.0, ratatui_core::layout::Rect::new(0, 0, 64, 32));
+        state.execute(ctx);
+        state.execute(ctx);
+        let telemetry = state.telemetry();
+        assert_eq!(telemetry.executions, 2);
+        assert_eq!(telemetry.cache_hits, 1);
+        assert_eq!(telemetry.cache_hit_rate(), Some(0.5));
+        assert_eq!(telemetry.texture_recreations, 0);
+
+        // The area outgrows the default texture.
+        let larger = ShaderContext::new(0.0, ratatui_core::layout::Rect::new(0, 0, 128, 32));
+        state.execute(larger);
+        assert_eq!(state.telemetry().texture_recreations, 1);
+        state
+            .load_candidate(
+                wgpu::include_wgsl!("shaders/test_fragment.wgsl"),
+                Some("green"),
+            )
+            .unwrap();
+        state.promote_candidate();
+        assert_eq!(state.telemetry().pipeline_rebuilds, 1);
+        assert_eq!(state.telemetry().executions, 3);
+
+        state.reset_telemetry();
+        assert_eq!(state.telemetry(), crate::Telemetry::default());
+        assert_eq!(state.telemetry().cache_hit_rate(), None);
+    }
+
+    #[test]
+    fn context_layout() {
+        use crate::{CONTEXT_VERSION, CONTEXT_WGSL};
+        assert_eq!(std::mem::size_of::<ShaderContext>(), 7 * 16);
+        assert!(CONTEXT_WGSL.contains(&format!(
+            "TUI_SHADER_CONTEXT_VERSION: u32 = {CONTEXT_VERSION}u;"
+        )));
+
+        let main = "@fragment fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+            return vec4<f32>(f32(instance.x), uv.y + 1.0, time.x, 1.0);
+        }";
+        assert!(ShaderCanvasState::check(&format!("{CONTEXT_WGSL}\n{main}")).is_ok());
+        let outdated = CONTEXT_WGSL.replace(
+            &format!("= {CONTEXT_VERSION}u;"),
+            &format!("= {}u;", CONTEXT_VERSION + 1),
+        );
+        let error = ShaderCanvasState::check(&format!("{outdated}\n{main}")).unwrap_err();
+        assert_eq!(error.line, Some(2));
+
+        let source = format!("#include <context>\n{main}");
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(&source)).unwrap();
+        let ctx = ShaderContext::new(0.0, ratatui_core::layout::Rect::new(0, 0, 64, 32));
+        assert_eq!(state.execute(ctx)[0], [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn packed_outputs() {
+        use ratatui_core::style::Color;
+
+        use crate::Sample;
+
+        let source = "#include <pack>
+        @fragment fn pair(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+            return pack_rgb444_pair(vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 1.0, 1.0));
+        }
+        @fragment fn glyph(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+            return pack_rgb565_glyph(vec3<f32>(1.0, 1.0, 1.0), 5u, 3u);
+        }";
+        let ctx = ShaderContext::new(0.0, ratatui_core::layout::Rect::new(0, 0, 64, 32));
+        let mut state = ShaderCanvasState::builder()
+            .shader(crate::WgslShader::Source(source))
+            .entry_point("pair")
+            .build()
+            .unwrap();
+        let sample = Sample::new(state.execute(ctx)[0], (0, 0), (0.0, 0.0));
+        assert_eq!(
+            sample.unpack_rgb444_pair(),
+            (Color::Rgb(255, 0, 0), Color::Rgb(0, 255, 255))
+        );
+
+        let mut state = ShaderCanvasState::builder()
+            .shader(crate::WgslShader::Source(source))
+            .entry_point("glyph")
+            .build()
+            .unwrap();
+        let sample = Sample::new(state.execute(ctx)[0], (0, 0), (0.0, 0.0));
+        assert_eq!(sample.unpack_rgb565(), Color::Rgb(255, 255, 255));
+        assert_eq!(sample.glyph_index(3), 5);
+        assert_eq!(sample.glyph_index(0), 0);
+        assert_eq!(sample.glyph_index(40), sample.packed());
+    }
+
+    #[test]
+    fn fixed_clock() {
+        let source = "@group(0) @binding(0) var<uniform> time: f32;
+
+        @fragment
+        fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+            return vec4<f32>(fract(time), 0.0, 0.0, 1.0);
+        }";
+        let dt = std::time::Duration::from_millis(250);
+        let render = || {
+            let mut terminal =
+                ratatui_core::terminal::Terminal::new(TestBackend::new(8, 4)).unwrap();
+            let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+            state.set_clock_mode(crate::ClockMode::Fixed { dt });
+            state.set_elapsed(std::time::Duration::ZERO);
+            let mut buffers = Vec::new();
+            for _ in 0..3 {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+                let completed = terminal
+                    .draw(|frame| {
+                        frame.render_stateful_widget(ShaderCanvas::new(), frame.area(), &mut state)
+                    })
+                    .unwrap();
+                buffers.push(completed.buffer.clone());
+            }
+            assert_eq!(state.clock().elapsed(), dt * 3);
+            buffers
+        };
+        let buffers = render();
+        assert_ne!(buffers[0], buffers[1]);
+        assert_eq!(buffers, render());
+    }
+
+    #[test]
+    fn clock_source() {
+        struct Ticks(u64);
+
+        impl crate::ClockSource for Ticks {
+            fn elapsed(&self) -> std::time::Duration {
+                std::time::Duration::from_millis(self.0 * 100)
+            }
+
+            fn tick(&mut self) {
+                self.0 += 1;
+            }
+        }
+
+        let render = |state: &mut ShaderCanvasState| {
+            let mut terminal =
+                ratatui_core::terminal::Terminal::new(TestBackend::new(8, 4)).unwrap();
+            let completed = terminal
+                .draw(|frame| {
+                    frame.render_stateful_widget(ShaderCanvas::new(), frame.area(), state)
+                })
+                .unwrap();
+            completed.buffer.clone()
+        };
+        let mut state = ShaderCanvasState::default();
+        state.clock_mut().set_source(Ticks(0));
+        render(&mut state);
+        render(&mut state);
+        assert_eq!(
+            state.clock().elapsed(),
+            std::time::Duration::from_millis(200)
+        );
+
+        let external = crate::ExternalClock::new();
+        let mut driven = ShaderCanvasState::default();
+        driven.clock_mut().set_source(external.clone());
+        external.advance(std::time::Duration::from_millis(1500));
+        let mut mocked = ShaderCanvasState::default();
+        mocked
+            .clock_mut()
+            .set_source(|| std::time::Duration::from_millis(1500));
+        assert_eq!(render(&mut driven), render(&mut mocked));
+        assert_eq!(driven.clock().elapsed(), external.get());
+        driven.clock_mut().clear_source();
+        assert!(!driven.clock().has_source());
+    }
+
+    #[test]
+    fn frame_deadline() {
+        use std::sync::Mutex;
+
+        static ERRORS: Mutex<Vec<crate::TuiShaderError>> = Mutex::new(Vec::new());
+        let source = "@group(0) @binding(0) var<uniform> time: f32;
+
+        @fragment
+        fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+            var x = uv.x + time;
+            for (var i = 0; i < 200000; i++) {
+                x = fract(sin(x) * 43758.5453);
+            }
+            return vec4<f32>(x, 0.0, 0.0, 1.0);
+        }";
+        let mut state = ShaderCanvasState::new(crate::WgslShader::Source(source)).unwrap();
+        let deadline = std::time::Duration::from_millis(1);
+        state.set_frame_deadline(Some(deadline));
+        assert_eq!(state.frame_deadline(), Some(deadline));
+        state.on_error(|error| ERRORS.lock().unwrap().push(error));
+        let rect = ratatui_core::layout::Rect::new(0, 0, 64, 32);
+        let pixels = state.execute(ShaderContext::new(0.0, rect));
+        assert!(pixels.iter().all(|pixel| pixel == &[0; 4]));
+        state.execute(ShaderContext::new(1.0, rect));
+        let errors = ERRORS.lock().unwrap();
+        assert_eq!(errors[0], crate::TuiShaderError::Timeout(deadline));
+        assert!(matches!(errors[1], crate::TuiShaderError::DeviceLost(_)));
+    }
+
+    #[test]
+    fn memory_usage() {
+        let mut state = ShaderCanvasState::default();
+        let rect = ratatui_core::layout::Rect::new(0, 0, 64, 32);
+        state.execute(ShaderContext::new(0.0, rect));
+        let usage = state.memory_usage();
+        assert!(usage.textures >= 64 * 32 * 4);
+        assert!(usage.buffers >= 64 * 32 * 4);
+        assert!(usage.host >= 64 * 32 * 4);
+        assert!(crate::total_memory_usage().gpu() >= usage.gpu());
+        state.set_msaa(true);
+        assert_eq!(
+            state.memory_usage().textures,
+            usage.textures * 5,
+            "the multisampled texture holds 4 samples per pixel"
+        );
+        state.suspend();
+        assert!(state.memory_usage().gpu() < usage.gpu());
+    }
+
+    #[test]
+    fn compile_errors() {
+        use ratatui_core::style::Color;
+        let mut state = ShaderCanvasState::default();
+        let source = "@fragment fn main() -> @location(0) vec4<f32> {
+            return vec4<f32>(missing);
+        }";
+        assert!(
+            state
+                .load_candidate(crate::WgslShader::Source(source), None)
+                .is_err()
+        );
+        assert_eq!(state.compile_error().unwrap().line, Some(2));
+
+        let canvas = ShaderCanvas::new().show_compile_errors(true);
+        let buffer = testing::render(&canvas, &mut state, 60, 8, Duration::ZERO);
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.starts_with("error:"));
+        assert!(text.contains("2 |"));
+        assert!(buffer.content().iter().any(|cell| cell.bg == Color::Red));
+        let buffer = testing::render(&ShaderCanvas::new(), &mut state, 60, 8, Duration::ZERO);
+        assert!(buffer.content().iter().all(|cell| cell.bg != Color::Red));
+
+        let source = "@fragment fn main() -> @location(0) vec4<f32> { return vec4<f32>(1.0); }";
+        assert!(
+            state
+                .load_candidate(crate::WgslShader::Source(source), None)
+                .is_ok()
+        );
+        assert!(state.compile_error().is_none());
+    }
+}
+
+#[cfg(all(test, not(feature = "gpu")))]
+mod cpu_only_tests {
+    use std::time::Duration;
+
+    use crate::{ShaderCanvas, ShaderCanvasState, testing};
+
+    #[test]
+    fn default_state() {
+        let mut state = ShaderCanvasState::default();
+        let buffer = testing::render(&ShaderCanvas::new(), &mut state, 4, 2, Duration::ZERO);
+        let expected = ratatui_core::style::Color::Rgb(255, 0, 255);
+        assert!(buffer.content().iter().all(|cell| cell.bg == expected));
+        assert!(ShaderCanvasState::builder().build().is_ok());
+    }
 }
@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// The number of messages kept by [`logs`], older ones are dropped.
+pub const LOG_CAPACITY: usize = 1024;
+
+static LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Installs a global logger capturing the messages of wgpu, naga and every other crate using [`log`]
+/// into memory, where [`logs`] returns them. Anything written to stderr while the alternate screen is
+/// active wrecks the UI, so call this before creating the first state. Fails if a logger is already
+/// installed.
+///
+/// ```rust,no_run
+/// # use tui_shader::ShaderCanvasState;
+/// tui_shader::capture_logs(log::LevelFilter::Warn).unwrap();
+/// let state = ShaderCanvasState::default();
+/// for line in tui_shader::logs() {
+///     println!("{line}");
+/// }
+/// ```
+pub fn capture_logs(level: LevelFilter) -> Result<(), Box<dyn Error>> {
+    install(None, level)
+}
+
+/// Same as [`capture_logs`], but also appends every message to the file at `path`, e.g. to follow it
+/// with `tail -f` from another terminal.
+pub fn capture_logs_to_file(
+    path: impl AsRef<Path>,
+    level: LevelFilter,
+) -> Result<(), Box<dyn Error>> {
+    let file = File::options().create(true).append(true).open(path)?;
+    install(Some(file), level)
+}
+
+/// The last [`LOG_CAPACITY`] messages captured since [`capture_logs`] was called, oldest first.
+pub fn logs() -> Vec<String> {
+    LOGS.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .cloned()
+        .collect()
+}
+
+fn install(file: Option<File>, level: LevelFilter) -> Result<(), Box<dyn Error>> {
+    log::set_boxed_logger(Box::new(CaptureLogger {
+        file: file.map(Mutex::new),
+    }))?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+struct CaptureLogger {
+    file: Option<Mutex<File>>,
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!("[{} {}] {}", record.level(), record.target(), record.args());
+        if let Some(file) = &self.file {
+            let mut file = file.lock().unwrap_or_else(PoisonError::into_inner);
+            let _ = writeln!(file, "{line}");
+        }
+        let mut logs = LOGS.lock().unwrap_or_else(PoisonError::into_inner);
+        if logs.len() == LOG_CAPACITY {
+            logs.pop_front();
+        }
+        logs.push_back(line);
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file {
+            let _ = file.lock().unwrap_or_else(PoisonError::into_inner).flush();
+        }
+    }
+}
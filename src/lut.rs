@@ -0,0 +1,216 @@
+use std::error::Error;
+use std::path::Path;
+
+use crate::Pixel;
+#[cfg(feature = "gpu")]
+use crate::bindings::{BindingSlot, BindingsBuilder};
+
+/// The largest size of a table, the limit of the `.cube` specification.
+const MAX_SIZE: u32 = 256;
+
+/// [`CubeLut`] is a 3D color lookup table in the `.cube` format, which color grading tools like
+/// DaVinci Resolve or Photoshop export and many free film looks are distributed in. It maps every color
+/// to a graded color and is applied in one of two ways:
+///
+/// - On the CPU to every frame of a state with
+///   [`ShaderCanvasState::set_color_grade`](crate::ShaderCanvasState::set_color_grade), which works for
+///   every shader and [`CpuBackend`](crate::CpuBackend) without changing it.
+/// - In a shader, bound as a `texture_3d<f32>` with [`BindingsBuilder::lut_texture`] and sampled with
+///   `apply_lut` of the `lut` module of the [`stdlib`](crate::stdlib), e.g. to grade only the
+///   background.
+///
+/// ```rust,no_run
+/// # use tui_shader::{CubeLut, ShaderCanvasState, WgslShader};
+/// let mut state = ShaderCanvasState::new(WgslShader::Path("shader.wgsl")).unwrap();
+/// state.set_color_grade(Some(CubeLut::from_path("film.cube").unwrap()));
+/// ```
+///
+/// ```wgsl
+/// #include <lut>
+///
+/// @group(1) @binding(0) var lut: texture_3d<f32>;
+/// @group(1) @binding(1) var lut_sampler: sampler;
+///
+/// @fragment
+/// fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+///     return vec4<f32>(apply_lut(lut, lut_sampler, vec3<f32>(uv, 0.5)), 1.0);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubeLut {
+    title: Option<String>,
+    size: u32,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    /// The output colors, with red changing fastest and blue slowest.
+    table: Vec<[f32; 3]>,
+}
+
+impl CubeLut {
+    /// Reads the `.cube` file at `path`, see [`CubeLut::parse`].
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parses the contents of a `.cube` file. Only 3D tables are supported. `TITLE`, `LUT_3D_SIZE`,
+    /// `DOMAIN_MIN`, `DOMAIN_MAX` and `LUT_3D_INPUT_RANGE` are read, other keywords and `#` comments are
+    /// skipped.
+    pub fn parse(source: &str) -> Result<Self, Box<dyn Error>> {
+        let mut title = None;
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {message}", index + 1);
+            let Some((keyword, arguments)) = line
+                .split_once(char::is_whitespace)
+                .or_else(|| (!line.is_empty()).then_some((line, "")))
+            else {
+                continue;
+            };
+            let arguments = arguments.trim();
+            match keyword {
+                "TITLE" => title = Some(arguments.trim_matches('"').to_owned()),
+                "LUT_3D_SIZE" => {
+                    let parsed: u32 = arguments
+                        .parse()
+                        .map_err(|_| error(format!("invalid size `{arguments}`")))?;
+                    if !(2..=MAX_SIZE).contains(&parsed) {
+                        return Err(error(format!(
+                            "the size must be between 2 and {MAX_SIZE}, got {parsed}"
+                        ))
+                        .into());
+                    }
+                    size = Some(parsed);
+                }
+                "LUT_1D_SIZE" => return Err(error("1D tables aren't supported".to_owned()).into()),
+                "DOMAIN_MIN" => domain_min = parse_numbers(arguments).map_err(error)?,
+                "DOMAIN_MAX" => domain_max = parse_numbers(arguments).map_err(error)?,
+                "LUT_3D_INPUT_RANGE" => {
+                    let [min, max] = parse_numbers(arguments).map_err(error)?;
+                    domain_min = [min; 3];
+                    domain_max = [max; 3];
+                }
+                _ if keyword.starts_with(|c: char| c.is_ascii_digit() || "+-.".contains(c)) => {
+                    table.push(parse_numbers(line).map_err(error)?);
+                }
+                _ => {}
+            }
+        }
+        let size = size.ok_or("the file doesn't declare a LUT_3D_SIZE")?;
+        let expected = size.pow(3) as usize;
+        if table.len() != expected {
+            return Err(format!(
+                "expected {expected} entries for a table of size {size}, got {}",
+                table.len()
+            )
+            .into());
+        }
+        if (0..3).any(|channel| domain_min[channel] >= domain_max[channel]) {
+            return Err("the minimum of the domain must be below its maximum".into());
+        }
+        Ok(Self {
+            title,
+            size,
+            domain_min,
+            domain_max,
+            table,
+        })
+    }
+
+    /// The title of the table, if the file declares one.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// The number of entries along each axis of the table.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Maps `color` through the table, interpolating trilinearly between the nearest entries. Colors
+    /// outside of the domain of the table are clamped to it.
+    pub fn apply(&self, color: [f32; 3]) -> [f32; 3] {
+        let last = self.size - 1;
+        let mut lower = [0; 3];
+        let mut weight = [0.0; 3];
+        for channel in 0..3 {
+            let (min, max) = (self.domain_min[channel], self.domain_max[channel]);
+            let position = (color[channel].clamp(min, max) - min) / (max - min) * last as f32;
+            lower[channel] = (position as u32).min(last - 1);
+            weight[channel] = position - lower[channel] as f32;
+        }
+        let entry = |dr: u32, dg: u32, db: u32| {
+            let (r, g, b) = (lower[0] + dr, lower[1] + dg, lower[2] + db);
+            self.table[((b * self.size + g) * self.size + r) as usize]
+        };
+        let lerp =
+            |a: [f32; 3], b: [f32; 3], t: f32| std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t);
+        let [wr, wg, wb] = weight;
+        lerp(
+            lerp(
+                lerp(entry(0, 0, 0), entry(1, 0, 0), wr),
+                lerp(entry(0, 1, 0), entry(1, 1, 0), wr),
+                wg,
+            ),
+            lerp(
+                lerp(entry(0, 0, 1), entry(1, 0, 1), wr),
+                lerp(entry(0, 1, 1), entry(1, 1, 1), wr),
+                wg,
+            ),
+            wb,
+        )
+    }
+
+    /// The table as tightly packed RGBA bytes of a `size` x `size` x `size` texture, with red along the
+    /// width and blue along the depth. Outputs are clamped to `0.0` to `1.0`, the domain of the input is
+    /// ignored.
+    pub fn texture_data(&self) -> Vec<u8> {
+        self.table
+            .iter()
+            .flat_map(|&[r, g, b]| [to_byte(r), to_byte(g), to_byte(b), 255])
+            .collect()
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl BindingsBuilder {
+    /// Declares a `texture_3d<f32>` initialized with `lut`, see [`CubeLut`]. Sample it with a linear
+    /// sampler for smooth gradients.
+    #[must_use]
+    pub fn lut_texture(self, slot: impl Into<BindingSlot>, lut: &CubeLut) -> Self {
+        self.texture_3d_with_data(slot, lut.size, lut.size, lut.size, &lut.texture_data())
+    }
+}
+
+/// Maps the color channels of `pixels` through `lut`, leaving alpha untouched.
+pub(crate) fn apply_cube_lut(pixels: &mut [Pixel], lut: &CubeLut) {
+    for pixel in pixels {
+        let [r, g, b] = lut.apply([pixel[0], pixel[1], pixel[2]].map(|c| f32::from(c) / 255.0));
+        pixel[..3].copy_from_slice(&[to_byte(r), to_byte(g), to_byte(b)]);
+    }
+}
+
+fn parse_numbers<const N: usize>(arguments: &str) -> Result<[f32; N], String> {
+    let numbers = arguments
+        .split_whitespace()
+        .map(|number| {
+            number
+                .parse()
+                .map_err(|_| format!("invalid number `{number}`"))
+        })
+        .collect::<Result<Vec<f32>, String>>()?;
+    let count = numbers.len();
+    numbers
+        .try_into()
+        .map_err(|_| format!("expected {N} numbers, got {count}"))
+}
+
+fn to_byte(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
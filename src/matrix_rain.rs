@@ -0,0 +1,461 @@
+use bytemuck::{Pod, Zeroable};
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::{Position, Rect};
+use ratatui_core::style::{Color, Style};
+use ratatui_core::widgets::StatefulWidget;
+use std::time::Instant;
+
+use crate::clock::ShaderClock;
+use crate::context::ShaderContext;
+use crate::error::TuiShaderError;
+use crate::gpu::{
+    DEFAULT_SIZE, RenderTarget, VERTEX_SHADER_DESCRIPTOR, create_input_buffer,
+    storage_layout_entry, uniform_layout_entry,
+};
+use crate::{Pixel, color_to_vec4, row_padding};
+
+const COMPUTE_SHADER_DESCRIPTOR: wgpu::ShaderModuleDescriptor =
+    wgpu::include_wgsl!("shaders/matrix_rain.wgsl");
+const RENDER_SHADER_DESCRIPTOR: wgpu::ShaderModuleDescriptor =
+    wgpu::include_wgsl!("shaders/matrix_rain_render.wgsl");
+const COLUMN_SIZE: u64 = 16;
+const WORKGROUP_SIZE: u32 = 64;
+const MAX_DELTA_TIME: f32 = 0.1;
+
+/// Parameters of a [`MatrixRainState`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixRainConfig {
+    /// Characters the falling glyphs are picked from.
+    pub charset: Vec<char>,
+
+    /// Average falling speed of a drop in cells per second.
+    pub speed: f32,
+
+    /// Fraction between `0.0` and `1.0` controlling how many columns are raining at the same time.
+    pub density: f32,
+
+    /// Average length of the trail behind a drop in cells.
+    pub trail_length: f32,
+
+    /// Exponent applied to the brightness of the trail. Higher values fade the trail out faster.
+    pub fade: f32,
+
+    /// Color of the leading glyph of a drop.
+    pub head_color: [u8; 3],
+
+    /// Color of the trail behind a drop.
+    pub trail_color: [u8; 3],
+}
+
+impl Default for MatrixRainConfig {
+    fn default() -> Self {
+        Self {
+            charset: ('\u{FF66}'..='\u{FF9D}').chain('0'..='9').collect(),
+            speed: 12.0,
+            density: 0.6,
+            trail_length: 14.0,
+            fade: 1.5,
+            head_color: [220, 255, 220],
+            trail_color: [0, 255, 70],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct RainParams {
+    head_color: [f32; 4],
+    trail_color: [f32; 4],
+    resolution: [f32; 2],
+    delta_time: f32,
+    time: f32,
+    speed: f32,
+    density: f32,
+    trail_length: f32,
+    fade: f32,
+}
+
+/// [`MatrixRainState`] keeps track of the falling drops of every column on the GPU. A compute pass advances
+/// the drops each frame and a fragment shader renders their glyphs and trails.
+#[derive(Debug, Clone)]
+pub struct MatrixRainState {
+    target: RenderTarget,
+    compute_pipeline: wgpu::ComputePipeline,
+    render_pipeline: wgpu::RenderPipeline,
+    compute_bind_group_layout: wgpu::BindGroupLayout,
+    render_bind_group_layout: wgpu::BindGroupLayout,
+    compute_bind_group: wgpu::BindGroup,
+    render_bind_group: wgpu::BindGroup,
+    params_buffer: wgpu::Buffer,
+    column_buffer: wgpu::Buffer,
+    config: MatrixRainConfig,
+    clock: ShaderClock,
+    last_time: Option<f32>,
+    columns: u32,
+}
+
+impl MatrixRainState {
+    /// Creates a new [`MatrixRainState`] with the given [`MatrixRainConfig`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if no GPU device can be created, see [`MatrixRainState::try_new`].
+    pub fn new(config: MatrixRainConfig) -> Self {
+        Self::try_new(config).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Creates a new [`MatrixRainState`] with the given [`MatrixRainConfig`], failing with
+    /// [`TuiShaderError::NoBackend`] or [`TuiShaderError::NoDevice`] if no GPU device can be created.
+    pub fn try_new(config: MatrixRainConfig) -> Result<Self, TuiShaderError> {
+        let target = RenderTarget::new()?;
+        let device = target.device();
+        let vertex_shader = device.create_shader_module(VERTEX_SHADER_DESCRIPTOR);
+        let compute_shader = device.create_shader_module(COMPUTE_SHADER_DESCRIPTOR);
+        let render_shader = device.create_shader_module(RENDER_SHADER_DESCRIPTOR);
+        let params_buffer = create_input_buffer(device, RainParams::zeroed());
+        let column_buffer = create_column_buffer(device, DEFAULT_SIZE);
+
+        let compute_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &[
+                    uniform_layout_entry(0, wgpu::ShaderStages::COMPUTE),
+                    storage_layout_entry(1, wgpu::ShaderStages::COMPUTE, false),
+                ],
+            });
+        let render_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &[
+                    uniform_layout_entry(0, wgpu::ShaderStages::FRAGMENT),
+                    storage_layout_entry(1, wgpu::ShaderStages::FRAGMENT, true),
+                ],
+            });
+        let compute_bind_group = create_rain_bind_group(
+            device,
+            &compute_bind_group_layout,
+            &params_buffer,
+            &column_buffer,
+        );
+        let render_bind_group = create_rain_bind_group(
+            device,
+            &render_bind_group_layout,
+            &params_buffer,
+            &column_buffer,
+        );
+
+        let compute_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[&compute_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: Some(&compute_pipeline_layout),
+            module: &compute_shader,
+            entry_point: Some("update"),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[&render_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &vertex_shader,
+                entry_point: Some("main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &render_shader,
+                entry_point: Some("main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Ok(MatrixRainState {
+            target,
+            compute_pipeline,
+            render_pipeline,
+            compute_bind_group_layout,
+            render_bind_group_layout,
+            compute_bind_group,
+            render_bind_group,
+            params_buffer,
+            column_buffer,
+            config,
+            clock: ShaderClock::new(),
+            last_time: None,
+            columns: DEFAULT_SIZE,
+        })
+    }
+
+    pub(crate) fn execute(&mut self, ctx: ShaderContext) -> Vec<Pixel> {
+        let width = ctx.width();
+        let height = ctx.height();
+        if width > self.columns {
+            let device = self.target.device();
+            self.column_buffer = create_column_buffer(device, width);
+            self.compute_bind_group = create_rain_bind_group(
+                device,
+                &self.compute_bind_group_layout,
+                &self.params_buffer,
+                &self.column_buffer,
+            );
+            self.render_bind_group = create_rain_bind_group(
+                device,
+                &self.render_bind_group_layout,
+                &self.params_buffer,
+                &self.column_buffer,
+            );
+            self.columns = width;
+        }
+
+        let time = ctx.time[0];
+        let delta_time = self.last_time.map_or(0.0, |last_time| {
+            (time - last_time).clamp(0.0, MAX_DELTA_TIME)
+        });
+        self.last_time = Some(time);
+        let config = &self.config;
+        let params = RainParams {
+            head_color: color_to_vec4(config.head_color),
+            trail_color: color_to_vec4(config.trail_color),
+            resolution: [width as f32, height as f32],
+            delta_time,
+            time,
+            speed: config.speed,
+            density: config.density.clamp(0.0, 1.0),
+            trail_length: config.trail_length,
+            fade: config.fade,
+        };
+        self.target
+            .execute(width, height, |_, queue, command_encoder, texture_view| {
+                queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[params]));
+                {
+                    let mut compute_pass =
+                        command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                            label: None,
+                            timestamp_writes: None,
+                        });
+                    compute_pass.set_pipeline(&self.compute_pipeline);
+                    compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
+                    compute_pass.dispatch_workgroups(width.div_ceil(WORKGROUP_SIZE), 1, 1);
+                }
+                let mut render_pass =
+                    command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: None,
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            view: texture_view,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                                store: wgpu::StoreOp::Store,
+                            },
+                            depth_slice: None,
+                        })],
+                        depth_stencil_attachment: None,
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                    });
+                render_pass.set_pipeline(&self.render_pipeline);
+                render_pass.set_bind_group(0, &self.render_bind_group, &[]);
+                render_pass.draw(0..3, 0..1);
+            })
+    }
+
+    /// Sets the characters the falling glyphs are picked from.
+    pub fn set_charset(&mut self, charset: impl IntoIterator<Item = char>) {
+        self.config.charset = charset.into_iter().collect();
+    }
+
+    /// Sets the average falling speed of a drop in cells per second.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.config.speed = speed;
+    }
+
+    /// Sets the fraction of columns raining at the same time.
+    pub fn set_density(&mut self, density: f32) {
+        self.config.density = density;
+    }
+
+    /// Sets the exponent controlling how fast the trail behind a drop fades out.
+    pub fn set_fade(&mut self, fade: f32) {
+        self.config.fade = fade;
+    }
+
+    /// Sets the color of the leading glyph of a drop.
+    pub fn set_head_color(&mut self, color: [u8; 3]) {
+        self.config.head_color = color;
+    }
+
+    /// Sets the color of the trail behind a drop.
+    pub fn set_trail_color(&mut self, color: [u8; 3]) {
+        self.config.trail_color = color;
+    }
+
+    /// Gets the [`MatrixRainConfig`] of the [`MatrixRainState`].
+    pub fn get_config(&self) -> &MatrixRainConfig {
+        &self.config
+    }
+
+    /// Gets the [`MatrixRainState`]'s [`Instant`].
+    pub fn get_instant(&self) -> Instant {
+        self.clock.get_instant()
+    }
+
+    /// Gets the [`ShaderClock`] driving the `time` input of the [`MatrixRainState`].
+    pub fn clock(&self) -> &ShaderClock {
+        &self.clock
+    }
+
+    /// Gets a mutable reference to the [`ShaderClock`], e.g. to pause and resume the animation.
+    pub fn clock_mut(&mut self) -> &mut ShaderClock {
+        &mut self.clock
+    }
+
+    /// Registers `callback` to receive the errors of advancing and drawing the drops on the GPU, like
+    /// [`ShaderCanvasState::on_error`](crate::ShaderCanvasState::on_error), instead of panicking inside
+    /// `terminal.draw`. A failed frame draws no glyphs, leaving the cells of the area untouched.
+    pub fn on_error(&mut self, callback: fn(TuiShaderError)) {
+        self.target.on_error(callback);
+    }
+}
+
+impl Default for MatrixRainState {
+    fn default() -> Self {
+        Self::new(MatrixRainConfig::default())
+    }
+}
+
+/// [`MatrixRain`] draws the falling glyphs of a [`MatrixRainState`]. Cells without a glyph are left
+/// untouched, so the effect can be layered on top of other widgets.
+///
+/// ```rust,no_run
+/// # use tui_shader::{MatrixRain, MatrixRainState};
+/// let mut terminal = ratatui::init();
+/// let mut state = MatrixRainState::default();
+/// terminal.draw(|frame| {
+///     frame.render_stateful_widget(MatrixRain::new(),
+///         frame.area(),
+///         &mut state);
+/// }).unwrap();
+/// ratatui::restore();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MatrixRain {
+    pub background: Option<Color>,
+}
+
+impl MatrixRain {
+    /// Creates a new instance of [`MatrixRain`]. Equivalent to [`MatrixRain::default()`]
+    pub fn new() -> Self {
+        Self { background: None }
+    }
+
+    /// Sets the background color of the glyphs. By default the background is left untouched.
+    #[must_use]
+    pub fn background(mut self, background: Color) -> Self {
+        self.background = Some(background);
+        self
+    }
+}
+
+impl StatefulWidget for MatrixRain {
+    type State = MatrixRainState;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(&self, area, buf, state);
+    }
+}
+
+impl StatefulWidget for &MatrixRain {
+    type State = MatrixRainState;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        if area.is_empty() {
+            return;
+        }
+        let width = area.width;
+        let height = area.height;
+        let time = state.clock().elapsed().as_secs_f32();
+        state.clock_mut().tick();
+        let ctx = ShaderContext::new(time, area);
+        let samples = state.execute(ctx);
+        let stride = (u32::from(width) + row_padding(width.into())) as usize;
+        let charset = &state.get_config().charset;
+
+        for y in 0..height {
+            for x in 0..width {
+                let index = y as usize * stride + x as usize;
+                let value = samples[index];
+                if value[3] == 0 || charset.is_empty() {
+                    continue;
+                }
+                let character = charset[(value[3] - 1) as usize % charset.len()];
+                let mut style = Style::new().fg(Color::Rgb(value[0], value[1], value[2]));
+                if let Some(background) = self.background {
+                    style = style.bg(background);
+                }
+                let cell = buf
+                    .cell_mut(Position::new(x + area.x, y + area.y))
+                    .expect("unable to get cell");
+                cell.set_style(style);
+                cell.set_char(character);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "widget-ref")]
+impl ratatui::widgets::StatefulWidgetRef for MatrixRain {
+    type State = MatrixRainState;
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(self, area, buf, state);
+    }
+}
+
+fn create_column_buffer(device: &wgpu::Device, columns: u32) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: columns.max(1) as u64 * COLUMN_SIZE,
+        usage: wgpu::BufferUsages::STORAGE,
+        mapped_at_creation: false,
+    })
+}
+
+fn create_rain_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    params_buffer: &wgpu::Buffer,
+    column_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: params_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: column_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
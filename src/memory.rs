@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The bytes of GPU and host memory held by the GPU backends of all states, see [`MemoryTracker`].
+static TEXTURE_BYTES: AtomicU64 = AtomicU64::new(0);
+static BUFFER_BYTES: AtomicU64 = AtomicU64::new(0);
+static HOST_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// An estimate of the memory held by a [`ShaderCanvasState`](crate::ShaderCanvasState), returned by
+/// [`ShaderCanvasState::memory_usage`](crate::ShaderCanvasState::memory_usage), or by all states together,
+/// returned by [`total_memory_usage`]. Apps with many shaded panes can enforce their own budget with it,
+/// e.g. by suspending the panes which are out of sight with
+/// [`ShaderCanvasState::suspend`](crate::ShaderCanvasState::suspend).
+///
+/// GPU memory is estimated from the sizes of the textures and buffers the states created. Drivers add
+/// alignment, and the compiled pipelines and the memory of the device itself aren't counted.
+///
+/// ```rust,no_run
+/// # use tui_shader::ShaderCanvasState;
+/// # let mut hidden_panes: Vec<ShaderCanvasState> = Vec::new();
+/// const BUDGET: u64 = 256 << 20;
+/// if tui_shader::total_memory_usage().gpu() > BUDGET
+///     && let Some(largest) = hidden_panes
+///         .iter_mut()
+///         .max_by_key(|state| state.memory_usage().gpu())
+/// {
+///     largest.suspend();
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MemoryUsage {
+    /// Bytes of GPU memory in textures: the render targets and the textures bound to the shader.
+    pub textures: u64,
+
+    /// Bytes of GPU memory in buffers: the inputs of the shader, the storage buffers bound to it and the
+    /// buffers the pixels are read back through.
+    pub buffers: u64,
+
+    /// Bytes of host memory in the pixels and cells kept from the last frames.
+    pub host: u64,
+}
+
+impl MemoryUsage {
+    /// The bytes of GPU memory, [`textures`](MemoryUsage::textures) and
+    /// [`buffers`](MemoryUsage::buffers) together.
+    pub fn gpu(&self) -> u64 {
+        self.textures + self.buffers
+    }
+
+    /// The bytes of GPU and host memory together.
+    pub fn total(&self) -> u64 {
+        self.gpu() + self.host
+    }
+
+    /// Adds the usage of `other`.
+    pub(crate) fn merge(&mut self, other: Self) {
+        self.textures += other.textures;
+        self.buffers += other.buffers;
+        self.host += other.host;
+    }
+}
+
+/// The memory held by the GPU backends of all states of the process, including the ones sharing a
+/// device through a `ShaderHandle`. Updated whenever a state renders, suspends or is dropped. Without
+/// the `gpu` feature, nothing is counted.
+pub fn total_memory_usage() -> MemoryUsage {
+    MemoryUsage {
+        textures: TEXTURE_BYTES.load(Ordering::Relaxed),
+        buffers: BUFFER_BYTES.load(Ordering::Relaxed),
+        host: HOST_BYTES.load(Ordering::Relaxed),
+    }
+}
+
+/// Adds the memory of a GPU backend to [`total_memory_usage`], keeping the last reported usage so that
+/// updates only add the difference and dropping the tracker removes it again.
+#[cfg(feature = "gpu")]
+#[derive(Debug, Default)]
+pub(crate) struct MemoryTracker {
+    reported: MemoryUsage,
+}
+
+#[cfg(feature = "gpu")]
+impl MemoryTracker {
+    pub(crate) fn report(&mut self, usage: MemoryUsage) {
+        let previous = std::mem::replace(&mut self.reported, usage);
+        for (total, previous, current) in [
+            (&TEXTURE_BYTES, previous.textures, usage.textures),
+            (&BUFFER_BYTES, previous.buffers, usage.buffers),
+            (&HOST_BYTES, previous.host, usage.host),
+        ] {
+            total.fetch_add(current, Ordering::Relaxed);
+            total.fetch_sub(previous, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl Drop for MemoryTracker {
+    fn drop(&mut self) {
+        self.report(MemoryUsage::default());
+    }
+}
+
+/// The bytes of all mip levels and samples of `texture`.
+#[cfg(feature = "gpu")]
+pub(crate) fn texture_bytes(texture: &wgpu::Texture) -> u64 {
+    let format = texture.format();
+    let block_size = u64::from(format.block_copy_size(None).unwrap_or(4));
+    let (block_width, block_height) = format.block_dimensions();
+    let size = texture.size();
+    let layers = u64::from(size.depth_or_array_layers);
+    let bytes: u64 = (0..texture.mip_level_count())
+        .map(|level| {
+            let width = (size.width >> level).max(1).div_ceil(block_width);
+            let height = (size.height >> level).max(1).div_ceil(block_height);
+            u64::from(width) * u64::from(height) * layers * block_size
+        })
+        .sum();
+    bytes * u64::from(texture.sample_count())
+}
@@ -0,0 +1,200 @@
+use std::error::Error;
+use std::path::Path;
+
+#[cfg(feature = "gltf")]
+use crate::camera::{IDENTITY, dot, multiply};
+use crate::camera::{cross, normalize, sub};
+use crate::scene::{Mesh, SceneVertex};
+
+/// The color of surfaces without a material or vertex colors.
+#[cfg(feature = "obj")]
+const DEFAULT_COLOR: [f32; 3] = [0.8, 0.8, 0.8];
+
+impl Mesh {
+    /// Loads the triangles of every object of the Wavefront OBJ file at `path` into a single mesh.
+    /// Faces with more than three vertices are triangulated. Vertices are colored with the vertex colors
+    /// of the file, or the diffuse color of their material if the `.mtl` file referenced by the OBJ file
+    /// can be read. Missing normals are computed from the faces.
+    ///
+    /// Models are usually far larger or smaller than the view of the default
+    /// [`Camera`](crate::Camera), fit them into it with [`Mesh::centered`].
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{Mesh, SceneState};
+    /// let mesh = Mesh::from_obj("teapot.obj").unwrap().centered();
+    /// let state = SceneState::new(mesh);
+    /// ```
+    #[cfg(feature = "obj")]
+    pub fn from_obj(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let options = tobj::LoadOptions {
+            single_index: true,
+            triangulate: true,
+            ..tobj::LoadOptions::default()
+        };
+        let (models, materials) = tobj::load_obj(path.as_ref(), &options)?;
+        let materials = materials.unwrap_or_default();
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for model in models {
+            let mesh = model.mesh;
+            let color = mesh
+                .material_id
+                .and_then(|id| materials.get(id)?.diffuse)
+                .unwrap_or(DEFAULT_COLOR);
+            let start = vertices.len() as u32;
+            for (index, position) in mesh.positions.chunks_exact(3).enumerate() {
+                let attribute = |values: &[f32], default| {
+                    values
+                        .get(index * 3..index * 3 + 3)
+                        .map_or(default, |value| [value[0], value[1], value[2]])
+                };
+                vertices.push(SceneVertex::new(
+                    [position[0], position[1], position[2]],
+                    attribute(&mesh.normals, [0.0; 3]),
+                    attribute(&mesh.vertex_color, color),
+                ));
+            }
+            let mesh_indices: Vec<u32> = mesh.indices.iter().map(|index| start + index).collect();
+            if mesh.normals.is_empty() {
+                compute_normals(&mut vertices, &mesh_indices);
+            }
+            indices.extend(mesh_indices);
+        }
+        Self::new(vertices, indices)
+    }
+
+    /// Loads the triangles of every mesh in the default scene of the glTF file at `path`, either a
+    /// `.gltf` file with its buffers or a binary `.glb` file, into a single mesh. The transforms of the
+    /// nodes are applied to the vertices. Vertices are colored with their first set of vertex colors,
+    /// multiplied with the base color of their material. Textures are ignored and missing normals are
+    /// computed from the faces.
+    ///
+    /// Models are usually far larger or smaller than the view of the default
+    /// [`Camera`](crate::Camera), fit them into it with [`Mesh::centered`].
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{Mesh, SceneState};
+    /// let mesh = Mesh::from_gltf("product.glb").unwrap().centered();
+    /// let state = SceneState::new(mesh);
+    /// ```
+    #[cfg(feature = "gltf")]
+    pub fn from_gltf(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let gltf::Gltf { document, blob } = gltf::Gltf::open(path)?;
+        let buffers = gltf::import_buffers(&document, path.parent(), blob)?;
+        let scene = document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+            .ok_or("the file doesn't contain a scene")?;
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for node in scene.nodes() {
+            load_gltf_node(&node, IDENTITY, &buffers, &mut vertices, &mut indices)?;
+        }
+        Self::new(vertices, indices)
+    }
+}
+
+/// Appends the triangles of `node` and its children, transformed into the space of the scene.
+#[cfg(feature = "gltf")]
+fn load_gltf_node(
+    node: &gltf::Node<'_>,
+    parent: [[f32; 4]; 4],
+    buffers: &[gltf::buffer::Data],
+    vertices: &mut Vec<SceneVertex>,
+    indices: &mut Vec<u32>,
+) -> Result<(), Box<dyn Error>> {
+    let transform = multiply(parent, node.transform().matrix());
+    if let Some(mesh) = node.mesh() {
+        for primitive in mesh.primitives() {
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                continue;
+            }
+            let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()].0));
+            let positions: Vec<[f32; 3]> = reader
+                .read_positions()
+                .ok_or("a primitive has no positions")?
+                .collect();
+            let normals: Option<Vec<[f32; 3]>> = reader.read_normals().map(Iterator::collect);
+            let colors: Option<Vec<[f32; 3]>> = reader
+                .read_colors(0)
+                .map(|colors| colors.into_rgb_f32().collect());
+            let [r, g, b, _] = primitive
+                .material()
+                .pbr_metallic_roughness()
+                .base_color_factor();
+            let start = vertices.len() as u32;
+            for (index, &position) in positions.iter().enumerate() {
+                let normal = normals.as_ref().map_or([0.0; 3], |normals| normals[index]);
+                let color = colors.as_ref().map_or([1.0; 3], |colors| colors[index]);
+                vertices.push(SceneVertex::new(
+                    transform_point(&transform, position),
+                    transform_normal(&transform, normal),
+                    [color[0] * r, color[1] * g, color[2] * b],
+                ));
+            }
+            let primitive_indices: Vec<u32> = match reader.read_indices() {
+                Some(read) => read.into_u32().map(|index| start + index).collect(),
+                None => (start..vertices.len() as u32).collect(),
+            };
+            if let Some(index) = primitive_indices
+                .iter()
+                .find(|&&index| index as usize >= vertices.len())
+            {
+                return Err(format!("the index {} is out of bounds", index - start).into());
+            }
+            if normals.is_none() {
+                compute_normals(vertices, &primitive_indices);
+            }
+            indices.extend(primitive_indices);
+        }
+    }
+    for child in node.children() {
+        load_gltf_node(&child, transform, buffers, vertices, indices)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "gltf")]
+fn transform_point(matrix: &[[f32; 4]; 4], point: [f32; 3]) -> [f32; 3] {
+    std::array::from_fn(|row| {
+        matrix[0][row] * point[0]
+            + matrix[1][row] * point[1]
+            + matrix[2][row] * point[2]
+            + matrix[3][row]
+    })
+}
+
+/// Transforms `normal` with the cofactor matrix of the upper 3x3 part of `matrix`, which keeps normals
+/// perpendicular to the surface under non-uniform scaling.
+#[cfg(feature = "gltf")]
+fn transform_normal(matrix: &[[f32; 4]; 4], normal: [f32; 3]) -> [f32; 3] {
+    let [x, y, z] =
+        [0, 1, 2].map(|column| [matrix[column][0], matrix[column][1], matrix[column][2]]);
+    let cofactor = [cross(y, z), cross(z, x), cross(x, y)];
+    let sign = dot(x, cofactor[0]).signum();
+    normalize(std::array::from_fn(|row| {
+        (cofactor[0][row] * normal[0] + cofactor[1][row] * normal[1] + cofactor[2][row] * normal[2])
+            * sign
+    }))
+}
+
+/// Sets the normals of the vertices referenced by `indices` to the average of the normals of the
+/// triangles sharing them, weighted by their area.
+fn compute_normals(vertices: &mut [SceneVertex], indices: &[u32]) {
+    for &index in indices {
+        vertices[index as usize].normal = [0.0; 3];
+    }
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|corner| vertices[triangle[corner] as usize].position);
+        let face = cross(sub(b, a), sub(c, a));
+        for &index in triangle {
+            let normal = &mut vertices[index as usize].normal;
+            *normal = std::array::from_fn(|axis| normal[axis] + face[axis]);
+        }
+    }
+    for &index in indices {
+        let vertex = &mut vertices[index as usize];
+        vertex.normal = normalize(vertex.normal);
+    }
+}
@@ -0,0 +1,178 @@
+use std::error::Error;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use midir::{Ignore, MidiInput, MidiInputConnection};
+
+use crate::provider::UniformProvider;
+use crate::state::Uniforms;
+
+/// The kind of MIDI message a uniform of a [`MidiMapping`] follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MidiControl {
+    ControlChange(u8),
+    Note(u8),
+}
+
+/// [`MidiMapping`] declares which MIDI controls drive which uniforms of a [`MidiUniforms`] provider.
+/// Values are normalized to `f32`s between `0.0` and `1.0`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MidiMapping {
+    controls: Vec<(MidiControl, String)>,
+    channel: Option<u8>,
+}
+
+impl MidiMapping {
+    /// Creates a new, empty [`MidiMapping`] listening on all channels. Equivalent to
+    /// [`MidiMapping::default()`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the uniform called `uniform` to the value of the control change `controller`, e.g. a knob
+    /// or fader.
+    #[must_use]
+    pub fn control_change(mut self, controller: u8, uniform: impl Into<String>) -> Self {
+        self.controls
+            .push((MidiControl::ControlChange(controller), uniform.into()));
+        self
+    }
+
+    /// Sets the uniform called `uniform` to the velocity of `note` while it is held and to `0.0` once
+    /// it is released, e.g. for drum pads.
+    #[must_use]
+    pub fn note(mut self, note: u8, uniform: impl Into<String>) -> Self {
+        self.controls
+            .push((MidiControl::Note(note), uniform.into()));
+        self
+    }
+
+    /// Only listens to messages on `channel`, counted from `0` to `15` as they are sent on the wire.
+    #[must_use]
+    pub fn channel(mut self, channel: u8) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    /// Stores the value `message` carries for the controls in `values`, which has one slot per control.
+    pub(crate) fn handle(&self, message: &[u8], values: &[AtomicU32]) {
+        let [status, data, value, ..] = *message else {
+            return;
+        };
+        if self.channel.is_some_and(|channel| channel != status & 0x0f) {
+            return;
+        }
+        let (control, value) = match status & 0xf0 {
+            0xb0 => (MidiControl::ControlChange(data), value),
+            0x90 => (MidiControl::Note(data), value),
+            0x80 => (MidiControl::Note(data), 0),
+            _ => return,
+        };
+        let value = f32::from(value.min(127)) / 127.0;
+        for (slot, (mapped, _)) in values.iter().zip(&self.controls) {
+            if *mapped == control {
+                slot.store(value.to_bits(), Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// [`MidiUniforms`] is a [`UniformProvider`] setting uniforms from a MIDI controller, so live
+/// performers can play terminal visuals from hardware. Which controls drive which uniforms is declared
+/// by a [`MidiMapping`].
+///
+/// Messages are received on a thread of the MIDI driver and stored right away. The provider only
+/// copies the latest values into the uniform buffer before the next execution, so turning a knob never
+/// recompiles or rebuilds anything.
+///
+/// ```rust,no_run
+/// # use tui_shader::{MidiMapping, MidiUniforms, ShaderCanvasState, WgslShader};
+/// let mut state = ShaderCanvasState::new(WgslShader::Path("visuals.wgsl")).unwrap();
+/// let mapping = MidiMapping::new()
+///     .control_change(74, "cutoff")
+///     .control_change(71, "resonance")
+///     .note(36, "kick");
+/// state.add_provider(MidiUniforms::connect(mapping, Some("nanoKONTROL")).unwrap());
+/// ```
+pub struct MidiUniforms {
+    uniforms: Vec<String>,
+    values: Arc<[AtomicU32]>,
+    port: String,
+    _connection: MidiInputConnection<()>,
+}
+
+impl MidiUniforms {
+    /// Connects to the first MIDI input port whose name contains `port`, or to the first port if `port`
+    /// is `None`. Fails if there is no such port or the MIDI driver can't be opened.
+    pub fn connect(mapping: MidiMapping, port: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        let mut input = MidiInput::new("tui-shader")?;
+        input.ignore(Ignore::All);
+        let ports = input.ports();
+        let port = ports
+            .iter()
+            .find(|candidate| {
+                port.is_none_or(|port| {
+                    input
+                        .port_name(candidate)
+                        .is_ok_and(|name| name.contains(port))
+                })
+            })
+            .ok_or("no matching MIDI input port")?;
+        let port_name = input.port_name(port)?;
+        let values: Arc<[AtomicU32]> = mapping
+            .controls
+            .iter()
+            .map(|_| AtomicU32::new(0.0_f32.to_bits()))
+            .collect();
+        let uniforms = mapping
+            .controls
+            .iter()
+            .map(|(_, uniform)| uniform.clone())
+            .collect();
+        let received = Arc::clone(&values);
+        let connection = input.connect(
+            port,
+            "tui-shader",
+            move |_, message, _| mapping.handle(message, &received),
+            (),
+        )?;
+        Ok(Self {
+            uniforms,
+            values,
+            port: port_name,
+            _connection: connection,
+        })
+    }
+
+    /// The names of the MIDI input ports, to let users pick their controller.
+    pub fn ports() -> Result<Vec<String>, Box<dyn Error>> {
+        let input = MidiInput::new("tui-shader")?;
+        Ok(input
+            .ports()
+            .iter()
+            .filter_map(|port| input.port_name(port).ok())
+            .collect())
+    }
+
+    /// The name of the port the provider is connected to.
+    pub fn port(&self) -> &str {
+        &self.port
+    }
+}
+
+impl std::fmt::Debug for MidiUniforms {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MidiUniforms")
+            .field("uniforms", &self.uniforms)
+            .field("port", &self.port)
+            .finish_non_exhaustive()
+    }
+}
+
+impl UniformProvider for MidiUniforms {
+    fn provide(&mut self, uniforms: &mut Uniforms<'_>) {
+        for (name, value) in self.uniforms.iter().zip(self.values.iter()) {
+            uniforms.set(name, f32::from_bits(value.load(Ordering::Relaxed)));
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::bindings::{BindingSlot, BindingsBuilder};
+
+/// The blue noise, one byte per pixel, precomputed with the void-and-cluster method.
+const BLUE_NOISE_256: &[u8; 256 * 256] = include_bytes!("textures/blue_noise_256.bin");
+
+/// The width and height of the built-in textures in pixels.
+const SIZE: u32 = 256;
+
+/// The number of lattice cells across the texture in the red, green, blue and alpha channels of the
+/// value and Perlin noise textures.
+const CELLS: [u32; 4] = [8, 16, 32, 64];
+
+/// A tiling noise texture which ships with `tui-shader`, bound with
+/// [`BindingsBuilder::builtin_texture`]. Sampling a texture is much cheaper than computing good noise
+/// for every fragment, and blue noise can't be computed in a shader at all.
+///
+/// The value and Perlin noise textures hold four layers of noise, one per channel, with 8, 16, 32 and
+/// 64 lattice cells across the texture. Summing the channels with halving weights gives fractal noise.
+///
+/// ```rust,no_run
+/// # use tui_shader::{BindingsBuilder, BuiltinTexture, ShaderCanvasState, WgslShader};
+/// let bindings = BindingsBuilder::new().builtin_texture((1, 0), BuiltinTexture::BlueNoise256);
+/// let state = ShaderCanvasState::new_with_bindings(WgslShader::Path("dither.wgsl"), None, bindings)
+///     .unwrap();
+/// ```
+///
+/// ```wgsl
+/// @group(1) @binding(0) var blue_noise: texture_2d<f32>;
+///
+/// @fragment
+/// fn main(@builtin(position) position: vec4<f32>, @location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+///     let threshold = textureLoad(blue_noise, vec2<u32>(position.xy) % 256u, 0).r;
+///     return vec4<f32>(vec3<f32>(step(threshold, uv.x)), 1.0);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinTexture {
+    /// 256x256 smoothly interpolated random values.
+    ValueNoise256,
+
+    /// 256x256 gradient noise, with fewer blocky artifacts than [`BuiltinTexture::ValueNoise256`].
+    PerlinNoise256,
+
+    /// 256x256 blue noise in the color channels, with opaque alpha. Every value occurs equally often
+    /// and neighboring pixels differ as much as possible, which makes it ideal for dithering and for
+    /// offsetting ray marching steps.
+    BlueNoise256,
+}
+
+impl BuiltinTexture {
+    /// The width and height of the texture in pixels.
+    pub fn size(self) -> u32 {
+        SIZE
+    }
+
+    /// The tightly packed RGBA bytes of the texture.
+    pub fn data(self) -> Vec<u8> {
+        match self {
+            Self::ValueNoise256 => lattice_noise(value_noise),
+            Self::PerlinNoise256 => lattice_noise(perlin_noise),
+            Self::BlueNoise256 => BLUE_NOISE_256
+                .iter()
+                .flat_map(|&value| [value, value, value, 255])
+                .collect(),
+        }
+    }
+}
+
+impl BindingsBuilder {
+    /// Declares a `texture_2d<f32>` initialized with a [`BuiltinTexture`].
+    #[must_use]
+    pub fn builtin_texture(self, slot: impl Into<BindingSlot>, texture: BuiltinTexture) -> Self {
+        self.texture_with_data(slot, texture.size(), texture.size(), &texture.data())
+    }
+}
+
+/// Fills every channel with `noise`, using the lattice sizes of [`CELLS`] and one seed per channel.
+fn lattice_noise(noise: fn(f32, f32, u32, u32) -> f32) -> Vec<u8> {
+    let mut data = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            for (seed, cells) in CELLS.into_iter().enumerate() {
+                let scale = cells as f32 / SIZE as f32;
+                let value = noise(x as f32 * scale, y as f32 * scale, cells, seed as u32);
+                data.push((value.clamp(0.0, 1.0) * 255.0).round() as u8);
+            }
+        }
+    }
+    data
+}
+
+/// Value noise between `0.0` and `1.0` at `x`, `y`, repeating every `period` cells.
+fn value_noise(x: f32, y: f32, period: u32, seed: u32) -> f32 {
+    let corner = |dx: u32, dy: u32| {
+        let (cx, cy) = lattice_cell(x, y, dx, dy, period);
+        hash(cx, cy, seed) as f32 / u32::MAX as f32
+    };
+    let (u, v) = (smoothstep(x.fract()), smoothstep(y.fract()));
+    lerp(
+        lerp(corner(0, 0), corner(1, 0), u),
+        lerp(corner(0, 1), corner(1, 1), u),
+        v,
+    )
+}
+
+/// Perlin noise mapped to `0.0` and `1.0` at `x`, `y`, repeating every `period` cells.
+fn perlin_noise(x: f32, y: f32, period: u32, seed: u32) -> f32 {
+    let (fx, fy) = (x.fract(), y.fract());
+    let corner = |dx: u32, dy: u32| {
+        let (cx, cy) = lattice_cell(x, y, dx, dy, period);
+        let angle = hash(cx, cy, seed) as f32 / u32::MAX as f32 * std::f32::consts::TAU;
+        angle.cos() * (fx - dx as f32) + angle.sin() * (fy - dy as f32)
+    };
+    let (u, v) = (quintic(fx), quintic(fy));
+    let value = lerp(
+        lerp(corner(0, 0), corner(1, 0), u),
+        lerp(corner(0, 1), corner(1, 1), u),
+        v,
+    );
+    // Gradient noise in 2D stays within ±sqrt(0.5).
+    value * std::f32::consts::FRAC_1_SQRT_2 + 0.5
+}
+
+/// The lattice cell `dx`, `dy` cells away from the one containing `x`, `y`, wrapped to the period.
+fn lattice_cell(x: f32, y: f32, dx: u32, dy: u32, period: u32) -> (u32, u32) {
+    ((x as u32 + dx) % period, (y as u32 + dy) % period)
+}
+
+/// An integer hash of a lattice cell, based on the finalizer of MurmurHash3.
+fn hash(x: u32, y: u32, seed: u32) -> u32 {
+    let mut h =
+        x.wrapping_mul(0x8da6_b343) ^ y.wrapping_mul(0xd816_3841) ^ seed.wrapping_mul(0xcb1a_b31f);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn quintic(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
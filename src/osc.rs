@@ -0,0 +1,178 @@
+use std::error::Error;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+use crate::provider::UniformProvider;
+use crate::state::Uniforms;
+use crate::uniforms::UniformValue;
+
+/// The largest packet read from the socket, the limit of a UDP datagram.
+const MAX_PACKET_SIZE: usize = 65_507;
+
+/// [`OscUniforms`] is a [`UniformProvider`] listening for [Open Sound Control](https://opensoundcontrol.stanford.edu)
+/// messages on a UDP socket and writing their arguments to uniforms, so live-coding environments like
+/// SuperCollider or TidalCycles can drive the visuals of a terminal.
+///
+/// Addresses are mapped to uniforms with [`OscUniforms::route`], or by the part after a
+/// [`prefix`](OscUniforms::prefix), so `/shader/speed` sets the uniform `speed`. Numeric and boolean
+/// arguments are converted to floats, one argument sets an `f32` and two to four set the vector of the
+/// same length. Other arguments are skipped and bundles are unpacked, ignoring their time tags.
+///
+/// The socket is read before every execution of the shader and the latest value of every uniform is
+/// kept, so the values survive a change of shader.
+///
+/// ```rust,no_run
+/// # use tui_shader::{OscUniforms, ShaderCanvasState, WgslShader};
+/// let mut state = ShaderCanvasState::new(WgslShader::Path("visuals.wgsl")).unwrap();
+/// let osc = OscUniforms::bind("0.0.0.0:57130")
+///     .unwrap()
+///     .prefix("/shader")
+///     .route("/tidal/cps", "cps");
+/// state.add_provider(osc);
+/// ```
+#[derive(Debug)]
+pub struct OscUniforms {
+    socket: UdpSocket,
+    routes: Vec<(String, String)>,
+    prefix: Option<String>,
+    values: Vec<(String, UniformValue)>,
+}
+
+impl OscUniforms {
+    /// Creates a new [`OscUniforms`] listening on `address`. Fails if the socket can't be bound.
+    pub fn bind(address: impl ToSocketAddrs) -> Result<Self, Box<dyn Error>> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            routes: Vec::new(),
+            prefix: None,
+            values: Vec::new(),
+        })
+    }
+
+    /// Writes the arguments of messages sent to `address` to the uniform called `uniform`.
+    #[must_use]
+    pub fn route(mut self, address: impl Into<String>, uniform: impl Into<String>) -> Self {
+        self.routes.push((address.into(), uniform.into()));
+        self
+    }
+
+    /// Writes the arguments of messages sent to `<prefix>/<name>` to the uniform called `name`.
+    #[must_use]
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// The address the socket is bound to, e.g. to find the port picked when binding to port `0`.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// The uniform `address` is mapped to, if any.
+    fn uniform<'a>(&'a self, address: &'a str) -> Option<&'a str> {
+        if let Some((_, uniform)) = self.routes.iter().find(|(route, _)| route == address) {
+            return Some(uniform);
+        }
+        let name = address
+            .strip_prefix(self.prefix.as_deref()?)?
+            .strip_prefix('/')?;
+        (!name.is_empty() && !name.contains('/')).then_some(name)
+    }
+
+    /// Reads all packets waiting on the socket and keeps the values of the messages they contain.
+    fn receive(&mut self) {
+        let mut packet = vec![0; MAX_PACKET_SIZE];
+        let mut messages = Vec::new();
+        while let Ok(length) = self.socket.recv(&mut packet) {
+            parse_packet(&packet[..length], &mut messages);
+        }
+        for (address, arguments) in messages {
+            let Some(uniform) = self.uniform(&address).map(str::to_owned) else {
+                continue;
+            };
+            let value = match arguments[..] {
+                [x] => UniformValue::Float(x),
+                [x, y] => UniformValue::Vec2([x, y]),
+                [x, y, z] => UniformValue::Vec3([x, y, z]),
+                [x, y, z, w, ..] => UniformValue::Vec4([x, y, z, w]),
+                [] => continue,
+            };
+            match self.values.iter_mut().find(|(name, _)| *name == uniform) {
+                Some((_, previous)) => *previous = value,
+                None => self.values.push((uniform, value)),
+            }
+        }
+    }
+}
+
+impl UniformProvider for OscUniforms {
+    fn provide(&mut self, uniforms: &mut Uniforms<'_>) {
+        self.receive();
+        for (name, value) in &self.values {
+            uniforms.set(name, *value);
+        }
+    }
+}
+
+/// Appends the address and numeric arguments of the messages in `packet` to `messages`. Malformed
+/// packets are dropped.
+fn parse_packet(packet: &[u8], messages: &mut Vec<(String, Vec<f32>)>) {
+    if let Some(mut elements) = packet.strip_prefix(b"#bundle\0") {
+        // Skips the time tag.
+        elements = elements.get(8..).unwrap_or_default();
+        while let Some((size, rest)) = split_u32(elements) {
+            let Some(element) = rest.get(..size as usize) else {
+                return;
+            };
+            parse_packet(element, messages);
+            elements = &rest[size as usize..];
+        }
+    } else if let Some(message) = parse_message(packet) {
+        messages.push(message);
+    }
+}
+
+fn parse_message(packet: &[u8]) -> Option<(String, Vec<f32>)> {
+    let (address, rest) = split_string(packet)?;
+    let (tags, mut rest) = split_string(rest)?;
+    let mut arguments = Vec::new();
+    for tag in tags.strip_prefix(',')?.chars() {
+        let value;
+        (value, rest) = match tag {
+            'f' => split_u32(rest).map(|(bits, rest)| (Some(f32::from_bits(bits)), rest))?,
+            'i' => split_u32(rest).map(|(bits, rest)| (Some(bits as i32 as f32), rest))?,
+            'd' => split_u64(rest).map(|(bits, rest)| (Some(f64::from_bits(bits) as f32), rest))?,
+            'h' => split_u64(rest).map(|(bits, rest)| (Some(bits as i64 as f32), rest))?,
+            'T' => (Some(1.0), rest),
+            'F' => (Some(0.0), rest),
+            'N' | 'I' => (None, rest),
+            's' | 'S' => (None, split_string(rest)?.1),
+            'b' => {
+                let (size, rest) = split_u32(rest)?;
+                (None, rest.get((size as usize).next_multiple_of(4)..)?)
+            }
+            _ => return None,
+        };
+        arguments.extend(value);
+    }
+    Some((address.to_owned(), arguments))
+}
+
+/// Splits a string padded with nul bytes to a multiple of 4 bytes off `data`.
+fn split_string(data: &[u8]) -> Option<(&str, &[u8])> {
+    let length = data.iter().position(|&byte| byte == 0)?;
+    let string = std::str::from_utf8(&data[..length]).ok()?;
+    Some((string, data.get((length + 1).next_multiple_of(4)..)?))
+}
+
+fn split_u32(data: &[u8]) -> Option<(u32, &[u8])> {
+    let (bytes, rest) = data.split_first_chunk()?;
+    Some((u32::from_be_bytes(*bytes), rest))
+}
+
+fn split_u64(data: &[u8]) -> Option<(u64, &[u8])> {
+    let (bytes, rest) = data.split_first_chunk()?;
+    Some((u64::from_be_bytes(*bytes), rest))
+}
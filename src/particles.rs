@@ -0,0 +1,569 @@
+use bytemuck::{Pod, Zeroable};
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::{Position, Rect};
+use ratatui_core::style::{Color, Style};
+use ratatui_core::widgets::StatefulWidget;
+use std::time::Instant;
+
+use crate::clock::ShaderClock;
+use crate::context::ShaderContext;
+use crate::error::TuiShaderError;
+use crate::gpu::{RenderTarget, create_input_buffer, storage_layout_entry, uniform_layout_entry};
+use crate::style::{CharacterRule, StyleRule};
+use crate::{Pixel, Sample, color_to_vec4, row_padding};
+
+const COMPUTE_SHADER_DESCRIPTOR: wgpu::ShaderModuleDescriptor =
+    wgpu::include_wgsl!("shaders/particles_compute.wgsl");
+const RENDER_SHADER_DESCRIPTOR: wgpu::ShaderModuleDescriptor =
+    wgpu::include_wgsl!("shaders/particles_render.wgsl");
+const PARTICLE_SIZE: u64 = 32;
+const WORKGROUP_SIZE: u32 = 64;
+const MAX_DELTA_TIME: f32 = 0.1;
+
+/// Parameters of a [`ParticleCanvasState`]. All positions, velocities and accelerations are given in
+/// normalized coordinates, where `(0.0, 0.0)` is the top left and `(1.0, 1.0)` the bottom right corner
+/// of the rendered area.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParticleConfig {
+    /// Maximum number of particles alive at the same time. Limited to the number of particles the
+    /// storage buffer of the device can hold.
+    pub max_particles: u32,
+
+    /// Number of particles spawned per second.
+    pub spawn_rate: f32,
+
+    /// Lifetime of a particle in seconds. Each particle lives between half and the full lifetime.
+    pub lifetime: f32,
+
+    /// Acceleration applied to every particle.
+    pub gravity: (f32, f32),
+
+    /// Constant drift added to the velocity of every particle.
+    pub wind: (f32, f32),
+
+    /// Velocity of a newly spawned particle.
+    pub velocity: (f32, f32),
+
+    /// Random deviation added to [`ParticleConfig::velocity`] on spawn.
+    pub spread: f32,
+
+    /// Top left corner of the area in which particles spawn.
+    pub emitter_min: (f32, f32),
+
+    /// Bottom right corner of the area in which particles spawn.
+    pub emitter_max: (f32, f32),
+
+    /// Color of the particles. Particles fade out over their lifetime.
+    pub color: [u8; 3],
+}
+
+impl ParticleConfig {
+    /// Particles falling slowly from the top edge, drifting a little to the side.
+    pub fn snow() -> Self {
+        Self::default()
+    }
+
+    /// Fast particles falling straight down from the top edge.
+    pub fn rain() -> Self {
+        Self {
+            spawn_rate: 120.0,
+            lifetime: 2.0,
+            gravity: (0.0, 0.8),
+            wind: (0.02, 0.0),
+            velocity: (0.0, 0.8),
+            spread: 0.02,
+            color: [110, 150, 255],
+            ..Self::default()
+        }
+    }
+
+    /// Particles bursting upwards from the bottom center and falling back down.
+    pub fn sparks() -> Self {
+        Self {
+            spawn_rate: 80.0,
+            lifetime: 1.5,
+            gravity: (0.0, 1.2),
+            wind: (0.0, 0.0),
+            velocity: (0.0, -1.0),
+            spread: 0.4,
+            emitter_min: (0.45, 0.95),
+            emitter_max: (0.55, 1.0),
+            color: [255, 170, 40],
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for ParticleConfig {
+    /// Returns the [`ParticleConfig::snow`] preset.
+    fn default() -> Self {
+        Self {
+            max_particles: 1024,
+            spawn_rate: 30.0,
+            lifetime: 8.0,
+            gravity: (0.0, 0.02),
+            wind: (0.03, 0.0),
+            velocity: (0.0, 0.05),
+            spread: 0.02,
+            emitter_min: (0.0, 0.0),
+            emitter_max: (1.0, 0.0),
+            color: [255, 255, 255],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct ParticleParams {
+    gravity: [f32; 2],
+    wind: [f32; 2],
+    velocity: [f32; 2],
+    emitter_min: [f32; 2],
+    emitter_max: [f32; 2],
+    delta_time: f32,
+    time: f32,
+    lifetime: f32,
+    spread: f32,
+    spawn_budget: u32,
+    particle_count: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct RenderParams {
+    color: [f32; 4],
+    resolution: [f32; 2],
+    padding: [f32; 2],
+}
+
+/// [`ParticleCanvasState`] simulates a particle system on the GPU. A compute pass updates the particles
+/// stored in a storage buffer and a render pass draws every particle as a single cell.
+#[derive(Debug, Clone)]
+pub struct ParticleCanvasState {
+    target: RenderTarget,
+    compute_pipeline: wgpu::ComputePipeline,
+    render_pipeline: wgpu::RenderPipeline,
+    particle_buffer: wgpu::Buffer,
+    params_buffer: wgpu::Buffer,
+    spawned_buffer: wgpu::Buffer,
+    render_params_buffer: wgpu::Buffer,
+    compute_bind_group: wgpu::BindGroup,
+    render_bind_group: wgpu::BindGroup,
+    config: ParticleConfig,
+    clock: ShaderClock,
+    last_time: Option<f32>,
+    spawn_accumulator: f32,
+}
+
+impl ParticleCanvasState {
+    /// Creates a new [`ParticleCanvasState`] with the given [`ParticleConfig`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if no GPU device can be created, see [`ParticleCanvasState::try_new`].
+    pub fn new(config: ParticleConfig) -> Self {
+        Self::try_new(config).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Creates a new [`ParticleCanvasState`] with the given [`ParticleConfig`], failing with
+    /// [`TuiShaderError::NoBackend`] or [`TuiShaderError::NoDevice`] if no GPU device can be created.
+    /// [`ParticleConfig::max_particles`] is lowered to the number of particles the device can hold and
+    /// simulate in a single pass.
+    pub fn try_new(mut config: ParticleConfig) -> Result<Self, TuiShaderError> {
+        let target = RenderTarget::new()?;
+        let device = target.device();
+        let limits = device.limits();
+        let buffer_size =
+            u64::from(limits.max_storage_buffer_binding_size).min(limits.max_buffer_size);
+        config.max_particles = config
+            .max_particles
+            .min(u32::try_from(buffer_size / PARTICLE_SIZE).unwrap_or(u32::MAX))
+            .min(
+                limits
+                    .max_compute_workgroups_per_dimension
+                    .saturating_mul(WORKGROUP_SIZE),
+            );
+        let compute_shader = device.create_shader_module(COMPUTE_SHADER_DESCRIPTOR);
+        let render_shader = device.create_shader_module(RENDER_SHADER_DESCRIPTOR);
+        let particle_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: config.max_particles.max(1) as u64 * PARTICLE_SIZE,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX,
+            mapped_at_creation: false,
+        });
+        let params_buffer = create_input_buffer(device, ParticleParams::zeroed());
+        let spawned_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 4,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let render_params_buffer = create_input_buffer(device, RenderParams::zeroed());
+
+        let compute_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &[
+                    storage_layout_entry(0, wgpu::ShaderStages::COMPUTE, false),
+                    uniform_layout_entry(1, wgpu::ShaderStages::COMPUTE),
+                    storage_layout_entry(2, wgpu::ShaderStages::COMPUTE, false),
+                ],
+            });
+        let compute_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &compute_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: particle_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: spawned_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        let compute_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[&compute_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: Some(&compute_pipeline_layout),
+            module: &compute_shader,
+            entry_point: Some("main"),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        let render_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &[uniform_layout_entry(0, wgpu::ShaderStages::VERTEX_FRAGMENT)],
+            });
+        let render_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &render_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: render_params_buffer.as_entire_binding(),
+            }],
+        });
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[&render_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &render_shader,
+                entry_point: Some("vertex"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: PARTICLE_SIZE,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    // The position and the age and lifetime of a `Particle`, skipping its velocity.
+                    attributes: &[
+                        wgpu::VertexAttribute {
+                            format: wgpu::VertexFormat::Float32x2,
+                            offset: 0,
+                            shader_location: 0,
+                        },
+                        wgpu::VertexAttribute {
+                            format: wgpu::VertexFormat::Float32x2,
+                            offset: 16,
+                            shader_location: 1,
+                        },
+                    ],
+                }],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &render_shader,
+                entry_point: Some("fragment"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::PointList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Ok(ParticleCanvasState {
+            target,
+            compute_pipeline,
+            render_pipeline,
+            particle_buffer,
+            params_buffer,
+            spawned_buffer,
+            render_params_buffer,
+            compute_bind_group,
+            render_bind_group,
+            config,
+            clock: ShaderClock::new(),
+            last_time: None,
+            spawn_accumulator: 0.0,
+        })
+    }
+
+    pub(crate) fn execute(&mut self, ctx: ShaderContext) -> Vec<Pixel> {
+        let width = ctx.width();
+        let height = ctx.height();
+        let time = ctx.time[0];
+        let delta_time = self.last_time.map_or(0.0, |last_time| {
+            (time - last_time).clamp(0.0, MAX_DELTA_TIME)
+        });
+        self.last_time = Some(time);
+        self.spawn_accumulator += self.config.spawn_rate.max(0.0) * delta_time;
+        let spawn_budget = self.spawn_accumulator.floor();
+        self.spawn_accumulator -= spawn_budget;
+
+        let config = &self.config;
+        let params = ParticleParams {
+            gravity: config.gravity.into(),
+            wind: config.wind.into(),
+            velocity: config.velocity.into(),
+            emitter_min: config.emitter_min.into(),
+            emitter_max: config.emitter_max.into(),
+            delta_time,
+            time,
+            lifetime: config.lifetime,
+            spread: config.spread,
+            spawn_budget: spawn_budget as u32,
+            particle_count: config.max_particles,
+        };
+        let render_params = RenderParams {
+            color: color_to_vec4(config.color),
+            resolution: [width as f32, height as f32],
+            padding: [0.0; 2],
+        };
+        self.target
+            .execute(width, height, |_, queue, command_encoder, texture_view| {
+                queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[params]));
+                queue.write_buffer(&self.spawned_buffer, 0, bytemuck::cast_slice(&[0u32]));
+                queue.write_buffer(
+                    &self.render_params_buffer,
+                    0,
+                    bytemuck::cast_slice(&[render_params]),
+                );
+                {
+                    let mut compute_pass =
+                        command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                            label: None,
+                            timestamp_writes: None,
+                        });
+                    compute_pass.set_pipeline(&self.compute_pipeline);
+                    compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
+                    compute_pass.dispatch_workgroups(
+                        config.max_particles.div_ceil(WORKGROUP_SIZE),
+                        1,
+                        1,
+                    );
+                }
+                let mut render_pass =
+                    command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: None,
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            view: texture_view,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                                store: wgpu::StoreOp::Store,
+                            },
+                            depth_slice: None,
+                        })],
+                        depth_stencil_attachment: None,
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                    });
+                render_pass.set_pipeline(&self.render_pipeline);
+                render_pass.set_bind_group(0, &self.render_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, self.particle_buffer.slice(..));
+                render_pass.draw(0..config.max_particles, 0..1);
+            })
+    }
+
+    /// Sets the number of particles spawned per second.
+    pub fn set_spawn_rate(&mut self, spawn_rate: f32) {
+        self.config.spawn_rate = spawn_rate;
+    }
+
+    /// Sets the acceleration applied to every particle.
+    pub fn set_gravity(&mut self, x: f32, y: f32) {
+        self.config.gravity = (x, y);
+    }
+
+    /// Sets the constant drift added to the velocity of every particle.
+    pub fn set_wind(&mut self, x: f32, y: f32) {
+        self.config.wind = (x, y);
+    }
+
+    /// Sets the lifetime of newly spawned particles in seconds.
+    pub fn set_lifetime(&mut self, lifetime: f32) {
+        self.config.lifetime = lifetime;
+    }
+
+    /// Sets the color of the particles.
+    pub fn set_color(&mut self, color: [u8; 3]) {
+        self.config.color = color;
+    }
+
+    /// Gets the [`ParticleConfig`] of the [`ParticleCanvasState`]. The maximum number of particles is fixed
+    /// on creation.
+    pub fn get_config(&self) -> ParticleConfig {
+        self.config
+    }
+
+    /// Gets the [`ParticleCanvasState`]'s [`Instant`].
+    pub fn get_instant(&self) -> Instant {
+        self.clock.get_instant()
+    }
+
+    /// Gets the [`ShaderClock`] driving the `time` input of the [`ParticleCanvasState`].
+    pub fn clock(&self) -> &ShaderClock {
+        &self.clock
+    }
+
+    /// Gets a mutable reference to the [`ShaderClock`], e.g. to pause and resume the animation.
+    pub fn clock_mut(&mut self) -> &mut ShaderClock {
+        &mut self.clock
+    }
+
+    /// Registers `callback` to receive the errors of simulating and drawing the particles on the GPU, like
+    /// [`ShaderCanvasState::on_error`](crate::ShaderCanvasState::on_error), instead of panicking inside
+    /// `terminal.draw`. A failed frame draws no particles, leaving the cells below untouched.
+    pub fn on_error(&mut self, callback: fn(TuiShaderError)) {
+        self.target.on_error(callback);
+    }
+}
+
+impl Default for ParticleCanvasState {
+    fn default() -> Self {
+        Self::new(ParticleConfig::default())
+    }
+}
+
+/// [`ParticleCanvas`] draws the particles of a [`ParticleCanvasState`] on top of whatever has already been
+/// rendered to the [`Buffer`]. Cells without a particle are left untouched.
+///
+/// ```rust,no_run
+/// # use tui_shader::{ParticleCanvas, ParticleCanvasState, ParticleConfig};
+/// let mut terminal = ratatui::init();
+/// let mut state = ParticleCanvasState::new(ParticleConfig::snow());
+/// terminal.draw(|frame| {
+///     frame.render_stateful_widget(ParticleCanvas::new(),
+///         frame.area(),
+///         &mut state);
+/// }).unwrap();
+/// ratatui::restore();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParticleCanvas {
+    pub character_rule: CharacterRule,
+    pub style_rule: StyleRule,
+}
+
+impl ParticleCanvas {
+    /// Creates a new instance of [`ParticleCanvas`]. Equivalent to [`ParticleCanvas::default()`]
+    pub fn new() -> Self {
+        Self {
+            character_rule: CharacterRule::Always('*'),
+            style_rule: StyleRule::ColorFg,
+        }
+    }
+
+    /// Applies a [`CharacterRule`] to a [`ParticleCanvas`].
+    #[must_use]
+    pub fn character_rule(mut self, character_rule: CharacterRule) -> Self {
+        self.character_rule = character_rule;
+        self
+    }
+
+    /// Applies a [`StyleRule`] to a [`ParticleCanvas`].
+    #[must_use]
+    pub fn style_rule(mut self, style_rule: StyleRule) -> Self {
+        self.style_rule = style_rule;
+        self
+    }
+}
+
+impl Default for ParticleCanvas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatefulWidget for ParticleCanvas {
+    type State = ParticleCanvasState;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(&self, area, buf, state);
+    }
+}
+
+impl StatefulWidget for &ParticleCanvas {
+    type State = ParticleCanvasState;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        if area.is_empty() {
+            return;
+        }
+        let width = area.width;
+        let height = area.height;
+        let time = state.clock().elapsed().as_secs_f32();
+        state.clock_mut().tick();
+        let ctx = ShaderContext::new(time, area);
+        let samples = state.execute(ctx);
+        let stride = (u32::from(width) + row_padding(width.into())) as usize;
+
+        for y in 0..height {
+            for x in 0..width {
+                let index = y as usize * stride + x as usize;
+                let value = samples[index];
+                if value[3] == 0 {
+                    continue;
+                }
+                let position = (x, y);
+                let uv = (x as f32 / width as f32, y as f32 / height as f32);
+                let character = match self.character_rule {
+                    CharacterRule::Always(character) => character,
+                    CharacterRule::Map(map) => map(Sample::new(value, position, uv)),
+                };
+                let color = Color::Rgb(value[0], value[1], value[2]);
+                let style = match self.style_rule {
+                    StyleRule::ColorFg => Style::new().fg(color),
+                    StyleRule::ColorBg => Style::new().bg(color),
+                    StyleRule::Map(map) => map(Sample::new(value, position, uv)),
+                };
+                let cell = buf
+                    .cell_mut(Position::new(x + area.x, y + area.y))
+                    .expect("unable to get cell");
+                cell.set_style(style);
+                cell.set_char(character);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "widget-ref")]
+impl ratatui::widgets::StatefulWidgetRef for ParticleCanvas {
+    type State = ParticleCanvasState;
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(self, area, buf, state);
+    }
+}
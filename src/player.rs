@@ -0,0 +1,169 @@
+use std::fmt;
+use std::time::Duration;
+
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+use crate::canvas::ShaderCanvas;
+use crate::state::ShaderCanvasState;
+
+/// Determines how a [`ShaderPlayer`] maps the time passed since it started to the `time` input of the shader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Playback {
+    /// Plays the shader for the given [`Duration`] once and holds the last frame afterwards.
+    Once(Duration),
+
+    /// Restarts the shader from the beginning every time the given [`Duration`] has passed.
+    Loop(Duration),
+
+    /// Plays the shader forwards for the given [`Duration`], then backwards, and repeats.
+    PingPong(Duration),
+}
+
+impl Playback {
+    /// The shader time after `elapsed` time has passed since playback started.
+    pub fn time(&self, elapsed: Duration) -> Duration {
+        match *self {
+            Playback::Once(duration) => elapsed.min(duration),
+            Playback::Loop(duration) if duration.is_zero() => Duration::ZERO,
+            Playback::Loop(duration) => {
+                Duration::from_nanos((elapsed.as_nanos() % duration.as_nanos()) as u64)
+            }
+            Playback::PingPong(duration) if duration.is_zero() => Duration::ZERO,
+            Playback::PingPong(duration) => {
+                let period = duration.as_nanos() * 2;
+                let position = elapsed.as_nanos() % period;
+                Duration::from_nanos(position.min(period - position) as u64)
+            }
+        }
+    }
+
+    /// Returns `true` if playback has ended after `elapsed` time. Only [`Playback::Once`] ever ends.
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        match *self {
+            Playback::Once(duration) => elapsed >= duration,
+            Playback::Loop(_) | Playback::PingPong(_) => false,
+        }
+    }
+}
+
+/// [`ShaderPlayer`] owns a [`ShaderCanvasState`] together with a [`Playback`] policy, so splash screens and
+/// transitions can be awaited instead of hand-counted with an [`Instant`](std::time::Instant). Render it
+/// with [`Frame::render_widget`](https://docs.rs/ratatui/latest/ratatui/struct.Frame.html#method.render_widget)
+/// by passing a mutable reference.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tui_shader::{Playback, ShaderCanvasState, ShaderPlayer, WgslShader};
+/// let mut terminal = ratatui::init();
+/// let state = ShaderCanvasState::new(WgslShader::Path("splash.wgsl")).unwrap();
+/// let mut player = ShaderPlayer::new(state, Playback::Once(Duration::from_secs(3)))
+///     .on_finish(|| println!("splash screen done"));
+/// while !player.is_finished() {
+///     terminal.draw(|frame| {
+///         frame.render_widget(&mut player, frame.area());
+///     }).unwrap();
+/// }
+/// ratatui::restore();
+/// ```
+pub struct ShaderPlayer {
+    state: ShaderCanvasState,
+    canvas: ShaderCanvas,
+    playback: Playback,
+    on_finish: Option<Box<dyn FnMut()>>,
+    notified: bool,
+}
+
+impl ShaderPlayer {
+    /// Creates a new [`ShaderPlayer`]. Playback starts immediately.
+    pub fn new(state: ShaderCanvasState, playback: Playback) -> Self {
+        let mut player = Self {
+            state,
+            canvas: ShaderCanvas::new(),
+            playback,
+            on_finish: None,
+            notified: false,
+        };
+        player.restart();
+        player
+    }
+
+    /// Applies a [`ShaderCanvas`] to a [`ShaderPlayer`], determining how the shader output is mapped to cells.
+    #[must_use]
+    pub fn canvas(mut self, canvas: ShaderCanvas) -> Self {
+        self.canvas = canvas;
+        self
+    }
+
+    /// Registers a callback that is called once, on the first render after playback has finished. To
+    /// notify another thread, send a message on a channel from within the callback.
+    #[must_use]
+    pub fn on_finish(mut self, on_finish: impl FnMut() + 'static) -> Self {
+        self.on_finish = Some(Box::new(on_finish));
+        self
+    }
+
+    /// Returns `true` if playback has ended.
+    pub fn is_finished(&self) -> bool {
+        self.playback.is_finished(self.state.clock().elapsed())
+    }
+
+    /// Restarts playback from the beginning, keeping the mode and source of the state's
+    /// [`ShaderClock`](crate::ShaderClock).
+    pub fn restart(&mut self) {
+        self.state.clock_mut().set_elapsed(Duration::ZERO);
+        self.notified = false;
+    }
+
+    /// Gets the [`Playback`] policy of the [`ShaderPlayer`].
+    pub fn get_playback(&self) -> Playback {
+        self.playback
+    }
+
+    /// Sets the [`Playback`] policy of the [`ShaderPlayer`] without restarting playback.
+    pub fn set_playback(&mut self, playback: Playback) {
+        self.playback = playback;
+        self.notified = self.is_finished();
+    }
+
+    /// Gets the [`ShaderCanvasState`] played by the [`ShaderPlayer`].
+    pub fn state(&self) -> &ShaderCanvasState {
+        &self.state
+    }
+
+    /// Gets a mutable reference to the [`ShaderCanvasState`], e.g. to update uniforms.
+    pub fn state_mut(&mut self) -> &mut ShaderCanvasState {
+        &mut self.state
+    }
+
+    /// Consumes the [`ShaderPlayer`] and returns the [`ShaderCanvasState`].
+    pub fn into_state(self) -> ShaderCanvasState {
+        self.state
+    }
+}
+
+impl fmt::Debug for ShaderPlayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShaderPlayer")
+            .field("state", &self.state)
+            .field("canvas", &self.canvas)
+            .field("playback", &self.playback)
+            .field("notified", &self.notified)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Widget for &mut ShaderPlayer {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let elapsed = self.state.clock().elapsed();
+        let time = self.playback.time(elapsed).as_secs_f32();
+        self.canvas.render_at(area, buf, &mut self.state, time);
+        if !self.notified && self.playback.is_finished(elapsed) {
+            self.notified = true;
+            if let Some(on_finish) = self.on_finish.as_mut() {
+                on_finish();
+            }
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::{StatefulWidget, Widget};
+
+use crate::canvas::ShaderCanvas;
+use crate::easing::Easing;
+use crate::state::ShaderCanvasState;
+use crate::util::WgslShader;
+
+/// Where the WGSL source of a [`PlaylistItem`] comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PlaylistSource {
+    Path(PathBuf),
+    Wgsl(String),
+}
+
+/// A shader of a [`ShaderPlaylist`], with optional settings overriding those of the playlist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistItem {
+    source: PlaylistSource,
+    duration: Option<Duration>,
+    transition: Option<(Duration, Easing)>,
+}
+
+impl PlaylistItem {
+    /// Creates a new [`PlaylistItem`] playing the WGSL shader at `path`. The file is read whenever the
+    /// item starts, so changes show up the next time it is played.
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        Self::new(PlaylistSource::Path(path.into()))
+    }
+
+    /// Creates a new [`PlaylistItem`] playing the WGSL `source`.
+    pub fn wgsl(source: impl Into<String>) -> Self {
+        Self::new(PlaylistSource::Wgsl(source.into()))
+    }
+
+    fn new(source: PlaylistSource) -> Self {
+        Self {
+            source,
+            duration: None,
+            transition: None,
+        }
+    }
+
+    /// Sets how long the item plays, instead of the [`duration`](ShaderPlaylist::duration) of the
+    /// playlist.
+    #[must_use]
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Sets the crossfade into the item, instead of the [`transition`](ShaderPlaylist::transition) of
+    /// the playlist.
+    #[must_use]
+    pub fn transition(mut self, duration: Duration, easing: Easing) -> Self {
+        self.transition = Some((duration, easing));
+        self
+    }
+
+    /// Compiles the shader of the item, after checking it with [`ShaderCanvasState::check`] so a broken
+    /// file is reported instead of failing inside wgpu.
+    fn load(&self) -> Result<ShaderCanvasState, Box<dyn Error>> {
+        let source = match &self.source {
+            PlaylistSource::Path(path) => std::fs::read_to_string(path)?,
+            PlaylistSource::Wgsl(source) => source.clone(),
+        };
+        ShaderCanvasState::check(&source)?;
+        ShaderCanvasState::new(WgslShader::Source(&source))
+    }
+}
+
+/// [`ShaderPlaylist`] cycles through a list of shaders, crossfading from one to the next, e.g. for a
+/// screensaver or a demo reel. Like a [`ShaderPlayer`](crate::ShaderPlayer), it owns the
+/// [`ShaderCanvasState`] and is rendered by passing a mutable reference.
+///
+/// The shaders are played in order and start over after the last one. With a
+/// [`directory`](ShaderPlaylist::directory), all `.wgsl` files in it are played in the order of their
+/// names, and the directory is scanned again whenever the playlist moves on, so files added while it
+/// runs join the rotation. Shaders which fail to load are skipped, see
+/// [`ShaderPlaylist::last_error`].
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tui_shader::{Easing, PlaylistItem, ShaderPlaylist};
+/// let mut terminal = ratatui::init();
+/// let mut playlist = ShaderPlaylist::new()
+///     .duration(Duration::from_secs(30))
+///     .transition(Duration::from_secs(2), Easing::CubicInOut)
+///     .directory("screensavers")
+///     .item(PlaylistItem::path("intro.wgsl").duration(Duration::from_secs(5)));
+/// terminal.draw(|frame| {
+///     frame.render_widget(&mut playlist, frame.area());
+/// }).unwrap();
+/// ratatui::restore();
+/// ```
+pub struct ShaderPlaylist {
+    items: Vec<PlaylistItem>,
+    directory: Option<PathBuf>,
+    duration: Duration,
+    transition: (Duration, Easing),
+    canvas: ShaderCanvas,
+    state: Option<ShaderCanvasState>,
+    /// The index of the item playing.
+    current: Option<usize>,
+    /// The time of the clock of the state at which the current item started.
+    started: f32,
+    last_error: Option<String>,
+}
+
+impl Default for ShaderPlaylist {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            directory: None,
+            duration: Duration::from_secs(10),
+            transition: (Duration::from_secs(1), Easing::Linear),
+            canvas: ShaderCanvas::new(),
+            state: None,
+            current: None,
+            started: 0.0,
+            last_error: None,
+        }
+    }
+}
+
+impl ShaderPlaylist {
+    /// Creates a new, empty [`ShaderPlaylist`] playing every shader for 10 seconds with a crossfade of
+    /// 1 second. Equivalent to [`ShaderPlaylist::default()`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `item` to the playlist.
+    #[must_use]
+    pub fn item(mut self, item: PlaylistItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Plays all `.wgsl` files in `directory`, after the items added with
+    /// [`ShaderPlaylist::item`]. The directory is read on the first render.
+    #[must_use]
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Sets how long each shader plays, unless the item sets its own duration. The crossfade into a
+    /// shader counts towards it.
+    #[must_use]
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Sets the crossfade from one shader to the next, unless the item sets its own.
+    #[must_use]
+    pub fn transition(mut self, duration: Duration, easing: Easing) -> Self {
+        self.transition = (duration, easing);
+        self
+    }
+
+    /// Applies a [`ShaderCanvas`] to a [`ShaderPlaylist`], determining how the shader output is mapped to
+    /// cells.
+    #[must_use]
+    pub fn canvas(mut self, canvas: ShaderCanvas) -> Self {
+        self.canvas = canvas;
+        self
+    }
+
+    /// The items of the playlist, including the files found in the directory.
+    pub fn items(&self) -> &[PlaylistItem] {
+        &self.items
+    }
+
+    /// The index of the item playing, `None` before the first render or if no item could be loaded.
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// The error of the last item which failed to load, if any.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// Gets the [`ShaderCanvasState`] playing the shaders, `None` before the first shader was loaded.
+    pub fn state(&self) -> Option<&ShaderCanvasState> {
+        self.state.as_ref()
+    }
+
+    /// Gets a mutable reference to the [`ShaderCanvasState`], e.g. to update uniforms. The state keeps
+    /// its clock and settings when the playlist moves on.
+    pub fn state_mut(&mut self) -> Option<&mut ShaderCanvasState> {
+        self.state.as_mut()
+    }
+
+    /// Moves on to the next shader right away, skipping shaders which fail to load.
+    pub fn next(&mut self) {
+        self.scan_directory();
+        let start = self.current.map_or(0, |current| current + 1);
+        for offset in 0..self.items.len() {
+            let index = (start + offset) % self.items.len();
+            match self.items[index].load() {
+                Ok(next) => {
+                    self.play(index, next);
+                    return;
+                }
+                Err(error) => self.last_error = Some(error.to_string()),
+            }
+        }
+    }
+
+    fn play(&mut self, index: usize, next: ShaderCanvasState) {
+        let item = &self.items[index];
+        let (duration, easing) = item.transition.unwrap_or(self.transition);
+        let state = match self.state.take() {
+            Some(mut state) if self.current.is_some() => {
+                state.transition_to(next, duration, easing);
+                state
+            }
+            _ => next,
+        };
+        self.started = state.clock().elapsed().as_secs_f32();
+        self.state = Some(state);
+        self.current = Some(index);
+    }
+
+    /// Adds the `.wgsl` files in the directory which aren't in the playlist yet, in the order of their
+    /// names.
+    fn scan_directory(&mut self) {
+        let Some(directory) = &self.directory else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = match std::fs::read_dir(directory) {
+            Ok(entries) => entries
+                .filter_map(|entry| Some(entry.ok()?.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == "wgsl")
+                })
+                .collect(),
+            Err(error) => {
+                self.last_error = Some(format!("{}: {error}", directory.display()));
+                return;
+            }
+        };
+        paths.sort();
+        for path in paths {
+            let known = self
+                .items
+                .iter()
+                .any(|item| matches!(&item.source, PlaylistSource::Path(known) if *known == path));
+            if !known {
+                self.items.push(PlaylistItem::path(path));
+            }
+        }
+    }
+
+    /// Whether the current item has played for its duration.
+    fn is_due(&self) -> bool {
+        let (Some(current), Some(state)) = (self.current, &self.state) else {
+            return true;
+        };
+        let duration = self.items[current].duration.unwrap_or(self.duration);
+        state.clock().elapsed().as_secs_f32() - self.started >= duration.as_secs_f32()
+    }
+}
+
+impl fmt::Debug for ShaderPlaylist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShaderPlaylist")
+            .field("items", &self.items)
+            .field("directory", &self.directory)
+            .field("duration", &self.duration)
+            .field("transition", &self.transition)
+            .field("canvas", &self.canvas)
+            .field("current", &self.current)
+            .field("last_error", &self.last_error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Widget for &mut ShaderPlaylist {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.is_due() {
+            self.next();
+        }
+        if let Some(state) = &mut self.state {
+            StatefulWidget::render(&self.canvas, area, buf, state);
+        }
+    }
+}
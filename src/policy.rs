@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+
+use wgpu::naga;
+use wgpu::naga::valid::Capabilities;
+
+use crate::check::ShaderDiagnostics;
+
+/// Limits for shaders from sources which aren't trusted, like the themes of plugins, checked before the
+/// shader reaches the GPU. A shader which hangs the GPU can take down the whole desktop, and one which
+/// binds more textures than the adapter supports fails to compile in the middle of the app.
+///
+/// Apply a policy with [`ShaderCanvasStateBuilder::policy`](crate::ShaderCanvasStateBuilder::policy), or
+/// check a shader against it with
+/// [`ShaderCanvasState::check_with_policy`](crate::ShaderCanvasState::check_with_policy), e.g. before
+/// loading it as a candidate. Only WGSL shaders can be checked against a policy.
+///
+/// ```rust,no_run
+/// # use tui_shader::{ShaderCanvasState, ShaderPolicy, WgslShader};
+/// let state = ShaderCanvasState::builder()
+///     .shader(WgslShader::Path("plugins/theme.wgsl"))
+///     .policy(ShaderPolicy::untrusted())
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShaderPolicy {
+    /// The capabilities the shader may use beyond the core of WGSL.
+    pub capabilities: Capabilities,
+
+    /// The highest estimated number of loop iterations per pixel, `None` for no limit. Nested loops
+    /// multiply, loops in called functions count once per call. The number of iterations of a loop is
+    /// counted from its counter, a local variable which starts at a constant, changes by a constant step
+    /// once per iteration and is compared against a constant by the exit condition, e.g. `8` for
+    /// `for (var i = 0; i < 16; i += 2)`. Loops without such a counter, or whose counter never reaches
+    /// the bound, are rejected while there is a limit.
+    pub max_loop_iterations: Option<u64>,
+
+    /// The highest number of textures the shader may bind, counting every element of a binding array,
+    /// `None` for no limit.
+    pub max_texture_bindings: Option<u32>,
+}
+
+impl Default for ShaderPolicy {
+    /// The same as [`ShaderPolicy::trusted`].
+    fn default() -> Self {
+        Self::trusted()
+    }
+}
+
+impl ShaderPolicy {
+    /// Allows everything the GPU supports, which is what shaders are checked against without a policy.
+    pub const fn trusted() -> Self {
+        Self {
+            capabilities: Capabilities::all(),
+            max_loop_iterations: None,
+            max_texture_bindings: None,
+        }
+    }
+
+    /// Restricts shaders to the baseline capabilities of WebGPU, about 4096 loop iterations per pixel and
+    /// 4 textures.
+    pub fn untrusted() -> Self {
+        Self {
+            capabilities: Capabilities::default(),
+            max_loop_iterations: Some(4096),
+            max_texture_bindings: Some(4),
+        }
+    }
+
+    /// Checks the limits which aren't covered by validating with the capabilities.
+    pub(crate) fn enforce(
+        &self,
+        module: &naga::Module,
+        source: &str,
+    ) -> Result<(), ShaderDiagnostics> {
+        if let Some(max) = self.max_texture_bindings {
+            let textures = texture_bindings(module);
+            if textures > max {
+                return Err(ShaderDiagnostics::at(
+                    format!("the shader binds {textures} textures, at most {max} are allowed"),
+                    naga::Span::default(),
+                    source,
+                ));
+            }
+        }
+        if let Some(max) = self.max_loop_iterations {
+            let mut costs = LoopCosts::new(module);
+            for entry_point in &module.entry_points {
+                let iterations = costs
+                    .block(&entry_point.function, &entry_point.function.body)
+                    .map_err(|span| {
+                        ShaderDiagnostics::at(
+                            "the loop has no constant bound".to_owned(),
+                            span,
+                            source,
+                        )
+                    })?;
+                if iterations > max {
+                    return Err(ShaderDiagnostics::at(
+                        format!(
+                            "the loops of `{}` run about {iterations} iterations per pixel, at most \
+                             {max} are allowed",
+                            entry_point.name
+                        ),
+                        naga::Span::default(),
+                        source,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The number of textures bound by `module`, saturating for arrays without a constant size.
+fn texture_bindings(module: &naga::Module) -> u32 {
+    module
+        .global_variables
+        .iter()
+        .filter(|(_, variable)| variable.binding.is_some())
+        .map(|(_, variable)| match module.types[variable.ty].inner {
+            naga::TypeInner::Image { .. } => 1,
+            naga::TypeInner::BindingArray { base, size } => {
+                match (&module.types[base].inner, size) {
+                    (naga::TypeInner::Image { .. }, naga::ArraySize::Constant(size)) => size.get(),
+                    (naga::TypeInner::Image { .. }, _) => u32::MAX,
+                    _ => 0,
+                }
+            }
+            _ => 0,
+        })
+        .fold(0, u32::saturating_add)
+}
+
+/// Estimates the loop iterations of the functions of a module. Errors carry the span of a loop without
+/// a constant bound.
+struct LoopCosts<'a> {
+    module: &'a naga::Module,
+    functions: HashMap<naga::Handle<naga::Function>, u64>,
+}
+
+impl<'a> LoopCosts<'a> {
+    fn new(module: &'a naga::Module) -> Self {
+        Self {
+            module,
+            functions: HashMap::new(),
+        }
+    }
+
+    /// The iterations of one call of the function `handle`. Recursion is invalid in WGSL.
+    fn function(&mut self, handle: naga::Handle<naga::Function>) -> Result<u64, naga::Span> {
+        if let Some(&cost) = self.functions.get(&handle) {
+            return Ok(cost);
+        }
+        let function = &self.module.functions[handle];
+        let cost = self.block(function, &function.body)?;
+        self.functions.insert(handle, cost);
+        Ok(cost)
+    }
+
+    /// The iterations of one execution of `block`, assuming every branch is taken.
+    fn block(&mut self, function: &naga::Function, block: &naga::Block) -> Result<u64, naga::Span> {
+        let mut cost = 0u64;
+        let mut previous = None;
+        for (statement, span) in block.span_iter() {
+            let statement_cost = match statement {
+                naga::Statement::Block(block) => self.block(function, block)?,
+                naga::Statement::If { accept, reject, .. } => self
+                    .block(function, accept)?
+                    .saturating_add(self.block(function, reject)?),
+                naga::Statement::Switch { cases, .. } => {
+                    let mut cases_cost = 0u64;
+                    for case in cases {
+                        cases_cost = cases_cost.saturating_add(self.block(function, &case.body)?);
+                    }
+                    cases_cost
+                }
+                naga::Statement::Loop {
+                    body,
+                    continuing,
+                    break_if,
+                } => {
+                    let bound =
+                        loop_bound(self.module, function, previous, body, continuing, *break_if)
+                            .ok_or(*span)?;
+                    let inner = self
+                        .block(function, body)?
+                        .saturating_add(self.block(function, continuing)?);
+                    bound.saturating_mul(inner.saturating_add(1))
+                }
+                naga::Statement::Call { function, .. } => self.function(*function)?,
+                _ => 0,
+            };
+            cost = cost.saturating_add(statement_cost);
+            if !matches!(statement, naga::Statement::Emit(_)) {
+                previous = Some(statement);
+            }
+        }
+        Ok(cost)
+    }
+}
+
+/// The number of iterations of a loop with `body` and `continuing`, counted from its counter: a local
+/// variable which the exit condition compares against a constant, and which changes by a constant step
+/// exactly once per iteration. `for` and `while` loops check their condition at the start of the body,
+/// `break if` is checked after the step. `previous` is the statement before the loop, which sets the
+/// counter of a `for` loop nested in another loop.
+fn loop_bound(
+    module: &naga::Module,
+    function: &naga::Function,
+    previous: Option<&naga::Statement>,
+    body: &naga::Block,
+    continuing: &naga::Block,
+    break_if: Option<naga::Handle<naga::Expression>>,
+) -> Option<u64> {
+    let is_break = |block: &naga::Block| matches!(block.first(), Some(naga::Statement::Break));
+    let (condition, exits_if_true) = match break_if {
+        Some(condition) => (condition, true),
+        None => body
+            .iter()
+            .find(|statement| !matches!(statement, naga::Statement::Emit(_)))
+            .and_then(|statement| match statement {
+                naga::Statement::If {
+                    condition, accept, ..
+                } if is_break(accept) => Some((*condition, true)),
+                naga::Statement::If {
+                    condition, reject, ..
+                } if is_break(reject) => Some((*condition, false)),
+                _ => None,
+            })?,
+    };
+    let naga::Expression::Binary { op, left, right } = function.expressions[condition] else {
+        return None;
+    };
+    let (counter, bound, op) = match (
+        loaded_variable(function, left),
+        constant_value(module, &function.expressions[right]),
+    ) {
+        (Some(counter), Some(bound)) => (counter, bound, op),
+        _ => (
+            loaded_variable(function, right)?,
+            constant_value(module, &function.expressions[left])?,
+            swap_operands(op)?,
+        ),
+    };
+    let op = if exits_if_true { negate(op)? } else { op };
+
+    let counter_stores = stores(function, body, counter) + stores(function, continuing, counter);
+    if counter_stores != 1 {
+        return None;
+    }
+    let step = body
+        .iter()
+        .chain(continuing.iter())
+        .find_map(|statement| counter_step(module, function, counter, statement))?;
+    let start = match previous {
+        Some(naga::Statement::Store { pointer, value })
+            if is_variable(function, *pointer, counter) =>
+        {
+            constant_value(module, &function.expressions[*value])?
+        }
+        // The counter isn't set anywhere else, so it still has the value it was declared with.
+        _ if stores(function, &function.body, counter) == counter_stores => {
+            match function.local_variables[counter].init {
+                Some(init) => constant_value(module, &function.expressions[init])?,
+                None => 0.0,
+            }
+        }
+        _ => return None,
+    };
+    let (start, checked_before) = match break_if {
+        Some(_) => (start + step, 1),
+        None => (start, 0),
+    };
+    let iterations = trip_count(start, step, bound, op)?;
+    let (min, max) = scalar_range(&module.types[function.local_variables[counter].ty].inner);
+    let last = start + iterations * step;
+    if last < min || last > max {
+        // The counter overflows before the loop exits.
+        return None;
+    }
+    Some((iterations as u64).saturating_add(checked_before))
+}
+
+/// How often a counter starting at `start` and changing by `step` passes `counter op bound`, `None` if it
+/// keeps passing forever.
+fn trip_count(start: f64, step: f64, bound: f64, op: naga::BinaryOperator) -> Option<f64> {
+    use naga::BinaryOperator::*;
+    let distance = (bound - start) / step;
+    match op {
+        Less | LessEqual | Greater | GreaterEqual => {
+            let passes = match op {
+                Less => start < bound,
+                LessEqual => start <= bound,
+                Greater => start > bound,
+                _ => start >= bound,
+            };
+            if !passes {
+                Some(0.0)
+            } else if (step > 0.0) != matches!(op, Less | LessEqual) {
+                None
+            } else if matches!(op, Less | Greater) {
+                Some(distance.ceil())
+            } else {
+                Some(distance.floor() + 1.0)
+            }
+        }
+        NotEqual => (distance >= 0.0 && distance.fract() == 0.0).then_some(distance),
+        Equal => Some(if start == bound { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+/// The step a statement adds to `counter`, if it is `counter += step` or `counter -= step` with a
+/// non-zero constant step.
+fn counter_step(
+    module: &naga::Module,
+    function: &naga::Function,
+    counter: naga::Handle<naga::LocalVariable>,
+    statement: &naga::Statement,
+) -> Option<f64> {
+    let naga::Statement::Store { pointer, value } = *statement else {
+        return None;
+    };
+    if !is_variable(function, pointer, counter) {
+        return None;
+    }
+    let naga::Expression::Binary { op, left, right } = function.expressions[value] else {
+        return None;
+    };
+    let step = match op {
+        naga::BinaryOperator::Add if loaded_variable(function, left) == Some(counter) => {
+            constant_value(module, &function.expressions[right])?
+        }
+        naga::BinaryOperator::Add if loaded_variable(function, right) == Some(counter) => {
+            constant_value(module, &function.expressions[left])?
+        }
+        naga::BinaryOperator::Subtract if loaded_variable(function, left) == Some(counter) => {
+            -constant_value(module, &function.expressions[right])?
+        }
+        _ => return None,
+    };
+    (step != 0.0).then_some(step)
+}
+
+/// The number of statements in `block` which may change `variable`: stores to it and calls which get a
+/// pointer to it.
+fn stores(
+    function: &naga::Function,
+    block: &naga::Block,
+    variable: naga::Handle<naga::LocalVariable>,
+) -> usize {
+    block
+        .iter()
+        .map(|statement| match statement {
+            naga::Statement::Store { pointer, .. } => {
+                usize::from(is_variable(function, *pointer, variable))
+            }
+            naga::Statement::Call { arguments, .. } => arguments
+                .iter()
+                .filter(|argument| is_variable(function, **argument, variable))
+                .count(),
+            naga::Statement::Block(block) => stores(function, block, variable),
+            naga::Statement::If { accept, reject, .. } => {
+                stores(function, accept, variable) + stores(function, reject, variable)
+            }
+            naga::Statement::Switch { cases, .. } => cases
+                .iter()
+                .map(|case| stores(function, &case.body, variable))
+                .sum(),
+            naga::Statement::Loop {
+                body, continuing, ..
+            } => stores(function, body, variable) + stores(function, continuing, variable),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Whether `pointer` points to `variable`.
+fn is_variable(
+    function: &naga::Function,
+    pointer: naga::Handle<naga::Expression>,
+    variable: naga::Handle<naga::LocalVariable>,
+) -> bool {
+    matches!(function.expressions[pointer], naga::Expression::LocalVariable(handle) if handle == variable)
+}
+
+/// The local variable `expression` loads.
+fn loaded_variable(
+    function: &naga::Function,
+    expression: naga::Handle<naga::Expression>,
+) -> Option<naga::Handle<naga::LocalVariable>> {
+    match function.expressions[expression] {
+        naga::Expression::Load { pointer } => match function.expressions[pointer] {
+            naga::Expression::LocalVariable(variable) => Some(variable),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The comparison with its operands swapped, e.g. `>` for `<`.
+fn swap_operands(op: naga::BinaryOperator) -> Option<naga::BinaryOperator> {
+    use naga::BinaryOperator::*;
+    Some(match op {
+        Less => Greater,
+        LessEqual => GreaterEqual,
+        Greater => Less,
+        GreaterEqual => LessEqual,
+        Equal | NotEqual => op,
+        _ => return None,
+    })
+}
+
+/// The comparison which is true whenever `op` is false, e.g. `>=` for `<`.
+fn negate(op: naga::BinaryOperator) -> Option<naga::BinaryOperator> {
+    use naga::BinaryOperator::*;
+    Some(match op {
+        Less => GreaterEqual,
+        LessEqual => Greater,
+        Greater => LessEqual,
+        GreaterEqual => Less,
+        Equal => NotEqual,
+        NotEqual => Equal,
+        _ => return None,
+    })
+}
+
+/// The smallest and largest value of an integer type, infinite for other types.
+fn scalar_range(inner: &naga::TypeInner) -> (f64, f64) {
+    match *inner {
+        naga::TypeInner::Scalar(naga::Scalar {
+            kind: naga::ScalarKind::Uint,
+            width,
+        }) => (0.0, 2f64.powi(i32::from(width) * 8) - 1.0),
+        naga::TypeInner::Scalar(naga::Scalar {
+            kind: naga::ScalarKind::Sint,
+            width,
+        }) => {
+            let half = 2f64.powi(i32::from(width) * 8 - 1);
+            (-half, half - 1.0)
+        }
+        _ => (f64::NEG_INFINITY, f64::INFINITY),
+    }
+}
+
+/// The value of a numeric literal or constant.
+fn constant_value(module: &naga::Module, expression: &naga::Expression) -> Option<f64> {
+    let literal = match *expression {
+        naga::Expression::Literal(literal) => literal,
+        naga::Expression::Constant(handle) => {
+            match module.global_expressions[module.constants[handle].init] {
+                naga::Expression::Literal(literal) => literal,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    match literal {
+        naga::Literal::F64(value) | naga::Literal::AbstractFloat(value) => Some(value),
+        naga::Literal::F32(value) => Some(f64::from(value)),
+        naga::Literal::U32(value) => Some(f64::from(value)),
+        naga::Literal::I32(value) => Some(f64::from(value)),
+        naga::Literal::U64(value) => Some(value as f64),
+        naga::Literal::I64(value) | naga::Literal::AbstractInt(value) => Some(value as f64),
+        _ => None,
+    }
+}
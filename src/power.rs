@@ -0,0 +1,196 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use crate::frame::Frame;
+
+/// The longest time between two renders of a static canvas.
+const MAX_STATIC_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How much a [`ShaderCanvas`](crate::ShaderCanvas) may draw from the GPU and CPU, for always-on uses like
+/// animated prompt backgrounds or tmux status panes, set with
+/// [`ShaderCanvas::power_profile`](crate::ShaderCanvas::power_profile).
+///
+/// Renders more frequent than the frame rate of the profile reuse the last frame instead of running the
+/// shader, and the render scale of the canvas is multiplied with the scale of the profile. Profiles which
+/// skip static frames also back off while the shader keeps producing the same frame, e.g. because its
+/// clock is paused or it doesn't animate, halving the frame rate with every unchanged render down to one
+/// render per second. `run` of the `runner` feature additionally limits its frame rate to the one of the
+/// profile of its canvas, so the process sleeps in between.
+///
+/// | Profile       | Frame rate | Render scale | Skips static frames |
+/// |---------------|------------|--------------|---------------------|
+/// | `Performance` | unlimited  | 1.0          | no                  |
+/// | `Balanced`    | 30         | 0.75         | yes                 |
+/// | `LowPower`    | 10         | 0.5          | yes                 |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PowerProfile {
+    /// Renders every frame at full scale.
+    #[default]
+    Performance,
+
+    /// Saves power without visibly degrading most shaders.
+    Balanced,
+
+    /// For backgrounds which are on all day.
+    LowPower,
+}
+
+impl PowerProfile {
+    /// The highest number of renders per second, `None` if unlimited.
+    pub fn max_fps(self) -> Option<u32> {
+        match self {
+            Self::Performance => None,
+            Self::Balanced => Some(30),
+            Self::LowPower => Some(10),
+        }
+    }
+
+    /// The factor the render scale of the canvas is multiplied with.
+    pub fn render_scale(self) -> f32 {
+        match self {
+            Self::Performance => 1.0,
+            Self::Balanced => 0.75,
+            Self::LowPower => 0.5,
+        }
+    }
+
+    /// Whether renders back off while the frames don't change.
+    pub fn skips_static_frames(self) -> bool {
+        self != Self::Performance
+    }
+
+    /// The time between two renders, before backing off.
+    fn frame_time(self) -> Duration {
+        self.max_fps()
+            .map_or(Duration::ZERO, |fps| Duration::from_secs(1) / fps.max(1))
+    }
+}
+
+/// Lowers the frame rate of a [`ShaderCanvasState`](crate::ShaderCanvasState) which sits idle, set with
+/// [`ShaderCanvasState::set_idle_frame_rate`](crate::ShaderCanvasState::set_idle_frame_rate). Once
+/// neither pointer input nor a change of a uniform happened for [`after`](IdleFrameRate::after), canvases
+/// rendering the state run the shader at most [`fps`](IdleFrameRate::fps) times per second and reuse the
+/// last frame in between. The next activity restores the full frame rate right away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IdleFrameRate {
+    /// How long the state has to be inactive to count as idle.
+    pub after: Duration,
+
+    /// The highest number of renders per second while idle.
+    pub fps: u32,
+}
+
+impl IdleFrameRate {
+    /// Creates a new [`IdleFrameRate`].
+    pub fn new(after: Duration, fps: u32) -> Self {
+        Self { after, fps }
+    }
+}
+
+impl Default for IdleFrameRate {
+    /// Two renders per second after ten seconds without activity.
+    fn default() -> Self {
+        Self::new(Duration::from_secs(10), 2)
+    }
+}
+
+/// Tracks the activity of a state with an [`IdleFrameRate`] to decide when the next render is due.
+#[derive(Debug, Clone)]
+pub(crate) struct IdleThrottle {
+    config: Option<IdleFrameRate>,
+    last_activity: Instant,
+    last_render: Option<Instant>,
+}
+
+impl Default for IdleThrottle {
+    fn default() -> Self {
+        Self {
+            config: None,
+            last_activity: Instant::now(),
+            last_render: None,
+        }
+    }
+}
+
+impl IdleThrottle {
+    pub(crate) fn config(&self) -> Option<IdleFrameRate> {
+        self.config
+    }
+
+    pub(crate) fn set_config(&mut self, config: Option<IdleFrameRate>) {
+        self.config = config;
+    }
+
+    /// Whether nothing happened for longer than the configured time at `now`.
+    pub(crate) fn is_idle(&self, now: Instant) -> bool {
+        self.config
+            .is_some_and(|config| now.duration_since(self.last_activity) >= config.after)
+    }
+
+    /// Whether a render at `now` should reuse the last frame.
+    pub(crate) fn should_skip(&self, now: Instant) -> bool {
+        let (Some(config), Some(last_render)) = (self.config, self.last_render) else {
+            return false;
+        };
+        self.is_idle(now)
+            && now.duration_since(last_render) < Duration::from_secs(1) / config.fps.max(1)
+    }
+
+    pub(crate) fn record_render(&mut self, now: Instant) {
+        self.last_render = Some(now);
+    }
+
+    pub(crate) fn record_activity(&mut self, now: Instant) {
+        self.last_activity = now;
+    }
+}
+
+/// Tracks the renders of a canvas with a [`PowerProfile`] to decide when the next one is due.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PowerThrottle {
+    last_render: Option<Instant>,
+    /// A hash of the last rendered frame.
+    last_frame: Option<u64>,
+    /// The number of renders in a row which produced the same frame as the one before.
+    static_renders: u32,
+}
+
+impl PowerThrottle {
+    /// Whether a render at `now` should reuse the last frame.
+    pub(crate) fn should_skip(&self, profile: PowerProfile, now: Instant) -> bool {
+        let Some(last_render) = self.last_render else {
+            return false;
+        };
+        let mut interval = profile.frame_time();
+        if profile.skips_static_frames() && self.static_renders > 0 {
+            let backoff = 1 << self.static_renders.min(16);
+            interval = (interval.max(Duration::from_millis(1)) * backoff).min(MAX_STATIC_INTERVAL);
+        }
+        now.duration_since(last_render) < interval
+    }
+
+    /// Records that a frame with the [`frame_hash`] `hash` was rendered at `now`.
+    pub(crate) fn record(&mut self, now: Instant, hash: Option<u64>) {
+        if hash.is_some() && hash == self.last_frame {
+            self.static_renders = self.static_renders.saturating_add(1);
+        } else {
+            self.static_renders = 0;
+        }
+        self.last_frame = hash;
+        self.last_render = Some(now);
+    }
+
+    /// Makes the next render run the shader, e.g. after input changed what the shader draws.
+    pub(crate) fn wake(&mut self) {
+        self.last_render = None;
+        self.static_renders = 0;
+    }
+}
+
+/// A hash of the pixels of `frame`, to notice when renders stop changing.
+pub(crate) fn frame_hash(frame: &Frame) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    frame.rows().for_each(|row| row.hash(&mut hasher));
+    hasher.finish()
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+
+use crate::stdlib;
+
+/// [`ShaderFeatures`] toggles the conditional blocks of a shader, so one file can contain optional
+/// debug visualizations or several quality tiers. Enabled with [`WgslShader::with_features`](crate::WgslShader::with_features).
+///
+/// Lines starting with `#` are directives of a small preprocessor, which runs before the shader is
+/// compiled:
+///
+/// ```wgsl
+/// #define HIGH_QUALITY
+///
+/// @fragment
+/// fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+/// #ifdef DEBUG_UV
+///     return vec4<f32>(uv, 0.0, 1.0);
+/// #else
+///     return vec4<f32>(0.0, 0.0, 1.0, 1.0);
+/// #endif
+/// }
+/// ```
+///
+/// `#ifdef NAME` and `#ifndef NAME` keep the following lines up to the matching `#else` or `#endif` if
+/// `NAME` is, or isn't, enabled. Blocks can be nested. `#define NAME` enables a feature from within the
+/// shader, `#define NAME value` also replaces `NAME` with `value` like
+/// [`WgslShader::with_defines`](crate::WgslShader::with_defines), whose names count as enabled as well.
+/// `#include <name>` adds a module of the [`stdlib`](crate::stdlib).
+///
+/// ```rust,no_run
+/// # use tui_shader::{ShaderCanvasState, ShaderFeatures, WgslShader};
+/// let features = ShaderFeatures::default().enable("DEBUG_UV");
+/// let state = ShaderCanvasState::new(WgslShader::Path("shader.wgsl").with_features(features)).unwrap();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ShaderFeatures {
+    enabled: BTreeSet<String>,
+}
+
+impl ShaderFeatures {
+    /// Creates a new [`ShaderFeatures`] without any enabled features. Equivalent to
+    /// [`ShaderFeatures::default()`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables the feature `name`.
+    #[must_use]
+    pub fn enable(mut self, name: impl Into<String>) -> Self {
+        self.enabled.insert(name.into());
+        self
+    }
+
+    /// Enables or disables the feature `name`, e.g. from a setting of the app.
+    #[must_use]
+    pub fn set(mut self, name: impl Into<String>, enabled: bool) -> Self {
+        let name = name.into();
+        if enabled {
+            self.enabled.insert(name);
+        } else {
+            self.enabled.remove(&name);
+        }
+        self
+    }
+
+    /// Returns `true` if the feature `name` is enabled.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.enabled.contains(name)
+    }
+}
+
+/// Runs the preprocessor over `source` and substitutes the `defines`. Removed lines are kept empty, so
+/// the line numbers in errors of the shader compiler still match the original file.
+pub(crate) fn preprocess(
+    source: &str,
+    features: &ShaderFeatures,
+    defines: &[(&str, &str)],
+) -> Result<String, Box<dyn Error>> {
+    let mut enabled = features.clone();
+    for (name, _) in defines {
+        enabled.enabled.insert(name.to_string());
+    }
+    let mut local_defines: Vec<(String, String)> = Vec::new();
+    let mut included = Vec::new();
+    // Whether the lines of each open block are kept, and whether its `#else` was reached.
+    let mut blocks: Vec<(bool, bool)> = Vec::new();
+    let mut output = String::with_capacity(source.len());
+    for (number, line) in source.lines().enumerate() {
+        let active = blocks.iter().all(|(keep, _)| *keep);
+        let Some(directive) = line.trim_start().strip_prefix('#') else {
+            if active {
+                output.push_str(line);
+            }
+            output.push('\n');
+            continue;
+        };
+        output.push('\n');
+        let error = |message: &str| format!("line {}: {message}", number + 1);
+        let mut words = directive.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some("ifdef"), Some(name)) => blocks.push((enabled.is_enabled(name), false)),
+            (Some("ifndef"), Some(name)) => blocks.push((!enabled.is_enabled(name), false)),
+            (Some("else"), None) => match blocks.last_mut() {
+                Some((keep, seen_else)) if !*seen_else => {
+                    *keep = !*keep;
+                    *seen_else = true;
+                }
+                _ => return Err(error("#else without #ifdef").into()),
+            },
+            (Some("endif"), None) => {
+                if blocks.pop().is_none() {
+                    return Err(error("#endif without #ifdef").into());
+                }
+            }
+            (Some("define"), Some(name)) if active => {
+                enabled.enabled.insert(name.to_string());
+                let value = words.collect::<Vec<_>>().join(" ");
+                if !value.is_empty() {
+                    local_defines.push((name.to_string(), value));
+                }
+            }
+            (Some("define"), Some(_)) => {}
+            (Some("include"), Some(name)) if active => {
+                let module = name.trim_start_matches('<').trim_end_matches('>');
+                if !stdlib::include(module, &mut included) {
+                    return Err(error(&format!("unknown module `{module}`")).into());
+                }
+            }
+            (Some("include"), Some(_)) => {}
+            _ => return Err(error(&format!("invalid directive `#{directive}`")).into()),
+        }
+    }
+    if !blocks.is_empty() {
+        return Err("#ifdef without #endif".into());
+    }
+    // Defines passed from Rust take precedence over the ones in the shader.
+    let mut all_defines = defines.to_vec();
+    all_defines.extend(
+        local_defines
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str())),
+    );
+    let mut output = substitute_defines(&output, &all_defines);
+    for module in included.into_iter().filter_map(stdlib::module) {
+        output.push('\n');
+        output.push_str(module);
+    }
+    Ok(output)
+}
+
+/// Replaces identifiers in `source` with the values in `defines`. Numbers are skipped as a whole, so
+/// the exponent of `1e5` isn't mistaken for an identifier.
+fn substitute_defines(source: &str, defines: &[(&str, &str)]) -> String {
+    if defines.is_empty() {
+        return source.to_string();
+    }
+    let mut output = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find(|c: char| c.is_alphanumeric() || c == '_') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let word = &rest[..end];
+        match defines.iter().find(|(name, _)| *name == word) {
+            Some((_, value)) if !word.starts_with(|c: char| c.is_ascii_digit()) => {
+                output.push_str(value);
+            }
+            _ => output.push_str(word),
+        }
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+    output
+}
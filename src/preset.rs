@@ -0,0 +1,101 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::ShaderCanvas;
+use crate::state::ShaderCanvasState;
+
+/// A serializable description of a shader and the [`ShaderCanvas`] rules it is rendered with. This allows
+/// apps to load user-themable shader setups for their panes from configuration files, e.g. `effects.toml`:
+///
+/// ```toml
+/// [panes.sidebar]
+/// shader = "shaders/plasma.wgsl"
+/// entry_point = "main"
+/// character_rule = { Always = "░" }
+/// style_rule = "ColorFg"
+/// ```
+///
+/// [`CharacterRule::Map`](crate::CharacterRule::Map) and [`StyleRule::Map`](crate::StyleRule::Map) hold
+/// functions and can't be (de)serialized. Omitted rules fall back to their default values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanvasPreset {
+    /// Path to the shader. Files with a `.wgsl` extension are loaded as WGSL, anything else as GLSL
+    /// which requires the `glsl` feature.
+    pub shader: PathBuf,
+
+    /// The entry point of the fragment shader. Uses the only entry point of the shader if omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_point: Option<String>,
+
+    /// The rules applied to the cells.
+    #[serde(flatten)]
+    pub canvas: ShaderCanvas,
+}
+
+impl CanvasPreset {
+    /// Creates a new [`CanvasPreset`] for the shader at `shader` with the default [`ShaderCanvas`] rules.
+    pub fn new(shader: impl Into<PathBuf>) -> Self {
+        Self {
+            shader: shader.into(),
+            entry_point: None,
+            canvas: ShaderCanvas::default(),
+        }
+    }
+
+    /// Sets the entry point of the fragment shader.
+    #[must_use]
+    pub fn entry_point(mut self, entry_point: impl Into<String>) -> Self {
+        self.entry_point = Some(entry_point.into());
+        self
+    }
+
+    /// Sets the [`ShaderCanvas`] describing the rules applied to the cells.
+    #[must_use]
+    pub fn canvas(mut self, canvas: ShaderCanvas) -> Self {
+        self.canvas = canvas;
+        self
+    }
+
+    /// Returns the [`ShaderCanvas`] to render the preset with.
+    pub fn to_canvas(&self) -> ShaderCanvas {
+        self.canvas.clone()
+    }
+
+    /// Loads and compiles the shader of the preset into a new [`ShaderCanvasState`].
+    pub fn to_state(&self) -> Result<ShaderCanvasState, Box<dyn Error>> {
+        let source = std::fs::read_to_string(&self.shader)?;
+        let is_wgsl = self
+            .shader
+            .extension()
+            .is_some_and(|extension| extension == "wgsl");
+        if is_wgsl {
+            let shader = crate::WgslShader::Source(&source);
+            match &self.entry_point {
+                Some(entry_point) => ShaderCanvasState::new_with_entry_point(shader, entry_point),
+                None => ShaderCanvasState::new(shader),
+            }
+        } else {
+            self.to_glsl_state(&source)
+        }
+    }
+
+    #[cfg(feature = "glsl")]
+    fn to_glsl_state(&self, source: &str) -> Result<ShaderCanvasState, Box<dyn Error>> {
+        let shader = crate::GlslShader::Source(source);
+        match &self.entry_point {
+            Some(entry_point) => ShaderCanvasState::new_with_entry_point(shader, entry_point),
+            None => ShaderCanvasState::new(shader),
+        }
+    }
+
+    #[cfg(not(feature = "glsl"))]
+    fn to_glsl_state(&self, _source: &str) -> Result<ShaderCanvasState, Box<dyn Error>> {
+        Err(format!(
+            "{} is not a wgsl shader, loading glsl shaders requires the `glsl` feature",
+            self.shader.display()
+        )
+        .into())
+    }
+}
@@ -0,0 +1,79 @@
+//! Complete shaders built around a small piece provided by the app, so common effects don't have to be
+//! written from scratch.
+
+use std::error::Error;
+
+use ratatui_core::style::Color;
+
+use crate::camera::Camera;
+use crate::state::ShaderCanvasState;
+use crate::uniforms::UniformValue;
+use crate::util::WgslShader;
+
+const RAYMARCH_SHADER: &str = include_str!("shaders/raymarch.wgsl");
+
+/// The number of steps and ambient occlusion samples of the ray marcher of [`raymarch`]. Set it with the
+/// `quality` uniform, which can change every frame, e.g. to draw large canvases at a lower quality.
+///
+/// | Tier     | Steps | Occlusion samples |
+/// |----------|-------|-------------------|
+/// | `Low`    | 24    | 0                 |
+/// | `Medium` | 64    | 3                 |
+/// | `High`   | 128   | 5                 |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RaymarchQuality {
+    /// Few steps and no ambient occlusion, for large canvases or slow GPUs.
+    Low,
+
+    /// The default tier.
+    #[default]
+    Medium,
+
+    /// Many steps, so thin details and grazing rays still hit the surface.
+    High,
+}
+
+impl From<RaymarchQuality> for UniformValue {
+    fn from(value: RaymarchQuality) -> Self {
+        Self::Uint(value as u32)
+    }
+}
+
+/// Creates a [`ShaderCanvasState`] ray marching the signed distance field `scene_sdf`, which has to
+/// define `fn scene(p: vec3<f32>) -> f32`. The surface is lit by a directional light, darkened by
+/// ambient occlusion and fades into fog with the distance. The source of the ray marcher is appended to
+/// `scene_sdf`, so line numbers in errors match it.
+///
+/// The scene is seen through the [`Camera`] of the state, see [`ShaderCanvasState::set_camera`], which
+/// orbits when pointer input is passed to [`ShaderCanvasState::handle_pointer`]. The look is set with
+/// these uniforms:
+///
+/// - `quality: u32`: see [`RaymarchQuality`], [`RaymarchQuality::Medium`] by default.
+/// - `surface_color: vec4<f32>`: the color of the surface, light gray by default.
+/// - `fog_color: vec4<f32>`: the color of the fog and the background, black by default.
+/// - `fog_density: f32`: how quickly the fog thickens with the distance, `0.02` by default.
+/// - `light_direction: vec3<f32>`: the direction towards the light.
+///
+/// ```rust,no_run
+/// # use tui_shader::presets::{self, RaymarchQuality};
+/// let mut state = presets::raymarch(
+///     "fn scene(p: vec3<f32>) -> f32 {
+///         return min(length(p) - 1.0, p.y + 1.0);
+///     }",
+/// )
+/// .unwrap();
+/// state.uniforms().set("quality", RaymarchQuality::High);
+/// ```
+pub fn raymarch(scene_sdf: &str) -> Result<ShaderCanvasState, Box<dyn Error>> {
+    let source = format!("{scene_sdf}\n{RAYMARCH_SHADER}");
+    let mut state = ShaderCanvasState::new(WgslShader::Source(&source))?;
+    state.set_camera(Some(Camera::default()));
+    state
+        .uniforms()
+        .set("quality", RaymarchQuality::default())
+        .set("surface_color", Color::Rgb(200, 200, 200))
+        .set("fog_color", Color::Black)
+        .set("fog_density", 0.02)
+        .set("light_direction", [0.6, 0.8, 0.4]);
+    Ok(state)
+}
@@ -0,0 +1,53 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::state::Uniforms;
+
+/// [`UniformProvider`] sets uniforms of a [`ShaderCanvasState`](crate::ShaderCanvasState) from an outside
+/// source, e.g. system metrics or audio levels. Providers are added with
+/// [`ShaderCanvasState::add_provider`](crate::ShaderCanvasState::add_provider) and run before every
+/// execution of the shader.
+///
+/// ```rust,no_run
+/// # use tui_shader::{ShaderCanvasState, UniformProvider, Uniforms};
+/// struct Battery;
+///
+/// impl UniformProvider for Battery {
+///     fn provide(&mut self, uniforms: &mut Uniforms<'_>) {
+///         uniforms.set("battery", 0.8);
+///     }
+/// }
+///
+/// let mut state = ShaderCanvasState::default();
+/// state.add_provider(Battery);
+/// ```
+pub trait UniformProvider: Send {
+    /// Sets the uniforms of the next frame. Uniforms the shader doesn't declare are ignored.
+    fn provide(&mut self, uniforms: &mut Uniforms<'_>);
+}
+
+/// The [`UniformProvider`]s of a [`ShaderCanvasState`](crate::ShaderCanvasState).
+#[derive(Clone, Default)]
+pub(crate) struct Providers(Vec<Arc<Mutex<dyn UniformProvider>>>);
+
+impl Providers {
+    pub(crate) fn push(&mut self, provider: impl UniformProvider + 'static) {
+        self.0.push(Arc::new(Mutex::new(provider)));
+    }
+
+    pub(crate) fn provide(&self, uniforms: &mut Uniforms<'_>) {
+        for provider in &self.0 {
+            provider
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .provide(uniforms);
+        }
+    }
+}
+
+impl std::fmt::Debug for Providers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Providers")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
@@ -0,0 +1,279 @@
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::{Position, Rect};
+use ratatui_core::style::Color;
+
+use crate::ansi::{AnsiColors, buffer_to_ansi};
+use crate::frame::Frame;
+use crate::state::ShaderCanvasState;
+#[cfg(feature = "gif")]
+use crate::util::color_to_rgb;
+
+#[cfg(feature = "gif")]
+const QUANTIZATION_SPEED: i32 = 10;
+
+/// A single recorded image in tightly packed RGBA.
+#[cfg(feature = "gif")]
+#[derive(Debug, Clone)]
+struct Image {
+    width: u16,
+    height: u16,
+    rgba: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+enum Recording {
+    #[cfg(feature = "gif")]
+    Gif { fps: u32, images: Vec<Image> },
+    Asciinema {
+        start: Option<Instant>,
+        width: u16,
+        height: u16,
+        events: Vec<(f64, String)>,
+    },
+}
+
+/// [`Recorder`] collects rendered frames and encodes them into an animation once finished.
+///
+/// Frames can either be captured from the pixels the shader produced ([`Recorder::capture`]), or from the
+/// cells of a [`Buffer`] after all widgets have been rendered ([`Recorder::capture_buffer`]).
+///
+/// ```rust,no_run
+/// # use tui_shader::{Recorder, ShaderCanvas, ShaderCanvasState};
+/// let mut terminal = ratatui::init();
+/// let mut state = ShaderCanvasState::default();
+/// let mut recorder = Recorder::asciinema("demo.cast");
+/// for _ in 0..90 {
+///     let completed = terminal.draw(|frame| {
+///         frame.render_stateful_widget(ShaderCanvas::new(), frame.area(), &mut state);
+///     }).unwrap();
+///     recorder.capture_buffer(completed.buffer);
+///     std::thread::sleep(std::time::Duration::from_millis(33));
+/// }
+/// ratatui::restore();
+/// recorder.finish().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    path: PathBuf,
+    recording: Recording,
+}
+
+impl Recorder {
+    /// Creates a new [`Recorder`] that writes an animated GIF to `path`, playing at `fps` frames per second.
+    /// Captured buffers are rendered with one pixel per cell.
+    #[cfg(feature = "gif")]
+    pub fn gif(path: impl AsRef<Path>, fps: u32) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            recording: Recording::Gif {
+                fps: fps.max(1),
+                images: Vec::new(),
+            },
+        }
+    }
+
+    /// Creates a new [`Recorder`] that writes an [asciinema v2](https://docs.asciinema.org/manual/asciicast/v2/)
+    /// cast file to `path`. Every capture becomes one event, timed by when it was captured, containing the
+    /// characters and colors of all cells.
+    pub fn asciinema(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            recording: Recording::Asciinema {
+                start: None,
+                width: 0,
+                height: 0,
+                events: Vec::new(),
+            },
+        }
+    }
+
+    /// Captures the pixels of the most recent render of the [`ShaderCanvasState`], before they were mapped to
+    /// cells. Asciinema recordings draw every pixel as a cell with the pixel's background color. Does nothing
+    /// if the state has not been rendered yet.
+    pub fn capture(&mut self, state: &ShaderCanvasState) {
+        let Some(frame) = state.frame() else {
+            return;
+        };
+        match &mut self.recording {
+            #[cfg(feature = "gif")]
+            Recording::Gif { images, .. } => images.push(Image {
+                width: frame.width as u16,
+                height: frame.height as u16,
+                rgba: frame.to_rgba(),
+            }),
+            Recording::Asciinema { .. } => self.capture_buffer(&frame_to_buffer(frame)),
+        }
+    }
+
+    /// Captures the cells of a [`Buffer`].
+    ///
+    /// GIF recordings use the background color of every cell. Cells without a background color use their
+    /// foreground color if they contain a visible symbol, and black otherwise.
+    pub fn capture_buffer(&mut self, buffer: &Buffer) {
+        match &mut self.recording {
+            #[cfg(feature = "gif")]
+            Recording::Gif { images, .. } => images.push(buffer_to_image(buffer)),
+            Recording::Asciinema {
+                start,
+                width,
+                height,
+                events,
+            } => {
+                let time = start.get_or_insert_with(Instant::now).elapsed();
+                *width = (*width).max(buffer.area.width);
+                *height = (*height).max(buffer.area.height);
+                let output = format!(
+                    "\x1b[H{}",
+                    buffer_to_ansi(buffer, "\r\n", AnsiColors::TrueColor)
+                );
+                events.push((time.as_secs_f64(), output));
+            }
+        }
+    }
+
+    /// The number of frames captured so far.
+    pub fn len(&self) -> usize {
+        match &self.recording {
+            #[cfg(feature = "gif")]
+            Recording::Gif { images, .. } => images.len(),
+            Recording::Asciinema { events, .. } => events.len(),
+        }
+    }
+
+    /// Returns `true` if no frames have been captured yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Encodes all captured frames and writes them to the file.
+    pub fn finish(self) -> Result<(), Box<dyn Error>> {
+        match self.recording {
+            #[cfg(feature = "gif")]
+            Recording::Gif { fps, images } => encode_gif(&self.path, fps, images),
+            Recording::Asciinema {
+                width,
+                height,
+                events,
+                ..
+            } => encode_asciinema(&self.path, width, height, events),
+        }
+    }
+}
+
+fn frame_to_buffer(frame: &Frame) -> Buffer {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, frame.width as u16, frame.height as u16));
+    for (y, row) in frame.rows().enumerate() {
+        for (x, pixel) in row.iter().enumerate() {
+            if let Some(cell) = buffer.cell_mut(Position::new(x as u16, y as u16)) {
+                cell.set_bg(Color::Rgb(pixel[0], pixel[1], pixel[2]));
+            }
+        }
+    }
+    buffer
+}
+
+#[cfg(feature = "gif")]
+fn buffer_to_image(buffer: &Buffer) -> Image {
+    let rgba = buffer
+        .content()
+        .iter()
+        .flat_map(|cell| {
+            let background = color_to_rgb(cell.bg);
+            let foreground = (!cell.symbol().trim().is_empty())
+                .then(|| color_to_rgb(cell.fg))
+                .flatten();
+            let [r, g, b] = background.or(foreground).unwrap_or([0, 0, 0]);
+            [r, g, b, 255]
+        })
+        .collect();
+    Image {
+        width: buffer.area.width,
+        height: buffer.area.height,
+        rgba,
+    }
+}
+
+/// Encodes the images into an animated GIF. The size of the animation is the size of the largest image.
+#[cfg(feature = "gif")]
+fn encode_gif(path: &Path, fps: u32, images: Vec<Image>) -> Result<(), Box<dyn Error>> {
+    let width = images.iter().map(|image| image.width).max().unwrap_or(1);
+    let height = images.iter().map(|image| image.height).max().unwrap_or(1);
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = gif::Encoder::new(file, width, height, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    let mut delays = frame_delays(fps);
+    for mut image in images {
+        if image.width == 0 || image.height == 0 {
+            continue;
+        }
+        let mut frame = gif::Frame::from_rgba_speed(
+            image.width,
+            image.height,
+            &mut image.rgba,
+            QUANTIZATION_SPEED,
+        );
+        frame.delay = delays.next().unwrap_or(1);
+        encoder.write_frame(&frame)?;
+    }
+    Ok(())
+}
+
+/// The delays of consecutive GIF frames in hundredths of a second. GIFs can't store fractions of them, so
+/// the rounding error of every frame is carried over to the next one to keep the total duration, e.g.
+/// `2, 1, 2` at 60 fps. Frames take at least a hundredth of a second.
+#[cfg(feature = "gif")]
+pub(crate) fn frame_delays(fps: u32) -> impl Iterator<Item = u16> {
+    let frame_time = 100.0 / f64::from(fps.max(1));
+    let mut elapsed = 0u64;
+    (1u64..).map(move |frame| {
+        let target = (frame as f64 * frame_time).round() as u64;
+        let delay = target.saturating_sub(elapsed).max(1);
+        elapsed += delay;
+        delay.min(u64::from(u16::MAX)) as u16
+    })
+}
+
+fn encode_asciinema(
+    path: &Path,
+    width: u16,
+    height: u16,
+    events: Vec<(f64, String)>,
+) -> Result<(), Box<dyn Error>> {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let mut file = BufWriter::new(File::create(path)?);
+    writeln!(
+        file,
+        r#"{{"version": 2, "width": {width}, "height": {height}, "timestamp": {timestamp}, "env": {{"TERM": "xterm-256color"}}}}"#
+    )?;
+    for (time, output) in events {
+        writeln!(file, r#"[{time:.6}, "o", "{}"]"#, escape_json(&output))?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            character if character.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", character as u32);
+            }
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
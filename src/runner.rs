@@ -0,0 +1,159 @@
+use std::error::Error;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
+};
+use ratatui::crossterm::execute;
+
+use crate::canvas::ShaderCanvas;
+use crate::state::ShaderCanvasState;
+
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Options for [`run`].
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    /// Maximum number of frames rendered per second, further limited by the
+    /// [`PowerProfile`](crate::PowerProfile) of the canvas.
+    pub fps: u32,
+
+    /// Quits the application after the given [`Duration`]. Runs until quit by an event if `None`.
+    pub duration: Option<Duration>,
+
+    /// The [`ShaderCanvas`] used to render the shader.
+    pub canvas: ShaderCanvas,
+
+    /// Called for every terminal [`Event`]. Returning [`ControlFlow::Break`] quits the application. If
+    /// `None`, the application quits on `Esc`, `q` or `Ctrl+C`.
+    pub on_event: Option<fn(&Event, &mut ShaderCanvasState) -> ControlFlow<()>>,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            fps: 60,
+            duration: None,
+            canvas: ShaderCanvas::new(),
+            on_event: None,
+        }
+    }
+}
+
+/// Runs a shader fullscreen in the terminal. Sets up the terminal, reads input on a background thread,
+/// renders the [`ShaderCanvas`] at the configured frame rate and restores the terminal on exit or panic.
+/// Mouse input is captured and passed to the state, so shaders can react to dragging, scrolling and
+/// clicking, see [`Interaction`](crate::Interaction).
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tui_shader::{RunOptions, WgslShader};
+/// tui_shader::run(WgslShader::Path("shader.wgsl"), RunOptions {
+///     fps: 30,
+///     duration: Some(Duration::from_secs(5)),
+///     ..RunOptions::default()
+/// }).unwrap();
+/// ```
+pub fn run<'a, S>(shader: S, options: RunOptions) -> Result<(), Box<dyn Error>>
+where
+    S: TryInto<wgpu::ShaderModuleDescriptor<'a>>,
+    S::Error: Into<Box<dyn Error>>,
+{
+    let mut state = ShaderCanvasState::new(shader).map_err(Into::into)?;
+    let mut terminal = ratatui::init();
+    state.detect_cell_size();
+    let result = execute!(std::io::stdout(), EnableMouseCapture)
+        .map_err(Into::into)
+        .and_then(|()| run_loop(&mut terminal, &mut state, &options));
+    let _ = execute!(std::io::stdout(), DisableMouseCapture);
+    ratatui::restore();
+    result
+}
+
+fn run_loop(
+    terminal: &mut ratatui::DefaultTerminal,
+    state: &mut ShaderCanvasState,
+    options: &RunOptions,
+) -> Result<(), Box<dyn Error>> {
+    let (sender, receiver) = flume::unbounded();
+    let running = Arc::new(AtomicBool::new(true));
+    let input_thread = {
+        let running = Arc::clone(&running);
+        std::thread::spawn(move || -> std::io::Result<()> {
+            while running.load(Ordering::Relaxed) {
+                if event::poll(INPUT_POLL_INTERVAL)? && sender.send(event::read()?).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        })
+    };
+
+    let fps = match options.canvas.power_profile.max_fps() {
+        Some(max_fps) => options.fps.min(max_fps),
+        None => options.fps,
+    };
+    let frame_time = Duration::from_secs(1) / fps.max(1);
+    let start = Instant::now();
+    let result = loop {
+        let frame_start = Instant::now();
+        if options
+            .duration
+            .is_some_and(|duration| start.elapsed() >= duration)
+        {
+            break Ok(());
+        }
+        if let Err(error) = terminal.draw(|frame| {
+            frame.render_stateful_widget(&options.canvas, frame.area(), state);
+        }) {
+            break Err(error.into());
+        }
+        let deadline = frame_start + frame_time;
+        let mut control_flow = ControlFlow::Continue(());
+        while let Ok(event) = receiver.recv_deadline(deadline) {
+            if let Event::Resize(..) = event {
+                state.detect_cell_size();
+            }
+            state.handle_event(&event);
+            control_flow = match options.on_event {
+                Some(on_event) => on_event(&event, state),
+                None => default_on_event(&event),
+            };
+            if control_flow.is_break() {
+                break;
+            }
+        }
+        if control_flow.is_break() {
+            break Ok(());
+        }
+        if input_thread.is_finished() {
+            break Err("input thread stopped unexpectedly".into());
+        }
+    };
+
+    running.store(false, Ordering::Relaxed);
+    drop(receiver);
+    match input_thread.join() {
+        Ok(Err(error)) if result.is_ok() => Err(error.into()),
+        _ => result,
+    }
+}
+
+fn default_on_event(event: &Event) -> ControlFlow<()> {
+    let Event::Key(key) = event else {
+        return ControlFlow::Continue(());
+    };
+    if key.kind != KeyEventKind::Press {
+        return ControlFlow::Continue(());
+    }
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => ControlFlow::Break(()),
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            ControlFlow::Break(())
+        }
+        _ => ControlFlow::Continue(()),
+    }
+}
@@ -0,0 +1,691 @@
+use std::error::Error;
+use std::time::Instant;
+
+use bytemuck::{Pod, Zeroable};
+use pollster::FutureExt;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::StatefulWidget;
+use wgpu::util::DeviceExt;
+
+use crate::Pixel;
+use crate::camera::{Camera, IDENTITY, OrbitControls, multiply};
+use crate::canvas::ShaderCanvas;
+use crate::clock::ShaderClock;
+use crate::context::{DEFAULT_CELL_SIZE, ShaderContext};
+use crate::error::TuiShaderError;
+use crate::frame::Frame;
+use crate::gpu::{DEFAULT_SIZE, RenderTarget, create_input_buffer, uniform_layout_entry};
+use crate::interaction::PointerEvent;
+
+const SHADER_DESCRIPTOR: wgpu::ShaderModuleDescriptor = wgpu::include_wgsl!("shaders/scene.wgsl");
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// A vertex of a [`Mesh`], read by the vertex shader of a [`SceneState`] from `@location(0)` to
+/// `@location(2)`.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct SceneVertex {
+    /// The position in model space.
+    pub position: [f32; 3],
+
+    /// The normal in model space. The built-in shader draws vertices with a zero normal unlit.
+    pub normal: [f32; 3],
+
+    /// The RGB color between `0.0` and `1.0`.
+    pub color: [f32; 3],
+}
+
+impl SceneVertex {
+    /// Creates a new [`SceneVertex`].
+    pub fn new(position: [f32; 3], normal: [f32; 3], color: [f32; 3]) -> Self {
+        Self {
+            position,
+            normal,
+            color,
+        }
+    }
+}
+
+/// The vertices and indices of the geometry drawn by a [`SceneState`], either as triangles or as lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mesh {
+    vertices: Vec<SceneVertex>,
+    indices: Vec<u32>,
+    lines: bool,
+}
+
+impl Mesh {
+    /// Creates a mesh of triangles, each formed by three consecutive `indices`. Fails if the number of
+    /// indices isn't a multiple of three or an index is out of bounds.
+    pub fn new(vertices: Vec<SceneVertex>, indices: Vec<u32>) -> Result<Self, Box<dyn Error>> {
+        Self::checked(vertices, indices, 3, false)
+    }
+
+    /// Creates a mesh of lines, each formed by two consecutive `indices`. Fails if the number of indices
+    /// is odd or an index is out of bounds.
+    pub fn lines(vertices: Vec<SceneVertex>, indices: Vec<u32>) -> Result<Self, Box<dyn Error>> {
+        Self::checked(vertices, indices, 2, true)
+    }
+
+    fn checked(
+        vertices: Vec<SceneVertex>,
+        indices: Vec<u32>,
+        per_primitive: usize,
+        lines: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        if !indices.len().is_multiple_of(per_primitive) {
+            return Err(format!(
+                "expected a multiple of {per_primitive} indices, got {}",
+                indices.len()
+            )
+            .into());
+        }
+        if let Some(index) = indices
+            .iter()
+            .find(|&&index| index as usize >= vertices.len())
+        {
+            return Err(format!(
+                "the index {index} is out of bounds for {} vertices",
+                vertices.len()
+            )
+            .into());
+        }
+        Ok(Self {
+            vertices,
+            indices,
+            lines,
+        })
+    }
+
+    /// A cube with an edge length of `1.0` centered on the origin, with one normal per face.
+    pub fn cube(color: [f32; 3]) -> Self {
+        let mut vertices = Vec::with_capacity(24);
+        let mut indices = Vec::with_capacity(36);
+        for axis in 0..3 {
+            for sign in [-1.0, 1.0] {
+                let mut normal = [0.0; 3];
+                normal[axis] = sign;
+                let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+                let start = vertices.len() as u32;
+                for (du, dv) in [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)] {
+                    let mut position = [0.0; 3];
+                    position[axis] = sign * 0.5;
+                    position[u] = du;
+                    position[v] = dv;
+                    vertices.push(SceneVertex::new(position, normal, color));
+                }
+                indices.extend([0, 1, 2, 0, 2, 3].map(|index| start + index));
+            }
+        }
+        Self {
+            vertices,
+            indices,
+            lines: false,
+        }
+    }
+
+    /// The edges of the triangles of the mesh as lines, each shared edge drawn once. Lines are drawn
+    /// unlit by the built-in shader, so their normals are cleared. Meshes of lines are returned as is.
+    pub fn wireframe(&self) -> Self {
+        if self.lines {
+            return self.clone();
+        }
+        let mut edges = Vec::new();
+        for triangle in self.indices.chunks_exact(3) {
+            for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+                let edge = (triangle[a].min(triangle[b]), triangle[a].max(triangle[b]));
+                if !edges.contains(&edge) {
+                    edges.push(edge);
+                }
+            }
+        }
+        Self {
+            vertices: self
+                .vertices
+                .iter()
+                .map(|vertex| SceneVertex {
+                    normal: [0.0; 3],
+                    ..*vertex
+                })
+                .collect(),
+            indices: edges.into_iter().flat_map(|(a, b)| [a, b]).collect(),
+            lines: true,
+        }
+    }
+
+    /// Moves the center of the bounds of the mesh to the origin and scales it uniformly to fit into a cube
+    /// with an edge length of `2.0`, so loaded models of any size fill the view of the default
+    /// [`Camera`].
+    #[must_use]
+    pub fn centered(mut self) -> Self {
+        let Some(first) = self.vertices.first() else {
+            return self;
+        };
+        let (mut min, mut max) = (first.position, first.position);
+        for vertex in &self.vertices {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(vertex.position[axis]);
+                max[axis] = max[axis].max(vertex.position[axis]);
+            }
+        }
+        let center: [f32; 3] = std::array::from_fn(|axis| (min[axis] + max[axis]) / 2.0);
+        let extent = (0..3).map(|axis| max[axis] - min[axis]).fold(0.0, f32::max);
+        let scale = if extent > 0.0 { 2.0 / extent } else { 1.0 };
+        for vertex in &mut self.vertices {
+            vertex.position =
+                std::array::from_fn(|axis| (vertex.position[axis] - center[axis]) * scale);
+        }
+        self
+    }
+
+    /// The vertices of the mesh.
+    pub fn vertices(&self) -> &[SceneVertex] {
+        &self.vertices
+    }
+
+    /// The indices of the mesh.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Whether the mesh consists of lines instead of triangles.
+    pub fn is_lines(&self) -> bool {
+        self.lines
+    }
+}
+
+/// The uniforms of the shader of a [`SceneState`].
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct SceneUniforms {
+    view_projection: [[f32; 4]; 4],
+    model: [[f32; 4]; 4],
+    eye: [f32; 4],
+    time: [f32; 4],
+}
+
+/// [`SceneState`] renders a [`Mesh`] with a depth buffer, seen through a [`Camera`], so small 3D scenes
+/// like spinning models or wireframes can be drawn into cells with a [`SceneCanvas`].
+///
+/// The built-in shader colors triangles with their vertex colors, lit from the camera, and draws lines
+/// unlit. Custom shaders passed to [`SceneState::with_shader`] define a `vertex` and a `fragment` entry
+/// point, read the [`SceneVertex`] attributes and get the camera as a uniform:
+///
+/// ```wgsl
+/// struct Camera {
+///     view_projection: mat4x4<f32>,
+///     model: mat4x4<f32>,
+///     eye: vec4<f32>,
+///     // x = seconds
+///     time: vec4<f32>,
+/// };
+///
+/// @group(0) @binding(0) var<uniform> camera: Camera;
+///
+/// struct VertexOutput {
+///     @builtin(position) position: vec4<f32>,
+///     @location(0) normal: vec3<f32>,
+/// };
+///
+/// @vertex
+/// fn vertex(@location(0) position: vec3<f32>, @location(1) normal: vec3<f32>) -> VertexOutput {
+///     let world = camera.model * vec4<f32>(position, 1.0);
+///     return VertexOutput(camera.view_projection * world, normal);
+/// }
+///
+/// @fragment
+/// fn fragment(input: VertexOutput) -> @location(0) vec4<f32> {
+///     return vec4<f32>(input.normal * 0.5 + 0.5, 1.0);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SceneState {
+    target: RenderTarget,
+    triangle_pipeline: wgpu::RenderPipeline,
+    line_pipeline: wgpu::RenderPipeline,
+    bind_group: wgpu::BindGroup,
+    uniform_buffer: wgpu::Buffer,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    depth_texture: wgpu::Texture,
+    mesh: Mesh,
+    camera: Camera,
+    orbit_controls: OrbitControls,
+    model: [[f32; 4]; 4],
+    clock: ShaderClock,
+    cell_size: (u16, u16),
+}
+
+impl SceneState {
+    /// Creates a new [`SceneState`] drawing `mesh` with the built-in shader.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no GPU device can be created, see [`SceneState::try_new`].
+    pub fn new(mesh: Mesh) -> Self {
+        Self::try_new(mesh).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Creates a new [`SceneState`] drawing `mesh` with the built-in shader, failing with
+    /// [`TuiShaderError::NoBackend`] or [`TuiShaderError::NoDevice`] if no GPU device can be created.
+    pub fn try_new(mesh: Mesh) -> Result<Self, TuiShaderError> {
+        Self::new_inner(mesh, SHADER_DESCRIPTOR).block_on()
+    }
+
+    /// Creates a new [`SceneState`] drawing `mesh` with a custom shader, see [`SceneState`]. Fails if the
+    /// shader can't be read or compiled.
+    pub fn with_shader<'a, S>(mesh: Mesh, shader: S) -> Result<Self, Box<dyn Error>>
+    where
+        S: TryInto<wgpu::ShaderModuleDescriptor<'a>>,
+        S::Error: Into<Box<dyn Error>>,
+    {
+        Ok(Self::new_inner(mesh, shader.try_into().map_err(Into::into)?).block_on()?)
+    }
+
+    async fn new_inner(
+        mesh: Mesh,
+        shader_descriptor: wgpu::ShaderModuleDescriptor<'_>,
+    ) -> Result<Self, TuiShaderError> {
+        let target = RenderTarget::new()?;
+        let device = target.device();
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = device.create_shader_module(shader_descriptor);
+        let uniform_buffer = create_input_buffer(device, SceneUniforms::zeroed());
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[uniform_layout_entry(0, wgpu::ShaderStages::VERTEX_FRAGMENT)],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let triangle_pipeline = create_scene_pipeline(
+            device,
+            &pipeline_layout,
+            &shader,
+            wgpu::PrimitiveTopology::TriangleList,
+        );
+        let line_pipeline = create_scene_pipeline(
+            device,
+            &pipeline_layout,
+            &shader,
+            wgpu::PrimitiveTopology::LineList,
+        );
+        if let Some(error) = device.pop_error_scope().await {
+            return Err(TuiShaderError::Render(error.to_string()));
+        }
+        let (vertex_buffer, index_buffer) = create_mesh_buffers(device, &mesh);
+        Ok(Self {
+            depth_texture: create_depth_texture(device, DEFAULT_SIZE, DEFAULT_SIZE),
+            target,
+            triangle_pipeline,
+            line_pipeline,
+            bind_group,
+            uniform_buffer,
+            vertex_buffer,
+            index_buffer,
+            mesh,
+            camera: Camera::default(),
+            orbit_controls: OrbitControls::default(),
+            model: IDENTITY,
+            clock: ShaderClock::new(),
+            cell_size: DEFAULT_CELL_SIZE,
+        })
+    }
+
+    pub(crate) fn execute(&mut self, ctx: ShaderContext) -> Vec<Pixel> {
+        let ctx = ctx.with_cell_size(self.cell_size);
+        let width = ctx.width();
+        let height = ctx.height();
+        if self.depth_texture.width() != width || self.depth_texture.height() != height {
+            self.depth_texture = create_depth_texture(self.target.device(), width, height);
+        }
+
+        let aspect = ctx.cell[2] / ctx.cell[3];
+        let eye = self.camera.eye;
+        let uniforms = SceneUniforms {
+            view_projection: self.camera.view_projection(aspect),
+            model: self.model,
+            eye: [eye[0], eye[1], eye[2], 1.0],
+            time: ctx.time,
+        };
+        let depth_view = self
+            .depth_texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        self.target
+            .execute(width, height, |_, queue, command_encoder, texture_view| {
+                queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+                let mut render_pass =
+                    command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: None,
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            view: texture_view,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                                store: wgpu::StoreOp::Store,
+                            },
+                            depth_slice: None,
+                        })],
+                        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                            view: &depth_view,
+                            depth_ops: Some(wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(1.0),
+                                store: wgpu::StoreOp::Discard,
+                            }),
+                            stencil_ops: None,
+                        }),
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                    });
+                if !self.mesh.indices.is_empty() {
+                    render_pass.set_pipeline(if self.mesh.lines {
+                        &self.line_pipeline
+                    } else {
+                        &self.triangle_pipeline
+                    });
+                    render_pass.set_bind_group(0, &self.bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+                    render_pass
+                        .set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                    render_pass.draw_indexed(0..self.mesh.indices.len() as u32, 0, 0..1);
+                }
+            })
+    }
+
+    /// Replaces the drawn mesh.
+    pub fn set_mesh(&mut self, mesh: Mesh) {
+        (self.vertex_buffer, self.index_buffer) = create_mesh_buffers(self.target.device(), &mesh);
+        self.mesh = mesh;
+    }
+
+    /// Gets the drawn [`Mesh`].
+    pub fn mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+
+    /// Sets the [`Camera`] the mesh is seen through.
+    pub fn set_camera(&mut self, camera: Camera) {
+        self.camera = camera;
+    }
+
+    /// Gets the [`Camera`] the mesh is seen through.
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Gets a mutable reference to the [`Camera`], e.g. to orbit around the mesh.
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    /// Sets the [`OrbitControls`] applied by [`SceneState::handle_pointer`].
+    pub fn set_orbit_controls(&mut self, controls: OrbitControls) {
+        self.orbit_controls = controls;
+    }
+
+    /// Orbits the [`Camera`] around its target when dragging and zooms when scrolling, see
+    /// [`OrbitControls`].
+    pub fn handle_pointer(&mut self, event: PointerEvent) {
+        self.orbit_controls.handle(&mut self.camera, event);
+    }
+
+    /// Same as [`SceneState::handle_pointer`] for a crossterm [`Event`](ratatui::crossterm::event::Event).
+    /// Returns `false` if the event isn't pointer input, see [`PointerEvent::from_crossterm`].
+    #[cfg(feature = "runner")]
+    pub fn handle_event(&mut self, event: &ratatui::crossterm::event::Event) -> bool {
+        let Some(event) = PointerEvent::from_crossterm(event) else {
+            return false;
+        };
+        self.handle_pointer(event);
+        true
+    }
+
+    /// Sets the matrix transforming the mesh into world space, in columns like the `mat4x4<f32>` of
+    /// WGSL.
+    pub fn set_model(&mut self, model: [[f32; 4]; 4]) {
+        self.model = model;
+    }
+
+    /// Rotates the mesh by `x`, `y` and `z` radians around the axes of the same name, in this order,
+    /// replacing the model matrix.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{Mesh, SceneState};
+    /// let mut state = SceneState::new(Mesh::cube([0.2, 0.8, 1.0]));
+    /// let time = state.clock().elapsed().as_secs_f32();
+    /// state.set_rotation(time * 0.5, time, 0.0);
+    /// ```
+    pub fn set_rotation(&mut self, x: f32, y: f32, z: f32) {
+        let (sx, cx) = x.sin_cos();
+        let (sy, cy) = y.sin_cos();
+        let (sz, cz) = z.sin_cos();
+        let rotate_x = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, cx, sx, 0.0],
+            [0.0, -sx, cx, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        let rotate_y = [
+            [cy, 0.0, -sy, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [sy, 0.0, cy, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        let rotate_z = [
+            [cz, sz, 0.0, 0.0],
+            [-sz, cz, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        self.model = multiply(rotate_z, multiply(rotate_y, rotate_x));
+    }
+
+    /// Sets the size of a cell in screen pixels, which determines the aspect ratio of the camera. Defaults
+    /// to 8x16 pixels.
+    pub fn set_cell_size(&mut self, width: u16, height: u16) {
+        self.cell_size = (width.max(1), height.max(1));
+    }
+
+    /// Queries the size of a terminal cell in screen pixels from the terminal, like
+    /// [`ShaderCanvasState::detect_cell_size`](crate::ShaderCanvasState::detect_cell_size), and uses it
+    /// for the aspect ratio of the camera. Returns `false` if the terminal doesn't report its size in
+    /// pixels, in which case the previous size is kept.
+    pub fn detect_cell_size(&mut self) -> bool {
+        let Some((width, height)) = crate::terminal::cell_size() else {
+            return false;
+        };
+        self.set_cell_size(width, height);
+        true
+    }
+
+    /// Gets the [`SceneState`]'s [`Instant`].
+    pub fn get_instant(&self) -> Instant {
+        self.clock.get_instant()
+    }
+
+    /// Gets the [`ShaderClock`] driving the `time` input of the [`SceneState`].
+    pub fn clock(&self) -> &ShaderClock {
+        &self.clock
+    }
+
+    /// Gets a mutable reference to the [`ShaderClock`], e.g. to pause and resume the animation.
+    pub fn clock_mut(&mut self) -> &mut ShaderClock {
+        &mut self.clock
+    }
+
+    /// Registers `callback` to receive the errors of drawing the mesh on the GPU, like
+    /// [`ShaderCanvasState::on_error`](crate::ShaderCanvasState::on_error), instead of panicking inside
+    /// `terminal.draw`. A failed frame is passed to the [`SceneCanvas`] as transparent pixels, which its
+    /// [`ShaderCanvas`] maps like any other frame.
+    pub fn on_error(&mut self, callback: fn(TuiShaderError)) {
+        self.target.on_error(callback);
+    }
+}
+
+/// [`SceneCanvas`] draws the [`Mesh`] of a [`SceneState`] into cells, mapped by a [`ShaderCanvas`].
+///
+/// ```rust,no_run
+/// # use tui_shader::{Mesh, SceneCanvas, SceneState};
+/// let mut terminal = ratatui::init();
+/// let mut state = SceneState::new(Mesh::cube([1.0, 0.6, 0.2]).wireframe());
+/// loop {
+///     let time = state.clock().elapsed().as_secs_f32();
+///     state.set_rotation(time * 0.7, time, 0.0);
+///     terminal.draw(|frame| {
+///         frame.render_stateful_widget(SceneCanvas::new(), frame.area(), &mut state);
+///     }).unwrap();
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct SceneCanvas {
+    canvas: ShaderCanvas,
+}
+
+impl SceneCanvas {
+    /// Creates a new instance of [`SceneCanvas`]. Equivalent to [`SceneCanvas::default()`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the [`ShaderCanvas`] mapping the rendered pixels to cells. Its
+    /// [`region`](ShaderCanvas::region) is ignored.
+    #[must_use]
+    pub fn canvas(mut self, canvas: ShaderCanvas) -> Self {
+        self.canvas = canvas;
+        self
+    }
+}
+
+impl StatefulWidget for SceneCanvas {
+    type State = SceneState;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(&self, area, buf, state);
+    }
+}
+
+impl StatefulWidget for &SceneCanvas {
+    type State = SceneState;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        if area.is_empty() {
+            return;
+        }
+        let (width, height) = self.canvas.scaled_size(area);
+        let time = state.clock().elapsed().as_secs_f32();
+        state.clock_mut().tick();
+        let ctx = ShaderContext::new(time, area).with_size(width, height);
+        let frame = Frame::new(width, height, state.execute(ctx));
+        self.canvas.style_frame(&frame, area, buf);
+    }
+}
+
+#[cfg(feature = "widget-ref")]
+impl ratatui::widgets::StatefulWidgetRef for SceneCanvas {
+    type State = SceneState;
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        StatefulWidget::render(self, area, buf, state);
+    }
+}
+
+fn create_scene_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    topology: wgpu::PrimitiveTopology,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vertex"),
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: size_of::<SceneVertex>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![
+                    0 => Float32x3,
+                    1 => Float32x3,
+                    2 => Float32x3,
+                ],
+            }],
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fragment"),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology,
+            ..Default::default()
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: DEPTH_FORMAT,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
+
+fn create_depth_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: DEPTH_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    })
+}
+
+/// Uploads the vertices and indices of `mesh`. Empty meshes get a placeholder, since buffers can't be
+/// empty.
+fn create_mesh_buffers(device: &wgpu::Device, mesh: &Mesh) -> (wgpu::Buffer, wgpu::Buffer) {
+    let vertices: &[SceneVertex] = if mesh.vertices.is_empty() {
+        &[SceneVertex::default()]
+    } else {
+        &mesh.vertices
+    };
+    let indices: &[u32] = if mesh.indices.is_empty() {
+        &[0]
+    } else {
+        &mesh.indices
+    };
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+    (vertex_buffer, index_buffer)
+}
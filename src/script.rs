@@ -0,0 +1,219 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use rhai::{AST, Dynamic, Engine, EvalAltResult, FLOAT, INT, Scope};
+
+use crate::cpu::{CpuBackend, PixelInput};
+
+const DEFAULT_MAX_OPERATIONS: u64 = 100_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_STRING_SIZE: usize = 4096;
+const MAX_COLLECTION_SIZE: usize = 4096;
+
+/// [`ScriptBackend`] is a [`CpuBackend`] running a [rhai](https://rhai.rs) script for every pixel. The
+/// script is reloaded whenever the file changes on disk, which allows users of an app to customize its
+/// effects without recompiling it.
+///
+/// The script is the body of the per-pixel function. It can read the variables `x`, `y`, `u`, `v`, `time`,
+/// `width` and `height` (see [`PixelInput`]) as well as all uniforms set with
+/// [`ShaderCanvasState::set_uniform`](crate::ShaderCanvasState::set_uniform), and evaluates to an array
+/// of `[r, g, b]` or `[r, g, b, a]`. Floats are expected between `0.0` and `1.0`, integers between `0`
+/// and `255`. Since the script declares no uniforms, setting a uniform of any name succeeds.
+///
+/// ```rhai
+/// let wave = sin(u * 10.0 + time) * 0.5 + 0.5;
+/// [wave, 0.2, 1.0 - v]
+/// ```
+///
+/// Every evaluation is limited to 100 000 operations by default (see [`ScriptBackend::max_operations`]),
+/// as well as in call depth, expression depth and string and array sizes. A script hitting a limit
+/// renders the rest of the frame black and reports the error through [`ScriptBackend::last_error`]
+/// instead of hanging the app. `print` and `debug` never write to the terminal: they go to the
+/// [`log`](https://docs.rs/log) facade when the `log-capture` feature is enabled and are discarded
+/// otherwise.
+///
+/// ```rust,no_run
+/// # use tui_shader::{ScriptBackend, ShaderCanvasState};
+/// let backend = ScriptBackend::new("effects/wave.rhai").unwrap();
+/// let state = ShaderCanvasState::from_backend(backend);
+/// ```
+pub struct ScriptBackend {
+    engine: Engine,
+    path: PathBuf,
+    ast: AST,
+    modified: Option<SystemTime>,
+    uniforms: Vec<(String, FLOAT)>,
+    scope: Scope<'static>,
+    last_error: Option<String>,
+    exhausted: bool,
+}
+
+impl ScriptBackend {
+    /// Creates a new [`ScriptBackend`] from the script at `path`. Fails if the script can't be read or
+    /// doesn't compile.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref().to_path_buf();
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(DEFAULT_MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS)
+            .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH)
+            .set_max_string_size(MAX_STRING_SIZE)
+            .set_max_array_size(MAX_COLLECTION_SIZE)
+            .set_max_map_size(MAX_COLLECTION_SIZE);
+        #[cfg(feature = "log-capture")]
+        engine
+            .on_print(|text| log::info!(target: "tui_shader::script", "{text}"))
+            .on_debug(
+                |text, _, position| log::debug!(target: "tui_shader::script", "{position}: {text}"),
+            );
+        #[cfg(not(feature = "log-capture"))]
+        engine.on_print(|_| {}).on_debug(|_, _, _| {});
+        let modified = modified_time(&path);
+        let ast = engine.compile_file(path.clone())?;
+        Ok(Self {
+            engine,
+            path,
+            ast,
+            modified,
+            uniforms: Vec::new(),
+            scope: Scope::new(),
+            last_error: None,
+            exhausted: false,
+        })
+    }
+
+    /// Sets the number of operations a single evaluation of the script may perform before it is aborted.
+    /// Defaults to 100 000.
+    #[must_use]
+    pub fn max_operations(mut self, operations: u64) -> Self {
+        self.engine.set_max_operations(operations);
+        self
+    }
+
+    /// The path of the script.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The error of the last reload or evaluation of the script, if it failed. A script that fails to
+    /// reload is replaced by the previous version until it is fixed.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    fn reload(&mut self) {
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+        match self.engine.compile_file(self.path.clone()) {
+            Ok(ast) => {
+                self.ast = ast;
+                self.last_error = None;
+            }
+            Err(error) => self.last_error = Some(error.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Debug for ScriptBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptBackend")
+            .field("path", &self.path)
+            .field("uniforms", &self.uniforms)
+            .field("last_error", &self.last_error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CpuBackend for ScriptBackend {
+    fn prepare(&mut self, time: f32, width: u32, height: u32) {
+        self.reload();
+        self.exhausted = false;
+        self.scope.clear();
+        self.scope
+            .push("x", 0 as INT)
+            .push("y", 0 as INT)
+            .push("u", 0.0 as FLOAT)
+            .push("v", 0.0 as FLOAT)
+            .push_constant("time", time as FLOAT)
+            .push_constant("width", width as INT)
+            .push_constant("height", height as INT);
+        for (name, value) in &self.uniforms {
+            self.scope.push_constant(name.as_str(), *value);
+        }
+    }
+
+    fn pixel(&mut self, input: PixelInput) -> [u8; 4] {
+        if self.exhausted {
+            return [0, 0, 0, 255];
+        }
+        let length = self.scope.len();
+        self.scope
+            .set_value("x", input.x as INT)
+            .set_value("y", input.y as INT)
+            .set_value("u", input.u as FLOAT)
+            .set_value("v", input.v as FLOAT);
+        let result = self
+            .engine
+            .eval_ast_with_scope::<Dynamic>(&mut self.scope, &self.ast);
+        self.scope.rewind(length);
+        if let Err(error) = &result {
+            // A script hitting a limit likely hits it for every pixel, so the rest of the frame is skipped.
+            self.exhausted = matches!(
+                **error,
+                EvalAltResult::ErrorTooManyOperations(_)
+                    | EvalAltResult::ErrorStackOverflow(_)
+                    | EvalAltResult::ErrorDataTooLarge(..)
+            );
+        }
+        match result.map_err(|error| error.to_string()).and_then(to_color) {
+            Ok(color) => color,
+            Err(error) => {
+                self.last_error = Some(error);
+                [0, 0, 0, 255]
+            }
+        }
+    }
+
+    fn set_uniform(&mut self, name: &str, value: f32) -> bool {
+        match self.uniforms.iter_mut().find(|(n, _)| n == name) {
+            Some((_, uniform)) => *uniform = value as FLOAT,
+            None => self.uniforms.push((name.to_string(), value as FLOAT)),
+        }
+        true
+    }
+}
+
+fn to_color(value: Dynamic) -> Result<[u8; 4], String> {
+    let channels = value
+        .into_array()
+        .map_err(|type_name| format!("expected the script to return an array, got {type_name}"))?;
+    if !(3..=4).contains(&channels.len()) {
+        return Err(format!(
+            "expected the script to return 3 or 4 channels, got {}",
+            channels.len()
+        ));
+    }
+    let mut color = [255; 4];
+    for (channel, value) in color.iter_mut().zip(channels) {
+        *channel = if let Some(value) = value.clone().try_cast::<FLOAT>() {
+            (value.clamp(0.0, 1.0) * 255.0).round() as u8
+        } else if let Some(value) = value.try_cast::<INT>() {
+            value.clamp(0, 255) as u8
+        } else {
+            return Err(String::from("expected the channels to be numbers"));
+        };
+    }
+    Ok(color)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
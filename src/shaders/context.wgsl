@@ -0,0 +1,17 @@
+// The built-in inputs of tui-shader, see `ShaderContext`.
+const TUI_SHADER_CONTEXT_VERSION: u32 = 1u;
+
+// x: seconds, y: seconds * 10, z: sin(seconds), w: cos(seconds)
+@group(0) @binding(0) var<uniform> time: vec4<f32>;
+// xy: position of the rect in cells, zw: size of the rendered area in pixels
+@group(0) @binding(1) var<uniform> rect: vec4<u32>;
+// x: seconds since local midnight, y: year, z: month, w: day of the month
+@group(0) @binding(4) var<uniform> date: vec4<f32>;
+// xy: size of a cell in screen pixels, zw: size of the rect in screen pixels
+@group(0) @binding(5) var<uniform> cell: vec4<f32>;
+// xy: normalized focus point, z: 1.0 if a focus point is set
+@group(0) @binding(6) var<uniform> focus: vec4<f32>;
+// xy: scroll offset in cells, zw: shift of uv caused by the offset
+@group(0) @binding(7) var<uniform> scroll: vec4<f32>;
+// x: id of the rendered instance
+@group(0) @binding(8) var<uniform> instance: vec4<u32>;
@@ -0,0 +1,31 @@
+struct Uniforms {
+    color: vec4<f32>,
+    // The focused rect in cells, relative to the rendered area: x, y, width, height.
+    focus: vec4<f32>,
+    radius: f32,
+    speed: f32,
+}
+
+@group(0) @binding(0) var<uniform> time: vec4<f32>;
+@group(0) @binding(1) var<uniform> rect: vec4<u32>;
+@group(0) @binding(2) var<uniform> uniforms: Uniforms;
+
+@fragment
+fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let cell = uv * vec2<f32>(f32(rect.z), f32(rect.w));
+    let focus_min = uniforms.focus.xy;
+    let focus_max = uniforms.focus.xy + uniforms.focus.zw;
+    // Cells are about twice as high as they are wide.
+    let outside = max(max(focus_min - cell, cell - focus_max), vec2<f32>(0.0));
+    let distance = length(outside * vec2<f32>(1.0, 2.0));
+    if distance <= 0.0 {
+        return vec4<f32>(0.0);
+    }
+    let falloff = clamp(1.0 - distance / max(uniforms.radius, 0.001), 0.0, 1.0);
+
+    // A highlight running around the rect.
+    let center = (focus_min + focus_max) * 0.5;
+    let angle = atan2((cell.y - center.y) * 2.0, cell.x - center.x);
+    let pulse = 0.7 + 0.3 * sin(angle * 2.0 - time.x * uniforms.speed * 3.0);
+    return vec4<f32>(uniforms.color.rgb, falloff * falloff * pulse);
+}
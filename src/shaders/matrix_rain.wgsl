@@ -0,0 +1,51 @@
+struct Column {
+    head: f32,
+    speed: f32,
+    trail: f32,
+    seed: f32,
+};
+
+struct Params {
+    head_color: vec4<f32>,
+    trail_color: vec4<f32>,
+    resolution: vec2<f32>,
+    delta_time: f32,
+    time: f32,
+    speed: f32,
+    density: f32,
+    trail_length: f32,
+    fade: f32,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> columns: array<Column>;
+
+fn hash(value: u32) -> u32 {
+    var state = value * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+fn random(seed: ptr<function, u32>) -> f32 {
+    *seed = hash(*seed);
+    return f32(*seed) / 4294967295.0;
+}
+
+@compute @workgroup_size(64)
+fn update(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if index >= u32(params.resolution.x) {
+        return;
+    }
+    var column = columns[index];
+    column.head += column.speed * params.delta_time;
+    if column.speed <= 0.0 || column.head - column.trail > params.resolution.y {
+        var seed = hash(index ^ bitcast<u32>(params.time));
+        let delay = random(&seed) * params.resolution.y / max(params.density, 0.01);
+        column.head = -delay;
+        column.speed = params.speed * mix(0.5, 1.5, random(&seed));
+        column.trail = params.trail_length * mix(0.5, 1.5, random(&seed));
+        column.seed = random(&seed);
+    }
+    columns[index] = column;
+}
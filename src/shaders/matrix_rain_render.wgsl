@@ -0,0 +1,47 @@
+struct Column {
+    head: f32,
+    speed: f32,
+    trail: f32,
+    seed: f32,
+};
+
+struct Params {
+    head_color: vec4<f32>,
+    trail_color: vec4<f32>,
+    resolution: vec2<f32>,
+    delta_time: f32,
+    time: f32,
+    speed: f32,
+    density: f32,
+    trail_length: f32,
+    fade: f32,
+};
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> columns: array<Column>;
+
+fn hash(value: u32) -> u32 {
+    var state = value * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+// The alpha channel does not hold transparency, it encodes which glyph of the charset to use.
+// A value of zero marks an empty cell.
+@fragment
+fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let cell = vec2<u32>(position.xy);
+    let column = columns[cell.x];
+    let distance = floor(column.head) - f32(cell.y);
+    if distance < 0.0 || distance > column.trail {
+        return vec4<f32>(0.0);
+    }
+    let tick = u32(params.time * 8.0 + column.seed * 64.0);
+    let glyph = hash(cell.x * 7919u + cell.y * 104729u + tick) % 255u + 1u;
+    var color = params.head_color.rgb;
+    if distance >= 1.0 {
+        let intensity = pow(1.0 - distance / (column.trail + 1.0), params.fade);
+        color = params.trail_color.rgb * intensity;
+    }
+    return vec4<f32>(color, f32(glyph) / 255.0);
+}
@@ -0,0 +1,66 @@
+struct Particle {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+    age: f32,
+    lifetime: f32,
+    seed: f32,
+    padding: f32,
+};
+
+struct Params {
+    gravity: vec2<f32>,
+    wind: vec2<f32>,
+    velocity: vec2<f32>,
+    emitter_min: vec2<f32>,
+    emitter_max: vec2<f32>,
+    delta_time: f32,
+    time: f32,
+    lifetime: f32,
+    spread: f32,
+    spawn_budget: u32,
+    particle_count: u32,
+};
+
+@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(1) var<uniform> params: Params;
+@group(0) @binding(2) var<storage, read_write> spawned: atomic<u32>;
+
+fn hash(value: u32) -> u32 {
+    var state = value * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+fn random(seed: ptr<function, u32>) -> f32 {
+    *seed = hash(*seed);
+    return f32(*seed) / 4294967295.0;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if index >= params.particle_count {
+        return;
+    }
+    var particle = particles[index];
+    if particle.age < particle.lifetime {
+        particle.velocity += params.gravity * params.delta_time;
+        particle.position += (particle.velocity + params.wind) * params.delta_time;
+        particle.age += params.delta_time;
+        let outside = particle.position.x < -0.1 || particle.position.x > 1.1
+            || particle.position.y < -0.1 || particle.position.y > 1.1;
+        if outside {
+            particle.age = particle.lifetime;
+        }
+    } else if atomicAdd(&spawned, 1u) < params.spawn_budget {
+        var seed = hash(index ^ bitcast<u32>(params.time));
+        let offset = vec2<f32>(random(&seed), random(&seed));
+        let jitter = vec2<f32>(random(&seed), random(&seed)) * 2.0 - 1.0;
+        particle.position = mix(params.emitter_min, params.emitter_max, offset);
+        particle.velocity = params.velocity + jitter * params.spread;
+        particle.age = 0.0;
+        particle.lifetime = params.lifetime * mix(0.5, 1.0, random(&seed));
+        particle.seed = random(&seed);
+    }
+    particles[index] = particle;
+}
@@ -0,0 +1,30 @@
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) fade: f32,
+};
+
+struct RenderParams {
+    color: vec4<f32>,
+    resolution: vec2<f32>,
+};
+
+@group(0) @binding(0) var<uniform> params: RenderParams;
+
+@vertex
+fn vertex(@location(0) position: vec2<f32>, @location(1) life: vec2<f32>) -> VertexOutput {
+    var output: VertexOutput;
+    if life.x >= life.y {
+        output.position = vec4<f32>(2.0, 2.0, 0.0, 1.0);
+        output.fade = 0.0;
+        return output;
+    }
+    let cell = (floor(position * params.resolution) + 0.5) / params.resolution;
+    output.position = vec4<f32>(cell.x * 2.0 - 1.0, 1.0 - cell.y * 2.0, 0.0, 1.0);
+    output.fade = 1.0 - life.x / life.y;
+    return output;
+}
+
+@fragment
+fn fragment(@location(0) fade: f32) -> @location(0) vec4<f32> {
+    return vec4<f32>(params.color.rgb, params.color.a * fade);
+}
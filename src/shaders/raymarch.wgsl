@@ -0,0 +1,90 @@
+// The ray marcher of `presets::raymarch`, appended to the `scene` function provided by the app.
+#include <camera>
+
+struct Uniforms {
+    camera_eye: vec3<f32>,
+    camera_fov: f32,
+    camera_forward: vec3<f32>,
+    // 0: low, 1: medium, 2: high, see `RaymarchQuality`.
+    quality: u32,
+    camera_right: vec3<f32>,
+    fog_density: f32,
+    camera_up: vec3<f32>,
+    surface_color: vec4<f32>,
+    fog_color: vec4<f32>,
+    light_direction: vec3<f32>,
+}
+
+@group(0) @binding(2) var<uniform> uniforms: Uniforms;
+@group(0) @binding(5) var<uniform> cell: vec4<f32>;
+
+const MAX_DISTANCE: f32 = 100.0;
+const HIT_DISTANCE: f32 = 0.001;
+
+fn max_steps() -> i32 {
+    return select(select(24, 64, uniforms.quality == 1u), 128, uniforms.quality >= 2u);
+}
+
+fn occlusion_samples() -> i32 {
+    return select(select(0, 3, uniforms.quality == 1u), 5, uniforms.quality >= 2u);
+}
+
+fn scene_normal(p: vec3<f32>) -> vec3<f32> {
+    let e = vec2<f32>(0.001, 0.0);
+    return normalize(vec3<f32>(
+        scene(p + e.xyy) - scene(p - e.xyy),
+        scene(p + e.yxy) - scene(p - e.yxy),
+        scene(p + e.yyx) - scene(p - e.yyx),
+    ));
+}
+
+// Darkens creases by sampling the distance field along the normal.
+fn ambient_occlusion(p: vec3<f32>, normal: vec3<f32>) -> f32 {
+    var occlusion = 0.0;
+    var weight = 1.0;
+    let samples = occlusion_samples();
+    for (var i = 1; i <= samples; i++) {
+        let offset = 0.05 * f32(i);
+        occlusion += (offset - scene(p + normal * offset)) * weight;
+        weight *= 0.6;
+    }
+    return clamp(1.0 - occlusion * 3.0, 0.0, 1.0);
+}
+
+@fragment
+fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let direction = camera_ray(
+        uv,
+        cell.z / max(cell.w, 1.0),
+        uniforms.camera_fov,
+        uniforms.camera_forward,
+        uniforms.camera_right,
+        uniforms.camera_up,
+    );
+    var distance = 0.0;
+    var hit = false;
+    let steps = max_steps();
+    for (var i = 0; i < steps; i++) {
+        let step = scene(uniforms.camera_eye + direction * distance);
+        if step < HIT_DISTANCE * max(distance, 1.0) {
+            hit = true;
+            break;
+        }
+        distance += step;
+        if distance > MAX_DISTANCE {
+            break;
+        }
+    }
+    if !hit {
+        return vec4<f32>(uniforms.fog_color.rgb, 1.0);
+    }
+    let p = uniforms.camera_eye + direction * distance;
+    let normal = scene_normal(p);
+    let light = normalize(uniforms.light_direction);
+    let diffuse = max(dot(normal, light), 0.0);
+    let specular = pow(max(dot(reflect(-light, normal), -direction), 0.0), 16.0) * 0.3;
+    let occlusion = ambient_occlusion(p, normal);
+    let color = uniforms.surface_color.rgb * (0.15 + 0.85 * diffuse) * occlusion + specular;
+    let fog = 1.0 - exp(-uniforms.fog_density * distance * distance);
+    return vec4<f32>(mix(color, uniforms.fog_color.rgb, fog), 1.0);
+}
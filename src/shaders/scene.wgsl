@@ -0,0 +1,44 @@
+struct Camera {
+    view_projection: mat4x4<f32>,
+    model: mat4x4<f32>,
+    eye: vec4<f32>,
+    time: vec4<f32>,
+};
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) color: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) world: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) color: vec3<f32>,
+};
+
+@group(0) @binding(0) var<uniform> camera: Camera;
+
+@vertex
+fn vertex(input: VertexInput) -> VertexOutput {
+    var output: VertexOutput;
+    let world = camera.model * vec4<f32>(input.position, 1.0);
+    output.position = camera.view_projection * world;
+    output.world = world.xyz;
+    output.normal = (camera.model * vec4<f32>(input.normal, 0.0)).xyz;
+    output.color = input.color;
+    return output;
+}
+
+@fragment
+fn fragment(input: VertexOutput) -> @location(0) vec4<f32> {
+    // Lines have no normal and are drawn unlit.
+    if dot(input.normal, input.normal) < 1e-8 {
+        return vec4<f32>(input.color, 1.0);
+    }
+    // Lit from the camera, on both sides of every face.
+    let light = normalize(camera.eye.xyz - input.world);
+    let diffuse = abs(dot(normalize(input.normal), light));
+    return vec4<f32>(input.color * (0.25 + 0.75 * diffuse), 1.0);
+}
@@ -0,0 +1,15 @@
+// Rays of a perspective camera, e.g. the one written to the `camera_*` uniforms by `Camera`.
+
+// The normalized direction of the ray through `uv` of a camera with the given aspect ratio, vertical
+// field of view in radians and normalized axes.
+fn camera_ray(
+    uv: vec2<f32>,
+    aspect: f32,
+    fov_y: f32,
+    forward: vec3<f32>,
+    right: vec3<f32>,
+    up: vec3<f32>,
+) -> vec3<f32> {
+    let screen = (uv * 2.0 - 1.0) * vec2<f32>(aspect, 1.0) * tan(fov_y * 0.5);
+    return normalize(forward + right * screen.x + up * screen.y);
+}
@@ -0,0 +1,18 @@
+// Fractal Brownian motion, built on the `noise` module.
+
+// Sums `octaves` layers of `value_noise`, each with double the frequency and half the amplitude of
+// the previous one. Returns values between 0.0 and 1.0.
+fn fbm(p: vec2<f32>, octaves: i32) -> f32 {
+    var value = 0.0;
+    var amplitude = 0.5;
+    var total = 0.0;
+    var position = p;
+    for (var octave = 0; octave < octaves; octave++) {
+        value += amplitude * value_noise(position);
+        total += amplitude;
+        // Shifts every octave, so the lattices of the layers don't line up.
+        position = position * 2.0 + vec2<f32>(17.3, 31.7);
+        amplitude *= 0.5;
+    }
+    return value / max(total, 1e-6);
+}
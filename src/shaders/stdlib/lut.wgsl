@@ -0,0 +1,10 @@
+// Color grading with 3D lookup tables.
+
+// Maps `color` through the lookup table `lut`, e.g. a `CubeLut`, sampled with a linear `lut_sampler`.
+// The coordinates are moved to the centers of the outer texels, so black and white hit the corners of
+// the table.
+fn apply_lut(lut: texture_3d<f32>, lut_sampler: sampler, color: vec3<f32>) -> vec3<f32> {
+    let size = vec3<f32>(textureDimensions(lut));
+    let coords = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)) * (size - 1.0) / size + 0.5 / size;
+    return textureSampleLevel(lut, lut_sampler, coords, 0.0).rgb;
+}
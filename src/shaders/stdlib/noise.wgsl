@@ -0,0 +1,44 @@
+// 2D noise functions, built on the `hash` module.
+
+// Smoothly interpolated random values between 0.0 and 1.0.
+fn value_noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+    let a = hash21(i);
+    let b = hash21(i + vec2<f32>(1.0, 0.0));
+    let c = hash21(i + vec2<f32>(0.0, 1.0));
+    let d = hash21(i + vec2<f32>(1.0, 1.0));
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+
+// Gradient noise between about -0.7 and 0.7, with a quintic fade.
+fn perlin_noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+    let a = dot(hash22(i) * 2.0 - 1.0, f);
+    let b = dot(hash22(i + vec2<f32>(1.0, 0.0)) * 2.0 - 1.0, f - vec2<f32>(1.0, 0.0));
+    let c = dot(hash22(i + vec2<f32>(0.0, 1.0)) * 2.0 - 1.0, f - vec2<f32>(0.0, 1.0));
+    let d = dot(hash22(i + vec2<f32>(1.0, 1.0)) * 2.0 - 1.0, f - vec2<f32>(1.0, 1.0));
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+
+// Simplex noise between about -1.0 and 1.0, with fewer directional artifacts than `perlin_noise`.
+fn simplex_noise(p: vec2<f32>) -> f32 {
+    let k1 = 0.366025404; // (sqrt(3) - 1) / 2
+    let k2 = 0.211324865; // (3 - sqrt(3)) / 6
+    let i = floor(p + (p.x + p.y) * k1);
+    let a = p - i + (i.x + i.y) * k2;
+    let m = step(a.y, a.x);
+    let o = vec2<f32>(m, 1.0 - m);
+    let b = a - o + k2;
+    let c = a - 1.0 + 2.0 * k2;
+    let h = max(vec3<f32>(0.5) - vec3<f32>(dot(a, a), dot(b, b), dot(c, c)), vec3<f32>(0.0));
+    let n = h * h * h * h * vec3<f32>(
+        dot(a, hash22(i) * 2.0 - 1.0),
+        dot(b, hash22(i + o) * 2.0 - 1.0),
+        dot(c, hash22(i + 1.0) * 2.0 - 1.0),
+    );
+    return dot(n, vec3<f32>(70.0));
+}
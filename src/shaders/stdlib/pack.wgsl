@@ -0,0 +1,31 @@
+// Packing two colors, or a color and a glyph index, into the 24 bits of the color of the output,
+// decoded in style rules with `Sample::unpack_rgb444_pair`, `Sample::unpack_rgb565` and
+// `Sample::glyph_index`. The output is blended over opaque black, so alpha can't carry data and is
+// always 1.0.
+
+// Quantizes `color` to 4 bits per channel.
+fn pack_rgb444(color: vec3<f32>) -> u32 {
+    let c = vec3<u32>(round(clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)) * 15.0));
+    return (c.r << 8u) | (c.g << 4u) | c.b;
+}
+
+// Quantizes `color` to 5 bits of red, 6 bits of green and 5 bits of blue.
+fn pack_rgb565(color: vec3<f32>) -> u32 {
+    let c = vec3<u32>(round(clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)) * vec3<f32>(31.0, 63.0, 31.0)));
+    return (c.r << 11u) | (c.g << 5u) | c.b;
+}
+
+// Writes the lowest 24 bits of `bits` into the output, the lowest byte into red.
+fn pack_output(bits: u32) -> vec4<f32> {
+    return vec4<f32>(unpack4x8unorm(bits).rgb, 1.0);
+}
+
+// `first` goes into the lowest 12 bits, `second` into the highest 12 bits.
+fn pack_rgb444_pair(first: vec3<f32>, second: vec3<f32>) -> vec4<f32> {
+    return pack_output(pack_rgb444(first) | (pack_rgb444(second) << 12u));
+}
+
+// `color` goes into red and green, `glyph` into the highest `bits` bits, between 1 and 8.
+fn pack_rgb565_glyph(color: vec3<f32>, glyph: u32, bits: u32) -> vec4<f32> {
+    return pack_output(pack_rgb565(color) | (glyph << (24u - bits)));
+}
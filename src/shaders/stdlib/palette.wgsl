@@ -0,0 +1,18 @@
+// Color palettes and conversions.
+
+// Inigo Quilez's cosine palette, `a + b * cos(2 * pi * (c * t + d))`.
+fn palette(t: f32, a: vec3<f32>, b: vec3<f32>, c: vec3<f32>, d: vec3<f32>) -> vec3<f32> {
+    return a + b * cos(6.28318530718 * (c * t + d));
+}
+
+// A rainbow cycling through all hues once per unit of `t`.
+fn rainbow(t: f32) -> vec3<f32> {
+    return palette(t, vec3<f32>(0.5), vec3<f32>(0.5), vec3<f32>(1.0), vec3<f32>(0.0, 0.33, 0.67));
+}
+
+// Converts hue, saturation and value between 0.0 and 1.0 to RGB.
+fn hsv_to_rgb(hsv: vec3<f32>) -> vec3<f32> {
+    let k = vec4<f32>(1.0, 2.0 / 3.0, 1.0 / 3.0, 3.0);
+    let p = abs(fract(hsv.xxx + k.xyz) * 6.0 - k.www);
+    return hsv.z * mix(k.xxx, clamp(p - k.xxx, vec3<f32>(0.0), vec3<f32>(1.0)), hsv.y);
+}
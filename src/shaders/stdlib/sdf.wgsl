@@ -0,0 +1,43 @@
+// Signed distance functions of 2D shapes, negative inside of the shape, and operators combining them.
+
+fn sd_circle(p: vec2<f32>, radius: f32) -> f32 {
+    return length(p) - radius;
+}
+
+fn sd_box(p: vec2<f32>, half_size: vec2<f32>) -> f32 {
+    let d = abs(p) - half_size;
+    return length(max(d, vec2<f32>(0.0))) + min(max(d.x, d.y), 0.0);
+}
+
+fn sd_rounded_box(p: vec2<f32>, half_size: vec2<f32>, radius: f32) -> f32 {
+    return sd_box(p, half_size - radius) - radius;
+}
+
+fn sd_segment(p: vec2<f32>, a: vec2<f32>, b: vec2<f32>) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let h = clamp(dot(pa, ba) / dot(ba, ba), 0.0, 1.0);
+    return length(pa - ba * h);
+}
+
+fn sd_ring(p: vec2<f32>, radius: f32, thickness: f32) -> f32 {
+    return abs(length(p) - radius) - thickness;
+}
+
+fn op_union(a: f32, b: f32) -> f32 {
+    return min(a, b);
+}
+
+fn op_intersection(a: f32, b: f32) -> f32 {
+    return max(a, b);
+}
+
+fn op_subtraction(a: f32, b: f32) -> f32 {
+    return max(a, -b);
+}
+
+// Blends the union of two shapes over a distance of `k`.
+fn op_smooth_union(a: f32, b: f32, k: f32) -> f32 {
+    let h = clamp(0.5 + 0.5 * (b - a) / k, 0.0, 1.0);
+    return mix(b, a, h) - k * h * (1.0 - h);
+}
@@ -0,0 +1,9 @@
+@group(1) @binding(0) var image: texture_2d<f32>;
+@group(1) @binding(1) var image_sampler: sampler;
+@group(1) @binding(2) var<storage, read> values: array<f32>;
+
+@fragment
+fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let color = textureSample(image, image_sampler, vec2<f32>(0.5, 0.5));
+    return vec4<f32>(color.r, values[0], color.b, 1.0);
+}
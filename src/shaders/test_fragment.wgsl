@@ -5,3 +5,57 @@
 fn green(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
     return vec4<f32>(0.0, 1.0, 0.0, 1.0);
 }
+
+struct Uniforms {
+    red: f32,
+    blue: f32,
+    tint: vec3<f32>,
+    count: u32,
+};
+
+@group(0) @binding(2) var<uniform> uniforms: Uniforms;
+
+@fragment
+fn user_uniforms(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(uniforms.red, 0.0, uniforms.blue, 1.0);
+}
+
+@fragment
+fn dynamic_uniforms(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(uniforms.tint * f32(uniforms.count) * 0.5, 1.0);
+}
+
+@fragment
+fn uv(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(uv.x, uv.y, 0.0, 1.0);
+}
+
+@fragment
+fn first_sample(@builtin(sample_index) sample_index: u32) -> @location(0) vec4<f32> {
+    return vec4<f32>(select(0.0, 1.0, sample_index == 0u), 0.0, 0.0, 1.0);
+}
+
+@group(0) @binding(4) var<uniform> date: vec4<f32>;
+
+@fragment
+fn wall_clock(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let valid_year = date.y >= 2000.0;
+    let valid_day = date.z >= 1.0 && date.z <= 12.0 && date.w >= 1.0 && date.w <= 31.0;
+    let valid_time = date.x >= 0.0 && date.x < 86400.0;
+    return vec4<f32>(select(0.0, 1.0, valid_year), select(0.0, 1.0, valid_day && valid_time), 0.0, 1.0);
+}
+
+@group(0) @binding(5) var<uniform> cell: vec4<f32>;
+
+@fragment
+fn cell_size(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(cell.x / 255.0, cell.y / 255.0, cell.w / (f32(rect.w) * cell.y), 1.0);
+}
+
+@group(0) @binding(6) var<uniform> focus: vec4<f32>;
+
+@fragment
+fn focus_point(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let near = focus.z > 0.5 && distance(uv, focus.xy) < 0.01;
+    return vec4<f32>(select(0.0, 1.0, near), 0.0, 0.0, 1.0);
+}
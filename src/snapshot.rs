@@ -0,0 +1,65 @@
+use std::error::Error;
+use std::path::Path;
+
+use image::{Rgba, RgbaImage};
+use ratatui_core::buffer::Buffer;
+
+use crate::font::{GLYPH_SIZE, glyph, glyph_pixel};
+use crate::state::ShaderCanvasState;
+use crate::util::color_to_rgb;
+
+const CELL_WIDTH: u32 = GLYPH_SIZE;
+const CELL_HEIGHT: u32 = GLYPH_SIZE * 2;
+
+impl ShaderCanvasState {
+    /// Writes the pixels of the most recent render to a PNG file, at the full resolution of the texture the
+    /// shader rendered to, i.e. one pixel per cell. Fails if the state has not been rendered yet.
+    pub fn snapshot_png(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let frame = self.frame().ok_or("the state has not been rendered yet")?;
+        let image = RgbaImage::from_raw(frame.width, frame.height, frame.to_rgba())
+            .ok_or("the frame does not match its dimensions")?;
+        image.save(path)?;
+        Ok(())
+    }
+}
+
+/// Writes a cell-accurate render of a [`Buffer`] to a PNG file. Every cell is drawn as an 8x16 pixel block
+/// filled with its background color, with its symbol drawn on top in its foreground color using an
+/// embedded bitmap font. Use this after rendering a [`ShaderCanvas`](crate::ShaderCanvas) to capture the
+/// chosen glyphs as well as the colors.
+///
+/// ```rust,no_run
+/// # use tui_shader::{ShaderCanvas, ShaderCanvasState, snapshot_buffer_png};
+/// let mut terminal = ratatui::init();
+/// let mut state = ShaderCanvasState::default();
+/// let completed = terminal.draw(|frame| {
+///     frame.render_stateful_widget(ShaderCanvas::new(), frame.area(), &mut state);
+/// }).unwrap();
+/// snapshot_buffer_png(completed.buffer, "screenshot.png").unwrap();
+/// ratatui::restore();
+/// ```
+pub fn snapshot_buffer_png(buffer: &Buffer, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+    let width = buffer.area.width as u32;
+    let height = buffer.area.height as u32;
+    let mut image = RgbaImage::new(width * CELL_WIDTH, height * CELL_HEIGHT);
+    for (index, cell) in buffer.content().iter().enumerate() {
+        let cell_x = index as u32 % width.max(1) * CELL_WIDTH;
+        let cell_y = index as u32 / width.max(1) * CELL_HEIGHT;
+        let [r, g, b] = color_to_rgb(cell.bg).unwrap_or([0, 0, 0]);
+        let background = Rgba([r, g, b, 255]);
+        let [r, g, b] = color_to_rgb(cell.fg).unwrap_or([255, 255, 255]);
+        let foreground = Rgba([r, g, b, 255]);
+        let glyph = cell.symbol().chars().next().and_then(glyph);
+        for y in 0..CELL_HEIGHT {
+            for x in 0..CELL_WIDTH {
+                let is_set = glyph
+                    .as_ref()
+                    .is_some_and(|glyph| glyph_pixel(glyph, x, y * GLYPH_SIZE / CELL_HEIGHT));
+                let color = if is_set { foreground } else { background };
+                image.put_pixel(cell_x + x, cell_y + y, color);
+            }
+        }
+    }
+    image.save(path)?;
+    Ok(())
+}
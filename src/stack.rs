@@ -0,0 +1,140 @@
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+use crate::Pixel;
+use crate::canvas::ShaderCanvas;
+use crate::context::ShaderContext;
+use crate::frame::Frame;
+use crate::state::ShaderCanvasState;
+
+/// How a layer of a [`ShaderStack`] is combined with the layers below it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendMode {
+    /// Draws the layer over the layers below it.
+    #[default]
+    Normal,
+
+    /// Adds the colors of the layer to the layers below it, brightening them.
+    Add,
+
+    /// Multiplies the colors of the layer with the layers below it, darkening them.
+    Multiply,
+
+    /// Inverts, multiplies and inverts the colors again, brightening the layers below.
+    Screen,
+}
+
+impl BlendMode {
+    fn blend(self, below: f32, above: f32) -> f32 {
+        match self {
+            Self::Normal => above,
+            Self::Add => (below + above).min(1.0),
+            Self::Multiply => below * above,
+            Self::Screen => 1.0 - (1.0 - below) * (1.0 - above),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Layer<'a> {
+    state: &'a mut ShaderCanvasState,
+    blend_mode: BlendMode,
+    opacity: f32,
+}
+
+/// [`ShaderStack`] renders several shaders into the same area. Every layer is executed once, the results
+/// are blended per pixel and only then mapped to cells with the [`ShaderCanvas`] of the stack, so the
+/// layers are blended correctly instead of overwriting each other's cells.
+///
+/// ```rust,no_run
+/// # use tui_shader::{BlendMode, ShaderCanvasState, ShaderStack, WgslShader};
+/// let mut terminal = ratatui::init();
+/// let mut background = ShaderCanvasState::default();
+/// let mut glow = ShaderCanvasState::new(WgslShader::Path("glow.wgsl")).unwrap();
+/// terminal.draw(|frame| {
+///     let stack = ShaderStack::new()
+///         .layer(&mut background, BlendMode::Normal, 1.0)
+///         .layer(&mut glow, BlendMode::Screen, 0.5);
+///     frame.render_widget(stack, frame.area());
+/// }).unwrap();
+/// ratatui::restore();
+/// ```
+#[derive(Debug, Default)]
+pub struct ShaderStack<'a> {
+    layers: Vec<Layer<'a>>,
+    canvas: ShaderCanvas,
+}
+
+impl<'a> ShaderStack<'a> {
+    /// Creates a new, empty [`ShaderStack`]. Equivalent to [`ShaderStack::default()`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a layer on top of the previous ones. The alpha of the shader's output is multiplied with
+    /// `opacity`, which is clamped between `0.0` and `1.0`.
+    #[must_use]
+    pub fn layer(
+        mut self,
+        state: &'a mut ShaderCanvasState,
+        blend_mode: BlendMode,
+        opacity: f32,
+    ) -> Self {
+        self.layers.push(Layer {
+            state,
+            blend_mode,
+            opacity: opacity.clamp(0.0, 1.0),
+        });
+        self
+    }
+
+    /// Sets the [`ShaderCanvas`] mapping the blended pixels to cells. Its
+    /// [`region`](ShaderCanvas::region) is ignored.
+    #[must_use]
+    pub fn canvas(mut self, canvas: ShaderCanvas) -> Self {
+        self.canvas = canvas;
+        self
+    }
+}
+
+impl Widget for ShaderStack<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() || self.layers.is_empty() {
+            return;
+        }
+        let (width, height) = self.canvas.scaled_size(area);
+        let mut pixels: Vec<Pixel> = Vec::new();
+        for layer in self.layers {
+            let time = layer.state.clock().elapsed().as_secs_f32();
+            let ctx = ShaderContext::new(time, area).with_size(width, height);
+            let layer_pixels = layer.state.execute(ctx);
+            if pixels.is_empty() {
+                pixels = vec![[0; 4]; layer_pixels.len()];
+            }
+            for (below, above) in pixels.iter_mut().zip(&layer_pixels) {
+                *below = composite(*below, *above, layer.blend_mode, layer.opacity);
+            }
+            let frame = Frame::new(width, height, layer_pixels);
+            layer.state.set_last_frame(frame, Some(area));
+        }
+        let frame = Frame::new(width, height, pixels);
+        self.canvas.style_frame(&frame, area, buf);
+    }
+}
+
+/// Blends `above` over `below` with the alpha of `above` multiplied by `opacity`.
+fn composite(below: Pixel, above: Pixel, blend_mode: BlendMode, opacity: f32) -> Pixel {
+    let alpha = f32::from(above[3]) / 255.0 * opacity;
+    let below_alpha = f32::from(below[3]) / 255.0;
+    let mut pixel = [0; 4];
+    for channel in 0..3 {
+        let below = f32::from(below[channel]) / 255.0;
+        let above = f32::from(above[channel]) / 255.0;
+        let blended = blend_mode.blend(below, above);
+        pixel[channel] = ((below + (blended - below) * alpha) * 255.0).round() as u8;
+    }
+    pixel[3] = ((alpha + below_alpha * (1.0 - alpha)) * 255.0).round() as u8;
+    pixel
+}
@@ -1,39 +1,205 @@
-use bytemuck::NoUninit;
+#[cfg(feature = "gpu")]
 use pollster::FutureExt;
-use std::time::Instant;
-use wgpu::{ExperimentalFeatures, util::DeviceExt};
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::{Position, Rect};
+#[cfg(feature = "gpu")]
+use std::error::Error;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
-use crate::{Pixel, bytes_per_row, context::ShaderContext};
+use crate::Pixel;
+#[cfg(feature = "gpu")]
+use crate::bindings::{BindingSlot, BindingsBuilder, TextureKind, extent, volume_bytes};
+use crate::budget::FrameBudget;
+use crate::builder::ShaderCanvasStateBuilder;
+use crate::camera::{Camera, OrbitControls};
+use crate::canvas::ShaderCanvas;
+#[cfg(feature = "gpu")]
+use crate::check::ShaderDiagnostics;
+use crate::clock::{ClockMode, ShaderClock};
+use crate::color::{ColorConfig, apply_lut};
+use crate::context::{DEFAULT_CELL_SIZE, ShaderContext};
+use crate::cpu::{self, CpuBackend};
+use crate::easing::Easing;
+#[cfg(feature = "gpu")]
+use crate::error::{self, TuiShaderError};
+use crate::frame::{Frame, FrameView};
+#[cfg(feature = "gpu")]
+use crate::gpu::{DEFAULT_FRAGMENT_SHADER_DESCRIPTOR, DeviceOptions, GpuBackend};
+#[cfg(feature = "gpu")]
+use crate::handle::ShaderHandle;
+use crate::interaction::{Interaction, PointerEvent};
+use crate::lut::{CubeLut, apply_cube_lut};
+use crate::memory::MemoryUsage;
+use crate::power::{IdleFrameRate, IdleThrottle, PowerThrottle};
+use crate::provider::{Providers, UniformProvider};
+use crate::style::Sample;
+use crate::telemetry::Telemetry;
+use crate::uniforms::UniformValue;
 
-pub(crate) const DEFAULT_SIZE: u32 = 64;
-const VERTEX_SHADER_DESCRIPTOR: wgpu::ShaderModuleDescriptor =
-    wgpu::include_wgsl!("shaders/fullscreen_vertex.wgsl");
 /// [`ShaderCanvasState`] holds the state to execute a render pass. It handles window/widget resizing automatically
 /// and creates new textures and buffers when necessary.
+///
+/// Shaders are only executed when their inputs change: as long as the clock is paused, the area keeps its
+/// size and no uniforms or bindings are written, the pixels of the previous frame are reused.
+///
+/// On native targets [`ShaderCanvasState`] is [`Send`] and [`Sync`], so it can be created on one thread and
+/// rendered on a dedicated render thread or in an async task. Apps sharing it between threads wrap it in
+/// an `Arc<Mutex<ShaderCanvasState>>`: rendering needs `&mut self`, so the lock is held for the duration of
+/// one frame, while parameters like uniforms are written in between frames. This requires [`CpuBackend`]s
+/// and [`UniformProvider`]s to be [`Send`], which their traits enforce.
+///
+/// Cloning a state shares the compiled shader (see `ShaderHandle`) and copies everything else: the
+/// uniforms, the contents of the bindings, the clock and the last frame. The clone renders into its own
+/// textures and can be changed without affecting the original. [`CpuBackend`]s and
+/// [`UniformProvider`]s can't be copied and are shared between clones.
 #[derive(Debug, Clone)]
 pub struct ShaderCanvasState {
-    device: wgpu::Device,
-    queue: wgpu::Queue,
-    pipeline: wgpu::RenderPipeline,
-    texture: wgpu::Texture,
-    output_buffer: wgpu::Buffer,
-    time_buffer: wgpu::Buffer,
-    rect_buffer: wgpu::Buffer,
-    bind_group: wgpu::BindGroup,
-    instant: Instant,
-    width: u32,
-    height: u32,
+    backend: Backend,
+    last_frame: Option<Frame>,
+    /// The area of the last render of a [`ShaderCanvas`], `None` if the last frame wasn't rendered into
+    /// cells.
+    last_area: Option<Rect>,
+    /// The cells styled by the last render of a [`ShaderCanvas`] with a damage region.
+    last_cells: Option<Buffer>,
+    clock: ShaderClock,
+    providers: Providers,
+    before_execute: Vec<fn(&mut Uniforms<'_>)>,
+    after_execute: Vec<fn(&FrameView<'_>)>,
+    #[cfg(feature = "gpu")]
+    on_error: Vec<fn(TuiShaderError)>,
+    color_config: ColorConfig,
+    /// The lookup table of `color_config`, `None` if it doesn't change any color.
+    color_lut: Option<[u8; 256]>,
+    /// The grade applied after the color conversions, see [`ShaderCanvasState::set_color_grade`].
+    color_grade: Option<Arc<CubeLut>>,
+    cell_size: (u16, u16),
+    focus_point: Option<(u16, u16)>,
+    /// The values derived from pointer input, `None` until the first [`PointerEvent`] so that uniforms
+    /// called like them are left alone.
+    interaction: Option<Interaction>,
+    /// The camera written to the `camera_*` uniforms, see [`ShaderCanvasState::set_camera`].
+    camera: Option<Camera>,
+    orbit_controls: OrbitControls,
+    /// The degradation of canvases rendered with a frame budget.
+    frame_budget: FrameBudget,
+    /// When the next render of a [`ShaderCanvas`] with a [`PowerProfile`](crate::PowerProfile) is due.
+    power: PowerThrottle,
+    /// The activity of the state, see [`ShaderCanvasState::set_idle_frame_rate`].
+    idle: IdleThrottle,
+    /// Whether the GPU resources are released, see [`ShaderCanvasState::suspend`].
+    suspended: bool,
+    /// The counters of the state and of the backends it replaced, see [`ShaderCanvasState::telemetry`].
+    telemetry: Telemetry,
+    /// The crossfade from the previous shader, see [`ShaderCanvasState::transition_to`].
+    transition: Option<Transition>,
+    /// Whether the next frame is rendered ahead of time, see [`ShaderCanvasState::set_prerender`].
+    #[cfg(feature = "gpu")]
+    prerender: bool,
+    /// The time of the last execution, from which the time of the next frame is predicted.
+    #[cfg(feature = "gpu")]
+    last_time: Option<f32>,
+    /// The shader loaded with [`ShaderCanvasState::load_candidate`], waiting to be promoted.
+    #[cfg(feature = "gpu")]
+    candidate: Option<Box<GpuBackend>>,
+    /// The backend replaced by the last promoted candidate, restored by [`ShaderCanvasState::rollback`].
+    #[cfg(feature = "gpu")]
+    previous: Option<Box<GpuBackend>>,
+    /// Why the last shader failed to load, see [`ShaderCanvasState::compile_error`].
+    #[cfg(feature = "gpu")]
+    compile_error: Option<ShaderDiagnostics>,
+    #[cfg(feature = "wall-clock")]
+    wall_clock: bool,
+}
+
+/// A crossfade from the backend a state had before [`ShaderCanvasState::transition_to`] to its current one.
+#[derive(Debug, Clone)]
+struct Transition {
+    from: Backend,
+    /// The time of the clock at the start, in seconds.
+    start: f32,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Transition {
+    /// The eased progress at `time` of the clock, `None` once the transition is over.
+    fn progress(&self, time: f32) -> Option<f32> {
+        let progress = (time - self.start) / self.duration.as_secs_f32();
+        (progress < 1.0).then(|| self.easing.apply(progress))
+    }
+}
+
+/// The counters of the work done by `backend`. [`CpuBackend`]s aren't counted.
+fn backend_telemetry(backend: &Backend) -> Telemetry {
+    match backend {
+        #[cfg(feature = "gpu")]
+        Backend::Gpu(backend) => backend.telemetry(),
+        Backend::Cpu(_) => Telemetry::default(),
+    }
+}
+
+/// Returns the counters of the work done by `backend` like [`backend_telemetry`] and starts them over.
+fn take_telemetry(backend: &mut Backend) -> Telemetry {
+    match backend {
+        #[cfg(feature = "gpu")]
+        Backend::Gpu(backend) => backend.take_telemetry(),
+        Backend::Cpu(_) => Telemetry::default(),
+    }
+}
+
+/// The memory held by `backend`. [`CpuBackend`]s aren't counted.
+fn backend_memory(backend: &Backend) -> MemoryUsage {
+    match backend {
+        #[cfg(feature = "gpu")]
+        Backend::Gpu(backend) => backend.memory_usage(),
+        Backend::Cpu(_) => MemoryUsage::default(),
+    }
+}
+
+/// Computes the pixels of a [`ShaderCanvasState`].
+#[derive(Clone)]
+enum Backend {
+    #[cfg(feature = "gpu")]
+    Gpu(Box<GpuBackend>),
+    Cpu(Arc<Mutex<dyn CpuBackend>>),
+}
+
+impl std::fmt::Debug for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "gpu")]
+            Self::Gpu(backend) => f.debug_tuple("Gpu").field(backend).finish(),
+            Self::Cpu(_) => f.debug_tuple("Cpu").finish_non_exhaustive(),
+        }
+    }
 }
 
 impl ShaderCanvasState {
+    /// Returns a [`ShaderCanvasStateBuilder`] to configure the shader, entry point, bindings, clock and
+    /// backend of a new state in one place.
+    pub fn builder<'a>() -> ShaderCanvasStateBuilder<'a> {
+        ShaderCanvasStateBuilder::new()
+    }
+
     /// Creates a new [`ShaderCanvasState`] instance, without specifying an entry point. This means that
     /// the wgsl shader must define exactly one `@fragment` function. Creation can fail if passed an invalid
     /// wgsl file, or if the specified path is invalid.
+    #[cfg(feature = "gpu")]
     pub fn new<'a, S: TryInto<wgpu::ShaderModuleDescriptor<'a>>>(
         shader: S,
     ) -> Result<Self, S::Error> {
         match shader.try_into() {
-            Ok(desc) => Ok(Self::new_inner(desc, None).block_on()),
+            Ok(desc) => Ok(Self::from_gpu(
+                GpuBackend::new(
+                    desc,
+                    None,
+                    &BindingsBuilder::default(),
+                    &DeviceOptions::default(),
+                )
+                .block_on()
+                .expect("unable to create device and queue from wgpu adapter"),
+            )),
             Err(error) => Err(error),
         }
     }
@@ -41,311 +207,1421 @@ impl ShaderCanvasState {
     /// Creates a new [`ShaderCanvasState`] instance with an entry point. This is necessary when your wgsl
     /// shader defines more than one `@fragment` function. In this case, the name of the function must be passed
     /// in.  Creation can fail if passed an invalid wgsl file, or if the specified path is invalid.
+    #[cfg(feature = "gpu")]
     pub fn new_with_entry_point<'a, S: TryInto<wgpu::ShaderModuleDescriptor<'a>>>(
         shader: S,
         entry_point: &'a str,
     ) -> Result<Self, S::Error> {
         match shader.try_into() {
-            Ok(desc) => Ok(Self::new_inner(desc, Some(entry_point)).block_on()),
+            Ok(desc) => Ok(Self::from_gpu(
+                GpuBackend::new(
+                    desc,
+                    Some(entry_point),
+                    &BindingsBuilder::default(),
+                    &DeviceOptions::default(),
+                )
+                .block_on()
+                .expect("unable to create device and queue from wgpu adapter"),
+            )),
             Err(error) => Err(error),
         }
     }
 
-    #[allow(clippy::needless_lifetimes)]
-    async fn new_inner<'a>(
-        fragment_shader_descriptor: wgpu::ShaderModuleDescriptor<'a>,
-        entry_point: Option<&str>,
-    ) -> Self {
-        let (device, queue) = get_device_and_queue().await;
-        let vertex_shader = device.create_shader_module(VERTEX_SHADER_DESCRIPTOR);
-        let fragment_shader = device.create_shader_module(fragment_shader_descriptor);
-        let texture = create_texture(&device, DEFAULT_SIZE, DEFAULT_SIZE);
-        let output_buffer = create_output_buffer(&device, DEFAULT_SIZE, DEFAULT_SIZE);
-        let ctx = ShaderContext::default();
-        let time_buffer = create_input_buffer(&device, ctx.time);
-        let rect_buffer = create_input_buffer(&device, ctx.rect);
-        let bind_group_layout = create_bind_group_layout(&device);
-        let bind_group =
-            create_bind_group(&device, &bind_group_layout, &[&time_buffer, &rect_buffer]);
-        let pipeline_layout = create_pipeline_layout(&device, &bind_group_layout);
-        let pipeline = create_render_pipeline(
-            &device,
-            &pipeline_layout,
-            &vertex_shader,
-            &fragment_shader,
-            entry_point,
-        );
-        ShaderCanvasState {
-            device,
-            queue,
-            pipeline,
-            texture,
-            output_buffer,
-            time_buffer,
-            rect_buffer,
-            bind_group,
-            instant: Instant::now(),
-            width: DEFAULT_SIZE,
-            height: DEFAULT_SIZE,
+    /// Creates a new [`ShaderCanvasState`] instance with additional textures, samplers and storage buffers
+    /// declared by a [`BindingsBuilder`]. Creation can fail if passed an invalid shader, or if the
+    /// bindings are invalid.
+    #[cfg(feature = "gpu")]
+    pub fn new_with_bindings<'a, S>(
+        shader: S,
+        entry_point: Option<&'a str>,
+        bindings: BindingsBuilder,
+    ) -> Result<Self, Box<dyn Error>>
+    where
+        S: TryInto<wgpu::ShaderModuleDescriptor<'a>>,
+        S::Error: Into<Box<dyn Error>>,
+    {
+        Self::new_on_device(shader, entry_point, bindings, &DeviceOptions::default())
+    }
+
+    /// Like [`ShaderCanvasState::new_with_bindings`], requesting the GPU device as configured by `device`.
+    #[cfg(feature = "gpu")]
+    pub(crate) fn new_on_device<'a, S>(
+        shader: S,
+        entry_point: Option<&'a str>,
+        bindings: BindingsBuilder,
+        device: &DeviceOptions,
+    ) -> Result<Self, Box<dyn Error>>
+    where
+        S: TryInto<wgpu::ShaderModuleDescriptor<'a>>,
+        S::Error: Into<Box<dyn Error>>,
+    {
+        bindings.validate()?;
+        let desc = shader.try_into().map_err(Into::into)?;
+        Ok(Self::from_gpu(
+            GpuBackend::new(desc, entry_point, &bindings, device).block_on()?,
+        ))
+    }
+
+    /// Creates a new [`ShaderCanvasState`] executing the shader of `handle`, without compiling it again.
+    /// Unlike a clone of a state, the new state starts with zeroed uniforms, the initial bindings and a
+    /// new clock.
+    #[cfg(feature = "gpu")]
+    pub fn from_handle(handle: &ShaderHandle) -> Self {
+        Self::from_gpu(GpuBackend::from_handle(handle))
+    }
+
+    /// The compiled shader executed by this state, `None` for states created with
+    /// [`ShaderCanvasState::from_backend`].
+    #[cfg(feature = "gpu")]
+    pub fn handle(&self) -> Option<ShaderHandle> {
+        match &self.backend {
+            Backend::Gpu(backend) => Some(backend.handle()),
+            Backend::Cpu(_) => None,
+        }
+    }
+
+    /// Creates a new [`ShaderCanvasState`] whose pixels are computed on the CPU by a [`CpuBackend`]
+    /// instead of a fragment shader. This doesn't require a GPU.
+    pub fn from_backend(backend: impl CpuBackend + 'static) -> Self {
+        Self::from_shared_backend(Arc::new(Mutex::new(backend)))
+    }
+
+    pub(crate) fn from_shared_backend(backend: Arc<Mutex<dyn CpuBackend>>) -> Self {
+        Self::with_backend(Backend::Cpu(backend))
+    }
+
+    #[cfg(feature = "gpu")]
+    fn from_gpu(backend: GpuBackend) -> Self {
+        Self::with_backend(Backend::Gpu(Box::new(backend)))
+    }
+
+    fn with_backend(backend: Backend) -> Self {
+        Self {
+            backend,
+            last_frame: None,
+            last_area: None,
+            last_cells: None,
+            providers: Providers::default(),
+            before_execute: Vec::new(),
+            after_execute: Vec::new(),
+            #[cfg(feature = "gpu")]
+            on_error: Vec::new(),
+            color_config: ColorConfig::default(),
+            color_lut: None,
+            color_grade: None,
+            cell_size: DEFAULT_CELL_SIZE,
+            focus_point: None,
+            interaction: None,
+            camera: None,
+            orbit_controls: OrbitControls::default(),
+            frame_budget: FrameBudget::default(),
+            power: PowerThrottle::default(),
+            idle: IdleThrottle::default(),
+            suspended: false,
+            telemetry: Telemetry::default(),
+            transition: None,
+            #[cfg(feature = "gpu")]
+            prerender: false,
+            #[cfg(feature = "gpu")]
+            last_time: None,
+            #[cfg(feature = "gpu")]
+            candidate: None,
+            #[cfg(feature = "gpu")]
+            previous: None,
+            #[cfg(feature = "gpu")]
+            compile_error: None,
+            #[cfg(feature = "wall-clock")]
+            wall_clock: false,
+            clock: ShaderClock::new(),
         }
     }
 
+    /// Runs the shader for the rect of `ctx`. Empty rects produce no pixels and don't touch the GPU, since
+    /// textures can't be zero-sized.
     pub(crate) fn execute(&mut self, ctx: ShaderContext) -> Vec<Pixel> {
-        self.execute_inner(ctx).block_on()
-    }
-
-    async fn execute_inner(&mut self, ctx: ShaderContext) -> Vec<Pixel> {
-        let width = ctx.width();
-        let height = ctx.height();
-        if bytes_per_row(width) != bytes_per_row(self.width) || height != self.height {
-            self.texture = create_texture(&self.device, width, height);
-            self.output_buffer = create_output_buffer(&self.device, width, height);
-        }
-        let bytes_per_row = bytes_per_row(width);
-        let texture_view = self
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
-        let render_target = wgpu::RenderPassColorAttachment {
-            view: &texture_view,
-            resolve_target: None,
-            ops: wgpu::Operations {
-                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
-                store: wgpu::StoreOp::Store,
-            },
-            depth_slice: None,
+        if ctx.width() == 0 || ctx.height() == 0 {
+            return Vec::new();
+        }
+        self.telemetry.executions += 1;
+        let ctx = self.prepare(ctx);
+        let mut pixels = self.run_backend(ctx);
+        if let Some(mut transition) = self.transition.take() {
+            if let Some(progress) = transition.progress(ctx.time[0]) {
+                std::mem::swap(&mut self.backend, &mut transition.from);
+                let previous = self.run_backend(ctx);
+                std::mem::swap(&mut self.backend, &mut transition.from);
+                let (width, height) = (ctx.width(), ctx.height());
+                if let Some(blended) = Frame::new(width, height, previous)
+                    .lerp(&Frame::new(width, height, pixels.clone()), progress)
+                {
+                    pixels = blended.pixels;
+                }
+                self.transition = Some(transition);
+            } else {
+                let retired = take_telemetry(&mut transition.from);
+                self.telemetry.merge(retired);
+            }
+        }
+        #[cfg(feature = "gpu")]
+        self.prerender_next(ctx);
+        self.grade(&mut pixels);
+        pixels
+    }
+
+    /// Applies the [`ColorConfig`] and the color grade to `pixels`.
+    fn grade(&self, pixels: &mut [Pixel]) {
+        if let Some(lut) = &self.color_lut {
+            apply_lut(pixels, lut);
+        }
+        if let Some(grade) = &self.color_grade {
+            apply_cube_lut(pixels, grade);
+        }
+    }
+
+    /// Runs the backend for the whole rect of `ctx`, which has been prepared already.
+    fn run_backend(&mut self, ctx: ShaderContext) -> Vec<Pixel> {
+        match &mut self.backend {
+            #[cfg(feature = "gpu")]
+            Backend::Gpu(backend) => {
+                let result = backend.execute(ctx).block_on();
+                if let Err(error) = &result
+                    && self.rollback()
+                {
+                    for callback in &self.on_error {
+                        callback(error.clone());
+                    }
+                    return self.run_backend(ctx);
+                }
+                if let Err(TuiShaderError::DeviceLost(_)) = &result
+                    && self.migrate()
+                {
+                    return self.run_backend(ctx);
+                }
+                self.report(result, ctx.width(), ctx.height())
+            }
+            Backend::Cpu(backend) => {
+                let mut backend = backend.lock().unwrap_or_else(PoisonError::into_inner);
+                cpu::execute(&mut *backend, ctx)
+            }
+        }
+    }
+
+    /// Renders the frame after `ctx` ahead of time if enabled, predicting its time from the time between
+    /// the last two executions.
+    #[cfg(feature = "gpu")]
+    fn prerender_next(&mut self, ctx: ShaderContext) {
+        let time = ctx.time[0];
+        let previous = self.last_time.replace(time);
+        let Backend::Gpu(backend) = &mut self.backend else {
+            return;
         };
-        let mut command_encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        if self.prerender
+            && let Some(previous) = previous
+            && time > previous
         {
-            let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: None,
-                color_attachments: &[Some(render_target)],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
-            render_pass.set_pipeline(&self.pipeline);
-            render_pass.set_bind_group(0, &self.bind_group, &[]);
-            render_pass.draw(0..3, 0..1);
-        }
-        command_encoder.copy_texture_to_buffer(
-            wgpu::TexelCopyTextureInfo {
-                texture: &self.texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d { x: 0, y: 0, z: 0 },
-                aspect: wgpu::TextureAspect::All,
-            },
-            wgpu::TexelCopyBufferInfo {
-                buffer: &self.output_buffer,
-                layout: wgpu::TexelCopyBufferLayout {
-                    offset: 0,
-                    bytes_per_row: Some(bytes_per_row),
-                    rows_per_image: Some(height),
-                },
-            },
-            wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
+            let interval = time - previous;
+            // Errors are reported once the frame is rendered for real.
+            let _ = backend
+                .prerender(ctx.with_time(time + interval), interval)
+                .block_on();
+        }
+    }
+
+    /// Runs the shader for the rects of all `ctxs`, sharing the uniforms, which are only prepared once. On
+    /// the GPU they are rendered into a single texture with one submission and readback.
+    pub(crate) fn execute_batch(&mut self, ctxs: &[ShaderContext]) -> Vec<Vec<Pixel>> {
+        let Some((first, rest)) = ctxs.split_first() else {
+            return Vec::new();
+        };
+        self.telemetry.executions += ctxs.len() as u64;
+        let mut prepared = vec![self.prepare(*first)];
+        prepared.extend(rest.iter().map(|ctx| self.complete(*ctx)));
+        let mut frames: Vec<Vec<Pixel>> = match &mut self.backend {
+            #[cfg(feature = "gpu")]
+            Backend::Gpu(backend) => {
+                let mut result = backend.execute_batch(&prepared).block_on();
+                if let Err(TuiShaderError::DeviceLost(_)) = &result
+                    && self.migrate()
+                    && let Backend::Gpu(backend) = &mut self.backend
+                {
+                    result = backend.execute_batch(&prepared).block_on();
+                }
+                match result {
+                    Ok(frames) => frames,
+                    Err(error) => prepared
+                        .iter()
+                        .map(|ctx| self.report(Err(error.clone()), ctx.width(), ctx.height()))
+                        .collect(),
+                }
+            }
+            Backend::Cpu(backend) => {
+                let mut backend = backend.lock().unwrap_or_else(PoisonError::into_inner);
+                prepared
+                    .iter()
+                    .map(|ctx| cpu::execute(&mut *backend, *ctx))
+                    .collect()
+            }
+        };
+        for pixels in &mut frames {
+            self.grade(pixels);
+        }
+        frames
+    }
+
+    /// Runs the shader only for `region`, given in pixels relative to the rect of `ctx` and clamped to it.
+    /// The shader still sees the whole rect, so the pixels match the same pixels of [`Self::execute`]. The
+    /// result is row padded like the output of [`Self::execute`] for a rect of the size of the region.
+    pub(crate) fn execute_region(&mut self, ctx: ShaderContext, region: Rect) -> Vec<Pixel> {
+        let bounds = Rect::new(
+            0,
+            0,
+            ctx.width().min(u16::MAX.into()) as u16,
+            ctx.height().min(u16::MAX.into()) as u16,
+        );
+        let region = region.intersection(bounds);
+        if region.is_empty() {
+            return Vec::new();
+        }
+        self.telemetry.executions += 1;
+        let ctx = self.prepare(ctx);
+        let mut pixels = match &mut self.backend {
+            #[cfg(feature = "gpu")]
+            Backend::Gpu(backend) => {
+                let mut result = backend.execute_region(ctx, region).block_on();
+                if let Err(TuiShaderError::DeviceLost(_)) = &result
+                    && self.migrate()
+                    && let Backend::Gpu(backend) = &mut self.backend
+                {
+                    result = backend.execute_region(ctx, region).block_on();
+                }
+                self.report(result, region.width.into(), region.height.into())
+            }
+            Backend::Cpu(backend) => {
+                let mut backend = backend.lock().unwrap_or_else(PoisonError::into_inner);
+                cpu::execute_region(&mut *backend, ctx, region)
+            }
+        };
+        self.grade(&mut pixels);
+        pixels
+    }
+
+    /// Renders the shader into `view` on the GPU, without reading the pixels back to the CPU, so other
+    /// wgpu based code can consume the output directly. `area` is the rect the shader sees as its
+    /// `rect` input, while the size of the texture determines the resolution. The time is taken from
+    /// the state's [`ShaderClock`](crate::ShaderClock).
+    ///
+    /// The texture must be created on the device of [`ShaderCanvasState::handle`], have the
+    /// [`Rgba8Unorm`](wgpu::TextureFormat::Rgba8Unorm) format and the
+    /// [`RENDER_ATTACHMENT`](wgpu::TextureUsages::RENDER_ATTACHMENT) usage. Returns an error otherwise,
+    /// or for states created with [`ShaderCanvasState::from_backend`].
+    ///
+    /// ```rust,no_run
+    /// # use ratatui_core::layout::Rect;
+    /// # use tui_shader::{ShaderCanvasState, WgslShader};
+    /// let mut state = ShaderCanvasState::new(WgslShader::Path("shader.wgsl")).unwrap();
+    /// let handle = state.handle().unwrap();
+    /// let texture = handle.device().create_texture(&wgpu::TextureDescriptor {
+    ///     label: None,
+    ///     size: wgpu::Extent3d { width: 640, height: 480, depth_or_array_layers: 1 },
+    ///     mip_level_count: 1,
+    ///     sample_count: 1,
+    ///     dimension: wgpu::TextureDimension::D2,
+    ///     format: wgpu::TextureFormat::Rgba8Unorm,
+    ///     usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+    ///     view_formats: &[],
+    /// });
+    /// let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    /// state.render_to_texture(&view, Rect::new(0, 0, 80, 24)).unwrap();
+    /// ```
+    #[cfg(feature = "gpu")]
+    pub fn render_to_texture(
+        &mut self,
+        view: &wgpu::TextureView,
+        area: Rect,
+    ) -> Result<(), Box<dyn Error>> {
+        let texture = view.texture();
+        let time = self.clock.elapsed().as_secs_f32();
+        let ctx = ShaderContext::new(time, area).with_size(texture.width(), texture.height());
+        let ctx = self.prepare(ctx);
+        match &mut self.backend {
+            Backend::Gpu(backend) => backend.render_to_view(ctx, view),
+            Backend::Cpu(_) => Err("states with a CPU backend can't render into textures".into()),
+        }
+    }
+
+    /// Passes the error of a failed execution to the callbacks registered with
+    /// [`ShaderCanvasState::on_error`] and returns transparent pixels of `width` x `height` instead.
+    /// Panics if there are no callbacks.
+    #[cfg(feature = "gpu")]
+    fn report(
+        &mut self,
+        result: Result<Vec<Pixel>, TuiShaderError>,
+        width: u32,
+        height: u32,
+    ) -> Vec<Pixel> {
+        if result.is_err() && !self.on_error.is_empty() {
+            self.telemetry.dropped_frames += 1;
+        }
+        error::recover(result, &self.on_error, width, height)
+    }
+
+    /// Runs the providers and completes `ctx` with the inputs configured on the state.
+    fn prepare(&mut self, ctx: ShaderContext) -> ShaderContext {
+        self.suspended = false;
+        self.clock.tick();
+        let ctx = self.complete(ctx);
+        if let Some(interaction) = self.interaction {
+            interaction.apply(&mut self.uniforms(), &ctx);
+        }
+        if let Some(camera) = self.camera {
+            camera.apply(&mut self.uniforms(), ctx.cell[2] / ctx.cell[3]);
+        }
+        self.run_providers();
+        ctx
+    }
+
+    /// Completes `ctx` with the inputs configured on the state.
+    fn complete(&self, ctx: ShaderContext) -> ShaderContext {
+        let mut ctx = ctx.with_cell_size(self.cell_size);
+        if let Some((x, y)) = self.focus_point {
+            ctx = ctx.with_focus_point(x, y);
+        }
+        #[cfg(feature = "wall-clock")]
+        let ctx = if self.wall_clock {
+            ctx.with_local_date()
+        } else {
+            ctx
+        };
+        ctx
+    }
+
+    fn run_providers(&mut self) {
+        let providers = self.providers.clone();
+        providers.provide(&mut self.uniforms());
+        for hook in self.before_execute.clone() {
+            hook(&mut self.uniforms());
+        }
+    }
+
+    pub(crate) fn set_last_frame(&mut self, frame: Frame, area: Option<Rect>) {
+        let frame = self.last_frame.insert(frame);
+        self.last_area = area;
+        for hook in &self.after_execute {
+            hook(&FrameView::new(frame));
+        }
+    }
+
+    /// Samples the last rendered frame under the cell at `position` in the coordinates of the terminal,
+    /// e.g. to find out what was under a mouse click. Returns `None` if the position lies outside of the
+    /// area the [`ShaderCanvas`] was last rendered into, or if nothing has been rendered into cells yet.
+    pub fn sample_at(&self, position: Position) -> Option<Sample> {
+        let frame = self.last_frame.as_ref()?;
+        let area = self.last_area?;
+        if !area.contains(position) {
+            return None;
+        }
+        let x = position.x - area.x;
+        let y = position.y - area.y;
+        let pixel = frame.sample_cell(x, y, area.width, area.height);
+        let uv = (
+            f32::from(x) / f32::from(area.width),
+            f32::from(y) / f32::from(area.height),
         );
-        self.queue
-            .write_buffer(&self.time_buffer, 0, bytemuck::cast_slice(&[ctx.time]));
-        self.queue
-            .write_buffer(&self.rect_buffer, 0, bytemuck::cast_slice(&[ctx.rect]));
-        self.queue.submit(Some(command_encoder.finish()));
-
-        let buffer_slice = self.output_buffer.slice(..);
-        let (sender, receiver) = flume::bounded(1);
-        buffer_slice.map_async(wgpu::MapMode::Read, move |r| {
-            sender
-                .send(r)
-                .expect("unable to send buffer slice data to receiver");
+        Some(Sample::new(pixel, (x, y), uv))
+    }
+
+    /// Executes the shader for `area` with one pixel per cell and yields a [`Sample`] for every cell, row by
+    /// row. Positions are relative to the top left corner of `area` and uvs are normalized to it, like the
+    /// samples passed to [`CharacterRule::Map`](crate::CharacterRule::Map). Use it to draw custom widgets
+    /// with the colors of a shader, e.g. a sparkline or a border. The result also becomes the last frame,
+    /// see [`ShaderCanvasState::sample_at`].
+    ///
+    /// ```rust,no_run
+    /// # use ratatui_core::layout::Rect;
+    /// # use tui_shader::ShaderCanvasState;
+    /// let mut state = ShaderCanvasState::default();
+    /// let border: Vec<_> = state
+    ///     .samples(Rect::new(0, 0, 20, 1))
+    ///     .map(|sample| sample.color())
+    ///     .collect();
+    /// ```
+    pub fn samples(&mut self, area: Rect) -> impl Iterator<Item = Sample> + use<> {
+        let time = self.clock.elapsed().as_secs_f32();
+        let pixels = self.execute(ShaderContext::new(time, area));
+        let frame = Frame::new(area.width.into(), area.height.into(), pixels);
+        self.set_last_frame(frame.clone(), Some(area));
+        let (width, height) = (area.width, area.height);
+        (0..height)
+            .flat_map(move |y| (0..width).map(move |x| (x, y)))
+            .map(move |(x, y)| {
+                let uv = (
+                    f32::from(x) / f32::from(width),
+                    f32::from(y) / f32::from(height),
+                );
+                Sample::new(frame.pixel(x.into(), y.into()), (x, y), uv)
+            })
+    }
+
+    /// The pixels of the most recent render, before they were mapped to cells, or `None` if the state
+    /// hasn't been rendered yet. Use it to build custom widgets or exporters on top of the render result.
+    pub fn last_frame(&self) -> Option<FrameView<'_>> {
+        self.last_frame.as_ref().map(FrameView::new)
+    }
+
+    pub(crate) fn frame(&self) -> Option<&Frame> {
+        self.last_frame.as_ref()
+    }
+
+    /// The area the last frame was rendered into, `None` if it wasn't rendered into cells.
+    pub(crate) fn last_area(&self) -> Option<Rect> {
+        self.last_area
+    }
+
+    /// The factor the render scale of a [`ShaderCanvas`] with a
+    /// [`frame_budget`](ShaderCanvas::frame_budget) is currently reduced by, `1.0` while its renders fit
+    /// the budget.
+    pub fn budget_scale(&self) -> f32 {
+        self.frame_budget.scale()
+    }
+
+    pub(crate) fn frame_budget_mut(&mut self) -> &mut FrameBudget {
+        &mut self.frame_budget
+    }
+
+    pub(crate) fn power_mut(&mut self) -> &mut PowerThrottle {
+        &mut self.power
+    }
+
+    pub(crate) fn idle_mut(&mut self) -> &mut IdleThrottle {
+        &mut self.idle
+    }
+
+    /// Lowers the frame rate while the state sits idle, see [`IdleFrameRate`], or renders every frame
+    /// again with `None`.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tui_shader::{IdleFrameRate, ShaderCanvasState};
+    /// let mut state = ShaderCanvasState::default();
+    /// state.set_idle_frame_rate(Some(IdleFrameRate::new(Duration::from_secs(30), 1)));
+    /// ```
+    pub fn set_idle_frame_rate(&mut self, idle: Option<IdleFrameRate>) {
+        self.idle.set_config(idle);
+    }
+
+    /// The [`IdleFrameRate`] of the state, if any.
+    pub fn idle_frame_rate(&self) -> Option<IdleFrameRate> {
+        self.idle.config()
+    }
+
+    /// Whether the state is idle and renders at the lowered frame rate of its [`IdleFrameRate`].
+    pub fn is_idle(&self) -> bool {
+        self.idle.is_idle(Instant::now())
+    }
+
+    /// Counts as activity, restoring the full frame rate of an idle state, e.g. when new data arrived
+    /// which is drawn on top of the canvas.
+    pub fn mark_active(&mut self) {
+        self.idle.record_activity(Instant::now());
+    }
+
+    /// The texture the most recent render on the GPU was drawn into, e.g. to copy it into a texture of
+    /// another wgpu based renderer on the device of [`ShaderCanvasState::handle`]. It has the
+    /// [`Rgba8Unorm`](wgpu::TextureFormat::Rgba8Unorm) format. The texture only grows when the area does,
+    /// so it is usually larger than the rendered area, which covers its top left corner. Areas exceeding
+    /// the maximum texture size are rendered in tiles, in which case it holds the last tile. `None` for
+    /// states created with [`ShaderCanvasState::from_backend`].
+    #[cfg(feature = "gpu")]
+    pub fn output_texture(&self) -> Option<&wgpu::Texture> {
+        match &self.backend {
+            Backend::Gpu(backend) => Some(backend.output_texture()),
+            Backend::Cpu(_) => None,
+        }
+    }
+
+    pub(crate) fn set_last_cells(&mut self, cells: Buffer) {
+        self.last_cells = Some(cells);
+    }
+
+    /// Takes the pixels and cells of the last render with a damage region out of the state.
+    pub(crate) fn take_last_render(&mut self) -> Option<(Frame, Buffer)> {
+        if self.last_frame.is_none() || self.last_cells.is_none() {
+            return None;
+        }
+        self.last_frame.take().zip(self.last_cells.take())
+    }
+
+    /// Sets a user defined `f32` uniform by name. User defined uniforms are declared as members of a struct
+    /// bound to `@group(0) @binding(2)` in the fragment shader:
+    ///
+    /// ```wgsl
+    /// struct Uniforms {
+    ///     speed: f32,
+    ///     threshold: f32,
+    /// };
+    ///
+    /// @group(0) @binding(2) var<uniform> uniforms: Uniforms;
+    /// ```
+    ///
+    /// Returns `false` if the shader doesn't declare a `f32` member with the given name. States created with
+    /// [`ShaderCanvasState::from_backend`] pass the value on to [`CpuBackend::set_uniform`]. Use
+    /// [`ShaderCanvasState::uniforms`] to set uniforms of other types.
+    pub fn set_uniform(&mut self, name: &str, value: f32) -> bool {
+        self.uniforms().try_set(name, value)
+    }
+
+    /// Enables or disables 4x multisampling of the rendered texture, which is resolved before the pixels
+    /// are read back. Since the canvas is a single fullscreen triangle, the fragment shader only runs
+    /// once per sample if it opts into per-sample shading by reading `@builtin(sample_index)` or
+    /// `@builtin(sample_mask)`. Shaders drawing hard shapes can use the sample index to offset their
+    /// coordinates and get antialiased edges. Does nothing for states created with
+    /// [`ShaderCanvasState::from_backend`].
+    #[cfg(feature = "gpu")]
+    pub fn set_msaa(&mut self, enabled: bool) {
+        if let Backend::Gpu(backend) = &mut self.backend {
+            backend.set_msaa(enabled);
+        }
+    }
+
+    /// Gives up waiting for the GPU when it doesn't finish a frame within `deadline`, instead of
+    /// freezing the app on a shader which loops forever. wgpu can't cancel work once it is submitted,
+    /// so the device of the state is destroyed to stop it, and the execution fails with
+    /// [`TuiShaderError::Timeout`]. From then on the state fails with [`TuiShaderError::DeviceLost`],
+    /// unless it migrates to a new device, see
+    /// [`DeviceOptions::migrate`](crate::gpu::DeviceOptions::migrate), after rolling back a promoted
+    /// shader. Other states sharing the device through a [`ShaderHandle`] lose it as well. A candidate
+    /// whose test frame misses the deadline is rejected by [`ShaderCanvasState::load_candidate`]. Waits
+    /// forever if `None`, which is the default.
+    ///
+    /// Set the deadline well above the time of a regular frame, since frames also wait for the ones
+    /// submitted before them.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tui_shader::{ShaderCanvasState, TuiShaderError};
+    /// let mut state = ShaderCanvasState::default();
+    /// state.set_frame_deadline(Some(Duration::from_secs(2)));
+    /// state.on_error(|error| {
+    ///     if let TuiShaderError::Timeout(_) = error {
+    ///         eprintln!("disabling the effect: {error}");
+    ///     }
+    /// });
+    /// ```
+    #[cfg(feature = "gpu")]
+    pub fn set_frame_deadline(&mut self, deadline: Option<Duration>) {
+        if let Backend::Gpu(backend) = &mut self.backend {
+            backend.set_deadline(deadline);
+        }
+        for backend in [&mut self.candidate, &mut self.previous]
+            .into_iter()
+            .flatten()
+        {
+            backend.set_deadline(deadline);
+        }
+    }
+
+    /// The deadline set with [`ShaderCanvasState::set_frame_deadline`].
+    #[cfg(feature = "gpu")]
+    pub fn frame_deadline(&self) -> Option<Duration> {
+        match &self.backend {
+            Backend::Gpu(backend) => backend.deadline(),
+            Backend::Cpu(_) => None,
+        }
+    }
+
+    /// Renders the next frame ahead of time right after every execution, at a time predicted from the
+    /// interval between the last two, and starts reading it back without waiting for it. If the next
+    /// render asks for the same area within one interval of the predicted time and no uniform or binding
+    /// changed in between, it gets that frame without waiting for the GPU, which hides the latency of
+    /// rendering and reading back from the draw call. When the prediction misses, the GPU wasted the work
+    /// of one frame. Does nothing for states created with [`ShaderCanvasState::from_backend`].
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{ShaderCanvas, ShaderCanvasState};
+    /// let mut terminal = ratatui::init();
+    /// let mut state = ShaderCanvasState::default();
+    /// state.set_prerender(true);
+    /// loop {
+    ///     terminal.draw(|frame| {
+    ///         frame.render_stateful_widget(ShaderCanvas::new(), frame.area(), &mut state);
+    ///     }).unwrap();
+    /// }
+    /// ```
+    #[cfg(feature = "gpu")]
+    pub fn set_prerender(&mut self, enabled: bool) {
+        self.prerender = enabled;
+        if let Backend::Gpu(backend) = &mut self.backend
+            && !enabled
+        {
+            backend.cancel_prerender();
+        }
+    }
+
+    /// Compiles `shader` into a standby slot next to the running one, for live coding setups. The
+    /// candidate is compiled on the same device, keeps the bindings of the state and, if the shader
+    /// declares the same uniforms, their values. It is validated by rendering a single pixel, so a
+    /// broken edit returns an error instead of blanking the canvas. Replaces the previous candidate.
+    /// Fails for states created with [`ShaderCanvasState::from_backend`].
+    ///
+    /// [`ShaderCanvasState::promote_candidate`] swaps the candidate in, [`ShaderCanvasState::rollback`]
+    /// returns to the shader it replaced. If an execution of a promoted shader fails, the state rolls
+    /// back on its own, passes the error to the callbacks of [`ShaderCanvasState::on_error`] and renders
+    /// with the previous shader.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{ShaderCanvasState, WgslShader};
+    /// let mut state = ShaderCanvasState::new(WgslShader::Path("shader.wgsl")).unwrap();
+    /// // After the file was edited:
+    /// match state.load_candidate(WgslShader::Path("shader.wgsl"), None) {
+    ///     Ok(()) => _ = state.promote_candidate(),
+    ///     Err(error) => eprintln!("keeping the running shader: {error}"),
+    /// }
+    /// ```
+    #[cfg(feature = "gpu")]
+    pub fn load_candidate<'a, S>(
+        &mut self,
+        shader: S,
+        entry_point: Option<&'a str>,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        S: TryInto<wgpu::ShaderModuleDescriptor<'a>>,
+        S::Error: Into<Box<dyn Error>>,
+    {
+        let Backend::Gpu(backend) = &self.backend else {
+            return Err("states with a cpu backend can't load shaders".into());
+        };
+        let desc = match shader.try_into() {
+            Ok(desc) => desc,
+            Err(error) => {
+                let error = error.into();
+                self.compile_error = Some(ShaderDiagnostics::from_error(&*error, None));
+                return Err(error);
+            }
+        };
+        let wgsl = match &desc.source {
+            wgpu::ShaderSource::Wgsl(source) => Some(source.to_string()),
+            _ => None,
+        };
+        let result = backend
+            .compile_candidate(desc, entry_point)
+            .block_on()
+            .and_then(|mut candidate| {
+                candidate
+                    .execute(ShaderContext::new(0.0, Rect::new(0, 0, 1, 1)))
+                    .block_on()?;
+                Ok(candidate)
+            });
+        match result {
+            Ok(candidate) => {
+                self.telemetry.pipeline_rebuilds += 1;
+                self.candidate = Some(Box::new(candidate));
+                self.compile_error = None;
+                Ok(())
+            }
+            Err(error) => {
+                self.compile_error = Some(ShaderDiagnostics::from_error(&error, wgsl.as_deref()));
+                Err(error.into())
+            }
+        }
+    }
+
+    /// Why the last call of [`ShaderCanvasState::load_candidate`] failed, `None` if it succeeded. For
+    /// WGSL shaders the diagnostics point at the offending line. Canvases with
+    /// [`ShaderCanvas::show_compile_errors`] show them instead of the running shader.
+    #[cfg(feature = "gpu")]
+    pub fn compile_error(&self) -> Option<&ShaderDiagnostics> {
+        self.compile_error.as_ref()
+    }
+
+    /// Sets the diagnostics shown by canvases with [`ShaderCanvas::show_compile_errors`], e.g. the ones
+    /// of [`ShaderCanvasState::check`] for a shader which failed to create a new state.
+    #[cfg(feature = "gpu")]
+    pub fn set_compile_error(&mut self, error: Option<ShaderDiagnostics>) {
+        self.compile_error = error;
+    }
+
+    /// Whether a candidate loaded with [`ShaderCanvasState::load_candidate`] waits to be promoted.
+    #[cfg(feature = "gpu")]
+    pub fn has_candidate(&self) -> bool {
+        self.candidate.is_some()
+    }
+
+    /// Swaps the candidate loaded with [`ShaderCanvasState::load_candidate`] in, keeping the shader it
+    /// replaces for [`ShaderCanvasState::rollback`]. Returns `false` if there is no candidate.
+    #[cfg(feature = "gpu")]
+    pub fn promote_candidate(&mut self) -> bool {
+        let Backend::Gpu(backend) = &mut self.backend else {
+            return false;
+        };
+        let Some(candidate) = self.candidate.take() else {
+            return false;
+        };
+        self.telemetry.merge(backend.take_telemetry());
+        self.previous = Some(std::mem::replace(backend, candidate));
+        true
+    }
+
+    /// Returns to the shader replaced by the last promoted candidate, dropping the promoted one. Returns
+    /// `false` if there is nothing to roll back to.
+    #[cfg(feature = "gpu")]
+    pub fn rollback(&mut self) -> bool {
+        let Backend::Gpu(backend) = &mut self.backend else {
+            return false;
+        };
+        let Some(previous) = self.previous.take() else {
+            return false;
+        };
+        self.telemetry.merge(backend.take_telemetry());
+        *backend = previous;
+        true
+    }
+
+    /// Moves the state to a new device after its device was lost, if its [`DeviceOptions::migrate`]
+    /// allow it. The candidate, the shader it replaced and a running transition were compiled for the
+    /// lost device and are dropped. Returns whether the state moved.
+    #[cfg(feature = "gpu")]
+    fn migrate(&mut self) -> bool {
+        let Backend::Gpu(backend) = &mut self.backend else {
+            return false;
+        };
+        if !backend.migrates() {
+            return false;
+        }
+        let Ok(migrated) = backend.migrate() else {
+            return false;
+        };
+        self.telemetry.merge(backend.take_telemetry());
+        self.telemetry.pipeline_rebuilds += 1;
+        **backend = migrated;
+        self.candidate = None;
+        self.previous = None;
+        self.transition = None;
+        true
+    }
+
+    /// Overwrites the whole user defined uniform buffer bound to `@group(0) @binding(2)` with raw bytes.
+    /// This is an escape hatch for data laid out by other means, e.g. structs generated with `encase` or
+    /// `crevice`, or data coming from C code.
+    ///
+    /// Fails if `bytes` is smaller than the uniform struct declared by the shader or larger than the
+    /// uniform buffer, or if the state was created with [`ShaderCanvasState::from_backend`]. Bytes
+    /// between the end of `bytes` and the end of the buffer are set to zero.
+    #[cfg(feature = "gpu")]
+    pub fn set_user_bytes(&mut self, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+        let Backend::Gpu(backend) = &mut self.backend else {
+            return Err("states with a cpu backend have no uniform buffer".into());
+        };
+        backend.set_user_bytes(bytes)
+    }
+
+    /// Overwrites the user defined uniform buffer bound to `@group(0) @binding(2)` with `uniforms`. Sharing
+    /// the `#[repr(C)]` struct with the shader, e.g. one written in Rust with rust-gpu, rules out any
+    /// mismatch between the layouts. Fails like [`ShaderCanvasState::set_user_bytes`].
+    #[cfg(feature = "gpu")]
+    pub fn set_uniforms<T: bytemuck::Pod>(&mut self, uniforms: &T) -> Result<(), Box<dyn Error>> {
+        self.set_user_bytes(bytemuck::bytes_of(uniforms))
+    }
+
+    /// Writes `data` to the start of the storage buffer declared at `slot` with
+    /// [`BindingsBuilder::storage_buffer`]. Fails if there is no storage buffer at `slot` or if `data`
+    /// doesn't fit into it.
+    #[cfg(feature = "gpu")]
+    pub fn write_buffer(
+        &mut self,
+        slot: impl Into<BindingSlot>,
+        data: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        let Backend::Gpu(backend) = &mut self.backend else {
+            return Err("states with a cpu backend have no bindings".into());
+        };
+        backend.write_buffer(slot.into(), data)
+    }
+
+    /// Uploads a frame of tightly packed RGBA bytes to the texture declared at `slot` with
+    /// [`BindingsBuilder::texture`], e.g. a decoded video or webcam frame. This is meant to be called every
+    /// frame: the texture is reused as long as the size of the frame doesn't change. Fails if there is no
+    /// texture at `slot` or if `data` doesn't contain `width * height * 4` bytes.
+    #[cfg(feature = "gpu")]
+    pub fn update_texture(
+        &mut self,
+        slot: impl Into<BindingSlot>,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<(), Box<dyn Error>> {
+        let Backend::Gpu(backend) = &mut self.backend else {
+            return Err("states with a cpu backend have no bindings".into());
+        };
+        backend.update_texture(
+            slot.into(),
+            data,
+            extent(width, height, 1),
+            TextureKind::Rgba2d,
+        )
+    }
+
+    /// Uploads tightly packed RGBA bytes, slice after slice, to the 3D texture declared at `slot` with
+    /// [`BindingsBuilder::texture_3d`]. The texture is reused as long as its size doesn't change. Fails if
+    /// there is no 3D texture at `slot` or if `data` doesn't contain `width * height * depth * 4` bytes.
+    #[cfg(feature = "gpu")]
+    pub fn update_texture_3d(
+        &mut self,
+        slot: impl Into<BindingSlot>,
+        data: &[u8],
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> Result<(), Box<dyn Error>> {
+        let Backend::Gpu(backend) = &mut self.backend else {
+            return Err("states with a cpu backend have no bindings".into());
+        };
+        let size = extent(width, height, depth);
+        backend.update_texture(slot.into(), data, size, TextureKind::Rgba3d)
+    }
+
+    /// Uploads `values` to the volume declared at `slot` with [`BindingsBuilder::volume`], e.g. the next
+    /// step of a simulation. The volume is reused as long as its size doesn't change. Fails if there is
+    /// no volume at `slot` or if `values` doesn't contain `width * height * depth` values.
+    #[cfg(feature = "gpu")]
+    pub fn update_volume(
+        &mut self,
+        slot: impl Into<BindingSlot>,
+        values: &[f32],
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> Result<(), Box<dyn Error>> {
+        let Backend::Gpu(backend) = &mut self.backend else {
+            return Err("states with a cpu backend have no bindings".into());
+        };
+        let size = extent(width, height, depth);
+        backend.update_texture(
+            slot.into(),
+            &volume_bytes(values),
+            size,
+            TextureKind::Volume,
+        )
+    }
+
+    /// Crossfades from the current shader to the one of `next` over `duration` of the state's clock,
+    /// following `easing`, instead of switching abruptly. Both shaders run while the transition lasts
+    /// and their outputs are blended per pixel.
+    ///
+    /// The state takes over the backend of `next`, that is its shader, uniforms and bindings, and keeps
+    /// its own clock, providers, hooks and settings. Uniforms set during the transition only reach the
+    /// new shader. Renders of a damage region and [`ShaderCanvas::render_many`] show the new shader right
+    /// away.
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tui_shader::{Easing, ShaderCanvasState, WgslShader};
+    /// let mut state = ShaderCanvasState::new(WgslShader::Path("aurora.wgsl")).unwrap();
+    /// let next = ShaderCanvasState::new(WgslShader::Path("plasma.wgsl")).unwrap();
+    /// state.transition_to(next, Duration::from_secs(2), Easing::CubicInOut);
+    /// ```
+    pub fn transition_to(&mut self, next: ShaderCanvasState, duration: Duration, easing: Easing) {
+        let mut backend = next.backend;
+        // The work done by `next` before isn't part of the work of this state.
+        take_telemetry(&mut backend);
+        let from = std::mem::replace(&mut self.backend, backend);
+        self.transition = Some(Transition {
+            from,
+            start: self.clock.elapsed().as_secs_f32(),
+            duration,
+            easing,
         });
-        let _ = self.device.poll(wgpu::PollType::wait_indefinitely());
-        receiver
-            .recv_async()
-            .await
-            .expect("unable to receive message all senders have been dropped")
-            .expect("on unexpected error occured");
-        let padded_buffer: Vec<Pixel>;
+    }
+
+    /// Whether a transition started with [`ShaderCanvasState::transition_to`] is still running. It ends
+    /// with the first execution after its duration passed.
+    pub fn is_transitioning(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Adds a [`UniformProvider`] which sets uniforms before every execution of the shader.
+    pub fn add_provider(&mut self, provider: impl UniformProvider + 'static) {
+        self.providers.push(provider);
+    }
+
+    /// Registers `hook` to run right before every execution of the shader, after the
+    /// [`UniformProvider`]s. Use it to compute uniforms lazily, only when the shader actually runs.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::ShaderCanvasState;
+    /// let mut state = ShaderCanvasState::default();
+    /// state.on_before_execute(|uniforms| {
+    ///     uniforms.set("load", 0.5);
+    /// });
+    /// ```
+    pub fn on_before_execute(&mut self, hook: fn(&mut Uniforms<'_>)) {
+        self.before_execute.push(hook);
+    }
+
+    /// Registers `hook` to run with the pixels of every render once they were read back, before they are
+    /// mapped to cells. Use it to inspect the result, e.g. for logging or to adapt the quality of the next
+    /// frame. Renders into textures with [`ShaderCanvasState::render_to_texture`] aren't read back and
+    /// don't run the hook.
+    pub fn on_after_execute(&mut self, hook: fn(&FrameView<'_>)) {
+        self.after_execute.push(hook);
+    }
+
+    /// Frees the GPU resources of the state right away: destroys its textures and buffers and waits for
+    /// the GPU to finish the work using them. Call it before the app exits and restores the terminal,
+    /// since some drivers crash or print validation messages when a process exits with work in flight.
+    /// Dropping a state waits for the GPU as well, but leaves freeing the resources to wgpu. The compiled
+    /// shader stays alive as long as clones or other states of the same [`ShaderHandle`] use it.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::ShaderCanvasState;
+    /// let mut terminal = ratatui::init();
+    /// let state = ShaderCanvasState::default();
+    /// // ... run the app
+    /// state.destroy();
+    /// ratatui::restore();
+    /// ```
+    pub fn destroy(self) {
+        #[cfg(feature = "gpu")]
+        if let Backend::Gpu(backend) = &self.backend {
+            backend.destroy();
+        }
+    }
+
+    /// Releases the render textures and readback buffers of the state while it isn't shown, e.g. because
+    /// its tab isn't selected or its pane is collapsed, so apps with many optional shaded views don't hold
+    /// video memory for all of them. The compiled shader, the uniforms and the contents of the bindings are
+    /// kept, as is the last frame. Also releases the resources of a loaded candidate, the shader it
+    /// replaced and a running transition. Does nothing for states created with
+    /// [`ShaderCanvasState::from_backend`].
+    ///
+    /// [`ShaderCanvasState::resume`] or the next render recreate the resources at the size of the area
+    /// they render.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::ShaderCanvasState;
+    /// # let mut state = ShaderCanvasState::default();
+    /// # let selected_tab = 0;
+    /// if selected_tab != 1 {
+    ///     state.suspend();
+    /// }
+    /// ```
+    pub fn suspend(&mut self) {
+        self.suspended = true;
+        self.last_cells = None;
+        #[cfg(feature = "gpu")]
         {
-            let view = buffer_slice.get_mapped_range();
-            padded_buffer = bytemuck::cast_slice(&view).to_vec();
+            let transition = self
+                .transition
+                .as_mut()
+                .map(|transition| &mut transition.from);
+            for backend in [Some(&mut self.backend), transition].into_iter().flatten() {
+                if let Backend::Gpu(backend) = backend {
+                    backend.suspend();
+                }
+            }
+            for backend in [&mut self.candidate, &mut self.previous]
+                .into_iter()
+                .flatten()
+            {
+                backend.suspend();
+            }
+        }
+    }
+
+    /// Marks a state released with [`ShaderCanvasState::suspend`] as shown again. Its resources are
+    /// recreated by the next render, which resumes the state as well.
+    pub fn resume(&mut self) {
+        self.suspended = false;
+    }
+
+    /// Whether the resources of the state are released, see [`ShaderCanvasState::suspend`].
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// The counters of the work the state did since it was created or
+    /// [`ShaderCanvasState::reset_telemetry`] was called, see [`Telemetry`]. Clones start with the
+    /// counters of the original.
+    pub fn telemetry(&self) -> Telemetry {
+        let mut telemetry = self.telemetry;
+        telemetry.merge(backend_telemetry(&self.backend));
+        if let Some(transition) = &self.transition {
+            telemetry.merge(backend_telemetry(&transition.from));
+        }
+        telemetry
+    }
+
+    /// Sets all counters of [`ShaderCanvasState::telemetry`] back to zero, e.g. to measure a single
+    /// interaction.
+    pub fn reset_telemetry(&mut self) {
+        self.telemetry = Telemetry::default();
+        take_telemetry(&mut self.backend);
+        if let Some(transition) = &mut self.transition {
+            take_telemetry(&mut transition.from);
+        }
+    }
+
+    /// An estimate of the GPU and host memory held by the state, including a candidate, the shader kept
+    /// for a rollback and the one a transition fades out. States sharing a `ShaderHandle` count their
+    /// own textures and buffers. The memory of a [`CpuBackend`] isn't known and not counted.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = backend_memory(&self.backend);
+        if let Some(transition) = &self.transition {
+            usage.merge(backend_memory(&transition.from));
+        }
+        #[cfg(feature = "gpu")]
+        for backend in [&self.candidate, &self.previous].into_iter().flatten() {
+            usage.merge(backend.memory_usage());
+        }
+        if let Some(frame) = &self.last_frame {
+            usage.host += (frame.pixels.len() * std::mem::size_of::<Pixel>()) as u64;
         }
-        padded_buffer
+        if let Some(cells) = &self.last_cells {
+            usage.host +=
+                (cells.content.len() * std::mem::size_of::<ratatui_core::buffer::Cell>()) as u64;
+        }
+        usage
+    }
+
+    /// Counts a render of a [`ShaderCanvas`] which styled the last frame again.
+    pub(crate) fn record_skipped_frame(&mut self) {
+        self.telemetry.skipped_frames += 1;
+    }
+
+    /// Registers `callback` to receive the errors of executions of the shader on the GPU, e.g. a lost
+    /// device, instead of panicking inside `terminal.draw`. Failed executions produce transparent black
+    /// pixels and are retried on the next render, the app can fall back to plain styling in the meantime.
+    ///
+    /// ```rust,no_run
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// # use tui_shader::ShaderCanvasState;
+    /// static SHADER_FAILED: AtomicBool = AtomicBool::new(false);
+    ///
+    /// let mut state = ShaderCanvasState::default();
+    /// state.on_error(|_| SHADER_FAILED.store(true, Ordering::Relaxed));
+    /// ```
+    #[cfg(feature = "gpu")]
+    pub fn on_error(&mut self, callback: fn(TuiShaderError)) {
+        self.on_error.push(callback);
+    }
+
+    /// Returns a handle to set the user defined uniforms of the shader by name. The layout of the uniform
+    /// struct is reflected from the shader, so uniforms can be added to a shader without defining a
+    /// matching struct in Rust.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{ShaderCanvasState, WgslShader};
+    /// # use ratatui::style::Color;
+    /// let mut state = ShaderCanvasState::new(WgslShader::Path("shader.wgsl")).unwrap();
+    /// state
+    ///     .uniforms()
+    ///     .set("threshold", 0.4)
+    ///     .set("tint", Color::Rgb(255, 128, 0));
+    /// ```
+    pub fn uniforms(&mut self) -> Uniforms<'_> {
+        Uniforms { state: self }
+    }
+
+    /// Sets the [`ColorConfig`] converting the colors of the shader before they are mapped to cells, e.g.
+    /// for shaders computing in linear space. Renders into textures with
+    /// [`ShaderCanvasState::render_to_texture`] aren't converted.
+    pub fn set_color_config(&mut self, config: ColorConfig) {
+        self.color_config = config;
+        self.color_lut = config.lut();
+    }
+
+    /// The [`ColorConfig`] of the state.
+    pub fn color_config(&self) -> ColorConfig {
+        self.color_config
+    }
+
+    /// Grades the colors of every frame with a 3D lookup table, after the conversions of the
+    /// [`ColorConfig`], or stops grading them with `None`. Renders into textures with
+    /// [`ShaderCanvasState::render_to_texture`] aren't graded, bind the table to the shader with
+    /// [`BindingsBuilder::lut_texture`](crate::BindingsBuilder::lut_texture) instead.
+    pub fn set_color_grade(&mut self, lut: Option<CubeLut>) {
+        self.color_grade = lut.map(Arc::new);
+    }
+
+    /// The 3D lookup table grading the colors of the state, if any.
+    pub fn color_grade(&self) -> Option<&CubeLut> {
+        self.color_grade.as_deref()
     }
 
     /// Sets the [`ShaderCanvasState`]'s [`Instant`]. This can be useful if you want to sync the time input variable
     /// across multiple fragment shaders, or a specific [`Instant`] is required.
-    pub fn set_instant(mut self, instant: Instant) {
-        self.instant = instant;
+    pub fn set_instant(&mut self, instant: Instant) {
+        self.clock.set_instant(instant);
+    }
+
+    /// Sets the `time` input to `elapsed`, from where it keeps advancing unless the clock is paused.
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        self.clock.set_elapsed(elapsed);
+    }
+
+    /// Switches how the clock of the state advances, see [`ClockMode`]. Use
+    /// [`ShaderCanvasState::set_elapsed`] to restart a fixed clock at zero.
+    pub fn set_clock_mode(&mut self, mode: ClockMode) {
+        self.clock.set_mode(mode);
+    }
+
+    /// Advances the `time` input by `offset`. States created at the same time can be phase-shifted this
+    /// way, so that several widgets running the same shader don't animate in lockstep.
+    pub fn offset(&mut self, offset: Duration) {
+        self.clock.offset(offset);
+    }
+
+    /// Passes the local date and time of day to the shader as a `vec4<f32>` bound to
+    /// `@group(0) @binding(4)`: x is the number of seconds since midnight, y the year, z the month
+    /// (`1` to `12`) and w the day of the month (`1` to `31`). All components are zero while disabled,
+    /// which is the default. Only shaders running on the GPU receive the date.
+    ///
+    /// ```wgsl
+    /// @group(0) @binding(4) var<uniform> date: vec4<f32>;
+    ///
+    /// @fragment
+    /// fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    ///     let daylight = sin(date.x / 86400.0 * 3.14159);
+    ///     return vec4<f32>(daylight, daylight, 0.5 + daylight * 0.5, 1.0);
+    /// }
+    /// ```
+    #[cfg(feature = "wall-clock")]
+    pub fn set_wall_clock(&mut self, enabled: bool) {
+        self.wall_clock = enabled;
+    }
+
+    /// Sets the size of a terminal cell in screen pixels, which is passed to the shader as a `vec4<f32>`
+    /// bound to `@group(0) @binding(5)`: x and y are the width and height of a cell, z and w the width
+    /// and height of the whole canvas in screen pixels. Shaders can use it to correct the aspect ratio of
+    /// cells, e.g. to draw circles instead of ellipses. Defaults to 8 x 16 pixels, zero sizes are
+    /// ignored.
+    ///
+    /// ```wgsl
+    /// @group(0) @binding(5) var<uniform> cell: vec4<f32>;
+    ///
+    /// @fragment
+    /// fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    ///     let aspect = cell.z / cell.w;
+    ///     let distance = length((uv - 0.5) * vec2<f32>(aspect, 1.0));
+    ///     return vec4<f32>(vec3<f32>(step(distance, 0.4)), 1.0);
+    /// }
+    /// ```
+    pub fn set_cell_size(&mut self, width: u16, height: u16) {
+        if width > 0 && height > 0 {
+            self.cell_size = (width, height);
+        }
+    }
+
+    /// The size of a terminal cell in screen pixels, see [`ShaderCanvasState::set_cell_size`].
+    pub fn cell_size(&self) -> (u16, u16) {
+        self.cell_size
+    }
+
+    /// Queries the size of a terminal cell in screen pixels from the terminal and passes it to the shader,
+    /// see [`ShaderCanvasState::set_cell_size`]. The size is read with the `TIOCGWINSZ` ioctl, falling
+    /// back to the `CSI 14 t` escape sequence for terminals leaving the pixel size out. Returns `false` if
+    /// the terminal doesn't report its size in pixels, as always on Windows, in which case the previous
+    /// size is kept. Call it again when the terminal is resized or its font changes, but not while another
+    /// thread reads events from the terminal, which could swallow the reply to the escape sequence.
+    ///
+    /// The size is only exposed to the shader, which corrects the aspect ratio itself as shown above.
+    pub fn detect_cell_size(&mut self) -> bool {
+        let Some((width, height)) = crate::terminal::cell_size() else {
+            return false;
+        };
+        self.set_cell_size(width, height);
+        true
+    }
+
+    /// Publishes the position of the cursor or the center of the focused widget, in the coordinates of the
+    /// terminal, so that shaders can spotlight, ripple or glow around wherever the user is working. It is
+    /// passed to the shader as a `vec4<f32>` bound to `@group(0) @binding(6)`: x and y are the center of
+    /// the cell normalized to the canvas like `uv`, z is `1.0` while a focus point is set and `0.0`
+    /// otherwise. Points outside of the canvas lie outside of the `0.0` to `1.0` range.
+    ///
+    /// ```wgsl
+    /// @group(0) @binding(6) var<uniform> focus: vec4<f32>;
+    ///
+    /// @fragment
+    /// fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    ///     let glow = focus.z * exp(-distance(uv, focus.xy) * 8.0);
+    ///     return vec4<f32>(glow, glow * 0.6, 0.2, 1.0);
+    /// }
+    /// ```
+    pub fn set_focus_point(&mut self, x: u16, y: u16) {
+        self.focus_point = Some((x, y));
+    }
+
+    /// Removes the focus point set with [`ShaderCanvasState::set_focus_point`].
+    pub fn clear_focus_point(&mut self) {
+        self.focus_point = None;
+    }
+
+    /// Updates the [`Interaction`] of the state with pointer input. From the first event on, the drag,
+    /// scroll and click values are passed to the shader, see [`Interaction`] for the uniforms.
+    pub fn handle_pointer(&mut self, event: PointerEvent) {
+        let time = self.clock.elapsed().as_secs_f32();
+        self.interaction.get_or_insert_default().handle(event, time);
+        self.power.wake();
+        self.idle.record_activity(Instant::now());
+        if let Some(camera) = &mut self.camera {
+            self.orbit_controls.handle(camera, event);
+        }
+    }
+
+    /// Same as [`ShaderCanvasState::handle_pointer`] for a crossterm [`Event`](ratatui::crossterm::event::Event).
+    /// Returns `false` if the event isn't pointer input, see [`PointerEvent::from_crossterm`].
+    #[cfg(feature = "runner")]
+    pub fn handle_event(&mut self, event: &ratatui::crossterm::event::Event) -> bool {
+        let Some(event) = PointerEvent::from_crossterm(event) else {
+            return false;
+        };
+        self.handle_pointer(event);
+        true
+    }
+
+    /// The values derived from the pointer input passed to [`ShaderCanvasState::handle_pointer`].
+    pub fn interaction(&self) -> Interaction {
+        self.interaction.unwrap_or_default()
+    }
+
+    /// Sets the [`Camera`] written to the `camera_*` uniforms before every execution, or stops writing
+    /// them with `None`. While a camera is set, [`ShaderCanvasState::handle_pointer`] also orbits it with
+    /// the [`OrbitControls`] of the state.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{Camera, ShaderCanvasState, WgslShader};
+    /// let mut state = ShaderCanvasState::new(WgslShader::Path("raymarch.wgsl")).unwrap();
+    /// state.set_camera(Some(Camera::new([0.0, 1.0, 4.0], [0.0; 3])));
+    /// ```
+    pub fn set_camera(&mut self, camera: Option<Camera>) {
+        self.camera = camera;
+    }
+
+    /// The [`Camera`] written to the uniforms, if any.
+    pub fn camera(&self) -> Option<&Camera> {
+        self.camera.as_ref()
+    }
+
+    /// Gets a mutable reference to the [`Camera`] written to the uniforms, if any.
+    pub fn camera_mut(&mut self) -> Option<&mut Camera> {
+        self.camera.as_mut()
+    }
+
+    /// Sets the [`OrbitControls`] moving the [`Camera`] in [`ShaderCanvasState::handle_pointer`].
+    pub fn set_orbit_controls(&mut self, controls: OrbitControls) {
+        self.orbit_controls = controls;
+    }
+
+    /// Resets the accumulated drag and scroll and forgets the last click, e.g. to return a scene rotated
+    /// by dragging to its initial view.
+    pub fn reset_interaction(&mut self) {
+        if let Some(interaction) = &mut self.interaction {
+            *interaction = Interaction::default();
+        }
     }
 
     /// Gets the [`ShaderCanvasState`]'s [`Instant`].
     pub fn get_instant(&self) -> Instant {
-        self.instant
+        self.clock.get_instant()
     }
-}
 
-impl Default for ShaderCanvasState {
-    fn default() -> Self {
-        Self::new(wgpu::include_wgsl!("shaders/default_fragment.wgsl")).unwrap()
+    /// Gets the [`ShaderClock`] driving the `time` input of the [`ShaderCanvasState`].
+    pub fn clock(&self) -> &ShaderClock {
+        &self.clock
     }
-}
-
-async fn get_device_and_queue() -> (wgpu::Device, wgpu::Queue) {
-    let instance = wgpu::Instance::default();
-
-    let adapter = instance
-        .request_adapter(&wgpu::RequestAdapterOptions::default())
-        .await
-        .expect("unable to create adapter from wgpu instance");
-
-    adapter
-        .request_device(&wgpu::DeviceDescriptor {
-            label: None,
-            required_features: wgpu::Features::empty(),
-            required_limits: wgpu::Limits::downlevel_defaults(),
-            memory_hints: wgpu::MemoryHints::Performance,
-            trace: wgpu::Trace::Off,
-            experimental_features: ExperimentalFeatures::disabled(),
-        })
-        .await
-        .expect("unable to create device and queue from wgpu adapter")
-}
 
-fn create_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
-    let texture_desc = wgpu::TextureDescriptor {
-        size: wgpu::Extent3d {
-            width,
-            height,
-            depth_or_array_layers: 1,
-        },
-        mip_level_count: 1,
-        sample_count: 1,
-        dimension: wgpu::TextureDimension::D2,
-        format: wgpu::TextureFormat::Rgba8Unorm,
-        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
-        label: None,
-        view_formats: &[wgpu::TextureFormat::Rgba8Unorm],
-    };
-    device.create_texture(&texture_desc)
-}
+    /// Gets a mutable reference to the [`ShaderClock`], e.g. to pause and resume the animation.
+    pub fn clock_mut(&mut self) -> &mut ShaderClock {
+        &mut self.clock
+    }
 
-fn create_output_buffer(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Buffer {
-    let row_size = width * 4;
-    let bytes_per_row = (row_size + 255) & !255;
+    /// Renders `count` frames of a default [`ShaderCanvas`] into in-memory [`Buffer`]s of the size of
+    /// `area`, without a terminal. The `time` input starts at zero and advances by `dt` every frame,
+    /// independently of the wall clock, so the output is deterministic.
+    pub fn capture_frames(&mut self, area: Rect, count: usize, dt: Duration) -> Vec<Buffer> {
+        self.capture_frames_with(&ShaderCanvas::default(), area, count, dt)
+    }
 
-    device.create_buffer(&wgpu::BufferDescriptor {
-        label: None,
-        size: (bytes_per_row * height) as wgpu::BufferAddress,
-        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-        mapped_at_creation: false,
-    })
+    /// Same as [`ShaderCanvasState::capture_frames`] but renders the given [`ShaderCanvas`].
+    pub fn capture_frames_with(
+        &mut self,
+        canvas: &ShaderCanvas,
+        area: Rect,
+        count: usize,
+        dt: Duration,
+    ) -> Vec<Buffer> {
+        (0..count)
+            .map(|frame| {
+                let mut buffer = Buffer::empty(area);
+                let time = dt.as_secs_f32() * frame as f32;
+                canvas.render_at(area, &mut buffer, self, time);
+                buffer
+            })
+            .collect()
+    }
 }
 
-fn create_input_buffer<A: NoUninit>(device: &wgpu::Device, content: A) -> wgpu::Buffer {
-    device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        label: None,
-        contents: bytemuck::cast_slice(&[content]),
-        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-    })
+/// Handle to the user defined uniforms of a [`ShaderCanvasState`], returned by
+/// [`ShaderCanvasState::uniforms`].
+#[derive(Debug)]
+pub struct Uniforms<'a> {
+    state: &'a mut ShaderCanvasState,
 }
 
-fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
-    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-        entries: &[
-            wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
-                },
-                count: None,
-            },
-            wgpu::BindGroupLayoutEntry {
-                binding: 1,
-                visibility: wgpu::ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
-                },
-                count: None,
-            },
-        ],
-        label: None,
-    })
-}
+impl Uniforms<'_> {
+    /// Sets the uniform called `name`, ignoring names the shader doesn't declare and values that don't fit
+    /// the type of the uniform. See [`UniformValue`] for the supported values.
+    pub fn set(&mut self, name: &str, value: impl Into<UniformValue>) -> &mut Self {
+        self.try_set(name, value);
+        self
+    }
+
+    /// Sets the uniform called `name`. Returns `false` if the shader doesn't declare such a uniform or
+    /// the value doesn't fit its type. A [`CpuBackend`] only receives scalar values.
+    pub fn try_set(&mut self, name: &str, value: impl Into<UniformValue>) -> bool {
+        let value = value.into();
+        let (written, changed) = match &mut self.state.backend {
+            #[cfg(feature = "gpu")]
+            Backend::Gpu(backend) => backend.set_uniform(name, value),
+            // Values written to a CPU backend can't be compared, every write counts as a change.
+            Backend::Cpu(backend) => {
+                let written = value.as_f32().is_some_and(|value| {
+                    backend
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .set_uniform(name, value)
+                });
+                (written, written)
+            }
+        };
+        if changed {
+            self.state.idle.record_activity(Instant::now());
+        }
+        written
+    }
 
-fn create_bind_group(
-    device: &wgpu::Device,
-    layout: &wgpu::BindGroupLayout,
-    buffers: &[&wgpu::Buffer],
-) -> wgpu::BindGroup {
-    let mut entries = Vec::new();
-    buffers.iter().for_each(|buffer| {
-        entries.push(wgpu::BindGroupEntry {
-            binding: entries.len() as u32,
-            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                buffer,
-                offset: 0,
-                size: None,
-            }),
-        })
-    });
-    device.create_bind_group(&wgpu::BindGroupDescriptor {
-        layout,
-        entries: &entries,
-        label: None,
-    })
+    /// The names of the uniforms declared by the shader. Empty for states created with
+    /// [`ShaderCanvasState::from_backend`].
+    pub fn names(&self) -> Vec<String> {
+        match &self.state.backend {
+            #[cfg(feature = "gpu")]
+            Backend::Gpu(backend) => backend.uniform_names(),
+            Backend::Cpu(_) => Vec::new(),
+        }
+    }
 }
 
-fn create_pipeline_layout(
-    device: &wgpu::Device,
-    bind_group_layout: &wgpu::BindGroupLayout,
-) -> wgpu::PipelineLayout {
-    device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        label: None,
-        bind_group_layouts: &[bind_group_layout],
-        push_constant_ranges: &[],
-    })
+#[cfg(feature = "gpu")]
+impl Default for ShaderCanvasState {
+    fn default() -> Self {
+        Self::new(DEFAULT_FRAGMENT_SHADER_DESCRIPTOR).unwrap()
+    }
 }
 
-fn create_render_pipeline(
-    device: &wgpu::Device,
-    pipeline_layout: &wgpu::PipelineLayout,
-    vertex_shader: &wgpu::ShaderModule,
-    fragment_shader: &wgpu::ShaderModule,
-    entry_point: Option<&str>,
-) -> wgpu::RenderPipeline {
-    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-        label: None,
-        layout: Some(pipeline_layout),
-        vertex: wgpu::VertexState {
-            module: vertex_shader,
-            entry_point: Some("main"),
-            buffers: &[],
-            compilation_options: wgpu::PipelineCompilationOptions::default(),
-        },
-        fragment: Some(wgpu::FragmentState {
-            module: fragment_shader,
-            entry_point,
-            compilation_options: wgpu::PipelineCompilationOptions::default(),
-            targets: &[Some(wgpu::ColorTargetState {
-                format: wgpu::TextureFormat::Rgba8Unorm,
-                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                write_mask: wgpu::ColorWrites::ALL,
-            })],
-        }),
-        primitive: wgpu::PrimitiveState::default(),
-        depth_stencil: None,
-        multisample: wgpu::MultisampleState::default(),
-        multiview: None,
-        cache: None,
-    })
+/// Without the `gpu` feature, the pixels of the default shader are computed on the CPU.
+#[cfg(not(feature = "gpu"))]
+impl Default for ShaderCanvasState {
+    fn default() -> Self {
+        Self::from_backend(|_: cpu::PixelInput| [255, 0, 255, 255])
+    }
 }
@@ -0,0 +1,102 @@
+//! A standard library of WGSL functions, so shaders don't have to copy the same noise and distance
+//! functions into every file. Include modules with the `#include <name>` directive of the
+//! preprocessor, which every [`WgslShader`](crate::WgslShader) runs:
+//!
+//! ```wgsl
+//! #include <fbm>
+//! #include <palette>
+//!
+//! @group(0) @binding(0) var<uniform> time: vec4<f32>;
+//!
+//! @fragment
+//! fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+//!     let clouds = fbm(uv * 4.0 + time.x * 0.1, 5);
+//!     return vec4<f32>(rainbow(clouds), 1.0);
+//! }
+//! ```
+//!
+//! Included modules are appended to the end of the shader, together with the modules they depend on, so
+//! the line numbers in errors still match the file. The function names are reserved in shaders which
+//! include them. Shaders embedded with [`include_wgsl!`](wgpu::include_wgsl!) aren't preprocessed,
+//! concatenate the constants of this module with the shader instead.
+//!
+//! | Module     | Functions                                                                      |
+//! |------------|--------------------------------------------------------------------------------|
+//! | `hash`     | `hash11`, `hash21`, `hash22`, `hash33`                                         |
+//! | `noise`    | `value_noise`, `perlin_noise`, `simplex_noise`                                 |
+//! | `fbm`      | `fbm`                                                                          |
+//! | `sdf`      | `sd_circle`, `sd_box`, `sd_rounded_box`, `sd_segment`, `sd_ring`, `op_union`, `op_intersection`, `op_subtraction`, `op_smooth_union` |
+//! | `palette`  | `palette`, `rainbow`, `hsv_to_rgb`                                             |
+//! | `rotation` | `rotate2d`, `rotate_x`, `rotate_y`, `rotate_z`                                 |
+//! | `lut`      | `apply_lut`                                                                    |
+//! | `pack`     | `pack_rgb444`, `pack_rgb565`, `pack_output`, `pack_rgb444_pair`, `pack_rgb565_glyph` |
+//! | `camera`   | `camera_ray`                                                                   |
+//! | `context`  | the built-in inputs `time`, `rect`, `date`, `cell`, `focus`, `scroll`, `instance`, see [`CONTEXT_WGSL`](crate::CONTEXT_WGSL) |
+
+/// Hash functions returning pseudo random values between `0.0` and `1.0`.
+pub const HASH: &str = include_str!("shaders/stdlib/hash.wgsl");
+
+/// Value, Perlin and simplex noise in 2D. Requires [`HASH`].
+pub const NOISE: &str = include_str!("shaders/stdlib/noise.wgsl");
+
+/// Fractal Brownian motion summing octaves of value noise. Requires [`NOISE`].
+pub const FBM: &str = include_str!("shaders/stdlib/fbm.wgsl");
+
+/// Signed distance functions of 2D shapes and operators combining them.
+pub const SDF: &str = include_str!("shaders/stdlib/sdf.wgsl");
+
+/// Cosine palettes and color conversions.
+pub const PALETTE: &str = include_str!("shaders/stdlib/palette.wgsl");
+
+/// 2D and 3D rotation matrices.
+pub const ROTATION: &str = include_str!("shaders/stdlib/rotation.wgsl");
+
+/// Color grading with 3D lookup tables like [`CubeLut`](crate::CubeLut).
+pub const LUT: &str = include_str!("shaders/stdlib/lut.wgsl");
+
+/// Packing two colors or a color and a glyph index into the output, decoded with
+/// [`Sample::unpack_rgb444_pair`](crate::Sample::unpack_rgb444_pair) and
+/// [`Sample::glyph_index`](crate::Sample::glyph_index).
+pub const PACK: &str = include_str!("shaders/stdlib/pack.wgsl");
+
+/// Rays of a perspective camera for ray marching, see [`Camera`](crate::Camera).
+pub const CAMERA: &str = include_str!("shaders/stdlib/camera.wgsl");
+
+/// The modules by name, with the modules they depend on.
+const MODULES: &[(&str, &str, &[&str])] = &[
+    ("hash", HASH, &[]),
+    ("noise", NOISE, &["hash"]),
+    ("fbm", FBM, &["noise"]),
+    ("sdf", SDF, &[]),
+    ("palette", PALETTE, &[]),
+    ("rotation", ROTATION, &[]),
+    ("lut", LUT, &[]),
+    ("pack", PACK, &[]),
+    ("camera", CAMERA, &[]),
+    ("context", crate::context::CONTEXT_WGSL, &[]),
+];
+
+/// The source of the module called `name`, without the modules it depends on.
+pub fn module(name: &str) -> Option<&'static str> {
+    MODULES
+        .iter()
+        .find(|(module, ..)| *module == name)
+        .map(|(_, source, _)| *source)
+}
+
+/// Appends the module called `name` to `included`, after the modules it depends on, unless it is
+/// included already. Returns `false` if there is no such module.
+#[cfg(feature = "gpu")]
+pub(crate) fn include(name: &str, included: &mut Vec<&'static str>) -> bool {
+    let Some((name, _, dependencies)) = MODULES.iter().find(|(module, ..)| *module == name) else {
+        return false;
+    };
+    if included.contains(name) {
+        return true;
+    }
+    for dependency in *dependencies {
+        include(dependency, included);
+    }
+    included.push(name);
+    true
+}
@@ -0,0 +1,238 @@
+use std::io::BufRead;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::provider::UniformProvider;
+use crate::state::Uniforms;
+use crate::uniforms::UniformValue;
+
+/// The values received by a [`UniformStream`], shared with the thread reading them.
+#[derive(Debug, Default)]
+struct Received {
+    values: Vec<(String, UniformValue)>,
+    last_error: Option<String>,
+    closed: bool,
+}
+
+impl Received {
+    fn set(&mut self, name: String, value: UniformValue) {
+        match self.values.iter_mut().find(|(known, _)| *known == name) {
+            Some((_, previous)) => *previous = value,
+            None => self.values.push((name, value)),
+        }
+    }
+}
+
+/// [`UniformStream`] is a [`UniformProvider`] reading uniforms line by line from a pipe in a background
+/// thread, so shell scripts can animate a shader, e.g. `sensors | my-filter | shader-app`. Every line is
+/// one of:
+///
+/// - A JSON object mapping uniforms to numbers, booleans or arrays of two to four numbers, e.g.
+///   `{"speed": 0.5, "tint": [1.0, 0.2, 0.2]}`.
+/// - Numbers separated by whitespace or commas, e.g. `0.5 1.2`, which set the uniforms passed to the
+///   constructor in order.
+///
+/// The latest value of every uniform is kept and written before every execution of the shader. Lines
+/// which can't be parsed are skipped, see [`UniformStream::last_error`].
+///
+/// ```rust,no_run
+/// # use tui_shader::{ShaderCanvasState, UniformStream, WgslShader};
+/// let mut state = ShaderCanvasState::new(WgslShader::Path("monitor.wgsl")).unwrap();
+/// state.add_provider(UniformStream::stdin(["temperature", "fan_speed"]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct UniformStream {
+    received: Arc<Mutex<Received>>,
+}
+
+impl UniformStream {
+    /// Creates a new [`UniformStream`] reading from standard input. Lines of plain numbers set the
+    /// uniforms called `names` in order.
+    ///
+    /// The reading thread blocks on standard input until it is closed, even after the stream was dropped.
+    pub fn stdin(names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self::from_reader(std::io::BufReader::new(std::io::stdin()), names)
+    }
+
+    /// Creates a new [`UniformStream`] reading from `reader`, e.g. a named pipe or the output of a child
+    /// process. Lines of plain numbers set the uniforms called `names` in order.
+    pub fn from_reader(
+        reader: impl BufRead + Send + 'static,
+        names: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let names: Vec<String> = names.into_iter().map(Into::into).collect();
+        let received = Arc::new(Mutex::new(Received::default()));
+        let shared = Arc::clone(&received);
+        std::thread::spawn(move || {
+            for line in reader.lines() {
+                let parsed = line
+                    .map_err(|error| error.to_string())
+                    .and_then(|line| parse_line(&line, &names));
+                let mut received = shared.lock().unwrap_or_else(PoisonError::into_inner);
+                match parsed {
+                    Ok(values) => {
+                        for (name, value) in values {
+                            received.set(name, value);
+                        }
+                    }
+                    Err(error) => received.last_error = Some(error),
+                }
+            }
+            shared.lock().unwrap_or_else(PoisonError::into_inner).closed = true;
+        });
+        Self { received }
+    }
+
+    /// The error of the last line which couldn't be read or parsed, if any.
+    pub fn last_error(&self) -> Option<String> {
+        self.lock().last_error.clone()
+    }
+
+    /// Whether the input was closed. The values received before are kept.
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Received> {
+        self.received.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl UniformProvider for UniformStream {
+    fn provide(&mut self, uniforms: &mut Uniforms<'_>) {
+        for (name, value) in &self.lock().values {
+            uniforms.set(name, *value);
+        }
+    }
+}
+
+/// The uniforms set by `line`, see [`UniformStream`].
+fn parse_line(line: &str, names: &[String]) -> Result<Vec<(String, UniformValue)>, String> {
+    let line = line.trim();
+    if line.starts_with('{') {
+        return JsonParser { rest: line }.object();
+    }
+    line.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|number| !number.is_empty())
+        .zip(names)
+        .map(|(number, name)| {
+            let value = number
+                .parse::<f32>()
+                .map_err(|error| format!("invalid number `{number}`: {error}"))?;
+            Ok((name.clone(), UniformValue::Float(value)))
+        })
+        .collect()
+}
+
+/// Parses the flat JSON objects of a [`UniformStream`].
+struct JsonParser<'a> {
+    rest: &'a str,
+}
+
+impl JsonParser<'_> {
+    fn object(&mut self) -> Result<Vec<(String, UniformValue)>, String> {
+        let mut values = Vec::new();
+        self.expect("{")?;
+        if !self.eat("}") {
+            loop {
+                let name = self.string()?;
+                self.expect(":")?;
+                values.push((name, self.value()?));
+                if self.eat("}") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+        if !self.rest.trim().is_empty() {
+            return Err(format!(
+                "unexpected `{}` after the object",
+                self.rest.trim()
+            ));
+        }
+        Ok(values)
+    }
+
+    fn value(&mut self) -> Result<UniformValue, String> {
+        if self.eat("[") {
+            let mut numbers = Vec::new();
+            if !self.eat("]") {
+                loop {
+                    numbers.push(self.number()?);
+                    if self.eat("]") {
+                        break;
+                    }
+                    self.expect(",")?;
+                }
+            }
+            return match numbers[..] {
+                [x, y] => Ok(UniformValue::Vec2([x, y])),
+                [x, y, z] => Ok(UniformValue::Vec3([x, y, z])),
+                [x, y, z, w] => Ok(UniformValue::Vec4([x, y, z, w])),
+                _ => Err(format!("expected 2 to 4 numbers, got {}", numbers.len())),
+            };
+        }
+        if self.eat("true") {
+            return Ok(true.into());
+        }
+        if self.eat("false") {
+            return Ok(false.into());
+        }
+        self.number().map(UniformValue::Float)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut string = String::new();
+        let mut chars = self.rest.char_indices();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[index + 1..];
+                    return Ok(string);
+                }
+                '\\' => match chars.next() {
+                    Some((_, escaped @ ('"' | '\\' | '/'))) => string.push(escaped),
+                    _ => return Err("unsupported escape in a name".to_owned()),
+                },
+                c => string.push(c),
+            }
+        }
+        Err("unterminated string".to_owned())
+    }
+
+    fn number(&mut self) -> Result<f32, String> {
+        self.skip_whitespace();
+        let end = self
+            .rest
+            .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+            .unwrap_or(self.rest.len());
+        let (number, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        number
+            .parse()
+            .map_err(|_| format!("expected a number, got `{number}`"))
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(format!("expected `{expected}`"))
+        }
+    }
+
+    fn eat(&mut self, expected: &str) -> bool {
+        self.skip_whitespace();
+        match self.rest.strip_prefix(expected) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+}
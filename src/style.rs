@@ -4,6 +4,7 @@ use crate::Pixel;
 
 /// Determines which character to use for Cell.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CharacterRule {
     /// [`CharacterRule::Always`] takes a single char and applies it to all cells.
     Always(char),
@@ -27,6 +28,7 @@ pub enum CharacterRule {
     ///     .character_rule(char_map)
     ///     .style_rule(StyleRule::ColorFg);
     /// ```
+    #[cfg_attr(feature = "serde", serde(skip))]
     Map(fn(Sample) -> char),
 }
 
@@ -39,6 +41,7 @@ impl Default for CharacterRule {
 
 /// Determines how to use the output of the fragment shader to style a Cell.
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StyleRule {
     /// [`StyleRule::ColorFg`] only applies the color from the shader to the foreground of the Cell.
     ColorFg,
@@ -67,11 +70,13 @@ pub enum StyleRule {
     /// let canvas = ShaderCanvas::new()
     ///     .style_rule(style_map);
     /// ```
+    #[cfg_attr(feature = "serde", serde(skip))]
     Map(fn(Sample) -> Style),
 }
 
 /// Primarily used in [`CharacterRule::Map`] and [`StyleRule::Map`], it provides access to a cells color and position
 /// allowing to map the output of the shader to more complex behaviour.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Sample {
     pixel: Pixel,
     position: (u16, u16),
@@ -130,4 +135,79 @@ impl Sample {
     pub fn v(&self) -> f32 {
         self.uv.1
     }
+
+    /// The red, green and blue channels of the [`Sample`] as one 24 bit value, red holding the lowest
+    /// byte, for shaders which pack other data than a color into their output. The output of shaders is
+    /// blended over opaque black, so alpha can't carry data.
+    ///
+    /// The `pack` module of the [`stdlib`](crate::stdlib) writes the layouts decoded by
+    /// [`Sample::unpack_rgb444_pair`], [`Sample::unpack_rgb565`] and [`Sample::glyph_index`]. Color
+    /// conversions and grades of the state change the channels and break packed values, so leave them
+    /// off for packing shaders.
+    pub fn packed(&self) -> u32 {
+        u32::from_le_bytes([self.pixel[0], self.pixel[1], self.pixel[2], 0])
+    }
+
+    /// Decodes two RGB444 colors, the first one from the lowest 12 bits and the second one from the
+    /// highest, as written by `pack_rgb444_pair`. Use them e.g. for the foreground and background of
+    /// half block characters.
+    ///
+    /// ```rust
+    /// # use tui_shader::{CharacterRule, ShaderCanvas, StyleRule};
+    /// # use ratatui::style::Style;
+    /// let canvas = ShaderCanvas::new()
+    ///     .character_rule(CharacterRule::Always('▀'))
+    ///     .style_rule(StyleRule::Map(|sample| {
+    ///         let (top, bottom) = sample.unpack_rgb444_pair();
+    ///         Style::new().fg(top).bg(bottom)
+    ///     }));
+    /// ```
+    pub fn unpack_rgb444_pair(&self) -> (Color, Color) {
+        let packed = self.packed();
+        (rgb444(packed as u16), rgb444((packed >> 12) as u16))
+    }
+
+    /// Decodes the RGB565 color in red and green, as written by `pack_rgb565_glyph`.
+    pub fn unpack_rgb565(&self) -> Color {
+        rgb565(self.packed() as u16)
+    }
+
+    /// The index in the highest `bits` of the 24 bits of [`Sample::packed`], as written by
+    /// `pack_rgb565_glyph`, e.g. to pick a character from a ramp in a [`CharacterRule::Map`]. `bits` is
+    /// clamped to 24, `0` bits always give `0`.
+    ///
+    /// ```rust
+    /// # use tui_shader::{CharacterRule, ShaderCanvas};
+    /// const RAMP: [char; 4] = [' ', '░', '▒', '▓'];
+    /// let canvas = ShaderCanvas::new()
+    ///     .character_rule(CharacterRule::Map(|sample| RAMP[sample.glyph_index(2) as usize]));
+    /// ```
+    pub fn glyph_index(&self, bits: u32) -> u32 {
+        match bits.min(PACKED_BITS) {
+            0 => 0,
+            bits => self.packed() >> (PACKED_BITS - bits),
+        }
+    }
+}
+
+/// The number of bits of [`Sample::packed`].
+const PACKED_BITS: u32 = 24;
+
+/// Expands an RGB444 color in the lowest 12 bits of `value` to 8 bits per channel.
+fn rgb444(value: u16) -> Color {
+    let channel = |shift: u16| ((value >> shift) & 0xf) as u8 * 17;
+    Color::Rgb(channel(8), channel(4), channel(0))
+}
+
+/// Expands an RGB565 color to 8 bits per channel, repeating the highest bits in the lowest ones so
+/// that full intensity stays full.
+fn rgb565(value: u16) -> Color {
+    let r = (value >> 11) as u8 & 0x1f;
+    let g = (value >> 5) as u8 & 0x3f;
+    let b = value as u8 & 0x1f;
+    Color::Rgb(
+        (r << 3) | (r >> 2),
+        (g << 2) | (g >> 4),
+        (b << 3) | (b >> 2),
+    )
 }
@@ -0,0 +1,86 @@
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+use crate::canvas::ShaderCanvas;
+use crate::context::ShaderContext;
+use crate::frame::Frame;
+use crate::state::ShaderCanvasState;
+
+/// [`SharedSurface`] is the output of a single execution of a shader for a large area, e.g. the whole
+/// terminal, which several widgets can draw parts of. A background rendered this way continues seamlessly
+/// behind multiple panes while the shader only runs once per frame.
+///
+/// ```rust,no_run
+/// # use ratatui::layout::{Constraint, Layout};
+/// # use tui_shader::{ShaderCanvas, ShaderCanvasState, SharedSurface};
+/// let mut terminal = ratatui::init();
+/// let mut state = ShaderCanvasState::default();
+/// let canvas = ShaderCanvas::new();
+/// terminal.draw(|frame| {
+///     let surface = SharedSurface::new(&mut state, frame.area());
+///     let [left, right] = Layout::horizontal([Constraint::Fill(1); 2]).margin(2).areas(frame.area());
+///     frame.render_widget(surface.view(&canvas), left);
+///     frame.render_widget(surface.view(&canvas), right);
+/// }).unwrap();
+/// ratatui::restore();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedSurface {
+    frame: Frame,
+    area: Rect,
+}
+
+impl SharedSurface {
+    /// Executes the shader of `state` once for `area`, using the time of the state's
+    /// [`ShaderClock`](crate::ShaderClock).
+    pub fn new(state: &mut ShaderCanvasState, area: Rect) -> Self {
+        let time = state.clock().elapsed().as_secs_f32();
+        let width = area.width.into();
+        let height = area.height.into();
+        let frame = Frame::new(width, height, state.execute(ShaderContext::new(time, area)));
+        state.set_last_frame(frame.clone(), Some(area));
+        Self { frame, area }
+    }
+
+    /// The area the shader was executed for.
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    /// Returns a widget drawing the part of the surface below the area it is rendered into, mapped to
+    /// cells with `canvas`. Cells outside of the surface are left untouched.
+    pub fn view<'a>(&'a self, canvas: &'a ShaderCanvas) -> SurfaceView<'a> {
+        SurfaceView {
+            surface: self,
+            canvas,
+        }
+    }
+}
+
+/// A widget drawing part of a [`SharedSurface`], created with [`SharedSurface::view`].
+#[derive(Debug, Clone, Copy)]
+pub struct SurfaceView<'a> {
+    surface: &'a SharedSurface,
+    canvas: &'a ShaderCanvas,
+}
+
+impl Widget for SurfaceView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(self.surface.area).intersection(buf.area);
+        if area.is_empty() || self.surface.frame.pixels.is_empty() {
+            return;
+        }
+        for position in area.positions() {
+            self.canvas
+                .style_cell(&self.surface.frame, self.surface.area, position, buf);
+        }
+    }
+}
+
+#[cfg(feature = "widget-ref")]
+impl ratatui::widgets::WidgetRef for SurfaceView<'_> {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        Widget::render(*self, area, buf);
+    }
+}
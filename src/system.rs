@@ -0,0 +1,95 @@
+use std::time::{Duration, Instant};
+
+use sysinfo::System;
+
+use crate::provider::UniformProvider;
+use crate::state::Uniforms;
+
+/// [`SystemMetrics`] is a [`UniformProvider`] exposing the load of the machine to shaders, which makes
+/// animated system monitors a matter of a few lines of WGSL. It sets the following uniforms:
+///
+/// | Uniform        | Type        | Explanation                                                 |
+/// |----------------|-------------|-------------------------------------------------------------|
+/// | `cpu_usage`    | `f32`       | usage of all CPUs between `0.0` and `1.0`                   |
+/// | `memory_usage` | `f32`       | used memory between `0.0` and `1.0`                         |
+/// | `load_average` | `vec3<f32>` | load average over 1, 5 and 15 minutes, zero on Windows      |
+///
+/// ```rust,no_run
+/// # use tui_shader::{ShaderCanvasState, SystemMetrics, WgslShader};
+/// let mut state = ShaderCanvasState::new(WgslShader::Path("monitor.wgsl")).unwrap();
+/// state.add_provider(SystemMetrics::new());
+/// ```
+pub struct SystemMetrics {
+    system: System,
+    interval: Duration,
+    last_refresh: Option<Instant>,
+}
+
+impl SystemMetrics {
+    /// Creates a new [`SystemMetrics`] refreshing the metrics once per second. Equivalent to
+    /// [`SystemMetrics::default()`]
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+            interval: Duration::from_secs(1),
+            last_refresh: None,
+        }
+    }
+
+    /// Sets how often the metrics are refreshed. CPU usage is measured between two refreshes, so it is
+    /// never refreshed more often than [`sysinfo::MINIMUM_CPU_UPDATE_INTERVAL`].
+    #[must_use]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        self
+    }
+
+    fn refresh(&mut self) {
+        let now = Instant::now();
+        if self
+            .last_refresh
+            .is_some_and(|last_refresh| now.duration_since(last_refresh) < self.interval)
+        {
+            return;
+        }
+        self.last_refresh = Some(now);
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+    }
+}
+
+impl Default for SystemMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for SystemMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SystemMetrics")
+            .field("interval", &self.interval)
+            .field("last_refresh", &self.last_refresh)
+            .finish_non_exhaustive()
+    }
+}
+
+impl UniformProvider for SystemMetrics {
+    fn provide(&mut self, uniforms: &mut Uniforms<'_>) {
+        self.refresh();
+        let cpu_usage = self.system.global_cpu_usage() / 100.0;
+        let total_memory = self.system.total_memory();
+        let memory_usage = if total_memory == 0 {
+            0.0
+        } else {
+            self.system.used_memory() as f64 / total_memory as f64
+        };
+        let load = System::load_average();
+        uniforms
+            .set("cpu_usage", cpu_usage.clamp(0.0, 1.0))
+            .set("memory_usage", memory_usage.clamp(0.0, 1.0))
+            .set(
+                "load_average",
+                [load.one as f32, load.five as f32, load.fifteen as f32],
+            );
+    }
+}
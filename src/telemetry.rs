@@ -0,0 +1,63 @@
+/// Counters of the work a [`ShaderCanvasState`](crate::ShaderCanvasState) did since it was created or
+/// [reset](crate::ShaderCanvasState::reset_telemetry), returned by
+/// [`ShaderCanvasState::telemetry`](crate::ShaderCanvasState::telemetry). They tell whether a slow app
+/// spends its time in the crate rather than in the shader, e.g. textures recreated on every frame while
+/// the terminal is resized, or frames which never hit the cache because a uniform changes all the time.
+///
+/// ```rust,no_run
+/// # use tui_shader::ShaderCanvasState;
+/// # let state = ShaderCanvasState::default();
+/// let telemetry = state.telemetry();
+/// if let Some(rate) = telemetry.cache_hit_rate() {
+///     eprintln!("{} executions, {:.0}% from the cache", telemetry.executions, rate * 100.0);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Telemetry {
+    /// The frames the shader was asked for, including the ones served from the cache or rendered ahead
+    /// of time. Every area of a batch counts.
+    pub executions: u64,
+
+    /// Executions which reused the pixels of the previous one, since no input changed.
+    pub cache_hits: u64,
+
+    /// Executions which got a frame rendered ahead of time, see `ShaderCanvasState::set_prerender`.
+    pub prerender_hits: u64,
+
+    /// Shaders compiled after the state was created: candidates and migrations to another device.
+    pub pipeline_rebuilds: u64,
+
+    /// Render textures created because the area outgrew them, multisampling was enabled or the state
+    /// was suspended.
+    pub texture_recreations: u64,
+
+    /// Readbacks which blocked because the GPU hadn't finished the frame yet.
+    pub buffer_map_waits: u64,
+
+    /// Renders of a [`ShaderCanvas`](crate::ShaderCanvas) which styled the last frame again instead of
+    /// executing the shader, because of a power profile, an idle frame rate or a frame budget.
+    pub skipped_frames: u64,
+
+    /// Executions which failed and produced transparent black pixels, see `ShaderCanvasState::on_error`.
+    pub dropped_frames: u64,
+}
+
+impl Telemetry {
+    /// The fraction of [`executions`](Telemetry::executions) served from the cache, `None` before the
+    /// first one.
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        (self.executions > 0).then(|| self.cache_hits as f64 / self.executions as f64)
+    }
+
+    /// Adds the counters of `other`.
+    pub(crate) fn merge(&mut self, other: Self) {
+        self.executions += other.executions;
+        self.cache_hits += other.cache_hits;
+        self.prerender_hits += other.prerender_hits;
+        self.pipeline_rebuilds += other.pipeline_rebuilds;
+        self.texture_recreations += other.texture_recreations;
+        self.buffer_map_waits += other.buffer_map_waits;
+        self.skipped_frames += other.skipped_frames;
+        self.dropped_frames += other.dropped_frames;
+    }
+}
@@ -0,0 +1,102 @@
+//! Querying the size of a terminal cell in screen pixels. On Unix, the terminal reports its size in
+//! pixels through the `TIOCGWINSZ` ioctl, or in reply to the `CSI 14 t` escape sequence if the ioctl
+//! leaves the pixel size out, as many terminals and multiplexers do.
+
+#[cfg(unix)]
+use std::fs::{File, OpenOptions};
+#[cfg(unix)]
+use std::io::{Read, Write};
+#[cfg(unix)]
+use std::os::fd::AsRawFd;
+
+/// The longest reply to `CSI 14 t` that is read before giving up.
+#[cfg(unix)]
+const MAX_REPLY_LENGTH: usize = 32;
+
+/// The size of a cell of the controlling terminal in screen pixels, `None` if there is no terminal or it
+/// doesn't report its size in pixels.
+#[cfg(unix)]
+pub(crate) fn cell_size() -> Option<(u16, u16)> {
+    let tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    // SAFETY: `winsize` only holds integers, so all zeroes is a valid value.
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    // SAFETY: `TIOCGWINSZ` writes a `winsize` into `size`, which lives until the call returns.
+    if unsafe { libc::ioctl(tty.as_raw_fd(), libc::TIOCGWINSZ, &mut size) } != 0
+        || size.ws_col == 0
+        || size.ws_row == 0
+    {
+        return None;
+    }
+    let (width, height) = if size.ws_xpixel > 0 && size.ws_ypixel > 0 {
+        (size.ws_xpixel, size.ws_ypixel)
+    } else {
+        query_pixel_size(&tty)?
+    };
+    Some((width / size.ws_col, height / size.ws_row))
+        .filter(|&(width, height)| width > 0 && height > 0)
+}
+
+/// Windows consoles don't report their size in pixels.
+#[cfg(not(unix))]
+pub(crate) fn cell_size() -> Option<(u16, u16)> {
+    None
+}
+
+/// Asks the terminal for the size of its text area in pixels with `CSI 14 t`. The terminal is switched
+/// to non-canonical mode without echo while waiting for the reply, at most 100 ms per byte.
+#[cfg(unix)]
+fn query_pixel_size(tty: &File) -> Option<(u16, u16)> {
+    let fd = tty.as_raw_fd();
+    // SAFETY: `termios` only holds integers, so all zeroes is a valid value, which `tcgetattr` overwrites.
+    let mut original: libc::termios = unsafe { std::mem::zeroed() };
+    // SAFETY: `original` is a valid `termios` for `tcgetattr` to write into.
+    if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+        return None;
+    }
+    let mut raw = original;
+    raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+    raw.c_cc[libc::VMIN] = 0;
+    raw.c_cc[libc::VTIME] = 1;
+    // SAFETY: `raw` is a valid `termios` read by `tcsetattr`.
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+        return None;
+    }
+    let reply = read_reply(tty);
+    // SAFETY: `original` is the valid `termios` returned by `tcgetattr`.
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+    parse_pixel_size(&reply?)
+}
+
+/// Sends `CSI 14 t` and reads the reply up to its final `t`. `None` if the terminal doesn't reply in
+/// time, since a read returns no bytes once the timeout set by [`query_pixel_size`] expires.
+#[cfg(unix)]
+fn read_reply(mut tty: &File) -> Option<Vec<u8>> {
+    tty.write_all(b"\x1b[14t").ok()?;
+    tty.flush().ok()?;
+    let mut reply = Vec::new();
+    let mut byte = [0];
+    while reply.len() < MAX_REPLY_LENGTH {
+        if tty.read(&mut byte).ok()? == 0 {
+            return None;
+        }
+        reply.push(byte[0]);
+        if byte[0] == b't' {
+            return Some(reply);
+        }
+    }
+    None
+}
+
+/// Parses the `CSI 4 ; height ; width t` reply to `CSI 14 t` into the width and height in pixels.
+#[cfg(unix)]
+pub(crate) fn parse_pixel_size(reply: &[u8]) -> Option<(u16, u16)> {
+    let reply = std::str::from_utf8(reply).ok()?;
+    let mut fields = reply.strip_prefix("\x1b[4;")?.strip_suffix('t')?.split(';');
+    let height = fields.next()?.parse().ok()?;
+    let width = fields.next()?.parse().ok()?;
+    Some((width, height))
+}
@@ -0,0 +1,328 @@
+//! Helpers for deterministic snapshot tests of shader-styled UIs.
+//!
+//! Shaders only depend on their inputs, so rendering with a fixed time and area produces the same
+//! [`Buffer`] on every run, up to small differences in floating point precision between GPUs.
+//! [`assert_snapshot`] compares such a [`Buffer`] against a snapshot file with a per-channel color
+//! tolerance to absorb those differences.
+//!
+//! ```rust,no_run
+//! # use std::time::Duration;
+//! # use tui_shader::{ShaderCanvas, ShaderCanvasState, testing};
+//! let mut state = ShaderCanvasState::default();
+//! let buffer = testing::render(&ShaderCanvas::new(), &mut state, 20, 5, Duration::from_secs(1));
+//! testing::assert_snapshot(&buffer, "tests/snapshots/default.txt", testing::ColorTolerance::new(2));
+//! ```
+//!
+//! The inputs hold no random values: shaders which need them hash them from the inputs, e.g. with the
+//! `hash` module of the [`stdlib`](crate::stdlib), and seed the hash with the id of the rendered instance
+//! to differ between canvases. [`render_seeded`] pins that seed along with the time.
+//!
+//! A missing snapshot file is created from the rendered [`Buffer`]. Set the `TUI_SHADER_UPDATE_SNAPSHOTS`
+//! environment variable to overwrite existing snapshots instead of comparing against them.
+//!
+//! [`assert_buffers_similar`] compares two rendered [`Buffer`]s the same way, and additionally tolerates
+//! a number of differing cells, e.g. where a value close to a threshold of a
+//! [`CharacterRule`](crate::CharacterRule) picks another symbol on another GPU.
+
+use std::fmt::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use ratatui_core::backend::TestBackend;
+use ratatui_core::buffer::{Buffer, Cell};
+use ratatui_core::layout::Rect;
+use ratatui_core::style::Color;
+use ratatui_core::terminal::Terminal;
+
+use crate::canvas::ShaderCanvas;
+use crate::clock::ShaderClock;
+use crate::state::ShaderCanvasState;
+
+/// The environment variable which, when set, makes [`assert_snapshot`] overwrite existing snapshots.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "TUI_SHADER_UPDATE_SNAPSHOTS";
+
+/// How much the channels of RGB colors may differ for [`assert_snapshot`] and [`assert_buffers_similar`]
+/// to consider two cells the same. Other colors have to match exactly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ColorTolerance {
+    /// The largest difference of the red, green and blue channels.
+    pub channel: u8,
+}
+
+impl ColorTolerance {
+    /// Colors have to match exactly.
+    pub const EXACT: Self = Self::new(0);
+
+    /// Creates a new [`ColorTolerance`] allowing each channel to differ by up to `channel`.
+    pub const fn new(channel: u8) -> Self {
+        Self { channel }
+    }
+
+    /// Whether `a` and `b` are within the tolerance.
+    fn matches(self, a: Color, b: Color) -> bool {
+        match (a, b) {
+            (Color::Rgb(ar, ag, ab), Color::Rgb(br, bg, bb)) => {
+                channels_match([ar, ag, ab], [br, bg, bb], self.channel)
+            }
+            _ => a == b,
+        }
+    }
+}
+
+/// Creates a paused [`ShaderClock`] that always reports `elapsed` as the time passed. Assign it to a
+/// state via `clock_mut` to freeze the animation at a known point in time.
+pub fn frozen_clock(elapsed: Duration) -> ShaderClock {
+    ShaderClock::frozen(elapsed)
+}
+
+/// Renders a [`ShaderCanvas`] into a new [`Buffer`] of `width` x `height` cells, with `time` as the
+/// `time` input regardless of the state's [`ShaderClock`].
+pub fn render(
+    canvas: &ShaderCanvas,
+    state: &mut ShaderCanvasState,
+    width: u16,
+    height: u16,
+    time: Duration,
+) -> Buffer {
+    let area = Rect::new(0, 0, width, height);
+    let mut buffer = Buffer::empty(area);
+    canvas.render_at(area, &mut buffer, state, time.as_secs_f32());
+    buffer
+}
+
+/// Same as [`render`], but with `seed` as the id of the rendered instance, which shaders seed their
+/// random values with, see [`ShaderCanvas::instance`].
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tui_shader::{ShaderCanvas, ShaderCanvasState, testing};
+/// let mut state = ShaderCanvasState::default();
+/// let canvas = ShaderCanvas::new();
+/// let a = testing::render_seeded(&canvas, &mut state, 20, 5, Duration::ZERO, 42);
+/// let b = testing::render_seeded(&canvas, &mut state, 20, 5, Duration::ZERO, 42);
+/// assert_eq!(a, b);
+/// ```
+pub fn render_seeded(
+    canvas: &ShaderCanvas,
+    state: &mut ShaderCanvasState,
+    width: u16,
+    height: u16,
+    time: Duration,
+    seed: u32,
+) -> Buffer {
+    render(&canvas.clone().instance(seed), state, width, height, time)
+}
+
+/// Asserts that a [`Buffer`] matches the snapshot stored at `path`. Symbols and non-RGB colors have to
+/// match exactly, while RGB colors only have to be within `tolerance`.
+///
+/// # Panics
+///
+/// Panics with a list of the mismatching cells if the [`Buffer`] doesn't match the snapshot, or if the
+/// snapshot can't be read or written.
+pub fn assert_snapshot(buffer: &Buffer, path: impl AsRef<Path>, tolerance: ColorTolerance) {
+    let path = path.as_ref();
+    let actual = buffer_to_string(buffer);
+    if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() || !path.exists() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("unable to create snapshot directory");
+        }
+        std::fs::write(path, actual).expect("unable to write snapshot");
+        return;
+    }
+    let expected = std::fs::read_to_string(path).expect("unable to read snapshot");
+    if let Err(message) = compare(&expected, &actual, tolerance) {
+        panic!("snapshot {} does not match: {message}", path.display());
+    }
+}
+
+/// Asserts that two [`Buffer`]s of the same area differ in at most `max_diff_cells` cells. Cells differ
+/// if their symbols or modifiers differ, or if their colors aren't within `tolerance`. Unlike comparing
+/// the [`Buffer`]s with `assert_eq!`, this passes between GPUs which round slightly differently.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tui_shader::{ShaderCanvas, ShaderCanvasState, testing};
+/// # let mut state = ShaderCanvasState::default();
+/// # let expected = testing::render(&ShaderCanvas::new(), &mut state, 20, 5, Duration::ZERO);
+/// let buffer = testing::render(&ShaderCanvas::new(), &mut state, 20, 5, Duration::ZERO);
+/// testing::assert_buffers_similar(&buffer, &expected, testing::ColorTolerance::new(2), 1);
+/// ```
+///
+/// # Panics
+///
+/// Panics with a list of the differing cells if more than `max_diff_cells` differ, or if the areas of
+/// the [`Buffer`]s differ.
+pub fn assert_buffers_similar(
+    a: &Buffer,
+    b: &Buffer,
+    tolerance: ColorTolerance,
+    max_diff_cells: usize,
+) {
+    assert_eq!(a.area, b.area, "the areas of the buffers differ");
+    let width = a.area.width.max(1) as usize;
+    let mismatches: Vec<String> = a
+        .content()
+        .iter()
+        .zip(b.content())
+        .enumerate()
+        .filter(|(_, (a, b))| {
+            a.symbol() != b.symbol()
+                || a.modifier != b.modifier
+                || !tolerance.matches(a.fg, b.fg)
+                || !tolerance.matches(a.bg, b.bg)
+        })
+        .map(|(i, (a, b))| {
+            format!(
+                "({}, {}): `{}` and `{}`",
+                i % width,
+                i / width,
+                format_cell(a),
+                format_cell(b)
+            )
+        })
+        .collect();
+    assert!(
+        mismatches.len() <= max_diff_cells,
+        "{} cells differ, at most {max_diff_cells} may:\n{}",
+        mismatches.len(),
+        mismatches.join("\n")
+    );
+}
+
+/// Renders a [`ShaderCanvas`] into a [`TestBackend`] of `width` x `height` cells and returns the result
+/// formatted by [`buffer_to_string`]. The `time` input comes from the state's [`ShaderClock`], so use
+/// [`frozen_clock`] for reproducible output.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tui_shader::{ShaderCanvas, ShaderCanvasState, testing};
+/// let mut state = ShaderCanvasState::default();
+/// *state.clock_mut() = testing::frozen_clock(Duration::ZERO);
+/// let dump = testing::render_to_string(&ShaderCanvas::new(), &mut state, 2, 1);
+/// assert_eq!(dump, "  - #ff00ff\t  - #ff00ff\n");
+/// ```
+pub fn render_to_string(
+    canvas: &ShaderCanvas,
+    state: &mut ShaderCanvasState,
+    width: u16,
+    height: u16,
+) -> String {
+    let mut terminal =
+        Terminal::new(TestBackend::new(width, height)).expect("unable to create terminal");
+    terminal
+        .draw(|frame| frame.render_stateful_widget(canvas, frame.area(), state))
+        .expect("unable to draw to terminal");
+    buffer_to_string(terminal.backend().buffer())
+}
+
+/// Formats a [`Buffer`] as compact, diff-friendly text with one line per row and the cells of a row
+/// separated by tabs. Every cell is written as its symbol, foreground and background color separated by
+/// spaces. RGB colors are written as hex codes like `#ff00ff`, indexed colors like `@42`, named colors
+/// by their name and [`Color::Reset`] as `-`.
+pub fn buffer_to_string(buffer: &Buffer) -> String {
+    let mut output = String::new();
+    let width = buffer.area.width as usize;
+    for row in buffer.content().chunks(width.max(1)) {
+        let cells: Vec<String> = row.iter().map(format_cell).collect();
+        output.push_str(&cells.join("\t"));
+        output.push('\n');
+    }
+    output
+}
+
+fn format_cell(cell: &Cell) -> String {
+    let mut output = String::from(cell.symbol());
+    let _ = write!(
+        output,
+        " {} {}",
+        format_color(cell.fg),
+        format_color(cell.bg)
+    );
+    output
+}
+
+fn format_color(color: Color) -> String {
+    match color {
+        Color::Reset => String::from("-"),
+        Color::Rgb(r, g, b) => format!("#{r:02x}{g:02x}{b:02x}"),
+        Color::Indexed(index) => format!("@{index}"),
+        color => color.to_string(),
+    }
+}
+
+fn compare(expected: &str, actual: &str, tolerance: ColorTolerance) -> Result<(), String> {
+    let expected_rows: Vec<&str> = expected.lines().collect();
+    let actual_rows: Vec<&str> = actual.lines().collect();
+    if expected_rows.len() != actual_rows.len() {
+        return Err(format!(
+            "expected {} rows, got {}",
+            expected_rows.len(),
+            actual_rows.len()
+        ));
+    }
+    let mut mismatches = Vec::new();
+    for (y, (expected_row, actual_row)) in expected_rows.iter().zip(&actual_rows).enumerate() {
+        let expected_cells: Vec<&str> = expected_row.split('\t').collect();
+        let actual_cells: Vec<&str> = actual_row.split('\t').collect();
+        if expected_cells.len() != actual_cells.len() {
+            return Err(format!(
+                "expected {} cells in row {y}, got {}",
+                expected_cells.len(),
+                actual_cells.len()
+            ));
+        }
+        for (x, (expected_cell, actual_cell)) in
+            expected_cells.iter().zip(&actual_cells).enumerate()
+        {
+            if !cells_match(expected_cell, actual_cell, tolerance) {
+                mismatches.push(format!(
+                    "({x}, {y}): expected `{expected_cell}`, got `{actual_cell}`"
+                ));
+            }
+        }
+    }
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches.join("\n"))
+    }
+}
+
+fn cells_match(expected: &str, actual: &str, tolerance: ColorTolerance) -> bool {
+    let mut expected = expected.rsplitn(3, ' ');
+    let mut actual = actual.rsplitn(3, ' ');
+    let (Some(expected_bg), Some(expected_fg), Some(expected_symbol)) =
+        (expected.next(), expected.next(), expected.next())
+    else {
+        return false;
+    };
+    let (Some(actual_bg), Some(actual_fg), Some(actual_symbol)) =
+        (actual.next(), actual.next(), actual.next())
+    else {
+        return false;
+    };
+    expected_symbol == actual_symbol
+        && colors_match(expected_fg, actual_fg, tolerance)
+        && colors_match(expected_bg, actual_bg, tolerance)
+}
+
+fn colors_match(expected: &str, actual: &str, tolerance: ColorTolerance) -> bool {
+    match (parse_rgb(expected), parse_rgb(actual)) {
+        (Some(expected), Some(actual)) => channels_match(expected, actual, tolerance.channel),
+        _ => expected == actual,
+    }
+}
+
+fn channels_match(a: [u8; 3], b: [u8; 3], tolerance: u8) -> bool {
+    a.iter().zip(b).all(|(a, b)| a.abs_diff(b) <= tolerance)
+}
+
+fn parse_rgb(color: &str) -> Option<[u8; 3]> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
@@ -0,0 +1,147 @@
+#[cfg(feature = "gpu")]
+use std::error::Error;
+
+#[cfg(feature = "gpu")]
+use crate::bindings::{BindingSlot, BindingsBuilder};
+use crate::font::{GLYPH_SIZE, glyph, glyph_pixel};
+#[cfg(feature = "gpu")]
+use crate::state::ShaderCanvasState;
+
+/// The number of spaces a tab is expanded to.
+const TAB_WIDTH: usize = 4;
+
+/// Determines how [`TextTexture::new`] lays out text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FontOptions {
+    /// The number of texture pixels per pixel of the 8x8 font.
+    pub scale: u32,
+    /// Empty rows of font pixels between two lines.
+    pub line_spacing: u32,
+    /// Empty texture pixels around the text, so effects like glows have room to spread.
+    pub padding: u32,
+}
+
+impl Default for FontOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1,
+            line_spacing: 0,
+            padding: 0,
+        }
+    }
+}
+
+/// [`TextTexture`] rasterizes text with the embedded 8x8 bitmap font into a texture, so shaders can warp,
+/// dissolve or burn text, e.g. lines piped into the app. The texture is white, with the coverage of the
+/// glyphs in the alpha channel. Characters missing from the font are left blank.
+///
+/// ```rust,no_run
+/// # use tui_shader::{BindingsBuilder, FontOptions, ShaderCanvasState, TextTexture, WgslShader};
+/// let text = TextTexture::new("GAME OVER", FontOptions { scale: 2, ..FontOptions::default() });
+/// let bindings = BindingsBuilder::new()
+///     .text_texture((1, 0), &text)
+///     .sampler((1, 1), wgpu::FilterMode::Nearest);
+/// let state = ShaderCanvasState::new_with_bindings(WgslShader::Path("burn.wgsl"), None, bindings)
+///     .unwrap();
+/// ```
+///
+/// ```wgsl
+/// @group(1) @binding(0) var text: texture_2d<f32>;
+/// @group(1) @binding(1) var text_sampler: sampler;
+///
+/// @fragment
+/// fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+///     let coverage = textureSample(text, text_sampler, uv).a;
+///     return vec4<f32>(coverage, coverage * 0.5, 0.0, 1.0);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextTexture {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl TextTexture {
+    /// Rasterizes `text`, one line of the texture per line of the text. The texture is as wide as the
+    /// longest line and at least 1x1 pixels, even for empty text.
+    pub fn new(text: &str, options: FontOptions) -> Self {
+        let scale = options.scale.max(1);
+        let lines: Vec<Vec<char>> = text
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .flat_map(|character| match character {
+                        '\t' => vec![' '; TAB_WIDTH],
+                        character => vec![character],
+                    })
+                    .collect()
+            })
+            .collect();
+        let columns = lines.iter().map(Vec::len).max().unwrap_or(0) as u32;
+        let line_height = GLYPH_SIZE + options.line_spacing;
+        let text_height = (lines.len() as u32 * line_height).saturating_sub(options.line_spacing);
+        let width = (columns * GLYPH_SIZE * scale + options.padding * 2).max(1);
+        let height = (text_height * scale + options.padding * 2).max(1);
+        let mut data = [255, 255, 255, 0].repeat((width * height) as usize);
+        for (row, line) in lines.iter().enumerate() {
+            for (column, character) in line.iter().enumerate() {
+                let Some(glyph) = glyph(*character) else {
+                    continue;
+                };
+                let left = options.padding + column as u32 * GLYPH_SIZE * scale;
+                let top = options.padding + row as u32 * line_height * scale;
+                for y in 0..GLYPH_SIZE * scale {
+                    for x in 0..GLYPH_SIZE * scale {
+                        if glyph_pixel(&glyph, x / scale, y / scale) {
+                            let index = ((top + y) * width + left + x) as usize * 4;
+                            data[index + 3] = 255;
+                        }
+                    }
+                }
+            }
+        }
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// The width of the texture in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the texture in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The tightly packed RGBA bytes of the texture.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl BindingsBuilder {
+    /// Declares a `texture_2d<f32>` initialized with the rasterized `text`, see [`TextTexture`].
+    #[must_use]
+    pub fn text_texture(self, slot: impl Into<BindingSlot>, text: &TextTexture) -> Self {
+        self.texture_with_data(slot, text.width, text.height, &text.data)
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl ShaderCanvasState {
+    /// Replaces the texture declared at `slot` with the rasterized `text`, e.g. when new lines were piped
+    /// in. See [`ShaderCanvasState::update_texture`].
+    pub fn update_text_texture(
+        &mut self,
+        slot: impl Into<BindingSlot>,
+        text: &TextTexture,
+    ) -> Result<(), Box<dyn Error>> {
+        self.update_texture(slot, &text.data, text.width, text.height)
+    }
+}
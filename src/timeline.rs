@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use crate::easing::Easing;
+use crate::state::ShaderCanvasState;
+
+/// A single value on a track of an [`AnimationTimeline`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    /// Point in time of the keyframe, relative to the start of the timeline.
+    pub time: Duration,
+
+    /// Value of the uniform at [`Keyframe::time`].
+    pub value: f32,
+
+    /// Easing used to interpolate from the previous keyframe to this one.
+    pub easing: Easing,
+}
+
+#[derive(Debug, Clone)]
+struct Track {
+    name: String,
+    keyframes: Vec<Keyframe>,
+}
+
+impl Track {
+    fn value(&self, elapsed: Duration) -> Option<f32> {
+        let next = self
+            .keyframes
+            .iter()
+            .position(|keyframe| keyframe.time > elapsed);
+        match next {
+            Some(0) => self.keyframes.first().map(|keyframe| keyframe.value),
+            Some(index) => {
+                let from = self.keyframes[index - 1];
+                let to = self.keyframes[index];
+                let span = (to.time - from.time).as_secs_f32();
+                let t = (elapsed - from.time).as_secs_f32() / span;
+                Some(to.easing.interpolate(from.value, to.value, t))
+            }
+            None => self.keyframes.last().map(|keyframe| keyframe.value),
+        }
+    }
+}
+
+/// [`AnimationTimeline`] interpolates named uniform values over keyframes. Each uniform has its own track,
+/// and every keyframe decides how it is eased into. Before the first and after the last keyframe of a track,
+/// the value of the nearest keyframe is held.
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use tui_shader::{AnimationTimeline, Easing, ShaderCanvas, ShaderCanvasState, WgslShader};
+/// let timeline = AnimationTimeline::new()
+///     .keyframe("brightness", Duration::ZERO, 0.0, Easing::Linear)
+///     .keyframe("brightness", Duration::from_secs(2), 1.0, Easing::QuadOut)
+///     .keyframe("zoom", Duration::from_secs(1), 1.0, Easing::Linear)
+///     .keyframe("zoom", Duration::from_secs(4), 3.0, Easing::QuadInOut);
+///
+/// let mut terminal = ratatui::init();
+/// let mut state = ShaderCanvasState::new(WgslShader::Path("shader.wgsl")).unwrap();
+/// while state.clock().elapsed() < timeline.duration() {
+///     timeline.apply(&mut state);
+///     terminal.draw(|frame| {
+///         frame.render_stateful_widget(ShaderCanvas::new(),
+///             frame.area(),
+///             &mut state);
+///     }).unwrap();
+/// }
+/// ratatui::restore();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnimationTimeline {
+    tracks: Vec<Track>,
+    looping: bool,
+}
+
+impl AnimationTimeline {
+    /// Creates a new, empty [`AnimationTimeline`]. Equivalent to [`AnimationTimeline::default()`]
+    pub fn new() -> Self {
+        Self {
+            tracks: Vec::new(),
+            looping: false,
+        }
+    }
+
+    /// Adds a keyframe for the uniform `name`. Keyframes can be added in any order.
+    #[must_use]
+    pub fn keyframe(mut self, name: &str, time: Duration, value: f32, easing: Easing) -> Self {
+        let keyframe = Keyframe {
+            time,
+            value,
+            easing,
+        };
+        match self.tracks.iter_mut().find(|track| track.name == name) {
+            Some(track) => {
+                let index = track
+                    .keyframes
+                    .partition_point(|existing| existing.time <= time);
+                track.keyframes.insert(index, keyframe);
+            }
+            None => self.tracks.push(Track {
+                name: name.to_string(),
+                keyframes: vec![keyframe],
+            }),
+        }
+        self
+    }
+
+    /// Restarts the [`AnimationTimeline`] from the beginning once its duration has passed.
+    #[must_use]
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// The point in time of the last keyframe across all tracks.
+    pub fn duration(&self) -> Duration {
+        self.tracks
+            .iter()
+            .filter_map(|track| track.keyframes.last())
+            .map(|keyframe| keyframe.time)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The value of the uniform `name` at `elapsed`, or `None` if there is no track for it.
+    pub fn value(&self, name: &str, elapsed: Duration) -> Option<f32> {
+        let elapsed = self.wrap(elapsed);
+        self.tracks
+            .iter()
+            .find(|track| track.name == name)
+            .and_then(|track| track.value(elapsed))
+    }
+
+    /// The values of all uniforms at `elapsed`.
+    pub fn values(&self, elapsed: Duration) -> impl Iterator<Item = (&str, f32)> {
+        let elapsed = self.wrap(elapsed);
+        self.tracks.iter().filter_map(move |track| {
+            track
+                .value(elapsed)
+                .map(|value| (track.name.as_str(), value))
+        })
+    }
+
+    /// Sets all uniforms of the [`ShaderCanvasState`] to their values at the time elapsed on the state's
+    /// [`ShaderClock`](crate::ShaderClock), so the timeline stops while the clock is paused. Call this
+    /// once per frame before rendering.
+    pub fn apply(&self, state: &mut ShaderCanvasState) {
+        let elapsed = state.clock().elapsed();
+        for (name, value) in self.values(elapsed) {
+            state.set_uniform(name, value);
+        }
+    }
+
+    fn wrap(&self, elapsed: Duration) -> Duration {
+        let duration = self.duration();
+        if self.looping && !duration.is_zero() {
+            Duration::from_nanos((elapsed.as_nanos() % duration.as_nanos()) as u64)
+        } else {
+            elapsed
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use ratatui_core::style::Color;
+#[cfg(feature = "gpu")]
+use wgpu::naga;
+
+use crate::util::color_to_rgb;
+
+#[cfg(feature = "gpu")]
+pub(crate) const USER_UNIFORMS_BINDING: u32 = 2;
+#[cfg(feature = "gpu")]
+const MIN_BUFFER_SIZE: usize = 16;
+
+/// Layout of the user defined uniform struct bound to `@group(0) @binding(2)`, reflected from the
+/// fragment shader source.
+#[cfg(feature = "gpu")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct UniformLayout {
+    fields: Vec<UniformField>,
+    size: usize,
+}
+
+#[cfg(feature = "gpu")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UniformField {
+    name: String,
+    offset: usize,
+    kind: ScalarKind,
+    components: usize,
+}
+
+#[cfg(feature = "gpu")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScalarKind {
+    Float,
+    Int,
+    Uint,
+}
+
+/// A value of a user defined uniform, see [`Uniforms::set`](crate::Uniforms::set). Scalars are converted
+/// to the type of the uniform they are written to. Vectors have to be at least as long as the uniform,
+/// superfluous components are dropped, so that a [`Color`] can be written to both `vec3<f32>` and
+/// `vec4<f32>` uniforms. Matrices are written to `mat4x4<f32>` uniforms, given in columns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UniformValue {
+    Float(f32),
+    Int(i32),
+    Uint(u32),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+    Mat4([[f32; 4]; 4]),
+}
+
+impl From<f32> for UniformValue {
+    fn from(value: f32) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<f64> for UniformValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value as f32)
+    }
+}
+
+impl From<i32> for UniformValue {
+    fn from(value: i32) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<u32> for UniformValue {
+    fn from(value: u32) -> Self {
+        Self::Uint(value)
+    }
+}
+
+impl From<bool> for UniformValue {
+    fn from(value: bool) -> Self {
+        Self::Uint(value.into())
+    }
+}
+
+impl From<[f32; 2]> for UniformValue {
+    fn from(value: [f32; 2]) -> Self {
+        Self::Vec2(value)
+    }
+}
+
+impl From<[f32; 3]> for UniformValue {
+    fn from(value: [f32; 3]) -> Self {
+        Self::Vec3(value)
+    }
+}
+
+impl From<[f32; 4]> for UniformValue {
+    fn from(value: [f32; 4]) -> Self {
+        Self::Vec4(value)
+    }
+}
+
+impl From<[[f32; 4]; 4]> for UniformValue {
+    fn from(value: [[f32; 4]; 4]) -> Self {
+        Self::Mat4(value)
+    }
+}
+
+impl From<Color> for UniformValue {
+    /// Converts the color into normalized RGBA components. [`Color::Reset`] becomes transparent black.
+    fn from(value: Color) -> Self {
+        match color_to_rgb(value) {
+            Some([r, g, b]) => {
+                Self::Vec4([r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0])
+            }
+            None => Self::Vec4([0.0; 4]),
+        }
+    }
+}
+
+impl UniformValue {
+    /// The value as a single `f32`, if it is a scalar.
+    pub fn as_f32(&self) -> Option<f32> {
+        match *self {
+            Self::Float(value) => Some(value),
+            Self::Int(value) => Some(value as f32),
+            Self::Uint(value) => Some(value as f32),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "gpu")]
+    fn components(&self) -> &[f32] {
+        match self {
+            Self::Vec2(value) => value,
+            Self::Vec3(value) => value,
+            Self::Vec4(value) => value,
+            Self::Mat4(value) => value.as_flattened(),
+            _ => &[],
+        }
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl UniformLayout {
+    pub(crate) fn reflect(source: &wgpu::ShaderSource) -> Self {
+        let module = match source {
+            wgpu::ShaderSource::Wgsl(source) => naga::front::wgsl::parse_str(source).ok(),
+            #[cfg(feature = "glsl")]
+            wgpu::ShaderSource::Glsl { shader, stage, .. } => {
+                naga::front::glsl::Frontend::default()
+                    .parse(&naga::front::glsl::Options::from(*stage), shader)
+                    .ok()
+            }
+            #[cfg(feature = "spirv")]
+            wgpu::ShaderSource::SpirV(words) => naga::front::spv::parse_u8_slice(
+                bytemuck::cast_slice(words),
+                &naga::front::spv::Options::default(),
+            )
+            .ok(),
+            _ => None,
+        };
+        module.map_or_else(Self::default, |module| Self::from_module(&module))
+    }
+
+    /// Reflects the user defined uniforms of an already parsed `module`.
+    pub(crate) fn from_module(module: &naga::Module) -> Self {
+        let Some(variable) = module.global_variables.iter().find_map(|(_, variable)| {
+            let binding = variable.binding.as_ref()?;
+            let is_user_uniform = variable.space == naga::AddressSpace::Uniform
+                && binding.group == 0
+                && binding.binding == USER_UNIFORMS_BINDING;
+            is_user_uniform.then_some(variable)
+        }) else {
+            return Self::default();
+        };
+        let naga::TypeInner::Struct { members, span } = &module.types[variable.ty].inner else {
+            return Self::default();
+        };
+        let fields = members
+            .iter()
+            .filter_map(|member| {
+                let (scalar, components) = match module.types[member.ty].inner {
+                    naga::TypeInner::Scalar(scalar) => (scalar, 1),
+                    naga::TypeInner::Vector { size, scalar } => (scalar, size as usize),
+                    // Only 4x4 matrices are tightly packed, the columns of smaller ones are padded.
+                    naga::TypeInner::Matrix {
+                        columns: naga::VectorSize::Quad,
+                        rows: naga::VectorSize::Quad,
+                        scalar,
+                    } => (scalar, 16),
+                    _ => return None,
+                };
+                let kind = match scalar {
+                    naga::Scalar::F32 => ScalarKind::Float,
+                    naga::Scalar::I32 => ScalarKind::Int,
+                    naga::Scalar::U32 => ScalarKind::Uint,
+                    _ => return None,
+                };
+                Some(UniformField {
+                    name: member.name.clone()?,
+                    offset: member.offset as usize,
+                    kind,
+                    components,
+                })
+            })
+            .collect();
+        Self {
+            fields,
+            size: *span as usize,
+        }
+    }
+
+    /// Size of the uniform buffer, padded to satisfy the alignment rules of uniform buffers.
+    pub(crate) fn buffer_size(&self) -> usize {
+        self.size
+            .next_multiple_of(MIN_BUFFER_SIZE)
+            .max(MIN_BUFFER_SIZE)
+    }
+
+    /// Size of the uniform struct declared by the shader, without the padding of the buffer.
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The names of all uniforms that can be set.
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|field| field.name.as_str())
+    }
+
+    /// Writes `value` into the uniform called `name` in `buffer`. Returns `false` if there is no such
+    /// uniform or the value doesn't fit its type.
+    pub(crate) fn write(&self, buffer: &mut [u8], name: &str, value: UniformValue) -> bool {
+        let Some(field) = self.fields.iter().find(|field| field.name == name) else {
+            return false;
+        };
+        let bytes: Vec<[u8; 4]> = if field.components == 1 {
+            let Some(value) = scalar_bytes(value, field.kind) else {
+                return false;
+            };
+            vec![value]
+        } else {
+            let components = value.components();
+            if field.kind != ScalarKind::Float || components.len() < field.components {
+                return false;
+            }
+            components[..field.components]
+                .iter()
+                .map(|component| component.to_ne_bytes())
+                .collect()
+        };
+        for (index, bytes) in bytes.iter().enumerate() {
+            let offset = field.offset + index * 4;
+            buffer[offset..offset + 4].copy_from_slice(bytes);
+        }
+        true
+    }
+}
+
+#[cfg(feature = "gpu")]
+fn scalar_bytes(value: UniformValue, kind: ScalarKind) -> Option<[u8; 4]> {
+    let bytes = match (value, kind) {
+        (UniformValue::Float(value), ScalarKind::Float) => value.to_ne_bytes(),
+        (UniformValue::Float(value), ScalarKind::Int) => (value as i32).to_ne_bytes(),
+        (UniformValue::Float(value), ScalarKind::Uint) => (value as u32).to_ne_bytes(),
+        (UniformValue::Int(value), ScalarKind::Float) => (value as f32).to_ne_bytes(),
+        (UniformValue::Int(value), ScalarKind::Int) => value.to_ne_bytes(),
+        (UniformValue::Int(value), ScalarKind::Uint) => (value as u32).to_ne_bytes(),
+        (UniformValue::Uint(value), ScalarKind::Float) => (value as f32).to_ne_bytes(),
+        (UniformValue::Uint(value), ScalarKind::Int) => (value as i32).to_ne_bytes(),
+        (UniformValue::Uint(value), ScalarKind::Uint) => value.to_ne_bytes(),
+        _ => return None,
+    };
+    Some(bytes)
+}
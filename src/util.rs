@@ -1,10 +1,20 @@
+#[cfg(feature = "gpu")]
 use std::error::Error;
 
+use ratatui_core::style::Color;
+
+#[cfg(any(feature = "glsl", feature = "spirv"))]
 use wgpu::naga;
 
+#[cfg(feature = "gpu")]
+use crate::check::parse;
+#[cfg(feature = "gpu")]
+use crate::preprocessor::{ShaderFeatures, preprocess};
+
 /// Utility `enum` to pass in a shader into [`ShaderCanvasState`](crate::ShaderCanvasState). Another option is to use the re-exported
 /// [`include_wgsl!`](wgpu::include_wgsl!) macro, which checks at runtime if the path to the file is valid and returns a
 /// [`ShaderModuleDescriptor`](wgpu::ShaderModuleDescriptor).
+#[cfg(feature = "gpu")]
 pub enum WgslShader<'a> {
     /// Use wgsl source code in a `&str`.
     Source(&'a str),
@@ -13,32 +23,232 @@ pub enum WgslShader<'a> {
     Path(&'a str),
 }
 
+#[cfg(feature = "gpu")]
 impl<'a> TryFrom<WgslShader<'a>> for wgpu::ShaderModuleDescriptor<'a> {
     type Error = Box<dyn Error>;
     fn try_from(value: WgslShader<'a>) -> Result<wgpu::ShaderModuleDescriptor<'a>, Self::Error> {
-        match value {
-            WgslShader::Source(source) => create_shader_module_descriptor(source.to_string()),
-            WgslShader::Path(path) => {
-                let source = match std::fs::read_to_string(path) {
-                    Ok(source) => source,
-                    Err(error) => return Err(Box::new(error)),
-                };
-                create_shader_module_descriptor(source)
+        value.with_defines(&[]).try_into()
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl<'a> WgslShader<'a> {
+    /// Replaces every identifier named like one of the `defines` with its value before the shader is
+    /// compiled, so one shader file can be specialized for every widget using it. Unlike uniforms, the
+    /// values are constants, e.g. array sizes or loop bounds.
+    ///
+    /// ```rust,no_run
+    /// # use tui_shader::{ShaderCanvasState, WgslShader};
+    /// let shader = WgslShader::Path("mandelbrot.wgsl").with_defines(&[("MAX_ITER", "64"), ("PALETTE", "2")]);
+    /// let state = ShaderCanvasState::new(shader).unwrap();
+    /// ```
+    #[must_use]
+    pub fn with_defines(self, defines: &'a [(&'a str, &'a str)]) -> SpecializedWgslShader<'a> {
+        SpecializedWgslShader {
+            shader: self,
+            defines,
+            features: ShaderFeatures::default(),
+        }
+    }
+
+    /// Enables the `#ifdef` blocks of the shader for `features`, see [`ShaderFeatures`].
+    #[must_use]
+    pub fn with_features(self, features: ShaderFeatures) -> SpecializedWgslShader<'a> {
+        self.with_defines(&[]).with_features(features)
+    }
+}
+
+/// A [`WgslShader`] specialized before compilation, created with [`WgslShader::with_defines`] or
+/// [`WgslShader::with_features`].
+#[cfg(feature = "gpu")]
+pub struct SpecializedWgslShader<'a> {
+    shader: WgslShader<'a>,
+    defines: &'a [(&'a str, &'a str)],
+    features: ShaderFeatures,
+}
+
+#[cfg(feature = "gpu")]
+impl<'a> SpecializedWgslShader<'a> {
+    /// Sets the constants substituted in the shader, see [`WgslShader::with_defines`].
+    #[must_use]
+    pub fn with_defines(mut self, defines: &'a [(&'a str, &'a str)]) -> Self {
+        self.defines = defines;
+        self
+    }
+
+    /// Sets the enabled features of the shader, see [`ShaderFeatures`].
+    #[must_use]
+    pub fn with_features(mut self, features: ShaderFeatures) -> Self {
+        self.features = features;
+        self
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl<'a> TryFrom<SpecializedWgslShader<'a>> for wgpu::ShaderModuleDescriptor<'a> {
+    type Error = Box<dyn Error>;
+    fn try_from(
+        value: SpecializedWgslShader<'a>,
+    ) -> Result<wgpu::ShaderModuleDescriptor<'a>, Self::Error> {
+        let source = match value.shader {
+            WgslShader::Source(source) => source.to_string(),
+            WgslShader::Path(path) => std::fs::read_to_string(path)?,
+        };
+        create_shader_module_descriptor(preprocess(&source, &value.features, value.defines)?)
+    }
+}
+
+/// Utility `enum` to pass in a GLSL fragment shader into [`ShaderCanvasState`](crate::ShaderCanvasState). The
+/// shader reads the same inputs as a wgsl shader, declared with GLSL syntax:
+///
+/// ```glsl
+/// #version 450
+/// layout(location = 0) in vec2 uv;
+/// layout(location = 0) out vec4 color;
+/// layout(set = 0, binding = 0) uniform Time { vec4 time; };
+///
+/// void main() {
+///     color = vec4(uv, sin(time.x) * 0.5 + 0.5, 1.0);
+/// }
+/// ```
+#[cfg(feature = "glsl")]
+pub enum GlslShader<'a> {
+    /// Use GLSL source code in a `&str`.
+    Source(&'a str),
+
+    /// Use a path to a GLSL shader.
+    Path(&'a str),
+}
+
+#[cfg(feature = "glsl")]
+impl<'a> TryFrom<GlslShader<'a>> for wgpu::ShaderModuleDescriptor<'a> {
+    type Error = Box<dyn Error>;
+    fn try_from(value: GlslShader<'a>) -> Result<wgpu::ShaderModuleDescriptor<'a>, Self::Error> {
+        let source = match value {
+            GlslShader::Source(source) => source.to_string(),
+            GlslShader::Path(path) => std::fs::read_to_string(path)?,
+        };
+        let options = naga::front::glsl::Options::from(naga::ShaderStage::Fragment);
+        match naga::front::glsl::Frontend::default().parse(&options, &source) {
+            Ok(_) => Ok(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Glsl {
+                    shader: source.into(),
+                    stage: naga::ShaderStage::Fragment,
+                    defines: &[],
+                },
+            }),
+            Err(error) => Err(Box::new(error)),
+        }
+    }
+}
+
+/// Utility `enum` to pass in an HLSL pixel shader into [`ShaderCanvasState`](crate::ShaderCanvasState). The
+/// shader is compiled to SPIR-V with the [DirectX Shader Compiler](https://github.com/microsoft/DirectXShaderCompiler),
+/// so the `dxc` executable must be installed and on the `PATH`. The entry point must be named `main`.
+/// Registers map to the bindings of the same number in the same space:
+///
+/// ```hlsl
+/// cbuffer Time : register(b0, space0) { float4 time; };
+///
+/// float4 main(float2 uv : TEXCOORD0) : SV_Target {
+///     return float4(uv, sin(time.x) * 0.5 + 0.5, 1.0);
+/// }
+/// ```
+#[cfg(feature = "hlsl")]
+pub enum HlslShader<'a> {
+    /// Use HLSL source code in a `&str`.
+    Source(&'a str),
+
+    /// Use a path to an HLSL shader.
+    Path(&'a str),
+}
+
+#[cfg(feature = "hlsl")]
+impl<'a> TryFrom<HlslShader<'a>> for wgpu::ShaderModuleDescriptor<'a> {
+    type Error = Box<dyn Error>;
+    fn try_from(value: HlslShader<'a>) -> Result<wgpu::ShaderModuleDescriptor<'a>, Self::Error> {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let id = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let stem = std::env::temp_dir().join(format!("tui-shader-{}-{id}", std::process::id()));
+        let input = stem.with_extension("hlsl");
+        let output = stem.with_extension("spv");
+        let path = match value {
+            HlslShader::Source(source) => {
+                std::fs::write(&input, source)?;
+                input.as_path()
+            }
+            HlslShader::Path(path) => std::path::Path::new(path),
+        };
+        let result = std::process::Command::new("dxc")
+            .args(["-T", "ps_6_0", "-E", "main", "-spirv", "-Fo"])
+            .arg(&output)
+            .arg(path)
+            .output();
+        let _ = std::fs::remove_file(&input);
+        let result = match result {
+            Ok(result) => result,
+            Err(error) => {
+                return Err(format!(
+                    "failed to run dxc, is the DirectX Shader Compiler installed? {error}"
+                )
+                .into());
             }
+        };
+        if !result.status.success() {
+            let _ = std::fs::remove_file(&output);
+            return Err(String::from_utf8_lossy(&result.stderr).into_owned().into());
+        }
+        let spirv = std::fs::read(&output);
+        let _ = std::fs::remove_file(&output);
+        create_spirv_shader_module_descriptor(&spirv?)
+    }
+}
+
+/// Utility `enum` to pass in a fragment shader compiled to SPIR-V into
+/// [`ShaderCanvasState`](crate::ShaderCanvasState), e.g. a shader written in Rust and compiled with
+/// [rust-gpu](https://rust-gpu.github.io). See the [crate level documentation](crate#shaders-written-in-rust)
+/// for the whole workflow.
+#[cfg(feature = "spirv")]
+pub enum SpirvShader<'a> {
+    /// Use a SPIR-V binary, e.g. embedded with [`include_bytes!`].
+    Bytes(&'a [u8]),
+
+    /// Use a path to a SPIR-V binary.
+    Path(&'a str),
+}
+
+#[cfg(feature = "spirv")]
+impl<'a> TryFrom<SpirvShader<'a>> for wgpu::ShaderModuleDescriptor<'a> {
+    type Error = Box<dyn Error>;
+    fn try_from(value: SpirvShader<'a>) -> Result<wgpu::ShaderModuleDescriptor<'a>, Self::Error> {
+        match value {
+            SpirvShader::Bytes(bytes) => create_spirv_shader_module_descriptor(bytes),
+            SpirvShader::Path(path) => create_spirv_shader_module_descriptor(&std::fs::read(path)?),
         }
     }
 }
 
+#[cfg(feature = "spirv")]
+fn create_spirv_shader_module_descriptor<'a>(
+    spirv: &[u8],
+) -> Result<wgpu::ShaderModuleDescriptor<'a>, Box<dyn Error>> {
+    naga::front::spv::parse_u8_slice(spirv, &naga::front::spv::Options::default())?;
+    Ok(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::SpirV(wgpu::util::make_spirv_raw(spirv).into_owned().into()),
+    })
+}
+
+#[cfg(feature = "gpu")]
 fn create_shader_module_descriptor<'a>(
     source: String,
 ) -> Result<wgpu::ShaderModuleDescriptor<'a>, Box<dyn Error>> {
-    match naga::front::wgsl::parse_str(source.as_str()) {
-        Ok(_) => Ok(wgpu::ShaderModuleDescriptor {
-            label: None,
-            source: wgpu::ShaderSource::Wgsl(source.into()),
-        }),
-        Err(error) => Err(Box::new(error)),
-    }
+    parse(&source)?;
+    Ok(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    })
 }
 
 pub(crate) type Pixel = [u8; 4];
@@ -53,3 +263,69 @@ pub(crate) fn row_padding(width: u32) -> u32 {
     let bytes_per_row = bytes_per_row(width);
     (bytes_per_row - row_size) / 4
 }
+
+#[cfg(feature = "gpu")]
+pub(crate) fn color_to_vec4(color: [u8; 3]) -> [f32; 4] {
+    [
+        color[0] as f32 / 255.0,
+        color[1] as f32 / 255.0,
+        color[2] as f32 / 255.0,
+        1.0,
+    ]
+}
+
+/// Converts a [`Color`] to RGB, using the default xterm palette for named and indexed colors. Returns
+/// `None` for [`Color::Reset`], whose actual color depends on the terminal.
+pub(crate) fn color_to_rgb(color: Color) -> Option<[u8; 3]> {
+    const NAMED: [[u8; 3]; 16] = [
+        [0, 0, 0],
+        [205, 0, 0],
+        [0, 205, 0],
+        [205, 205, 0],
+        [0, 0, 238],
+        [205, 0, 205],
+        [0, 205, 205],
+        [229, 229, 229],
+        [127, 127, 127],
+        [255, 0, 0],
+        [0, 255, 0],
+        [255, 255, 0],
+        [92, 92, 255],
+        [255, 0, 255],
+        [0, 255, 255],
+        [255, 255, 255],
+    ];
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some([r, g, b]),
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        Color::Indexed(index) => index,
+    };
+    Some(match index {
+        0..16 => NAMED[index as usize],
+        16..232 => {
+            let level = |value: u8| if value == 0 { 0 } else { value * 40 + 55 };
+            let index = index - 16;
+            [level(index / 36), level(index / 6 % 6), level(index % 6)]
+        }
+        _ => {
+            let level = (index - 232) * 10 + 8;
+            [level, level, level]
+        }
+    })
+}
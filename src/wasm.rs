@@ -0,0 +1,146 @@
+use std::error::Error;
+use std::path::Path;
+
+use wasmtime::{
+    Config, Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc,
+};
+
+use crate::cpu::{CpuBackend, PixelInput};
+
+/// The default of [`WasmBackend::fuel_per_pixel`].
+const DEFAULT_FUEL_PER_PIXEL: u64 = 100_000;
+
+/// The default of [`WasmBackend::max_memory`], 64 MiB.
+const DEFAULT_MAX_MEMORY: usize = 64 << 20;
+
+/// The most elements the tables of a module may hold.
+const MAX_TABLE_ELEMENTS: usize = 10_000;
+
+/// [`WasmBackend`] is a [`CpuBackend`] running a WebAssembly module for every pixel. The module is
+/// sandboxed: it gets no imports and only has access to its own memory. This makes it a good fit for
+/// plugins, which can be written in any language compiling to WebAssembly.
+///
+/// Every frame may run a limited number of instructions, see [`WasmBackend::fuel_per_pixel`], and the
+/// memory of the module is capped, see [`WasmBackend::max_memory`]. A module which exceeds them traps:
+/// the remaining pixels of the frame are black and the trap is kept in [`WasmBackend::last_error`], so a
+/// plugin which loops forever or leaks memory can't freeze or take down the app.
+///
+/// The module has to export a function `pixel(x: i32, y: i32, time: f32, width: i32, height: i32) -> i32`
+/// returning the color packed as `0xRRGGBBAA`. It can optionally export a function
+/// `prepare(time: f32, width: i32, height: i32)`, which is called once per frame before `pixel`.
+///
+/// ```rust,no_run
+/// # use tui_shader::{ShaderCanvasState, WasmBackend};
+/// let backend = WasmBackend::new("plugins/plasma.wasm").unwrap();
+/// let state = ShaderCanvasState::from_backend(backend);
+/// ```
+pub struct WasmBackend {
+    store: Store<StoreLimits>,
+    pixel: TypedFunc<(i32, i32, f32, i32, i32), i32>,
+    prepare: Option<TypedFunc<(f32, i32, i32), ()>>,
+    fuel_per_pixel: u64,
+    last_error: Option<String>,
+}
+
+impl WasmBackend {
+    /// Loads the module at `path`, either as a binary `.wasm` file or in the text format. Fails if the
+    /// module is invalid, requires imports or doesn't export a matching `pixel` function.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Loads a module from its binary or text representation.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let engine = Engine::new(Config::new().consume_fuel(true))?;
+        let module = Module::new(&engine, bytes)?;
+        let mut store = Store::new(&engine, limits(DEFAULT_MAX_MEMORY));
+        store.limiter(|limits| limits);
+        store.set_fuel(DEFAULT_FUEL_PER_PIXEL)?;
+        let instance = Instance::new(&mut store, &module, &[])?;
+        let pixel = instance.get_typed_func(&mut store, "pixel")?;
+        let prepare = match instance.get_func(&mut store, "prepare") {
+            Some(prepare) => Some(prepare.typed(&store)?),
+            None => None,
+        };
+        Ok(Self {
+            store,
+            pixel,
+            prepare,
+            fuel_per_pixel: DEFAULT_FUEL_PER_PIXEL,
+            last_error: None,
+        })
+    }
+
+    /// Sets the number of instructions the module may run per pixel, 100 000 by default. The budget is
+    /// shared by all pixels of a frame and `prepare`, so cheap pixels leave room for expensive ones, and a
+    /// pixel which loops forever uses up the whole frame.
+    #[must_use]
+    pub fn fuel_per_pixel(mut self, fuel: u64) -> Self {
+        self.fuel_per_pixel = fuel;
+        self
+    }
+
+    /// Sets the number of bytes the memory of the module may grow to, 64 MiB by default.
+    #[must_use]
+    pub fn max_memory(mut self, bytes: usize) -> Self {
+        *self.store.data_mut() = limits(bytes);
+        self
+    }
+
+    /// The error of the last call into the module, if it trapped.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+impl std::fmt::Debug for WasmBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmBackend")
+            .field("last_error", &self.last_error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CpuBackend for WasmBackend {
+    fn prepare(&mut self, time: f32, width: u32, height: u32) {
+        let pixels = u64::from(width) * u64::from(height) + 1;
+        if let Err(error) = self
+            .store
+            .set_fuel(pixels.saturating_mul(self.fuel_per_pixel))
+        {
+            self.last_error = Some(error.to_string());
+        }
+        if let Some(prepare) = &self.prepare
+            && let Err(error) = prepare.call(&mut self.store, (time, width as i32, height as i32))
+        {
+            self.last_error = Some(error.to_string());
+        }
+    }
+
+    fn pixel(&mut self, input: PixelInput) -> [u8; 4] {
+        let args = (
+            input.x as i32,
+            input.y as i32,
+            input.time,
+            input.width as i32,
+            input.height as i32,
+        );
+        match self.pixel.call(&mut self.store, args) {
+            Ok(color) => (color as u32).to_be_bytes(),
+            Err(error) => {
+                self.last_error = Some(error.to_string());
+                [0, 0, 0, 255]
+            }
+        }
+    }
+}
+
+/// The limits of the store of a module, trapping instead of failing to grow memories and tables.
+fn limits(max_memory: usize) -> StoreLimits {
+    StoreLimitsBuilder::new()
+        .memory_size(max_memory)
+        .table_elements(MAX_TABLE_ELEMENTS)
+        .trap_on_grow_failure(true)
+        .build()
+}
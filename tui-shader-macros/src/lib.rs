@@ -0,0 +1,213 @@
+//! Procedural macros for [`tui-shader`](https://docs.rs/tui-shader). Use them through the re-exports
+//! of the `tui-shader` crate, which are enabled by its `macros` feature.
+
+use std::path::PathBuf;
+
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+use naga::{
+    AddressSpace, Binding, Handle, Module, Scalar, ShaderStage, Type, TypeInner, UniqueArena,
+    VectorSize,
+};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{LitStr, Token, parse_macro_input};
+
+/// The size in bytes of the buffers bound to the built-in inputs.
+const BUILTIN_BUFFER_SIZE: u32 = 16;
+
+/// Embeds a WGSL file like `include_wgsl!` and validates it while compiling. The path is relative to
+/// the file the macro is called in.
+///
+/// Instead of panicking on the first draw, the build fails with the diagnostic of the shader compiler
+/// if the shader doesn't parse or validate, has no `@fragment` entry point or declares the built-in
+/// inputs of `@group(0)` with types that don't fit the data `tui-shader` binds to them.
+///
+/// An optional second argument names the entry point the shader is used with, e.g.
+/// `include_wgsl_validated!("shader.wgsl", "main")`. The build then also fails if there is no
+/// `@fragment` function with that name, or if it doesn't take the `@location(0) vec2<f32>` UV
+/// coordinates and return an `@location(0) vec4<f32>` color. Built-in arguments like
+/// `@builtin(position)` are allowed as well.
+#[proc_macro]
+pub fn include_wgsl_validated(input: TokenStream) -> TokenStream {
+    let Input { path, entry_point } = parse_macro_input!(input as Input);
+    let result = validate(&path).and_then(|module| match &entry_point {
+        Some(entry_point) => validate_entry_point(&module, &entry_point.value())
+            .map_err(|message| syn::Error::new(entry_point.span(), message)),
+        None => Ok(()),
+    });
+    match result {
+        Ok(()) => quote!(::tui_shader::include_wgsl!(#path)).into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+struct Input {
+    path: LitStr,
+    entry_point: Option<LitStr>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut entry_point = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            entry_point = Some(input.parse()?);
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Self { path, entry_point })
+    }
+}
+
+fn validate(path: &LitStr) -> syn::Result<Module> {
+    parse_and_validate(&path.value()).map_err(|message| syn::Error::new(path.span(), message))
+}
+
+fn parse_and_validate(path: &str) -> Result<Module, String> {
+    let full_path = resolve(path);
+    let source = std::fs::read_to_string(&full_path)
+        .map_err(|error| format!("failed to read {}: {error}", full_path.display()))?;
+    let module = naga::front::wgsl::parse_str(&source)
+        .map_err(|error| error.emit_to_string_with_path(&source, path))?;
+    Validator::new(ValidationFlags::all(), Capabilities::all())
+        .validate(&module)
+        .map_err(|error| error.emit_to_string_with_path(&source, path))?;
+    if !module
+        .entry_points
+        .iter()
+        .any(|entry_point| entry_point.stage == ShaderStage::Fragment)
+    {
+        return Err(format!("{path} has no @fragment entry point"));
+    }
+    validate_builtin_bindings(&module, path)?;
+    Ok(module)
+}
+
+/// Resolves `path` relative to the file the macro is called in, or to the manifest directory if the
+/// call site isn't a file.
+fn resolve(path: &str) -> PathBuf {
+    let caller = proc_macro::Span::call_site()
+        .local_file()
+        .and_then(|file| file.parent().map(PathBuf::from));
+    match caller {
+        Some(directory) => directory.join(path),
+        None => PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join(path),
+    }
+}
+
+/// Checks the variables declared for the built-in inputs, see the table in the `tui-shader` docs.
+fn validate_builtin_bindings(module: &Module, path: &str) -> Result<(), String> {
+    for (_, variable) in module.global_variables.iter() {
+        let Some(binding) = &variable.binding else {
+            continue;
+        };
+        if binding.group != 0 {
+            continue;
+        }
+        let name = variable.name.as_deref().unwrap_or("_");
+        let ty = &module.types[variable.ty].inner;
+        match binding.binding {
+            0 | 1 | 4 | 5 | 6 | 7 | 8 => {
+                if variable.space != AddressSpace::Uniform {
+                    return Err(format!(
+                        "{path}: `{name}` at @group(0) @binding({}) must be a var<uniform>",
+                        binding.binding
+                    ));
+                }
+                let size = ty.size(module.to_ctx());
+                if size > BUILTIN_BUFFER_SIZE {
+                    return Err(format!(
+                        "{path}: `{name}` at @group(0) @binding({}) is {size} bytes, but only \
+                         {BUILTIN_BUFFER_SIZE} bytes are bound, declare it as a vec4",
+                        binding.binding
+                    ));
+                }
+            }
+            2 if variable.space != AddressSpace::Uniform
+                || !matches!(ty, TypeInner::Struct { .. }) =>
+            {
+                return Err(format!(
+                    "{path}: `{name}` at @group(0) @binding(2) must be a var<uniform> of a struct"
+                ));
+            }
+            3 => {
+                return Err(format!(
+                    "{path}: @group(0) @binding(3) is reserved for the vertex shader"
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `name` is a `@fragment` function matching the output of the vertex shader and the
+/// color target.
+fn validate_entry_point(module: &Module, name: &str) -> Result<(), String> {
+    let Some(entry_point) = module
+        .entry_points
+        .iter()
+        .find(|entry_point| entry_point.name == name)
+    else {
+        return Err(format!("the shader has no entry point named `{name}`"));
+    };
+    if entry_point.stage != ShaderStage::Fragment {
+        return Err(format!("`{name}` is not a @fragment function"));
+    }
+    let mut inputs = Vec::new();
+    for argument in &entry_point.function.arguments {
+        flatten(
+            &module.types,
+            argument.ty,
+            argument.binding.as_ref(),
+            &mut inputs,
+        );
+    }
+    for (binding, ty) in inputs {
+        if let Some(Binding::Location { location, .. }) = binding
+            && (*location != 0 || !is_vector(ty, VectorSize::Bi))
+        {
+            return Err(format!(
+                "`{name}` can only take `@location(0) uv: vec2<f32>` and built-in arguments"
+            ));
+        }
+    }
+    let mut outputs = Vec::new();
+    if let Some(result) = &entry_point.function.result {
+        flatten(
+            &module.types,
+            result.ty,
+            result.binding.as_ref(),
+            &mut outputs,
+        );
+    }
+    let returns_color = outputs.iter().any(|(binding, ty)| {
+        matches!(binding, Some(Binding::Location { location: 0, .. }))
+            && is_vector(ty, VectorSize::Quad)
+    });
+    if !returns_color {
+        return Err(format!("`{name}` must return `@location(0) vec4<f32>`"));
+    }
+    Ok(())
+}
+
+/// Collects the bindings of an argument or result, looking into the members of structs.
+fn flatten<'a>(
+    types: &'a UniqueArena<Type>,
+    ty: Handle<Type>,
+    binding: Option<&'a Binding>,
+    out: &mut Vec<(Option<&'a Binding>, &'a TypeInner)>,
+) {
+    match (&types[ty].inner, binding) {
+        (TypeInner::Struct { members, .. }, None) => {
+            for member in members {
+                flatten(types, member.ty, member.binding.as_ref(), out);
+            }
+        }
+        (inner, binding) => out.push((binding, inner)),
+    }
+}
+
+fn is_vector(ty: &TypeInner, expected: VectorSize) -> bool {
+    matches!(ty, TypeInner::Vector { size, scalar } if *size == expected && *scalar == Scalar::F32)
+}